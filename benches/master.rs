@@ -0,0 +1,37 @@
+//! 設問マスタの参照
+//!
+//! 性能の目安（この値を大きく超える変更は見直す）
+//! - `question_by_id`: 200ns以下
+//! - `static_question`: 10ns以下
+//! - `questions`: 5µs以下（57設問の共有ハンドルの複製）
+//! - `embedded_master`: 100µs以下
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use simple_stresscheck::embedded::static_question;
+use simple_stresscheck::resource::embedded_master;
+use simple_stresscheck::QUESTIONS;
+
+fn master(c: &mut Criterion) {
+    // 初期化を計測に含めない
+    let _ = QUESTIONS.questions();
+
+    let mut group = c.benchmark_group("master");
+    group.bench_function("question_by_id", |b| {
+        b.iter(|| {
+            QUESTIONS
+                .question(black_box(57))
+                .map(|question| question.id)
+        })
+    });
+    group.bench_function("static_question", |b| {
+        b.iter(|| static_question(black_box(57)).map(|question| question.id))
+    });
+    group.bench_function("questions", |b| b.iter(|| QUESTIONS.questions().len()));
+    group.bench_function("embedded_master", |b| {
+        b.iter(|| embedded_master().unwrap().questions().len())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, master);
+criterion_main!(benches);
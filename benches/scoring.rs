@@ -0,0 +1,96 @@
+//! 採点と一括判定
+//!
+//! 性能の目安（この値を大きく超える変更は見直す）
+//! - `single/sumup`・`single/conversion`: 200ns以下
+//! - `single/check_result`: 1µs以下
+//! - `sumup_10000/batch`: 1ms以下（1000万行/秒以上）
+//! - `bulk_10000/read_and_score`: 50ms以下（20万行/秒以上）
+
+use std::io::Cursor;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use simple_stresscheck::batch::score_batch;
+use simple_stresscheck::simulate::Population;
+use simple_stresscheck::{read_bulk, score_slice, AnswerStore, CheckResult, StressCriteria};
+
+fn scoring(c: &mut Criterion) {
+    let rows = Population::new(10_000, 10)
+        .with_seed(1)
+        .generate()
+        .into_iter()
+        .map(|record| *record.answers.values())
+        .collect::<Vec<[u8; 57]>>();
+
+    let mut store = AnswerStore::default();
+    for &value in &rows[0] {
+        store.push(value).unwrap();
+    }
+    let mut group = c.benchmark_group("single");
+    group.bench_function("sumup", |b| {
+        b.iter(|| black_box(&store).to_sumup_score().unwrap())
+    });
+    group.bench_function("conversion", |b| {
+        b.iter(|| black_box(&store).to_conversion_score().unwrap())
+    });
+    group.bench_function("check_result", |b| {
+        b.iter(|| CheckResult::new(black_box(&store), StressCriteria::SUMUP).unwrap())
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("sumup_10000");
+    group.throughput(Throughput::Elements(rows.len() as u64));
+    group.bench_function("per_row", |b| {
+        b.iter(|| {
+            rows.iter()
+                .map(|row| {
+                    let mut store = AnswerStore::default();
+                    for &value in row {
+                        store.push(value).unwrap();
+                    }
+                    StressCriteria::SUMUP.evaluate(&store).unwrap()
+                })
+                .filter(|&has_stress| has_stress)
+                .count()
+        })
+    });
+    group.bench_function("slice", |b| {
+        b.iter(|| {
+            rows.iter()
+                .map(|row| score_slice(black_box(row), StressCriteria::SUMUP).unwrap())
+                .filter(|&scores| StressCriteria::SUMUP.judge(scores))
+                .count()
+        })
+    });
+    group.bench_function("batch", |b| {
+        b.iter(|| score_batch(black_box(&rows), StressCriteria::SUMUP).len())
+    });
+    group.finish();
+
+    let mut csv = String::from("id");
+    for id in 1..=57 {
+        csv.push_str(&format!(",q_{}", id));
+    }
+    csv.push('\n');
+    for (index, row) in rows.iter().enumerate() {
+        csv.push_str(&index.to_string());
+        for value in row {
+            csv.push_str(&format!(",{}", value));
+        }
+        csv.push('\n');
+    }
+    let mut group = c.benchmark_group("bulk_10000");
+    group.throughput(Throughput::Elements(rows.len() as u64));
+    group.bench_function("read_and_score", |b| {
+        b.iter(|| {
+            read_bulk(Cursor::new(csv.as_bytes()))
+                .into_iter()
+                .filter_map(|row| row.ok())
+                .filter(|(_, store)| StressCriteria::SUMUP.evaluate(store).unwrap())
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, scoring);
+criterion_main!(benches);
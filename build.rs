@@ -0,0 +1,85 @@
+//! 同梱の57設問マスタ(resources/57.json)から、実行時に解析せずに参照できる定数データを生成する
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+const MASTER: &str = "resources/57.json";
+
+fn string(value: &Value) -> String {
+    format!("{:?}", value.as_str().expect("string"))
+}
+
+fn optional_string(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => format!("Some({:?})", text),
+        _ => "None".to_string(),
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", MASTER);
+    let master: Value =
+        serde_json::from_str(&fs::read_to_string(MASTER).expect("master")).expect("master json");
+
+    let mut questions = vec![];
+    let mut themes = vec![];
+    for theme in master["simple_stress"].as_array().expect("themes") {
+        let mut outer_questions = vec![];
+        for outer_question in theme["questions"].as_array().expect("outer questions") {
+            let start = questions.len();
+            for question in outer_question["questions"].as_array().expect("questions") {
+                // 設問番号で配列を引けるよう、出現順と設問番号が一致することを前提とする
+                assert_eq!(
+                    question["id"].as_u64(),
+                    Some(questions.len() as u64 + 1),
+                    "question ids must be sequential"
+                );
+                let scores = question["scores"]
+                    .as_array()
+                    .expect("scores")
+                    .iter()
+                    .map(|score| {
+                        format!(
+                            "StaticScore {{ score: {}, text: {} }}",
+                            score["score"].as_u64().expect("score"),
+                            string(&score["text"])
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                questions.push(format!(
+                    "    StaticQuestion {{\n        id: {},\n        text: {},\n        furigana: {},\n        reverse: {},\n        scores: &[{}],\n    }},\n",
+                    question["id"].as_u64().expect("id"),
+                    string(&question["text"]),
+                    optional_string(question.get("furigana")),
+                    question["reverse"].as_bool().expect("reverse"),
+                    scores
+                ));
+            }
+            outer_questions.push(format!(
+                "StaticOuterQuestion {{ title: {}, questions: {}..{} }}",
+                optional_string(outer_question.get("title")),
+                start,
+                questions.len()
+            ));
+        }
+        themes.push(format!(
+            "    StaticTheme {{\n        theme: {},\n        questions: &[{}],\n    }},\n",
+            string(&theme["theme"]),
+            outer_questions.join(", ")
+        ));
+    }
+
+    let code = format!(
+        "/// 出現順の設問\npub static STATIC_QUESTIONS: [StaticQuestion; {}] = [\n{}];\n\n/// 教示文ごとのまとまり\npub static STATIC_THEMES: [StaticTheme; {}] = [\n{}];\n",
+        questions.len(),
+        questions.concat(),
+        themes.len(),
+        themes.concat()
+    );
+    let out = Path::new(&env::var("OUT_DIR").expect("OUT_DIR")).join("master.rs");
+    fs::write(out, code).expect("write master.rs");
+}
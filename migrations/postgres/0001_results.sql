@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS results (
+    token TEXT PRIMARY KEY,
+    department TEXT,
+    submitted_at TIMESTAMPTZ NOT NULL,
+    has_stress BOOLEAN NOT NULL,
+    shared_with_employer BOOLEAN NOT NULL,
+    document JSONB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS results_submitted_at ON results (submitted_at);
+
+-- 期間内の高ストレス者のうち事業者への提供に同意した者の抽出（面接指導の勧奨）に用いる
+CREATE INDEX IF NOT EXISTS results_high_stress_shared ON results (submitted_at)
+    WHERE has_stress AND shared_with_employer;
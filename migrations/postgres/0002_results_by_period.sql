@@ -0,0 +1,15 @@
+-- 受検者ごとの経年変化を保存するため、トークン・実施回の組を主キーにする
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'results' AND column_name = 'period'
+    ) THEN
+        ALTER TABLE results ADD COLUMN period TEXT NOT NULL DEFAULT '';
+        ALTER TABLE results DROP CONSTRAINT results_pkey;
+        ALTER TABLE results ADD PRIMARY KEY (token, period);
+    END IF;
+END
+$$;
+
+CREATE INDEX IF NOT EXISTS results_token_submitted_at ON results (token, submitted_at);
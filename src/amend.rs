@@ -0,0 +1,125 @@
+//! 回答の修正と監査証跡
+//!
+//! 紙の調査票を転記した際の誤りを、受検後に実施事務従事者が修正できるようにする。
+//! 修正のたびに設問・修正前後の回答・理由・修正者・日時を受検記録に残し、判定をやり直す。
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::{CheckRecord, CheckResult, Error, StressCriteria};
+
+/// 修正の理由と修正者
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmendmentReason {
+    /// 理由（「転記誤り」など）
+    pub reason: String,
+    /// 修正者
+    pub amended_by: String,
+    /// 修正日時
+    pub amended_at: DateTime<FixedOffset>,
+}
+
+impl AmendmentReason {
+    pub fn new<R, B>(reason: R, amended_by: B, amended_at: DateTime<FixedOffset>) -> Self
+    where
+        R: Into<String>,
+        B: Into<String>,
+    {
+        Self {
+            reason: reason.into(),
+            amended_by: amended_by.into(),
+            amended_at,
+        }
+    }
+}
+
+/// 1件の修正の記録
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Amendment {
+    /// 設問番号
+    pub question: u8,
+    /// 修正前の回答（未回答は0）
+    pub old_value: u8,
+    /// 修正後の回答
+    pub new_value: u8,
+    #[serde(flatten)]
+    pub reason: AmendmentReason,
+}
+
+impl CheckRecord {
+    /// 回答を修正し、選定方法に従って判定し直す
+    /// 修正後の回答で判定できない場合は修正しない。回答が変わらない場合は記録を残さない。
+    pub fn amend(
+        &mut self,
+        question_no: u8,
+        new_value: u8,
+        reason: AmendmentReason,
+        criteria: StressCriteria,
+    ) -> Result<CheckResult, Error> {
+        if !(1..=57).contains(&question_no) {
+            return Err(Error::IllegalQuestion);
+        }
+        let old_value = self.answers.values()[question_no as usize - 1];
+        let mut answers = self.answers.clone();
+        answers.insert(question_no, new_value)?;
+        let result = CheckResult::new(&answers, criteria)?;
+        self.answers = answers;
+        if old_value != new_value {
+            self.amendments.push(Amendment {
+                question: question_no,
+                old_value,
+                new_value,
+                reason,
+            });
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simulate::Population;
+
+    #[test]
+    fn test_amend() {
+        let mut record = Population::new(1, 1).with_seed(9).generate().remove(0);
+        let before = CheckResult::new(&record.answers, StressCriteria::SUMUP).unwrap();
+        let old_value = record.answers.values()[19];
+        let new_value = if old_value == 4 { 1 } else { 4 };
+        let at = DateTime::parse_from_rfc3339("2024-06-03T09:00:00+09:00").unwrap();
+        let reason = AmendmentReason::new("転記誤り", "保健師A", at);
+
+        let after = record
+            .amend(20, new_value, reason.clone(), StressCriteria::SUMUP)
+            .unwrap();
+        assert_ne!(after.scores, before.scores);
+        assert_eq!(record.answers.values()[19], new_value);
+        assert_eq!(
+            record.amendments,
+            vec![Amendment {
+                question: 20,
+                old_value,
+                new_value,
+                reason: reason.clone(),
+            }]
+        );
+        let value = serde_json::to_value(&record.amendments[0]).unwrap();
+        assert_eq!(value["amended_by"], "保健師A");
+
+        record
+            .amend(20, new_value, reason.clone(), StressCriteria::SUMUP)
+            .unwrap();
+        assert_eq!(record.amendments.len(), 1);
+        assert!(matches!(
+            record.amend(20, 5, reason.clone(), StressCriteria::SUMUP),
+            Err(Error::IllegalAnswer)
+        ));
+        assert!(matches!(
+            record.amend(58, 1, reason, StressCriteria::SUMUP),
+            Err(Error::IllegalQuestion)
+        ));
+        assert_eq!(record.answers.values()[19], new_value);
+        assert_eq!(record.amendments.len(), 1);
+    }
+}
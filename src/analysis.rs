@@ -0,0 +1,499 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{AnswerStore, CheckResult, Error, Scale, Stress, StressCriteria};
+
+/// 選定基準ごとの高ストレス者数
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CriteriaEstimate {
+    pub criteria: StressCriteria,
+    /// 判定対象の人数
+    pub respondents: usize,
+    /// 高ストレス者数
+    pub high_stress: usize,
+    /// 高ストレス者率
+    pub high_stress_rate: f64,
+}
+
+/// 候補となる複数の選定基準で高ストレス者数を試算する
+///
+/// 衛生委員会で数値基準を審議する際に、実際の回答に基づいて各基準の高ストレス者数を
+/// 比較するために用いる。回答欠落などで判定できない回答は対象外とする。
+pub fn dry_run(stores: &[AnswerStore], candidates: &[StressCriteria]) -> Vec<CriteriaEstimate> {
+    let scores = stores
+        .iter()
+        .filter_map(|store| {
+            let sumup = store.to_sumup_score().ok()?;
+            let conversion = store.to_conversion_score().ok()?;
+            Some((sumup.scores(), conversion.scores()))
+        })
+        .collect::<Vec<_>>();
+    let respondents = scores.len();
+    candidates
+        .iter()
+        .map(|criteria| {
+            let high_stress = scores
+                .iter()
+                .filter(|(sumup, conversion)| match criteria {
+                    StressCriteria::Sumup(_) => criteria.judge(*sumup),
+                    StressCriteria::Conversion(_) => criteria.judge(*conversion),
+                })
+                .count();
+            CriteriaEstimate {
+                criteria: *criteria,
+                respondents,
+                high_stress,
+                high_stress_rate: if respondents == 0 {
+                    0.0
+                } else {
+                    high_stress as f64 / respondents as f64
+                },
+            }
+        })
+        .collect()
+}
+
+/// 受検期間中の集計を判定結果の追加・取消のたびに更新する
+/// ダッシュボードで受検状況や高ストレス者率を再計算なしに表示するために用いる。
+#[derive(Debug, Clone, Default)]
+pub struct RunningAggregate {
+    expected: Option<usize>,
+    respondents: usize,
+    high_stress: usize,
+    score_sums: [u64; 3],
+    point_sums: BTreeMap<Scale, u64>,
+}
+
+/// 集計の現在値
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AggregateSnapshot {
+    /// 受検者数
+    pub respondents: usize,
+    /// 受検率
+    pub completion_rate: Option<f64>,
+    /// 高ストレス者数
+    pub high_stress: usize,
+    /// 高ストレス者率
+    pub high_stress_rate: Option<f64>,
+    /// 領域Ａ・Ｂ・Ｃの点数の平均
+    pub score_means: Option<(f64, f64, f64)>,
+    /// 尺度ごとの評価点の平均
+    pub evaluation_point_means: BTreeMap<Scale, f64>,
+}
+
+impl RunningAggregate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 受検対象者数を指定して受検率を算出できるようにする
+    pub fn with_expected(expected: usize) -> Self {
+        Self {
+            expected: Some(expected),
+            ..Self::default()
+        }
+    }
+
+    /// 判定結果を集計に加える
+    pub fn add(&mut self, result: &CheckResult) {
+        self.respondents += 1;
+        if result.has_stress {
+            self.high_stress += 1;
+        }
+        let (a, b, c) = result.scores;
+        for (sum, value) in self.score_sums.iter_mut().zip([a, b, c]) {
+            *sum += value as u64;
+        }
+        for (scale, &point) in &result.evaluation_points {
+            *self.point_sums.entry(*scale).or_default() += point as u64;
+        }
+    }
+
+    /// 集計に加えた判定結果を取り消す
+    /// 回答の修正時に古い結果を取り消してから新しい結果を加える。集計が空の場合は`false`を返す。
+    pub fn remove(&mut self, result: &CheckResult) -> bool {
+        if self.respondents == 0 {
+            return false;
+        }
+        self.respondents -= 1;
+        if result.has_stress {
+            self.high_stress = self.high_stress.saturating_sub(1);
+        }
+        let (a, b, c) = result.scores;
+        for (sum, value) in self.score_sums.iter_mut().zip([a, b, c]) {
+            *sum = sum.saturating_sub(value as u64);
+        }
+        for (scale, &point) in &result.evaluation_points {
+            if let Some(sum) = self.point_sums.get_mut(scale) {
+                *sum = sum.saturating_sub(point as u64);
+            }
+        }
+        true
+    }
+
+    /// 受検者数
+    pub fn respondents(&self) -> usize {
+        self.respondents
+    }
+
+    /// 高ストレス者数
+    pub fn high_stress(&self) -> usize {
+        self.high_stress
+    }
+
+    /// 集計の現在値を取得する
+    pub fn snapshot(&self) -> AggregateSnapshot {
+        let n = self.respondents as f64;
+        let mean = |sum: u64| sum as f64 / n;
+        let has_respondents = self.respondents > 0;
+        AggregateSnapshot {
+            respondents: self.respondents,
+            completion_rate: self
+                .expected
+                .filter(|&expected| expected > 0)
+                .map(|expected| n / expected as f64),
+            high_stress: self.high_stress,
+            high_stress_rate: has_respondents.then(|| self.high_stress as f64 / n),
+            score_means: has_respondents.then(|| {
+                (
+                    mean(self.score_sums[0]),
+                    mean(self.score_sums[1]),
+                    mean(self.score_sums[2]),
+                )
+            }),
+            evaluation_point_means: if has_respondents {
+                self.point_sums
+                    .iter()
+                    .map(|(&scale, &sum)| (scale, mean(sum)))
+                    .collect()
+            } else {
+                BTreeMap::new()
+            },
+        }
+    }
+}
+
+/// 判定が反転する回答の変更
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Flip {
+    /// 設問番号
+    pub question: u8,
+    /// 変更前の回答番号
+    pub from: u8,
+    /// 変更後の回答番号
+    pub to: u8,
+}
+
+/// 判定の境界付近にあるかの分析結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Sensitivity {
+    /// 現在の判定
+    pub has_stress: bool,
+    /// 1問の回答を1段階変えると判定が反転する変更
+    pub flips: Vec<Flip>,
+}
+
+impl Sensitivity {
+    /// 1問の変更で判定が反転するか
+    pub fn is_borderline(&self) -> bool {
+        !self.flips.is_empty()
+    }
+}
+
+/// 1問の回答を1段階変えた場合に判定が反転するものを列挙する
+///
+/// 境界付近の受検者を医師が確認する際や、入力誤りの点検に用いる。
+pub fn sensitivity(store: &AnswerStore, criteria: StressCriteria) -> Result<Sensitivity, Error> {
+    let has_stress = criteria.evaluate(store)?;
+    let mut flips = vec![];
+    for (index, &from) in store.values().iter().enumerate() {
+        let question = index as u8 + 1;
+        for to in [from.wrapping_sub(1), from + 1] {
+            if !(1..=4).contains(&to) {
+                continue;
+            }
+            let mut changed = store.clone();
+            changed.insert(question, to)?;
+            if criteria.evaluate(&changed)? != has_stress {
+                flips.push(Flip { question, from, to });
+            }
+        }
+    }
+    Ok(Sensitivity { has_stress, flips })
+}
+
+/// 設問ごとの回答の分布
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemDistribution {
+    /// 設問番号
+    pub question: u8,
+    /// 回答番号1〜4それぞれの人数
+    pub counts: [usize; 4],
+    /// 未回答の人数
+    pub unanswered: usize,
+    /// 回答番号の平均（逆転前）
+    pub mean: Option<f64>,
+}
+
+impl ItemDistribution {
+    /// 回答した人数
+    pub fn answered(&self) -> usize {
+        self.counts.iter().sum()
+    }
+}
+
+/// 全設問について回答番号ごとの人数を集計する
+///
+/// データの点検（回答の偏りや入力誤りの発見）や、設問ごとの項目分析に用いる。
+/// 未回答の設問を含む回答も、回答した設問は集計に含める。
+pub fn item_distributions(stores: &[AnswerStore]) -> Vec<ItemDistribution> {
+    let mut counts = [[0usize; 4]; 57];
+    let mut unanswered = [0usize; 57];
+    for store in stores {
+        for (index, &value) in store.values().iter().enumerate() {
+            match value {
+                1..=4 => counts[index][value as usize - 1] += 1,
+                _ => unanswered[index] += 1,
+            }
+        }
+    }
+    (0..57)
+        .map(|index| {
+            let answered: usize = counts[index].iter().sum();
+            let sum: usize = counts[index]
+                .iter()
+                .enumerate()
+                .map(|(answer, count)| (answer + 1) * count)
+                .sum();
+            ItemDistribution {
+                question: index as u8 + 1,
+                counts: counts[index],
+                unanswered: unanswered[index],
+                mean: (answered > 0).then(|| sum as f64 / answered as f64),
+            }
+        })
+        .collect()
+}
+
+/// 尺度間の相関行列
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CorrelationMatrix {
+    /// 行・列の尺度（`Scale::ALL`の順）
+    pub scales: Vec<Scale>,
+    /// 計算に用いた人数
+    pub respondents: usize,
+    /// ピアソンの相関係数。分散が0の尺度を含む組は`None`
+    pub values: Vec<Vec<Option<f64>>>,
+}
+
+impl CorrelationMatrix {
+    /// 2つの尺度の相関係数
+    pub fn get(&self, row: Scale, column: Scale) -> Option<f64> {
+        let index = |scale| self.scales.iter().position(|&s| s == scale);
+        self.values[index(row)?][index(column)?]
+    }
+
+    /// 先頭列を尺度名とするCSVで書き出す
+    pub fn write_csv<W: std::io::Write>(&self, writer: W) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(writer);
+        let mut header = vec!["scale"];
+        header.extend(self.scales.iter().map(|scale| scale.as_str()));
+        writer.write_record(&header).map_err(Error::CSVWriteError)?;
+        for (scale, row) in self.scales.iter().zip(&self.values) {
+            let mut record = vec![scale.as_str().to_string()];
+            record.extend(row.iter().map(|value| match value {
+                Some(value) => format!("{:.3}", value),
+                None => String::new(),
+            }));
+            writer.write_record(&record).map_err(Error::CSVWriteError)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// 18尺度の素点の相関行列を求める
+///
+/// 特異な集団や翻訳版の調査票について、尺度間の関係が通常と大きく異なっていないかを
+/// 点検するために用いる。回答欠落などで素点を求められない回答は対象外とする。
+pub fn scale_correlations(stores: &[AnswerStore]) -> CorrelationMatrix {
+    let rows = stores
+        .iter()
+        .filter_map(|store| {
+            let intermediate = store.to_intermediate_score().ok()?;
+            Some(Scale::ALL.map(|scale| intermediate.get(scale) as f64))
+        })
+        .collect::<Vec<_>>();
+    let n = rows.len() as f64;
+    let means = (0..Scale::ALL.len())
+        .map(|index| rows.iter().map(|row| row[index]).sum::<f64>() / n)
+        .collect::<Vec<_>>();
+    let covariance = |i: usize, j: usize| {
+        rows.iter()
+            .map(|row| (row[i] - means[i]) * (row[j] - means[j]))
+            .sum::<f64>()
+    };
+    let values = (0..Scale::ALL.len())
+        .map(|i| {
+            (0..Scale::ALL.len())
+                .map(|j| {
+                    let denominator = (covariance(i, i) * covariance(j, j)).sqrt();
+                    (denominator > 0.0).then(|| covariance(i, j) / denominator)
+                })
+                .collect()
+        })
+        .collect();
+    CorrelationMatrix {
+        scales: Scale::ALL.to_vec(),
+        respondents: rows.len(),
+        values,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Thresholds;
+
+    fn store(answer: u8) -> AnswerStore {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(answer).unwrap();
+        }
+        store
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let stores = vec![store(1), store(3), store(4), AnswerStore::default()];
+        let candidates = [
+            StressCriteria::SUMUP,
+            StressCriteria::Sumup(Thresholds {
+                b: 90,
+                ..Thresholds::SUMUP
+            }),
+            StressCriteria::CONVERSION,
+        ];
+        let estimates = dry_run(&stores, &candidates);
+        assert_eq!(estimates.len(), 3);
+        assert!(estimates.iter().all(|estimate| estimate.respondents == 3));
+        // 領域Ｂの合計点数は 38, 84, 107
+        assert_eq!(estimates[0].high_stress, 2);
+        assert_eq!(estimates[1].high_stress, 1);
+        assert_eq!(estimates[0].criteria, StressCriteria::SUMUP);
+    }
+
+    #[test]
+    fn test_running_aggregate() {
+        let mut aggregate = RunningAggregate::with_expected(4);
+        let low = CheckResult::new(&store(1), StressCriteria::SUMUP).unwrap();
+        let high = CheckResult::new(&store(4), StressCriteria::SUMUP).unwrap();
+
+        assert_eq!(aggregate.snapshot().high_stress_rate, None);
+        aggregate.add(&low);
+        aggregate.add(&high);
+        let snapshot = aggregate.snapshot();
+        assert_eq!(snapshot.respondents, 2);
+        assert_eq!(snapshot.completion_rate, Some(0.5));
+        assert_eq!(snapshot.high_stress_rate, Some(0.5));
+        assert_eq!(snapshot.score_means, Some((42.5, 72.5, 22.5)));
+        assert_eq!(snapshot.evaluation_point_means[&Scale::WorkControl], 3.0);
+
+        assert!(aggregate.remove(&high));
+        let snapshot = aggregate.snapshot();
+        assert_eq!(snapshot.high_stress, 0);
+        assert_eq!(snapshot.score_means, Some((50.0, 38.0, 9.0)));
+
+        assert!(aggregate.remove(&low));
+        assert!(!aggregate.remove(&low));
+        assert_eq!(aggregate.respondents(), 0);
+    }
+
+    #[test]
+    fn test_sensitivity() {
+        // 領域Ｂ（設問18〜46）の合計を87点とし、基準もちょうど87点とする
+        let mut borderline = AnswerStore::default();
+        for question in 1..=57 {
+            let answer = match question {
+                18..=20 => 2,
+                21..=46 => 3,
+                _ => 1,
+            };
+            borderline.push(answer).unwrap();
+        }
+        assert_eq!(borderline.to_sumup_score().unwrap().scores().1, 87);
+
+        let result = sensitivity(
+            &borderline,
+            StressCriteria::Sumup(Thresholds {
+                b: 87,
+                ..Thresholds::SUMUP
+            }),
+        )
+        .unwrap();
+        assert!(result.has_stress);
+        assert!(result.is_borderline());
+        assert!(result.flips.contains(&Flip {
+            question: 21,
+            from: 3,
+            to: 2
+        }));
+        assert!(result
+            .flips
+            .iter()
+            .all(|flip| (18..=46).contains(&flip.question)));
+
+        let result = sensitivity(&store(1), StressCriteria::SUMUP).unwrap();
+        assert!(!result.has_stress);
+        assert!(!result.is_borderline());
+    }
+
+    #[test]
+    fn test_scale_correlations() {
+        let stores = crate::simulate::Population::new(300, 1)
+            .with_seed(7)
+            .generate()
+            .into_iter()
+            .map(|record| record.answers)
+            .collect::<Vec<_>>();
+        let matrix = scale_correlations(&stores);
+        assert_eq!(matrix.respondents, 300);
+        assert_eq!(matrix.values.len(), 18);
+        for scale in Scale::ALL {
+            let value = matrix.get(scale, scale).unwrap();
+            assert!((value - 1.0).abs() < 1e-9);
+        }
+        let value = matrix.get(Scale::Tired, Scale::Anxious).unwrap();
+        assert_eq!(Some(value), matrix.get(Scale::Anxious, Scale::Tired));
+        assert!((-1.0..=1.0).contains(&value));
+
+        let constant = scale_correlations(&[store(2), store(2)]);
+        assert_eq!(constant.get(Scale::Tired, Scale::Anxious), None);
+
+        let mut output = vec![];
+        matrix.write_csv(&mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+        assert_eq!(text.lines().count(), 19);
+        assert!(text.starts_with("scale,mental_work_stress_volume,"));
+        assert!(text.lines().nth(1).unwrap().contains(",1.000,"));
+    }
+
+    #[test]
+    fn test_item_distributions() {
+        let mut partial = AnswerStore::default();
+        partial.insert(1, 2).unwrap();
+        let distributions = item_distributions(&[store(1), store(4), store(4), partial]);
+        assert_eq!(distributions.len(), 57);
+        assert_eq!(distributions[0].counts, [1, 1, 0, 2]);
+        assert_eq!(distributions[0].unanswered, 0);
+        assert_eq!(distributions[0].mean, Some(2.75));
+        assert_eq!(distributions[56].question, 57);
+        assert_eq!(distributions[56].answered(), 3);
+        assert_eq!(distributions[56].unanswered, 1);
+
+        let value = serde_json::to_value(&distributions[0]).unwrap();
+        assert_eq!(value["counts"], serde_json::json!([1, 1, 0, 2]));
+        assert_eq!(item_distributions(&[])[0].mean, None);
+    }
+}
@@ -0,0 +1,135 @@
+//! 受検者1人分の回答ファイル
+//!
+//! `q1 = 3`のように設問番号と回答番号を並べたTOML/YAMLを回答として読み込む。
+//! スクリプトからの試験や、実施事務従事者が個別の記録を手で直す際に用いる。
+//! キーは`q1`〜`q57`のほか、一括判定のCSVと同じ`q_1`〜`q_57`も受け付ける。
+//! YAMLの読み込みは`yaml` featureで有効になる。
+//! 設問番号順に回答番号をカンマで区切った`3,2,4,...`の形式も受け付ける（環境変数で渡す場合など）。
+
+use std::collections::BTreeMap;
+
+use crate::{AnswerStore, Error};
+
+impl AnswerStore {
+    /// TOMLの回答ファイルを読み込む
+    pub fn from_toml_str(value: &str) -> Result<Self, Error> {
+        let answers: BTreeMap<String, u8> =
+            toml::from_str(value).map_err(|e| Error::AnswerFileReadError(e.to_string()))?;
+        Self::from_answer_map(&answers)
+    }
+
+    /// YAMLの回答ファイルを読み込む
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml_str(value: &str) -> Result<Self, Error> {
+        let answers: BTreeMap<String, u8> =
+            serde_yaml::from_str(value).map_err(|e| Error::AnswerFileReadError(e.to_string()))?;
+        Self::from_answer_map(&answers)
+    }
+
+    /// 設問番号順に回答番号をカンマで区切った文字列を読み込む
+    /// 前後の空白は無視する。57個に満たない場合は未回答のまま残す。
+    pub fn from_csv_str(value: &str) -> Result<Self, Error> {
+        let mut store = AnswerStore::default();
+        for answer in value.trim().split(',') {
+            let answer = answer.trim();
+            store.push(
+                answer
+                    .parse()
+                    .map_err(|_| Error::AnswerFileReadError(answer.to_string()))?,
+            )?;
+        }
+        Ok(store)
+    }
+
+    /// 設問のキーと回答番号の対応から読み込む
+    /// 設問のキーとして読めないキーは`Error::IllegalQuestion`とする。
+    fn from_answer_map(answers: &BTreeMap<String, u8>) -> Result<Self, Error> {
+        let mut store = AnswerStore::default();
+        for (key, &score) in answers {
+            store.insert(question_no(key)?, score)?;
+        }
+        Ok(store)
+    }
+}
+
+/// `q12`・`q_12`から設問番号を取り出す
+fn question_no(key: &str) -> Result<u8, Error> {
+    let number = key
+        .strip_prefix("q_")
+        .or_else(|| key.strip_prefix('q'))
+        .ok_or(Error::IllegalQuestion)?;
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::IllegalQuestion);
+    }
+    number.parse().map_err(|_| Error::IllegalQuestion)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_csv_str() {
+        let value = vec!["3"; 57].join(", ");
+        let store = AnswerStore::from_csv_str(&format!(" {}\n", value)).unwrap();
+        assert!(store.values().iter().all(|&v| v == 3));
+        let store = AnswerStore::from_csv_str("1,2,3").unwrap();
+        assert_eq!(store.values()[..4], [1, 2, 3, 0]);
+        assert!(matches!(
+            AnswerStore::from_csv_str("1,,3"),
+            Err(Error::AnswerFileReadError(_))
+        ));
+        assert!(matches!(
+            AnswerStore::from_csv_str("1,5"),
+            Err(Error::IllegalAnswer)
+        ));
+        assert!(matches!(
+            AnswerStore::from_csv_str(&vec!["1"; 58].join(",")),
+            Err(Error::IllegalQuestion)
+        ));
+    }
+
+    #[test]
+    fn test_from_toml_str() {
+        let toml = (1..=57)
+            .map(|no| format!("q{} = {}\n", no, if no == 12 { 4 } else { 2 }))
+            .collect::<String>();
+        let store = AnswerStore::from_toml_str(&toml).unwrap();
+        assert_eq!(store.values()[11], 4);
+        assert_eq!(store.values().iter().filter(|&&v| v == 2).count(), 56);
+
+        let store = AnswerStore::from_toml_str("q_3 = 1\nq10 = 3\n").unwrap();
+        assert_eq!(store.values()[2], 1);
+        assert_eq!(store.values()[9], 3);
+        assert_eq!(store.values().iter().filter(|&&v| v == 0).count(), 55);
+
+        assert!(matches!(
+            AnswerStore::from_toml_str("q58 = 1"),
+            Err(Error::IllegalQuestion)
+        ));
+        assert!(matches!(
+            AnswerStore::from_toml_str("id = 1"),
+            Err(Error::IllegalQuestion)
+        ));
+        assert!(matches!(
+            AnswerStore::from_toml_str("q1 = 5"),
+            Err(Error::IllegalAnswer)
+        ));
+        assert!(matches!(
+            AnswerStore::from_toml_str("q1 = \"そうだ\""),
+            Err(Error::AnswerFileReadError(_))
+        ));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_from_yaml_str() {
+        let store = AnswerStore::from_yaml_str("q1: 3\nq_57: 4\n").unwrap();
+        assert_eq!(store.values()[0], 3);
+        assert_eq!(store.values()[56], 4);
+        assert!(matches!(
+            AnswerStore::from_yaml_str("- 1\n- 2\n"),
+            Err(Error::AnswerFileReadError(_))
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use futures_util::Stream;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+use crate::encoding::{decode_text, normalize_digits};
+use crate::header::HeaderAliases;
+use crate::{AnswerStore, BulkRow, CheckRecord, CheckResult, Error, StressCriteria};
+
+/// `read_bulk`の非同期版
+/// アップロードされたCSVを1行ずつ読み込むため、ブロッキングタスクを起こす必要がない。
+/// 文字コード（BOM付きUTF-8・Shift_JIS）・全角数字・列名の別名の扱いは`read_bulk`と同じ。
+/// 文字コードは行ごとに判別する。改行を含む引用符付きの値には対応しない。
+///
+/// `next_record`・`next_result`で1行ずつ取り出すほか、`into_records`・`into_results`で
+/// `Stream`に変換して`StreamExt`のコンビネータで扱える。
+pub struct AsyncBulkReader<R> {
+    reader: R,
+    aliases: HeaderAliases,
+    headers: Option<csv::StringRecord>,
+    buffer: Vec<u8>,
+}
+
+impl<R> AsyncBulkReader<R>
+where
+    R: AsyncBufRead + Unpin,
+{
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            aliases: HeaderAliases::default(),
+            headers: None,
+            buffer: vec![],
+        }
+    }
+
+    /// 列名の別名表を指定する
+    pub fn with_header_aliases(mut self, aliases: HeaderAliases) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// 次の行を受検記録として読み込む
+    /// 入力の終端に達した場合は`None`を返す。
+    pub async fn next_record(&mut self) -> Option<Result<CheckRecord, Error>> {
+        loop {
+            self.buffer.clear();
+            match self.reader.read_until(b'\n', &mut self.buffer).await {
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e.into())),
+            }
+            let line = match decode_text(&self.buffer) {
+                Ok(line) => normalize_digits(&line),
+                Err(e) => return Some(Err(e)),
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record = match csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(line.as_bytes())
+                .records()
+                .next()?
+            {
+                Ok(record) => record,
+                Err(e) => return Some(Err(Error::CSVReadError(e))),
+            };
+            match self.headers {
+                Some(ref headers) => {
+                    return Some(
+                        record
+                            .deserialize::<BulkRow>(Some(headers))
+                            .map(|row| row.into())
+                            .map_err(Error::CSVReadError),
+                    )
+                }
+                None => self.headers = Some(self.aliases.rename(&record)),
+            }
+        }
+    }
+
+    /// 次の行を読み込み、選定方法に従って判定する
+    pub async fn next_result(
+        &mut self,
+        criteria: StressCriteria,
+    ) -> Option<Result<(String, CheckResult), Error>> {
+        let record = self.next_record().await?;
+        Some(record.and_then(|record| {
+            let result = CheckResult::new(&record.answers, criteria)?;
+            Ok((record.id, result))
+        }))
+    }
+
+    /// 受検記録の`Stream`に変換する
+    /// 返す`Stream`は`Unpin`ではないため、`next`で取り出す場合は`std::pin::pin!`などで固定する。
+    pub fn into_records(self) -> impl Stream<Item = Result<CheckRecord, Error>> {
+        futures_util::stream::unfold(self, |mut reader| async move {
+            let record = reader.next_record().await?;
+            Some((record, reader))
+        })
+    }
+
+    /// 選定方法に従って判定した結果の`Stream`に変換する
+    /// 返す`Stream`は`Unpin`ではないため、`next`で取り出す場合は`std::pin::pin!`などで固定する。
+    pub fn into_results(
+        self,
+        criteria: StressCriteria,
+    ) -> impl Stream<Item = Result<(String, CheckResult), Error>> {
+        futures_util::stream::unfold(self, move |mut reader| async move {
+            let result = reader.next_result(criteria).await?;
+            Some((result, reader))
+        })
+    }
+}
+
+/// 非同期にCSVを全て読み込む
+pub async fn read_bulk_async<R>(reader: R) -> Vec<Result<(String, AnswerStore), Error>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    read_bulk_async_with_aliases(reader, &HeaderAliases::default()).await
+}
+
+/// 列名の別名表を指定して`read_bulk_async`と同様に読み込む
+pub async fn read_bulk_async_with_aliases<R>(
+    reader: R,
+    aliases: &HeaderAliases,
+) -> Vec<Result<(String, AnswerStore), Error>>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut reader = AsyncBulkReader::new(reader).with_header_aliases(aliases.clone());
+    let mut rows = vec![];
+    while let Some(record) = reader.next_record().await {
+        rows.push(record.map(|record| (record.id, record.answers)));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INPUT: &str = r#"id,"q_1",q_2,q_3,q_4,q_5,q_6,q_7,q_8,q_9,q_10,q_11,q_12,q_13,q_14,q_15,q_16,q_17,q_18,q_19,q_20,q_21,q_22,q_23,q_24,q_25,q_26,q_27,q_28,q_29,q_30,q_31,q_32,q_33,q_34,q_35,q_36,q_37,q_38,q_39,q_40,q_41,q_42,q_43,q_44,q_45,q_46,q_47,q_48,q_49,q_50,q_51,q_52,q_53,q_54,q_55,q_56,q_57
+
+"1",4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4,4
+"2",,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3"#;
+
+    #[tokio::test]
+    async fn test_read_bulk_async() {
+        let rows = read_bulk_async(INPUT.as_bytes()).await;
+        assert_eq!(rows.len(), 2);
+        let (id, store) = rows[0].as_ref().unwrap();
+        assert_eq!(id, "1");
+        assert_eq!(store.values[56], 4);
+        assert!(matches!(rows[1], Err(Error::CSVReadError(_))));
+    }
+
+    #[tokio::test]
+    async fn test_into_results() {
+        use futures_util::StreamExt;
+
+        let results = AsyncBulkReader::new(INPUT.as_bytes())
+            .into_results(StressCriteria::SUMUP)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(results.len(), 2);
+        let (id, result) = results[0].as_ref().unwrap();
+        assert_eq!(id, "1");
+        assert!(result.has_stress);
+        assert!(results[1].is_err());
+
+        let records = AsyncBulkReader::new(INPUT.as_bytes()).into_records();
+        let mut records = std::pin::pin!(records.filter_map(|record| async { record.ok() }));
+        assert_eq!(records.next().await.unwrap().id, "1");
+        assert!(records.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_bulk_async_encoding() {
+        let answers = vec!["４"; 57].join(",");
+        let input = format!(
+            "\u{FEFF}{}\n\"1\",{}\n",
+            INPUT.lines().next().unwrap(),
+            answers
+        );
+        let rows = read_bulk_async(input.as_bytes()).await;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].as_ref().unwrap().1.values[0], 4);
+
+        let input = input.replace("\u{FEFF}", "").replace("\"1\"", "社員１");
+        let (shift_jis, _, _) = encoding_rs::SHIFT_JIS.encode(&input);
+        let rows = read_bulk_async(&shift_jis[..]).await;
+        assert_eq!(rows[0].as_ref().unwrap().0, "社員1");
+
+        let header = INPUT
+            .lines()
+            .next()
+            .unwrap()
+            .replacen("id", "受検者コード", 1);
+        let input = format!("{}\n\"1\",{}\n", header, answers);
+        let aliases = HeaderAliases::default()
+            .with_aliases(&[("受検者コード".to_string(), "id".to_string())].into());
+        let rows = read_bulk_async_with_aliases(input.as_bytes(), &aliases).await;
+        assert_eq!(rows[0].as_ref().unwrap().0, "1");
+    }
+
+    #[tokio::test]
+    async fn test_next_result() {
+        let mut reader = AsyncBulkReader::new(INPUT.as_bytes());
+        let (id, result) = reader
+            .next_result(StressCriteria::SUMUP)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(id, "1");
+        assert!(result.has_stress);
+        assert!(reader
+            .next_result(StressCriteria::SUMUP)
+            .await
+            .unwrap()
+            .is_err());
+        assert!(reader.next_result(StressCriteria::SUMUP).await.is_none());
+    }
+}
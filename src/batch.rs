@@ -0,0 +1,132 @@
+//! 大量の回答の一括判定
+//!
+//! 回答を`[[u8; 57]]`の連続した配列で受け取り、合計点数方式の点数をまとめて算出する。
+//! 逆転項目の置き換えを分岐のない演算で行い、内側のループがベクトル化されやすい形にしている。
+//! 素点換算表方式では1行ずつ`score_slice`で算出する。
+
+use crate::{score_slice, Error, StressCriteria};
+
+/// 逆転項目で1、それ以外で0
+const REVERSE: [u8; 57] = {
+    let mut mask = [0; 57];
+    let mut i = 0;
+    while i < 57 {
+        let id = i + 1;
+        if (id >= 1 && id <= 7) || (id >= 11 && id <= 13) || id == 15 || (id >= 18 && id <= 20) {
+            mask[i] = 1;
+        }
+        i += 1;
+    }
+    mask
+};
+
+/// 1行分の合計点数方式の点数
+/// 未回答・範囲外の回答がある場合は`None`。
+#[inline]
+fn sumup_row(row: &[u8; 57]) -> Option<(u8, u8, u8)> {
+    let mut min = u8::MAX;
+    let mut max = 0;
+    let mut scores = [0u8; 57];
+    for i in 0..57 {
+        let value = row[i];
+        min = min.min(value);
+        max = max.max(value);
+        // 逆転項目は 5 - value、それ以外は value
+        scores[i] =
+            value.wrapping_add(REVERSE[i].wrapping_mul(5u8.wrapping_sub(value.wrapping_mul(2))));
+    }
+    if min < 1 || max > 4 {
+        return None;
+    }
+    let sum =
+        |range: std::ops::Range<usize>| scores[range].iter().map(|&v| v as u16).sum::<u16>() as u8;
+    // 領域Ｃは設問47〜55（満足度の56・57は含めない）
+    Some((sum(0..17), sum(17..46), sum(46..55)))
+}
+
+/// 1行分の判定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchScore {
+    /// 領域Ａ・Ｂ・Ｃの点数
+    pub scores: (u8, u8, u8),
+    pub has_stress: bool,
+}
+
+/// 回答の配列を一括で判定する
+pub fn score_batch(rows: &[[u8; 57]], criteria: StressCriteria) -> Vec<Result<BatchScore, Error>> {
+    rows.iter()
+        .map(|row| {
+            let scores = match criteria {
+                StressCriteria::Sumup(_) => sumup_row(row).ok_or_else(|| error_for(row))?,
+                StressCriteria::Conversion(_) => score_slice(row, criteria)?,
+            };
+            Ok(BatchScore {
+                scores,
+                has_stress: criteria.judge(scores),
+            })
+        })
+        .collect()
+}
+
+/// 1行ずつの判定と同じエラーを返す
+fn error_for(row: &[u8; 57]) -> Error {
+    if row.iter().any(|&value| value > 4) {
+        Error::IllegalAnswer
+    } else {
+        Error::NotFullfilled
+    }
+}
+
+/// 高ストレス者数を数える
+/// 判定できない行は数えない。
+pub fn count_high_stress(rows: &[[u8; 57]], criteria: StressCriteria) -> usize {
+    score_batch(rows, criteria)
+        .into_iter()
+        .filter(|result| matches!(result, Ok(score) if score.has_stress))
+        .count()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, Stress};
+
+    #[test]
+    fn test_score_batch() {
+        assert!((0..57).all(|i| (REVERSE[i] == 1) == (crate::reverse_if((i + 1, 1)) != 1)));
+        let rows = (0..57u32)
+            .map(|seed| {
+                let mut row = [0u8; 57];
+                for (i, value) in row.iter_mut().enumerate() {
+                    *value = ((i as u32 * 7 + seed * 3) % 4 + 1) as u8;
+                }
+                row
+            })
+            .collect::<Vec<_>>();
+        for criteria in [StressCriteria::SUMUP, StressCriteria::CONVERSION] {
+            let batch = score_batch(&rows, criteria);
+            for (row, result) in rows.iter().zip(batch) {
+                let mut store = AnswerStore::default();
+                for &value in row {
+                    store.push(value).unwrap();
+                }
+                let scores = criteria.score(&store).unwrap().scores();
+                let result = result.unwrap();
+                assert_eq!(result.scores, scores);
+                assert_eq!(result.has_stress, criteria.judge(scores));
+            }
+        }
+
+        let mut row = [1u8; 57];
+        row[5] = 0;
+        assert!(matches!(
+            score_batch(&[row], StressCriteria::SUMUP)[0],
+            Err(Error::NotFullfilled)
+        ));
+        row[5] = 5;
+        assert!(matches!(
+            score_batch(&[row], StressCriteria::SUMUP)[0],
+            Err(Error::IllegalAnswer)
+        ));
+    }
+}
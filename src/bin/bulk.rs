@@ -1,35 +1,470 @@
 use clap::Parser;
-use simple_stresscheck::{read_bulk, Error, Stress};
-use std::fs::File;
-use std::io::BufReader;
+use simple_stresscheck::checkpoint::Checkpoint;
+use simple_stresscheck::compress::{append_output, create_output, open_input};
+use simple_stresscheck::config::Config;
+use simple_stresscheck::export::Sanitizer;
+use simple_stresscheck::id_match::write_suggestions_csv;
+use simple_stresscheck::ledger::LedgerWriter;
+use simple_stresscheck::lock;
+use simple_stresscheck::missing::MissingTally;
+use simple_stresscheck::outcome::{exit_with, Outcome};
+use simple_stresscheck::roster::Roster;
+use simple_stresscheck::snapshot::RunSnapshot;
+use simple_stresscheck::stamp::{ResultDocument, Stamped};
+use simple_stresscheck::{read_bulk_many_with_aliases, CheckResult, Error, Stress, QUESTIONS};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 
 #[derive(Parser)]
 struct Args {
-    path: String,
+    /// 入力CSV。複数指定した場合はまとめて判定する
+    #[arg(required_unless_present = "print_default_config")]
+    paths: Vec<String>,
+    /// 実施設定ファイル(TOML)。項目は環境変数`STRESSCHECK_*`でも上書きできる
+    #[arg(long, env = "STRESSCHECK_CONFIG")]
+    config: Option<String>,
+    /// 既定の実施設定(TOML)を出力して終了する
+    #[arg(long)]
+    print_default_config: bool,
+    /// 出力先。`.gz`・`.zst`の場合は圧縮する。省略時は標準出力
+    #[arg(long)]
+    output: Option<String>,
+    /// 判定結果をハッシュチェーン形式の台帳(JSON Lines)にも書き出す
+    #[arg(long)]
+    ledger: Option<String>,
+    /// 判定結果を来歴付きの文書(JSON Lines)にも書き出す。`verify-document`で検証できる
+    #[arg(long)]
+    documents: Option<String>,
+    /// 設問ごとの未回答・不正な値の集計(JSON)を書き出す
+    #[arg(long)]
+    missing_report: Option<String>,
+    /// 入力のハッシュと全受検者の判定結果のスナップショット(JSON)を書き出す。`diff-snapshots`で版の間の差分を確認できる。
+    /// 再開した場合も処理済みの行を含む全ての行から作成し、読み込めなかった行は`入力ファイル:行番号`で記録する
+    #[arg(long)]
+    snapshot: Option<String>,
+    /// 対象者名簿(CSV)。受検記録と突き合わせ、件数を標準エラーに出力する
+    #[arg(long)]
+    roster: Option<String>,
+    /// 名簿と突き合わなかった対象者・受検記録の一覧(CSV)を書き出す
+    #[arg(long, requires = "roster")]
+    unmatched_report: Option<String>,
+    /// 名簿にない受検記録と未受検の対象者の間で、IDの誤りと思われる組の候補(CSV)を書き出す
+    #[arg(long, requires = "roster")]
+    match_suggestions: Option<String>,
+    /// 進捗(JSON)の保存先。中断した場合は`--resume-from`に指定して再開できる
+    #[arg(long)]
+    checkpoint: Option<String>,
+    /// 保存した進捗から再開する。処理済みの行は出力・通知しない。進捗は同じファイルに保存を続ける。
+    /// 出力先は進捗を保存した時点の大きさに切り詰めて追記し、台帳は最後の記録から連鎖を続ける。
+    /// 圧縮した出力先（`.gz`・`.zst`）には追記できない
+    #[arg(long)]
+    resume_from: Option<String>,
+    /// 進捗を保存する間隔（行数）
+    #[arg(long, default_value_t = 1000)]
+    checkpoint_interval: usize,
+    /// ロックファイル。他の実行が動いている間は何もせずに終了コード4で終了する
+    #[arg(long)]
+    lock: Option<String>,
+    /// 判定結果をParquetファイルにも書き出す。実施設定の`pseudonymize_columns`に`id`を含む場合は仮名にする。
+    /// 再開した場合も処理済みの行を含む全ての行を書き出す
+    #[cfg(feature = "arrow")]
+    #[arg(long)]
+    parquet: Option<String>,
+    /// 結果通知メールを送信する。宛先は名簿(token,email)から引く
+    #[cfg(feature = "mail")]
+    #[arg(long)]
+    mail_roster: Option<String>,
+    /// 送信元アドレス
+    #[cfg(feature = "mail")]
+    #[arg(long, requires = "mail_roster")]
+    mail_from: Option<String>,
+    /// SMTPサーバ。省略時はsendmailで送信する
+    #[cfg(feature = "mail")]
+    #[arg(long)]
+    smtp_host: Option<String>,
+    /// 送信せずに宛先のみを確認する
+    #[cfg(feature = "mail")]
+    #[arg(long)]
+    dry_run: bool,
+    /// 送信間隔（ミリ秒）
+    #[cfg(feature = "mail")]
+    #[arg(long, default_value_t = 500)]
+    mail_interval: u64,
 }
 
-fn main() -> Result<(), Error> {
-    let args = Args::parse();
-    let reader = BufReader::new(File::open(&args.path)?);
-    for row in read_bulk(reader) {
-        match row {
-            Ok((id, store)) => match store.to_sumup_score() {
+/// 入力ファイルと、そのファイル内のデータ行の番号
+type Position = (String, usize);
+
+/// 処理できなかった行を標準エラーに示す
+/// 行番号は入力ファイル内の0始まりのデータ行の番号。ユーザ特定キーは示さない。
+fn report_error((source, row): &Position, e: &Error) {
+    eprintln!(
+        "source = {}, row = {}, error = {} ({})",
+        source,
+        row,
+        e,
+        e.code()
+    );
+}
+
+/// 一括判定の進捗の記録
+struct Progress {
+    checkpoint: Checkpoint,
+    path: Option<String>,
+    interval: usize,
+    pending: usize,
+}
+
+impl Progress {
+    fn new(args: &Args, config: &Config) -> Result<Self, Error> {
+        let checkpoint = match args.resume_from {
+            Some(ref path) => Checkpoint::load(path, config)?,
+            None => Checkpoint::new(config)?,
+        };
+        Ok(Self {
+            checkpoint,
+            path: args.checkpoint.clone().or_else(|| args.resume_from.clone()),
+            interval: args.checkpoint_interval.max(1),
+            pending: 0,
+        })
+    }
+
+    fn contains(&self, (source, row): &Position) -> bool {
+        self.checkpoint.contains(source, *row)
+    }
+
+    /// 処理済みとし、保存する間隔に達したかを返す
+    fn mark(&mut self, (source, row): &Position) -> bool {
+        self.checkpoint.mark(source, *row);
+        self.pending += 1;
+        self.pending >= self.interval
+    }
+
+    #[cfg(feature = "mail")]
+    fn is_delivered(&self, (source, row): &Position) -> bool {
+        self.checkpoint.is_delivered(source, *row)
+    }
+
+    #[cfg(feature = "mail")]
+    fn mark_delivered(&mut self, (source, row): &Position) {
+        self.checkpoint.mark_delivered(source, *row);
+    }
+
+    /// 出力を反映し、その時点の出力先の大きさとともに保存する
+    /// 再開時には出力先をこの大きさに切り詰めるため、処理済みと記録した行だけが出力に残る。
+    fn save_with(&mut self, outputs: &mut Outputs) -> Result<(), Error> {
+        self.checkpoint.outputs.extend(outputs.sync()?);
+        self.save()
+    }
+
+    fn save(&mut self) -> Result<(), Error> {
+        if let Some(ref path) = self.path {
+            self.checkpoint.save(path)?;
+        }
+        self.pending = 0;
+        Ok(())
+    }
+}
+
+/// 判定結果の出力先
+struct Outputs {
+    output: Box<dyn Write>,
+    ledger: Option<LedgerWriter<Box<dyn Write>>>,
+    documents: Option<Box<dyn Write>>,
+    /// ファイルに書き出す出力先
+    paths: Vec<String>,
+}
+
+impl Outputs {
+    /// 出力先を開く
+    /// 再開する場合は処理済みの行の出力を残すよう、進捗を保存した時点の大きさに切り詰めて追記し、
+    /// 台帳は最後の記録から連鎖を続ける。
+    fn open(args: &Args, progress: &Progress) -> Result<Self, Error> {
+        let resuming = args.resume_from.is_some();
+        let open = |path: &String| match resuming {
+            true => append_output(path, progress.checkpoint.outputs.get(path).copied()),
+            false => create_output(path),
+        };
+        let output = match args.output.as_ref() {
+            Some(path) => open(path)?,
+            None => Box::new(std::io::stdout()),
+        };
+        let ledger = match args.ledger.as_ref() {
+            Some(path) if resuming && Path::new(path).exists() => {
+                Some(LedgerWriter::resume(open(path)?, open_input(path)?)?)
+            }
+            Some(path) => Some(LedgerWriter::new(open(path)?)),
+            None => None,
+        };
+        let documents = match args.documents.as_ref() {
+            Some(path) => Some(open(path)?),
+            None => None,
+        };
+        let paths = [&args.output, &args.ledger, &args.documents]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect();
+        Ok(Self {
+            output,
+            ledger,
+            documents,
+            paths,
+        })
+    }
+
+    /// 出力をファイルに反映し、出力先ごとの大きさ（バイト）を返す
+    fn sync(&mut self) -> Result<Vec<(String, u64)>, Error> {
+        self.output.flush()?;
+        if let Some(ref mut ledger) = self.ledger {
+            ledger.flush()?;
+        }
+        if let Some(ref mut documents) = self.documents {
+            documents.flush()?;
+        }
+        self.paths
+            .iter()
+            .map(|path| Ok((path.clone(), std::fs::metadata(path)?.len())))
+            .collect()
+    }
+}
+
+/// 判定結果を名簿の宛先へ通知する
+/// 二重に通知しないよう、送信できた行は1通ごとに処理済みとして保存する。
+/// 宛先のない行は通知できないため処理済みとし、送信に失敗した行は再開時に改めて送信する。
+#[cfg(feature = "mail")]
+fn send_notifications(
+    args: &Args,
+    results: &[(Position, String, CheckResult)],
+    progress: &mut Progress,
+) -> Result<(), Error> {
+    use simple_stresscheck::mail::{Mailer, Transport};
+    use simple_stresscheck::notify::{Language, Notifier};
+
+    let Some(ref path) = args.mail_roster else {
+        return Ok(());
+    };
+    let roster = Roster::load(path)?;
+    let addresses = roster
+        .entries
+        .iter()
+        .filter_map(|entry| Some((entry.token.as_str(), entry.email.as_deref()?)))
+        .collect::<HashMap<_, _>>();
+    for (position, id, _) in results {
+        if !addresses.contains_key(id.as_str()) {
+            progress.mark_delivered(position);
+        }
+    }
+    progress.save()?;
+    let notifier = Notifier::new(
+        Language::Ja,
+        args.mail_from.as_deref().unwrap_or("stresscheck@localhost"),
+    );
+    let messages = results
+        .iter()
+        .filter_map(|(_, id, result)| {
+            Some(notifier.render(id, addresses.get(id.as_str())?, result))
+        })
+        .collect::<Vec<_>>();
+    let positions = results
+        .iter()
+        .map(|(position, id, _)| (id.as_str(), position))
+        .collect::<HashMap<_, _>>();
+    let mailer = if args.dry_run {
+        Mailer::dry_run()
+    } else {
+        let transport = match args.smtp_host {
+            Some(ref host) => Transport::Smtp {
+                host: host.clone(),
+                port: None,
+                username: std::env::var("SMTP_USERNAME").ok(),
+                password: std::env::var("SMTP_PASSWORD").ok(),
+            },
+            None => Transport::Sendmail { command: None },
+        };
+        Mailer::new(&transport)?
+    }
+    .with_interval(std::time::Duration::from_millis(args.mail_interval));
+    let mut saved = Ok(());
+    mailer.send_each(&messages, |delivery| {
+        let position = positions.get(delivery.id.as_str());
+        match delivery.result {
+            Ok(()) => {
+                eprintln!(
+                    "id = {}, to = {}, sent = {}",
+                    delivery.id, delivery.to, delivery.sent
+                );
+                if let (Some(position), Ok(())) = (position, &saved) {
+                    progress.mark_delivered(position);
+                    saved = progress.save();
+                }
+            }
+            Err(e) => match position {
+                Some(position) => report_error(position, &e),
+                None => eprintln!("to = {}, error = {} ({})", delivery.to, e, e.code()),
+            },
+        }
+    });
+    saved
+}
+
+fn main() -> ExitCode {
+    exit_with(run(Args::parse()))
+}
+
+/// 一括判定する
+/// 判定できなかった行があれば`Outcome::PartialErrors`、全ての行が判定できなければ`Outcome::InvalidInput`とする。
+fn run(args: Args) -> Result<Outcome, Error> {
+    if args.print_default_config {
+        print!("{}", Config::default().to_toml_string()?);
+        return Ok(Outcome::Success);
+    }
+    let _lock = lock::acquire_if_requested(args.lock.as_deref())?;
+    let config = Config::resolve(args.config.as_ref())?;
+    let mut progress = Progress::new(&args, &config)?;
+    let mut outputs = Outputs::open(&args, &progress)?;
+    // 再開する場合は最初の実行で書き出している
+    if args.resume_from.is_none() {
+        writeln!(
+            outputs.output,
+            "questionnaire_version = {}, criteria = {:?}, min_group_size = {}, consent = {:?}",
+            config.questionnaire_version, config.criteria, config.min_group_size, config.consent
+        )?;
+    }
+    let mut snapshot = args.snapshot.as_ref().map(|_| RunSnapshot::default());
+    #[cfg(feature = "arrow")]
+    let pseudonymizer = simple_stresscheck::pseudonym::Pseudonymizer::from_env()?;
+    let mut rows = HashMap::<PathBuf, usize>::new();
+    let (mut succeeded, mut failed) = (0, 0);
+    #[cfg(feature = "mail")]
+    let mut results = vec![];
+    #[cfg(feature = "arrow")]
+    let mut records = vec![];
+    for row in read_bulk_many_with_aliases(&args.paths, &config.header_aliases())? {
+        let index = rows.entry(row.source.clone()).or_default();
+        let position = (row.source.display().to_string(), *index);
+        *index += 1;
+        // Parquetとスナップショットは再開した場合も処理済みの行を含む全ての行から作成する
+        #[cfg(feature = "arrow")]
+        if let Ok(ref record) = row.record {
+            let mut record = record.clone();
+            if config
+                .pseudonymize_columns
+                .iter()
+                .any(|column| column == "id")
+            {
+                record.id = match pseudonymizer {
+                    Some(ref pseudonymizer) => pseudonymizer.pseudonym(&record.id),
+                    None => simple_stresscheck::export::REDACTED.to_string(),
+                };
+            }
+            records.push(record);
+        }
+        if let Some(ref mut snapshot) = snapshot {
+            match row.record {
+                Ok(ref record) => {
+                    let result = CheckResult::new(&record.answers, config.criteria);
+                    snapshot.push(&record.id, &record.answers, result);
+                }
+                Err(ref e) => {
+                    snapshot.push_unreadable(&format!("{}:{}", position.0, position.1), e)
+                }
+            }
+        }
+        if progress.contains(&position) {
+            // 書き出し済みで通知できていない行は、通知のためだけに判定し直す
+            #[cfg(feature = "mail")]
+            if args.mail_roster.is_some() && !progress.is_delivered(&position) {
+                if let Ok(record) = row.record {
+                    if let Ok(result) = CheckResult::new(&record.answers, config.criteria) {
+                        results.push((position, record.id, result));
+                    }
+                }
+            }
+            continue;
+        }
+        match row.record {
+            Ok(record) => match config.criteria.score(&record.answers) {
                 Ok(score) => {
-                    println!(
+                    succeeded += 1;
+                    writeln!(
+                        outputs.output,
                         "id = {}, scores = {:?}, has_stress = {}",
-                        id,
+                        record.id,
                         score.scores(),
                         score.has_stress()
-                    );
+                    )?;
+                    let result = CheckResult::new(&record.answers, config.criteria)?;
+                    if let Some(ref mut ledger) = outputs.ledger {
+                        ledger.append(&record.id, &result)?;
+                    }
+                    if let Some(ref mut documents) = outputs.documents {
+                        let document = ResultDocument {
+                            id: record.id.clone(),
+                            result: result.clone(),
+                        };
+                        let stamped = Stamped::new(document, &config, &QUESTIONS)?;
+                        writeln!(documents, "{}", serde_json::to_string(&stamped).unwrap())?;
+                    }
+                    #[cfg(feature = "mail")]
+                    if args.mail_roster.is_some() {
+                        results.push((position.clone(), record.id, result));
+                    }
                 }
                 Err(e) => {
-                    dbg!("{}", e);
+                    failed += 1;
+                    report_error(&position, &e);
                 }
             },
             Err(e) => {
-                dbg!("{}", e);
+                failed += 1;
+                report_error(&position, &e);
             }
         }
+        if progress.mark(&position) {
+            progress.save_with(&mut outputs)?;
+        }
+    }
+    progress.save_with(&mut outputs)?;
+    if let (Some(path), Some(snapshot)) = (args.snapshot.as_ref(), snapshot) {
+        let snapshot = snapshot.stamp(&config, &QUESTIONS)?;
+        let mut output = create_output(path)?;
+        writeln!(output, "{}", serde_json::to_string(&snapshot).unwrap())?;
+    }
+    if let Some(ref path) = args.missing_report {
+        let mut tally = MissingTally::new().with_header_aliases(config.header_aliases());
+        for input in &args.paths {
+            tally.add_csv(open_input(input)?)?;
+        }
+        let report = serde_json::to_string_pretty(&tally.report()).unwrap();
+        std::fs::write(path, report)?;
+    }
+    if let Some(ref roster) = args.roster {
+        let roster = Roster::load(roster)?;
+        let records = read_bulk_many_with_aliases(&args.paths, &config.header_aliases())?
+            .into_iter()
+            .filter_map(|row| row.record.ok())
+            .collect::<Vec<_>>();
+        let join = roster.join(&records);
+        eprintln!("{}", serde_json::to_string(&join.summary()).unwrap());
+        let sanitizer = Sanitizer::from_config(&config);
+        if let Some(ref path) = args.unmatched_report {
+            join.write_unmatched_csv(create_output(path)?, &sanitizer)?;
+        }
+        if let Some(ref path) = args.match_suggestions {
+            write_suggestions_csv(create_output(path)?, &join.suggestions(), &sanitizer)?;
+        }
+    }
+    #[cfg(feature = "arrow")]
+    if let Some(ref path) = args.parquet {
+        use simple_stresscheck::columnar::{to_record_batch, write_parquet};
+        let batch = to_record_batch(&records, config.criteria)?;
+        write_parquet(std::fs::File::create(path)?, &batch)?;
     }
-    Ok(())
+    #[cfg(feature = "mail")]
+    send_notifications(&args, &results, &mut progress)?;
+    progress.save()?;
+    Ok(Outcome::from_counts(succeeded, failed))
 }
@@ -0,0 +1,40 @@
+use clap::Parser;
+use simple_stresscheck::compress::{create_output, open_input};
+use simple_stresscheck::config::Config;
+use simple_stresscheck::export::Sanitizer;
+use simple_stresscheck::snapshot::{diff_snapshots, read_snapshot};
+use simple_stresscheck::Error;
+use std::io::Write;
+
+/// 二つの版で判定したスナップショットを比べ、判定結果が変わった受検者を出力する
+#[derive(Parser)]
+struct Args {
+    /// 前回のスナップショット(JSON)
+    previous: String,
+    /// 今回のスナップショット(JSON)
+    current: String,
+    /// 実施設定ファイル(TOML)。出力の伏字・仮名化に用いる
+    #[arg(long, env = "STRESSCHECK_CONFIG")]
+    config: Option<String>,
+    /// 判定結果が変わった受検者の一覧(CSV)の出力先。省略時は標準出力
+    #[arg(long)]
+    output: Option<String>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let config = Config::resolve(args.config.as_ref())?;
+    let previous = read_snapshot(open_input(&args.previous)?)?;
+    let current = read_snapshot(open_input(&args.current)?)?;
+    let diff = diff_snapshots(&previous, &current);
+    eprintln!(
+        "previous_version = {}, current_version = {}, inputs_changed = {}",
+        diff.previous_version, diff.current_version, diff.inputs_changed
+    );
+    eprintln!("{}", serde_json::to_string(&diff.summary()).unwrap());
+    let output: Box<dyn Write> = match args.output {
+        Some(ref path) => create_output(path)?,
+        None => Box::new(std::io::stdout()),
+    };
+    diff.write_csv(output, &Sanitizer::from_config(&config))
+}
@@ -0,0 +1,64 @@
+use std::process::ExitCode;
+
+use chrono::{DateTime, FixedOffset, Local, Months};
+use clap::Parser;
+use simple_stresscheck::compress::create_output;
+use simple_stresscheck::config::Config;
+use simple_stresscheck::export::Sanitizer;
+use simple_stresscheck::lock;
+use simple_stresscheck::outcome::{exit_with, Outcome};
+use simple_stresscheck::repository::SqliteRepository;
+use simple_stresscheck::retention::{purge_older_than, RETENTION_YEARS};
+use simple_stresscheck::Error;
+
+/// 保存期間を過ぎた結果を廃棄し、廃棄記録をCSVで出力する
+#[derive(Parser)]
+struct Args {
+    /// 保存先(SQLite)
+    database: String,
+    /// 実施設定ファイル(TOML)。廃棄記録の伏字・仮名化に用いる
+    #[arg(long, env = "STRESSCHECK_CONFIG")]
+    config: Option<String>,
+    /// 保存期間（年）
+    #[arg(long, default_value_t = RETENTION_YEARS)]
+    years: u32,
+    /// 廃棄記録の出力先。省略時は標準出力
+    #[arg(long)]
+    log: Option<String>,
+    /// 削除せずに対象を列挙する
+    #[arg(long)]
+    dry_run: bool,
+    /// ロックファイル。他の実行が動いている間は何もせずに終了コード4で終了する
+    #[arg(long)]
+    lock: Option<String>,
+}
+
+fn main() -> ExitCode {
+    exit_with(run(Args::parse()))
+}
+
+fn run(args: Args) -> Result<Outcome, Error> {
+    let _lock = lock::acquire_if_requested(args.lock.as_deref())?;
+    let config = Config::resolve(args.config.as_ref())?;
+    let mut repository = SqliteRepository::open(&args.database)?;
+    let now: DateTime<FixedOffset> = Local::now().fixed_offset();
+    let log = purge_older_than(
+        &mut repository,
+        Months::new(12 * args.years),
+        now,
+        args.dry_run,
+    )?;
+    eprintln!(
+        "cutoff = {}, targets = {}, purged = {}, dry_run = {}",
+        log.cutoff,
+        log.entries.len(),
+        log.purged,
+        log.dry_run
+    );
+    let sanitizer = Sanitizer::from_config(&config);
+    match args.log {
+        Some(path) => log.write_csv(create_output(path)?, &sanitizer)?,
+        None => log.write_csv(std::io::stdout(), &sanitizer)?,
+    }
+    Ok(Outcome::Success)
+}
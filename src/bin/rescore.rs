@@ -0,0 +1,71 @@
+use std::process::ExitCode;
+
+use chrono::{DateTime, FixedOffset, Local};
+use clap::Parser;
+use simple_stresscheck::compress::create_output;
+use simple_stresscheck::config::Config;
+use simple_stresscheck::export::Sanitizer;
+use simple_stresscheck::lock;
+use simple_stresscheck::outcome::{exit_with, Outcome};
+use simple_stresscheck::repository::SqliteRepository;
+use simple_stresscheck::rescore::rescore_repository;
+use simple_stresscheck::Error;
+
+/// 保存済みの結果を現在の実施設定で判定し直し、判定が変わった受検者をCSVで出力する
+#[derive(Parser)]
+struct Args {
+    /// 保存先(SQLite)
+    database: String,
+    /// 実施設定ファイル(TOML)。項目は環境変数`STRESSCHECK_*`でも上書きできる
+    #[arg(long, env = "STRESSCHECK_CONFIG")]
+    config: Option<String>,
+    /// 対象の受付日時の開始(RFC 3339)
+    #[arg(long, default_value = "1970-01-01T00:00:00Z")]
+    from: String,
+    /// 対象の受付日時の終了(RFC 3339、この日時を含まない)
+    #[arg(long, default_value = "9999-12-31T23:59:59Z")]
+    to: String,
+    /// 差分の出力先。省略時は標準出力
+    #[arg(long)]
+    output: Option<String>,
+    /// 保存せずに差分のみを出力する
+    #[arg(long)]
+    dry_run: bool,
+    /// ロックファイル。他の実行が動いている間は何もせずに終了コード4で終了する
+    #[arg(long)]
+    lock: Option<String>,
+}
+
+fn main() -> ExitCode {
+    exit_with(run(Args::parse()))
+}
+
+fn run(args: Args) -> Result<Outcome, Error> {
+    let _lock = lock::acquire_if_requested(args.lock.as_deref())?;
+    let config = Config::resolve(args.config.as_ref())?;
+    let parse = |value: &str| DateTime::parse_from_rfc3339(value).map_err(|_| Error::IllegalConfig);
+    let mut repository = SqliteRepository::open(&args.database)?;
+    let now: DateTime<FixedOffset> = Local::now().fixed_offset();
+    let report = rescore_repository(
+        &mut repository,
+        parse(&args.from)?,
+        parse(&args.to)?,
+        config.criteria,
+        now,
+        args.dry_run,
+    )?;
+    eprintln!(
+        "rescored = {}, updated = {}, without_answers = {}, newly_high_stress = {}, no_longer_high_stress = {}",
+        report.rescored,
+        report.updated,
+        report.without_answers,
+        report.newly_high_stress(),
+        report.no_longer_high_stress()
+    );
+    let sanitizer = Sanitizer::from_config(&config);
+    match args.output {
+        Some(path) => report.write_csv(create_output(path)?, &sanitizer)?,
+        None => report.write_csv(std::io::stdout(), &sanitizer)?,
+    }
+    Ok(Outcome::Success)
+}
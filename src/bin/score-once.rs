@@ -0,0 +1,46 @@
+use clap::Parser;
+use serde::Serialize;
+use simple_stresscheck::config::Config;
+use simple_stresscheck::{AnswerStore, CheckResult, Error};
+
+/// 1人分の回答を判定し、結果をJSONで出力して終了する
+/// ファイルや標準入力を用いずに、サーバレス関数・コンテナから呼び出すためのもの。
+#[derive(Parser)]
+struct Args {
+    /// 設問番号順に回答番号をカンマで区切った回答（`3,2,4,...`）
+    #[arg(long, env = "STRESSCHECK_ANSWERS", hide_env_values = true)]
+    answers: String,
+    /// ユーザ特定キー。出力にそのまま含める
+    #[arg(long, env = "STRESSCHECK_ID")]
+    id: Option<String>,
+    /// 実施設定ファイル(TOML)。項目は環境変数`STRESSCHECK_*`でも上書きできる
+    #[arg(long, env = "STRESSCHECK_CONFIG")]
+    config: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Output {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    result: CheckResult,
+}
+
+fn score(args: &Args) -> Result<Output, Error> {
+    let config = Config::resolve(args.config.as_ref())?;
+    let store = AnswerStore::from_csv_str(&args.answers)?;
+    Ok(Output {
+        id: args.id.clone(),
+        result: CheckResult::new(&store, config.criteria)?,
+    })
+}
+
+fn main() {
+    let args = Args::parse();
+    match score(&args) {
+        Ok(output) => println!("{}", serde_json::to_string(&output).unwrap()),
+        Err(e) => {
+            println!("{}", serde_json::to_string(&e).unwrap());
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use clap::Parser;
+use simple_stresscheck::compress::create_output;
+use simple_stresscheck::simulate::{Population, DEFAULT_HIGH_STRESS_RATE};
+use simple_stresscheck::{write_bulk_records, Error};
+use std::io::Write;
+
+/// 負荷試験・デモ用の模擬集団を一括読み込み形式のCSVで生成する
+#[derive(Parser)]
+struct Args {
+    /// 人数
+    #[arg(long, default_value_t = 1000)]
+    size: usize,
+    /// 部署数
+    #[arg(long, default_value_t = 10)]
+    departments: usize,
+    /// 高ストレス状態にある人の割合
+    #[arg(long, default_value_t = DEFAULT_HIGH_STRESS_RATE)]
+    high_stress_rate: f64,
+    /// 乱数の種
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// 出力先。`.gz`・`.zst`の場合は圧縮する。省略時は標準出力
+    #[arg(long)]
+    output: Option<String>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let records = Population::new(args.size, args.departments)
+        .with_high_stress_rate(args.high_stress_rate)
+        .with_seed(args.seed)
+        .generate();
+    let output: Box<dyn Write> = match args.output {
+        Some(path) => create_output(path)?,
+        None => Box::new(std::io::stdout()),
+    };
+    write_bulk_records(output, &records)
+}
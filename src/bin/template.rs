@@ -0,0 +1,34 @@
+use std::fs::File;
+use std::io::BufWriter;
+
+use clap::Parser;
+use simple_stresscheck::template::{
+    bulk_columns, write_csv_template, write_hints_csv, write_xlsx_template,
+};
+use simple_stresscheck::{Error, QUESTIONS};
+
+#[derive(Parser)]
+struct Args {
+    /// 出力先。拡張子が`.xlsx`の場合は入力規則付きのXLSXにする。省略時はCSVを標準出力
+    #[arg(long)]
+    output: Option<String>,
+    /// 列ごとの必須・選択肢・説明の一覧(CSV)を書き出す
+    #[arg(long)]
+    hints: Option<String>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let columns = bulk_columns(&QUESTIONS);
+    match args.output {
+        Some(ref path) if path.to_ascii_lowercase().ends_with(".xlsx") => {
+            write_xlsx_template(BufWriter::new(File::create(path)?), &columns)?
+        }
+        Some(ref path) => write_csv_template(File::create(path)?, &columns)?,
+        None => write_csv_template(std::io::stdout().lock(), &columns)?,
+    }
+    if let Some(ref path) = args.hints {
+        write_hints_csv(File::create(path)?, &columns)?;
+    }
+    Ok(())
+}
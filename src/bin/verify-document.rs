@@ -0,0 +1,55 @@
+use std::io::BufRead;
+
+use clap::Parser;
+use simple_stresscheck::config::Config;
+use simple_stresscheck::stamp::read_stamped;
+use simple_stresscheck::{Error, SimpleStress, QUESTIONS};
+
+#[derive(Parser)]
+struct Args {
+    /// 検証する文書(JSON Lines)
+    path: String,
+    /// 作成時の実施設定ファイル(TOML)。項目は環境変数`STRESSCHECK_*`でも上書きできる
+    #[arg(long, env = "STRESSCHECK_CONFIG")]
+    config: Option<String>,
+    /// 作成時のマスタ(JSON)。省略時は組み込みのマスタ
+    #[arg(long)]
+    master: Option<String>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let config = Config::resolve(args.config.as_ref())?;
+    let loaded;
+    let master: &SimpleStress = match args.master.as_ref() {
+        Some(path) => {
+            loaded = SimpleStress::load(path)?;
+            &loaded
+        }
+        None => &QUESTIONS,
+    };
+    let reader = std::io::BufReader::new(std::fs::File::open(&args.path)?);
+    let mut failed = false;
+    let mut count = 0;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        count += 1;
+        let discrepancies = read_stamped(&line)?.verify(&config, master)?;
+        for discrepancy in &discrepancies {
+            println!(
+                "line = {}, {}",
+                index + 1,
+                serde_json::to_string(discrepancy).unwrap()
+            );
+        }
+        failed |= !discrepancies.is_empty();
+    }
+    if failed {
+        std::process::exit(1);
+    }
+    println!("OK documents = {}", count);
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use clap::Parser;
+use simple_stresscheck::manual::verify_against_manual;
+use simple_stresscheck::{Error, SimpleStress, QUESTIONS};
+
+#[derive(Parser)]
+struct Args {
+    /// 検証するマスタ(JSON)。省略時は組み込みのマスタを検証する
+    path: Option<String>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let loaded;
+    let master: &SimpleStress = match args.path {
+        Some(path) => {
+            loaded = SimpleStress::load(path)?;
+            &loaded
+        }
+        None => &QUESTIONS,
+    };
+    let discrepancies = master.verify();
+    // 採点の実装もあわせて回答例で自己診断する。回答例が同梱されていない場合は成功としない
+    let manual = verify_against_manual();
+    if discrepancies.is_empty()
+        && manual
+            .as_ref()
+            .is_ok_and(|mismatches| mismatches.is_empty())
+    {
+        println!("OK checksum = {}", master.checksum());
+        return Ok(());
+    }
+    for discrepancy in &discrepancies {
+        println!("{}", serde_json::to_string(discrepancy).unwrap());
+    }
+    match manual {
+        Ok(mismatches) => {
+            for mismatch in &mismatches {
+                println!("{}", serde_json::to_string(mismatch).unwrap());
+            }
+        }
+        Err(e) => eprintln!("{} ({})", e, e.code()),
+    }
+    std::process::exit(1);
+}
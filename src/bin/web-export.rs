@@ -0,0 +1,49 @@
+use clap::Parser;
+use simple_stresscheck::variant::TextVariant;
+use simple_stresscheck::web::{render_survey_with, SurveyOptions};
+use simple_stresscheck::{Error, SimpleStress, QUESTIONS};
+
+/// 静的な回答ページ(HTML)を生成する
+#[derive(Parser)]
+struct Args {
+    /// 出力先。省略時は標準出力
+    output: Option<String>,
+    /// ページの表題
+    #[arg(long, default_value = "ストレスチェック")]
+    title: String,
+    /// マスタ(JSON)。省略時は組み込みのマスタを用いる
+    #[arg(long)]
+    master: Option<String>,
+    /// 設問文にふりがなを添える
+    #[arg(long)]
+    furigana: bool,
+    /// 設問文を「やさしい日本語」で示す
+    #[arg(long)]
+    plain: bool,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let loaded;
+    let master: &SimpleStress = match args.master {
+        Some(path) => {
+            loaded = SimpleStress::load(path)?;
+            &loaded
+        }
+        None => &QUESTIONS,
+    };
+    let options = SurveyOptions {
+        furigana: args.furigana,
+        variant: if args.plain {
+            TextVariant::Plain
+        } else {
+            TextVariant::Standard
+        },
+    };
+    let html = render_survey_with(master, &args.title, &options);
+    match args.output {
+        Some(path) => std::fs::write(path, html)?,
+        None => print!("{}", html),
+    }
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::io::{stdin, Write};
+
+use clap::Parser;
+use simple_stresscheck::config::{Config, ConsentHandling};
+use simple_stresscheck::{Error, StressCriteria};
+
+/// 対話形式で実施設定ファイル(TOML)を作成する
+#[derive(Parser)]
+struct Args {
+    /// 出力先。省略時は標準出力
+    output: Option<String>,
+}
+
+fn main() -> Result<(), Error> {
+    let args = Args::parse();
+    let default = Config::default();
+
+    let criteria = match ask(
+        "高ストレス者の選定方法を選んでください。\n  1 => 合計点数方式（その1）  2 => 素点換算表方式（その2）",
+        "1",
+    )?
+    .as_str()
+    {
+        "2" => StressCriteria::CONVERSION,
+        _ => StressCriteria::SUMUP,
+    };
+
+    let min_group_size = loop {
+        let value = ask(
+            &format!(
+                "集団分析の最小人数を入力してください。(既定値 {})",
+                default.min_group_size
+            ),
+            &default.min_group_size.to_string(),
+        )?;
+        match value.parse::<usize>() {
+            Ok(size) if size > 0 => break size,
+            _ => eprintln!("1以上の半角数字で入力してください。"),
+        }
+    };
+
+    let consent = match ask(
+        "事業者への結果提供の取り扱いを選んでください。\n  1 => 本人の同意がある場合のみ提供  2 => 提供しない",
+        "1",
+    )?
+    .as_str()
+    {
+        "2" => ConsentHandling::NotShared,
+        _ => ConsentHandling::Explicit,
+    };
+
+    let config = Config {
+        criteria,
+        min_group_size,
+        consent,
+        ..default
+    };
+    config.validate()?;
+    let value = config.to_toml_string()?;
+    match args.output {
+        Some(path) => std::fs::write(path, value)?,
+        None => print!("{}", value),
+    }
+    Ok(())
+}
+
+fn ask(prompt: &str, default: &str) -> Result<String, Error> {
+    let mut buffer = String::new();
+    eprintln!("{}", prompt);
+    eprint!("> ");
+    std::io::stderr().flush()?;
+    stdin().read_line(&mut buffer)?;
+    let value = buffer.trim();
+    Ok(if value.is_empty() { default } else { value }.to_string())
+}
@@ -0,0 +1,191 @@
+//! 中断した一括判定の再開
+//!
+//! 入力ファイルごとに処理済みの行の範囲を記録し、再開時にはその行を読み飛ばす。
+//! 処理済みの行は判定結果の出力や結果通知メールの送信の対象としないため、途中で中断しても
+//! 最初からやり直したり、同じ人へ二重に通知したりせずに済む。
+//!
+//! 判定結果の書き出しと結果通知メールの送信は別々に記録する。書き出した時点の出力先の大きさも
+//! 記録し、再開時には出力先をその大きさに切り詰めることで、進捗を保存した後に書き出した行を
+//! 二重に出力しないようにする。
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::stamp::config_hash;
+use crate::Error;
+
+/// 行の範囲（`start`以上`end`未満、入力ファイル内の0始まりのデータ行の番号）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowRange {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 一括判定の進捗
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// 判定に用いた実施設定のハッシュ
+    pub config_hash: String,
+    /// 入力ファイルごとの処理済み（判定結果を書き出した）行
+    pub sources: BTreeMap<String, Vec<RowRange>>,
+    /// 入力ファイルごとの結果を通知した行
+    #[serde(default)]
+    pub delivered: BTreeMap<String, Vec<RowRange>>,
+    /// 出力先ごとの、進捗を保存した時点の大きさ（バイト）
+    #[serde(default)]
+    pub outputs: BTreeMap<String, u64>,
+}
+
+impl Checkpoint {
+    pub fn new(config: &Config) -> Result<Self, Error> {
+        Ok(Self {
+            config_hash: config_hash(config)?,
+            sources: BTreeMap::new(),
+            delivered: BTreeMap::new(),
+            outputs: BTreeMap::new(),
+        })
+    }
+
+    /// 保存した進捗を読み込む
+    /// 実施設定が異なる場合は判定結果が変わるため`CheckpointMismatch`とする。
+    pub fn load<P: AsRef<Path>>(path: P, config: &Config) -> Result<Self, Error> {
+        let value = std::fs::read_to_string(path)?;
+        let checkpoint: Checkpoint = serde_json::from_str(&value).map_err(Error::JSONReadError)?;
+        if checkpoint.config_hash != config_hash(config)? {
+            return Err(Error::CheckpointMismatch(checkpoint.config_hash));
+        }
+        Ok(checkpoint)
+    }
+
+    /// 進捗を保存する
+    /// 書き込み中に中断しても前回の内容が残るよう、一時ファイルに書いてから置き換える。
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        let mut temporary = path.as_os_str().to_owned();
+        temporary.push(".tmp");
+        std::fs::write(&temporary, serde_json::to_string_pretty(self).unwrap())?;
+        std::fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// 行を処理済みとする
+    pub fn mark(&mut self, source: &str, row: usize) {
+        mark(self.sources.entry(source.to_string()).or_default(), row);
+    }
+
+    /// 処理済みの行か
+    pub fn contains(&self, source: &str, row: usize) -> bool {
+        contains(self.sources.get(source), row)
+    }
+
+    /// 行の結果を通知済みとする
+    pub fn mark_delivered(&mut self, source: &str, row: usize) {
+        mark(self.delivered.entry(source.to_string()).or_default(), row);
+    }
+
+    /// 結果を通知済みの行か
+    pub fn is_delivered(&self, source: &str, row: usize) -> bool {
+        contains(self.delivered.get(source), row)
+    }
+
+    /// 処理済みの行数
+    pub fn processed(&self) -> usize {
+        self.sources
+            .values()
+            .flatten()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+}
+
+/// 行を範囲に加え、隣り合う範囲をまとめる
+fn mark(ranges: &mut Vec<RowRange>, row: usize) {
+    let index = ranges.partition_point(|range| range.end < row);
+    match ranges.get_mut(index) {
+        Some(range) if range.start <= row && row < range.end => return,
+        Some(range) if range.end == row => range.end += 1,
+        Some(range) if range.start == row + 1 => range.start = row,
+        _ => ranges.insert(
+            index,
+            RowRange {
+                start: row,
+                end: row + 1,
+            },
+        ),
+    }
+    if let Some(next) = ranges.get(index + 1).copied() {
+        if ranges[index].end == next.start {
+            ranges[index].end = next.end;
+            ranges.remove(index + 1);
+        }
+    }
+}
+
+/// 行が範囲に含まれるか
+fn contains(ranges: Option<&Vec<RowRange>>, row: usize) -> bool {
+    ranges.is_some_and(|ranges| {
+        ranges
+            .iter()
+            .any(|range| range.start <= row && row < range.end)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_mark() {
+        let mut checkpoint = Checkpoint::new(&Config::default()).unwrap();
+        for row in [0, 1, 2, 5, 4, 3, 2, 8] {
+            checkpoint.mark("a.csv", row);
+        }
+        assert_eq!(
+            checkpoint.sources["a.csv"],
+            vec![RowRange { start: 0, end: 6 }, RowRange { start: 8, end: 9 }]
+        );
+        assert!(checkpoint.contains("a.csv", 5));
+        assert!(!checkpoint.contains("a.csv", 6));
+        assert!(!checkpoint.contains("b.csv", 0));
+        assert_eq!(checkpoint.processed(), 7);
+
+        checkpoint.mark_delivered("a.csv", 1);
+        assert!(checkpoint.is_delivered("a.csv", 1));
+        assert!(!checkpoint.is_delivered("a.csv", 0));
+        assert_eq!(checkpoint.processed(), 7);
+    }
+
+    #[test]
+    fn test_save_load() {
+        let path = std::env::temp_dir().join("stresscheck_test_checkpoint.json");
+        let mut checkpoint = Checkpoint::new(&Config::default()).unwrap();
+        checkpoint.mark("a.csv", 0);
+        checkpoint.save(&path).unwrap();
+        assert_eq!(
+            Checkpoint::load(&path, &Config::default()).unwrap(),
+            checkpoint
+        );
+        let config = Config {
+            min_group_size: 20,
+            ..Config::default()
+        };
+        assert!(matches!(
+            Checkpoint::load(&path, &config),
+            Err(Error::CheckpointMismatch(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+
+        // 通知・出力先の記録がない進捗も読み込める
+        let value = format!(
+            "{{\"config_hash\": \"{}\", \"sources\": {{}}}}",
+            checkpoint.config_hash
+        );
+        std::fs::write(&path, value).unwrap();
+        let loaded = Checkpoint::load(&path, &Config::default()).unwrap();
+        assert!(loaded.delivered.is_empty() && loaded.outputs.is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+//! 高ストレス状態の持続・回復の分析
+//!
+//! 連続する2回の実施の両方に回答した受検者について、高ストレス状態の推移（持続・回復・
+//! 新規発生）を集団ごとに集計し、職場環境改善などの取組の効果を評価するために用いる。
+//! 集団は後の回の所属部署で分け、人数が最小人数に満たない集団は集計値を秘匿する。
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::group::{DEFAULT_MIN_GROUP_SIZE, UNKNOWN_GROUP};
+use crate::repository::StoredResult;
+
+/// 全体の集計の集団名
+pub const ALL_GROUPS: &str = "all";
+
+/// 前回・今回の高ストレス判定の組ごとの人数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TransitionCounts {
+    /// 前回・今回とも高ストレス
+    pub persisted: usize,
+    /// 前回は高ストレス、今回は該当せず
+    pub recovered: usize,
+    /// 前回は該当せず、今回は高ストレス
+    pub onset: usize,
+    /// 前回・今回とも該当せず
+    pub stayed_low: usize,
+}
+
+impl TransitionCounts {
+    fn add(&mut self, previous: bool, current: bool) {
+        match (previous, current) {
+            (true, true) => self.persisted += 1,
+            (true, false) => self.recovered += 1,
+            (false, true) => self.onset += 1,
+            (false, false) => self.stayed_low += 1,
+        }
+    }
+
+    /// 両方の回に回答した人数
+    pub fn respondents(&self) -> usize {
+        self.persisted + self.recovered + self.onset + self.stayed_low
+    }
+
+    /// 前回の高ストレス者のうち今回も高ストレスの割合
+    pub fn persistence_rate(&self) -> Option<f64> {
+        rate(self.persisted, self.persisted + self.recovered)
+    }
+
+    /// 前回の高ストレス者のうち今回は該当しない割合
+    pub fn recovery_rate(&self) -> Option<f64> {
+        rate(self.recovered, self.persisted + self.recovered)
+    }
+
+    /// 前回該当しなかった者のうち今回は高ストレスの割合
+    pub fn onset_rate(&self) -> Option<f64> {
+        rate(self.onset, self.onset + self.stayed_low)
+    }
+}
+
+fn rate(count: usize, total: usize) -> Option<f64> {
+    (total > 0).then(|| count as f64 / total as f64)
+}
+
+/// 集団ごとの推移
+/// 人数が最小人数に満たない集団は`counts`以下が`None`となる。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Transition {
+    /// 集団名
+    pub key: String,
+    /// 両方の回に回答した人数
+    pub respondents: usize,
+    pub counts: Option<TransitionCounts>,
+    pub persistence_rate: Option<f64>,
+    pub recovery_rate: Option<f64>,
+    pub onset_rate: Option<f64>,
+}
+
+impl Transition {
+    fn new(key: &str, counts: TransitionCounts, min_group_size: usize) -> Self {
+        let respondents = counts.respondents();
+        let counts = (respondents >= min_group_size && respondents > 0).then_some(counts);
+        Self {
+            key: key.to_string(),
+            respondents,
+            persistence_rate: counts.and_then(|counts| counts.persistence_rate()),
+            recovery_rate: counts.and_then(|counts| counts.recovery_rate()),
+            onset_rate: counts.and_then(|counts| counts.onset_rate()),
+            counts,
+        }
+    }
+
+    /// 少人数のため集計値が秘匿されているか
+    pub fn is_suppressed(&self) -> bool {
+        self.counts.is_none()
+    }
+}
+
+/// 連続する2回の判定結果による推移の分析
+/// 受検者はトークンで対応付け、片方の回にしか回答していない受検者は対象外とする。
+pub struct CohortAnalysis<'a> {
+    previous: &'a [StoredResult],
+    current: &'a [StoredResult],
+    min_group_size: usize,
+}
+
+impl<'a> CohortAnalysis<'a> {
+    pub fn new(previous: &'a [StoredResult], current: &'a [StoredResult]) -> Self {
+        Self {
+            previous,
+            current,
+            min_group_size: DEFAULT_MIN_GROUP_SIZE,
+        }
+    }
+
+    /// 集計値を表示する最小人数を変更する
+    pub fn with_min_group_size(mut self, min_group_size: usize) -> Self {
+        self.min_group_size = min_group_size;
+        self
+    }
+
+    /// 推移を集計する
+    /// 先頭が全体（`ALL_GROUPS`）の集計で、以降は所属部署ごとの集計。
+    pub fn transitions(&self) -> Vec<Transition> {
+        let previous = self
+            .previous
+            .iter()
+            .map(|stored| (stored.token.as_str(), stored.result.has_stress))
+            .collect::<HashMap<_, _>>();
+        let mut all = TransitionCounts::default();
+        let mut groups: BTreeMap<&str, TransitionCounts> = BTreeMap::new();
+        for stored in self.current {
+            let Some(&before) = previous.get(stored.token.as_str()) else {
+                continue;
+            };
+            let key = stored.department.as_deref().unwrap_or(UNKNOWN_GROUP);
+            all.add(before, stored.result.has_stress);
+            groups
+                .entry(key)
+                .or_default()
+                .add(before, stored.result.has_stress);
+        }
+        std::iter::once(Transition::new(ALL_GROUPS, all, self.min_group_size))
+            .chain(
+                groups
+                    .into_iter()
+                    .map(|(key, counts)| Transition::new(key, counts, self.min_group_size)),
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, CheckResult, StressCriteria};
+    use chrono::DateTime;
+
+    fn stored(token: &str, department: &str, period: &str, answer: u8) -> StoredResult {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(answer).unwrap();
+        }
+        StoredResult {
+            token: token.to_string(),
+            period: period.to_string(),
+            department: Some(department.to_string()),
+            submitted_at: DateTime::parse_from_rfc3339(&format!("{}-06-01T00:00:00Z", period))
+                .unwrap(),
+            consent: None,
+            result: CheckResult::new(&store, StressCriteria::SUMUP).unwrap(),
+            answers: None,
+            previous_results: vec![],
+        }
+    }
+
+    #[test]
+    fn test_transitions() {
+        // 回答4は高ストレス、回答1は該当しない
+        let previous = [
+            stored("a", "営業部", "2023", 4),
+            stored("b", "営業部", "2023", 4),
+            stored("c", "営業部", "2023", 1),
+            stored("d", "総務部", "2023", 4),
+            stored("x", "総務部", "2023", 4),
+        ];
+        let current = [
+            stored("a", "営業部", "2024", 4),
+            stored("b", "営業部", "2024", 1),
+            stored("c", "営業部", "2024", 4),
+            stored("d", "総務部", "2024", 1),
+            stored("y", "総務部", "2024", 4),
+        ];
+        let transitions = CohortAnalysis::new(&previous, &current)
+            .with_min_group_size(2)
+            .transitions();
+        assert_eq!(transitions.len(), 3);
+
+        let all = &transitions[0];
+        assert_eq!(all.key, ALL_GROUPS);
+        assert_eq!(all.respondents, 4);
+        assert_eq!(
+            all.counts,
+            Some(TransitionCounts {
+                persisted: 1,
+                recovered: 2,
+                onset: 1,
+                stayed_low: 0,
+            })
+        );
+        assert_eq!(all.recovery_rate, Some(2.0 / 3.0));
+        assert_eq!(all.onset_rate, Some(1.0));
+
+        assert_eq!(transitions[1].key, "営業部");
+        assert_eq!(transitions[1].persistence_rate, Some(0.5));
+        assert_eq!(transitions[2].key, "総務部");
+        assert_eq!(transitions[2].respondents, 1);
+        assert!(transitions[2].is_suppressed());
+        assert_eq!(transitions[2].recovery_rate, None);
+    }
+}
@@ -0,0 +1,168 @@
+//! Arrow/Parquet形式での出力
+//!
+//! 分析担当者がPolarsやSpark等で直接読み込めるよう、判定結果を列指向の形式で書き出す。
+//! 列は次の順で固定する。列名・型を変更する場合は`SCHEMA_VERSION`を上げること。
+//!
+//! - `id`, `department`, `age_band`, `employment_type`, `tenure`（文字列）
+//! - `q_1`〜`q_57`（回答番号、未回答は欠損）
+//! - `method`（選定方法）, `score_a`, `score_b`, `score_c`
+//! - `ep_<尺度>`（素点換算表による評価点、18列）
+//! - `has_stress`
+//!
+//! 判定できない記録は点数以降の列を欠損とする。
+
+use std::sync::Arc;
+
+use arrow_array::builder::{BooleanBuilder, StringBuilder, UInt8Builder};
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_schema::{DataType, Field, Schema};
+
+use crate::{CheckRecord, CheckResult, Error, Scale, StressCriteria};
+
+/// 列構成の版
+pub const SCHEMA_VERSION: &str = "1";
+
+/// 列構成
+pub fn schema() -> Schema {
+    let mut fields = vec![Field::new("id", DataType::Utf8, false)];
+    for name in ["department", "age_band", "employment_type", "tenure"] {
+        fields.push(Field::new(name, DataType::Utf8, true));
+    }
+    for i in 1..=57 {
+        fields.push(Field::new(format!("q_{}", i), DataType::UInt8, true));
+    }
+    fields.push(Field::new("method", DataType::Utf8, false));
+    for name in ["score_a", "score_b", "score_c"] {
+        fields.push(Field::new(name, DataType::UInt8, true));
+    }
+    for scale in Scale::ALL {
+        fields.push(Field::new(
+            format!("ep_{}", scale.as_str()),
+            DataType::UInt8,
+            true,
+        ));
+    }
+    fields.push(Field::new("has_stress", DataType::Boolean, true));
+    Schema::new(fields).with_metadata(
+        [(
+            "stresscheck.schema_version".to_string(),
+            SCHEMA_VERSION.to_string(),
+        )]
+        .into(),
+    )
+}
+
+/// 受検記録を判定し、Arrowのレコードバッチに変換する
+pub fn to_record_batch(
+    records: &[CheckRecord],
+    criteria: StressCriteria,
+) -> Result<RecordBatch, Error> {
+    let results = records
+        .iter()
+        .map(|record| CheckResult::new(&record.answers, criteria).ok())
+        .collect::<Vec<_>>();
+
+    let strings = |value: &dyn Fn(&CheckRecord) -> Option<&str>| {
+        let mut builder = StringBuilder::new();
+        for record in records {
+            builder.append_option(value(record));
+        }
+        Arc::new(builder.finish()) as ArrayRef
+    };
+    let numbers = |value: &dyn Fn(usize) -> Option<u8>| {
+        let mut builder = UInt8Builder::new();
+        for index in 0..records.len() {
+            builder.append_option(value(index));
+        }
+        Arc::new(builder.finish()) as ArrayRef
+    };
+
+    let mut columns: Vec<ArrayRef> = vec![
+        strings(&|record| Some(record.id.as_str())),
+        strings(&|record| record.department.as_deref()),
+        strings(&|record| Some(record.demographics.as_ref()?.age_band?.as_str())),
+        strings(&|record| Some(record.demographics.as_ref()?.employment_type?.as_str())),
+        strings(&|record| Some(record.demographics.as_ref()?.tenure?.as_str())),
+    ];
+    for question in 0..57 {
+        columns.push(numbers(&|index| {
+            Some(records[index].answers.values()[question]).filter(|&value| value != 0)
+        }));
+    }
+    let method = match criteria {
+        StressCriteria::Sumup(_) => "sumup",
+        StressCriteria::Conversion(_) => "conversion",
+    };
+    columns.push(strings(&|_| Some(method)));
+    let score = |result: &CheckResult, domain: usize| match domain {
+        0 => result.scores.0,
+        1 => result.scores.1,
+        _ => result.scores.2,
+    };
+    for domain in 0..3 {
+        columns.push(numbers(&|index| {
+            results[index].as_ref().map(|result| score(result, domain))
+        }));
+    }
+    for scale in Scale::ALL {
+        columns.push(numbers(&|index| {
+            results[index]
+                .as_ref()
+                .and_then(|result| result.evaluation_points.get(&scale).copied())
+        }));
+    }
+    let mut has_stress = BooleanBuilder::new();
+    for result in &results {
+        has_stress.append_option(result.as_ref().map(|result| result.has_stress));
+    }
+    columns.push(Arc::new(has_stress.finish()));
+
+    RecordBatch::try_new(Arc::new(schema()), columns)
+        .map_err(|e| Error::ColumnarWriteError(e.to_string()))
+}
+
+/// レコードバッチをParquetファイルとして書き出す
+pub fn write_parquet<W>(writer: W, batch: &RecordBatch) -> Result<(), Error>
+where
+    W: std::io::Write + Send,
+{
+    let mut writer = parquet::arrow::ArrowWriter::try_new(writer, batch.schema(), None)
+        .map_err(|e| Error::ColumnarWriteError(e.to_string()))?;
+    writer
+        .write(batch)
+        .map_err(|e| Error::ColumnarWriteError(e.to_string()))?;
+    writer
+        .close()
+        .map_err(|e| Error::ColumnarWriteError(e.to_string()))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simulate::Population;
+    use crate::AnswerStore;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::UInt8Type;
+    use arrow_array::Array;
+
+    #[test]
+    fn test_to_record_batch() {
+        let mut records = Population::new(5, 2).generate();
+        records[4].answers = AnswerStore::default();
+        let batch = to_record_batch(&records, StressCriteria::SUMUP).unwrap();
+        assert_eq!(batch.num_rows(), 5);
+        assert_eq!(batch.num_columns(), 5 + 57 + 4 + 18 + 1);
+        assert_eq!(batch.schema().field(5).name(), "q_1");
+        let score_b = batch
+            .column_by_name("score_b")
+            .unwrap()
+            .as_primitive::<UInt8Type>();
+        assert!(score_b.is_valid(0));
+        assert!(score_b.is_null(4));
+
+        let mut output = vec![];
+        write_parquet(&mut output, &batch).unwrap();
+        assert_eq!(&output[..4], b"PAR1");
+    }
+}
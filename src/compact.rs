@@ -0,0 +1,100 @@
+//! 回答の短い文字列表現
+//!
+//! 57問の回答（1〜4）を1問2ビットに詰め、版と検査用の2バイトを付けて
+//! URL安全なBase64（パディングなし、24文字）で表す。QRコードやURLに埋め込み、
+//! ネットワークのないキオスク端末からサーバへ回答を受け渡すために用いる。
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+use crate::{AnswerStore, Error};
+
+/// 符号化の版
+pub const VERSION: u8 = 1;
+
+/// 回答を詰めた部分のバイト数
+const PACKED_LEN: usize = 15;
+
+/// 符号化後のバイト数（版 + 回答 + 検査用）
+const ENCODED_LEN: usize = 1 + PACKED_LEN + 2;
+
+fn checksum(value: &[u8]) -> [u8; 2] {
+    let digest = Sha256::digest(value);
+    [digest[0], digest[1]]
+}
+
+/// 回答を符号化する
+/// 全設問に回答していない場合は`Error::NotFullfilled`とする。
+pub fn encode(store: &AnswerStore) -> Result<String, Error> {
+    let values = store.values();
+    if values.contains(&0) {
+        return Err(Error::NotFullfilled);
+    }
+    let mut bytes = Vec::with_capacity(ENCODED_LEN);
+    bytes.push(VERSION);
+    let mut packed = [0u8; PACKED_LEN];
+    for (index, &value) in values.iter().enumerate() {
+        if !(1..=4).contains(&value) {
+            return Err(Error::IllegalAnswer);
+        }
+        packed[index / 4] |= (value - 1) << ((index % 4) * 2);
+    }
+    bytes.extend_from_slice(&packed);
+    let sum = checksum(&bytes);
+    bytes.extend_from_slice(&sum);
+    Ok(URL_SAFE_NO_PAD.encode(bytes))
+}
+
+/// 文字列から回答を復元する
+/// 形式・版・検査用バイトが一致しない場合は`Error::IllegalEncoding`とする。
+pub fn decode(value: &str) -> Result<AnswerStore, Error> {
+    let bytes = URL_SAFE_NO_PAD
+        .decode(value.trim())
+        .map_err(|_| Error::IllegalEncoding)?;
+    if bytes.len() != ENCODED_LEN || bytes[0] != VERSION {
+        return Err(Error::IllegalEncoding);
+    }
+    let (body, sum) = bytes.split_at(1 + PACKED_LEN);
+    if checksum(body) != sum {
+        return Err(Error::IllegalEncoding);
+    }
+    let packed = &body[1..];
+    let mut store = AnswerStore::default();
+    for index in 0..57 {
+        store.push(((packed[index / 4] >> ((index % 4) * 2)) & 0b11) + 1)?;
+    }
+    // 57問分の2ビットを詰めた残りのビットは0でなければならない
+    if packed[PACKED_LEN - 1] >> 2 != 0 {
+        return Err(Error::IllegalEncoding);
+    }
+    Ok(store)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mut store = AnswerStore::default();
+        for i in 0..57 {
+            store.push(i % 4 + 1).unwrap();
+        }
+        let value = encode(&store).unwrap();
+        assert_eq!(value.len(), 24);
+        assert_eq!(decode(&value).unwrap().values(), store.values());
+
+        let mut tampered = value.into_bytes();
+        tampered[5] = if tampered[5] == b'A' { b'B' } else { b'A' };
+        assert!(matches!(
+            decode(&String::from_utf8(tampered).unwrap()),
+            Err(Error::IllegalEncoding)
+        ));
+        assert!(matches!(decode("!!"), Err(Error::IllegalEncoding)));
+        assert!(matches!(
+            encode(&AnswerStore::default()),
+            Err(Error::NotFullfilled)
+        ));
+    }
+}
@@ -0,0 +1,186 @@
+//! 実施回の間の集団比較
+//!
+//! 前回と今回の集団分析を集団ごとに比べ、尺度の平均の差に加えて効果量（Cohen's d）を
+//! 示す。人数の多い集団ではわずかな平均の差でも目立つため、差の大きさを効果量の目安で
+//! 判断できるようにする。
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use crate::group::{Dimension, GroupAnalysis};
+use crate::Scale;
+
+/// 効果量の大きさの目安（Cohenの基準）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Magnitude {
+    /// |d| < 0.2
+    Negligible,
+    /// 0.2 ≦ |d| < 0.5
+    Small,
+    /// 0.5 ≦ |d| < 0.8
+    Medium,
+    /// 0.8 ≦ |d|
+    Large,
+}
+
+impl Magnitude {
+    pub fn from_effect_size(d: f64) -> Self {
+        match d.abs() {
+            d if d < 0.2 => Magnitude::Negligible,
+            d if d < 0.5 => Magnitude::Small,
+            d if d < 0.8 => Magnitude::Medium,
+            _ => Magnitude::Large,
+        }
+    }
+}
+
+/// 尺度の変化
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScaleChange {
+    /// 前回の平均
+    pub previous_mean: f64,
+    /// 今回の平均
+    pub current_mean: f64,
+    /// 平均の差（今回−前回）
+    pub delta: f64,
+    /// 効果量。両方の回とも素点にばらつきがない場合は`None`
+    pub effect_size: Option<f64>,
+    pub magnitude: Option<Magnitude>,
+}
+
+/// 集団ごとの比較結果
+/// いずれかの回の人数が最小人数に満たない集団は`scales`が`None`となる。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupComparison {
+    /// 集団名
+    pub key: String,
+    /// 前回の受検者数
+    pub previous_respondents: usize,
+    /// 今回の受検者数
+    pub current_respondents: usize,
+    pub scales: Option<BTreeMap<Scale, ScaleChange>>,
+}
+
+impl GroupComparison {
+    /// 少人数のため比較結果が秘匿されているか
+    pub fn is_suppressed(&self) -> bool {
+        self.scales.is_none()
+    }
+}
+
+fn mean_and_variance(values: &[f64]) -> (f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = if values.len() > 1 {
+        values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    } else {
+        0.0
+    };
+    (mean, variance)
+}
+
+/// 2群の平均の差と効果量（プールした標準偏差によるCohen's d）
+pub fn scale_change(previous: &[f64], current: &[f64]) -> ScaleChange {
+    let (previous_mean, previous_variance) = mean_and_variance(previous);
+    let (current_mean, current_variance) = mean_and_variance(current);
+    let (n1, n2) = (previous.len() as f64, current.len() as f64);
+    let pooled =
+        (((n1 - 1.0) * previous_variance + (n2 - 1.0) * current_variance) / (n1 + n2 - 2.0)).sqrt();
+    let delta = current_mean - previous_mean;
+    let effect_size = (pooled.is_finite() && pooled > 0.0).then(|| delta / pooled);
+    ScaleChange {
+        previous_mean,
+        current_mean,
+        delta,
+        effect_size,
+        magnitude: effect_size.map(Magnitude::from_effect_size),
+    }
+}
+
+/// 前回・今回の集団分析を分類軸の集団ごとに比較する
+/// 秘匿の最小人数は今回の集団分析の設定に従う。
+pub fn compare(
+    previous: &GroupAnalysis,
+    current: &GroupAnalysis,
+    dimension: Dimension,
+) -> Vec<GroupComparison> {
+    let min_group_size = current.min_group_size().max(1);
+    let before = previous.scale_values(dimension);
+    let after = current.scale_values(dimension);
+    let keys = before.keys().chain(after.keys()).collect::<BTreeSet<_>>();
+    keys.into_iter()
+        .map(|key| {
+            let empty = vec![];
+            let before = before.get(key).unwrap_or(&empty);
+            let after = after.get(key).unwrap_or(&empty);
+            let shown = before.len() >= min_group_size && after.len() >= min_group_size;
+            let scales = shown.then(|| {
+                Scale::ALL
+                    .iter()
+                    .enumerate()
+                    .map(|(index, &scale)| {
+                        let values = |entries: &[[u8; 18]]| {
+                            entries
+                                .iter()
+                                .map(|scales| scales[index] as f64)
+                                .collect::<Vec<_>>()
+                        };
+                        (scale, scale_change(&values(before), &values(after)))
+                    })
+                    .collect()
+            });
+            GroupComparison {
+                key: key.to_string(),
+                previous_respondents: before.len(),
+                current_respondents: after.len(),
+                scales,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simulate::Population;
+    use crate::StressCriteria;
+
+    #[test]
+    fn test_scale_change() {
+        let change = scale_change(&[2.0, 4.0, 6.0], &[3.0, 5.0, 7.0]);
+        assert_eq!(change.delta, 1.0);
+        assert_eq!(change.effect_size, Some(0.5));
+        assert_eq!(change.magnitude, Some(Magnitude::Medium));
+
+        let change = scale_change(&[3.0, 3.0], &[3.0, 3.0]);
+        assert_eq!(change.effect_size, None);
+        assert_eq!(Magnitude::from_effect_size(-0.1), Magnitude::Negligible);
+        assert_eq!(Magnitude::from_effect_size(-1.2), Magnitude::Large);
+    }
+
+    #[test]
+    fn test_compare() {
+        let previous = Population::new(200, 2).with_seed(1).generate();
+        let mut current = Population::new(200, 2).with_seed(2).generate();
+        current.truncate(105);
+        let comparisons = compare(
+            &GroupAnalysis::new(&previous, StressCriteria::SUMUP),
+            &GroupAnalysis::new(&current, StressCriteria::SUMUP).with_min_group_size(50),
+            Dimension::Department,
+        );
+        assert_eq!(comparisons.len(), 2);
+        assert!(comparisons
+            .iter()
+            .all(|comparison| comparison.previous_respondents > 50));
+        let shown = comparisons
+            .iter()
+            .filter(|comparison| !comparison.is_suppressed())
+            .collect::<Vec<_>>();
+        assert!(!shown.is_empty());
+        let change = &shown[0].scales.as_ref().unwrap()[&Scale::WorkControl];
+        assert!((change.delta - (change.current_mean - change.previous_mean)).abs() < 1e-9);
+        assert!(change.effect_size.is_some());
+    }
+}
@@ -0,0 +1,181 @@
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, ErrorKind, Write};
+use std::path::Path;
+
+use crate::Error;
+
+/// 圧縮形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    /// .gz
+    Gzip,
+    /// .zst
+    Zstd,
+}
+
+impl Compression {
+    /// 拡張子から圧縮形式を判定する
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// 有効なfeatureで扱える形式か
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Compression::None => true,
+            Compression::Gzip => cfg!(feature = "gzip"),
+            Compression::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+}
+
+/// 入力ファイルを開く
+/// `.gz`・`.zst`の場合は透過的に展開する。対応するfeatureが無効な場合は`Error::UnsupportedCompression`となる。
+pub fn open_input<P: AsRef<Path>>(path: P) -> Result<Box<dyn BufRead>, Error> {
+    let path = path.as_ref();
+    let compression = Compression::from_path(path);
+    if !compression.is_supported() {
+        return Err(Error::UnsupportedCompression(compression));
+    }
+    let file = File::open(path)?;
+    match compression {
+        Compression::None => Ok(Box::new(BufReader::new(file))),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(BufReader::new(flate2::read::MultiGzDecoder::new(
+            file,
+        )))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok(Box::new(BufReader::new(zstd::stream::read::Decoder::new(
+            file,
+        )?))),
+        #[allow(unreachable_patterns)]
+        compression => Err(Error::UnsupportedCompression(compression)),
+    }
+}
+
+/// 出力ファイルを作成する
+/// `.gz`・`.zst`の場合は圧縮して書き出す。
+pub fn create_output<P: AsRef<Path>>(path: P) -> Result<Box<dyn Write>, Error> {
+    open_output(path, |path| File::create(path))
+}
+
+/// 出力ファイルに追記する。存在しない場合は作成する
+/// `length`を指定した場合は、そのバイト数に切り詰めてから追記する。中断した処理を再開する際に、
+/// 進捗を保存した後に書き出した分を取り除くために用いる。ファイルが`length`より短い場合はエラーとする。
+///
+/// 追記は非圧縮の出力のみ対応し、`.gz`・`.zst`の場合は`Error::UnsupportedCompression`となる。
+/// 中断で最後の圧縮単位が途中で切れたファイルに新しい圧縮単位を追記すると、
+/// 切れた圧縮単位より後を展開できなくなるため。
+pub fn append_output<P: AsRef<Path>>(
+    path: P,
+    length: Option<u64>,
+) -> Result<Box<dyn Write>, Error> {
+    let compression = Compression::from_path(path.as_ref());
+    if compression != Compression::None {
+        return Err(Error::UnsupportedCompression(compression));
+    }
+    open_output(path, |path| {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        if let Some(length) = length {
+            if file.metadata()?.len() < length {
+                return Err(std::io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("{}は進捗を保存した時点より短くなっています", path.display()),
+                ));
+            }
+            file.set_len(length)?;
+        }
+        Ok(file)
+    })
+}
+
+fn open_output<P, F>(path: P, open: F) -> Result<Box<dyn Write>, Error>
+where
+    P: AsRef<Path>,
+    F: FnOnce(&Path) -> std::io::Result<File>,
+{
+    let path = path.as_ref();
+    let compression = Compression::from_path(path);
+    if !compression.is_supported() {
+        return Err(Error::UnsupportedCompression(compression));
+    }
+    let file = open(path)?;
+    match compression {
+        Compression::None => Ok(Box::new(BufWriter::new(file))),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => Ok(Box::new(flate2::write::GzEncoder::new(
+            BufWriter::new(file),
+            flate2::Compression::default(),
+        ))),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => Ok(Box::new(
+            zstd::stream::write::Encoder::new(BufWriter::new(file), 0)?.auto_finish(),
+        )),
+        #[allow(unreachable_patterns)]
+        compression => Err(Error::UnsupportedCompression(compression)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(Compression::from_path("a.csv"), Compression::None);
+        assert_eq!(Compression::from_path("a.csv.gz"), Compression::Gzip);
+        assert_eq!(Compression::from_path("a.jsonl.zst"), Compression::Zstd);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = std::env::temp_dir().join("stresscheck_test_compress");
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in ["plain.csv", "gzip.csv.gz", "zstd.csv.zst"] {
+            let path = dir.join(name);
+            if !Compression::from_path(&path).is_supported() {
+                assert!(matches!(
+                    create_output(&path),
+                    Err(Error::UnsupportedCompression(_))
+                ));
+                continue;
+            }
+            let mut output = create_output(&path).unwrap();
+            output.write_all(b"id,q_1\n").unwrap();
+            drop(output);
+            let expected = match Compression::from_path(&path) {
+                Compression::None => {
+                    let mut output = append_output(&path, None).unwrap();
+                    output.write_all(b"1,4\n2,3\n").unwrap();
+                    drop(output);
+                    // 進捗を保存した時点（1行目まで）に切り詰めてから追記する
+                    assert!(append_output(&path, Some(100)).is_err());
+                    let mut output = append_output(&path, Some(11)).unwrap();
+                    output.write_all(b"2,1\n").unwrap();
+                    "id,q_1\n1,4\n2,1\n"
+                }
+                _ => {
+                    assert!(matches!(
+                        append_output(&path, None),
+                        Err(Error::UnsupportedCompression(_))
+                    ));
+                    "id,q_1\n"
+                }
+            };
+            let mut value = String::new();
+            open_input(&path)
+                .unwrap()
+                .read_to_string(&mut value)
+                .unwrap();
+            assert_eq!(value, expected);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
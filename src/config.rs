@@ -0,0 +1,244 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::group::DEFAULT_MIN_GROUP_SIZE;
+use crate::header::{is_column, HeaderAliases};
+use crate::locale::Locale;
+use crate::{Error, StressCriteria};
+
+/// 対応している調査票の版
+pub const QUESTIONNAIRE_VERSION: &str = "57";
+
+/// 高ストレス者の選定方法（`sumup`・`conversion`）を指定する環境変数
+pub const CRITERIA_ENV: &str = "STRESSCHECK_CRITERIA";
+/// 集団分析の最小人数を指定する環境変数
+pub const MIN_GROUP_SIZE_ENV: &str = "STRESSCHECK_MIN_GROUP_SIZE";
+/// 同意の取り扱い（`explicit`・`not_shared`）を指定する環境変数
+pub const CONSENT_ENV: &str = "STRESSCHECK_CONSENT";
+/// 伏字にする列（カンマ区切り）を指定する環境変数
+pub const REDACT_COLUMNS_ENV: &str = "STRESSCHECK_REDACT_COLUMNS";
+
+/// 事業者への結果提供に関する同意の取り扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentHandling {
+    /// 結果通知後に本人から個別に同意を取得した場合のみ提供する
+    #[default]
+    Explicit,
+    /// 事業者へは提供しない
+    NotShared,
+}
+
+/// 実施規程に基づく実施設定
+/// 衛生委員会で審議した内容を記録し、各コマンドで同じ基準を適用するために用いる。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 調査票の版
+    pub questionnaire_version: String,
+    /// 高ストレス者の選定方法
+    pub criteria: StressCriteria,
+    /// 集団分析の最小人数
+    pub min_group_size: usize,
+    /// 同意の取り扱い
+    pub consent: ConsentHandling,
+    /// CSV出力で伏字にする列
+    pub redact_columns: Vec<String>,
+    /// CSV出力で鍵付きハッシュによる仮名にする列（鍵は環境変数`STRESSCHECK_PSEUDONYM_KEY`）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pseudonymize_columns: Vec<String>,
+    /// 帳票の書式
+    pub locale: Locale,
+    /// 一括判定のCSVの列名の別名（別名→列名）。組み込みの別名に追加する
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub header_aliases: BTreeMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            questionnaire_version: QUESTIONNAIRE_VERSION.to_string(),
+            criteria: StressCriteria::default(),
+            min_group_size: DEFAULT_MIN_GROUP_SIZE,
+            consent: ConsentHandling::default(),
+            redact_columns: vec![],
+            pseudonymize_columns: vec![],
+            locale: Locale::default(),
+            header_aliases: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// TOML文字列から設定を読み込む
+    pub fn from_toml_str(value: &str) -> Result<Self, Error> {
+        let config: Config = toml::from_str(value).map_err(Error::ConfigReadError)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// TOMLファイルから設定を読み込む
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_toml_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// 設定ファイル（省略時は既定値）を読み込み、環境変数の指定で上書きする
+    pub fn resolve<P: AsRef<Path>>(path: Option<P>) -> Result<Self, Error> {
+        let config = match path {
+            Some(path) => Self::load(path)?,
+            None => Self::default(),
+        };
+        config.with_env_overrides(|name| std::env::var(name).ok())
+    }
+
+    /// `STRESSCHECK_*`の環境変数で指定された項目を上書きする
+    /// 設定ファイルを置けない環境（コンテナなど）で用いる。空の値は指定なしとみなす。
+    pub fn with_env_overrides<F>(mut self, var: F) -> Result<Self, Error>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let var = |name| var(name).filter(|value: &String| !value.trim().is_empty());
+        if let Some(value) = var(CRITERIA_ENV) {
+            self.criteria = match value.trim() {
+                "sumup" => StressCriteria::SUMUP,
+                "conversion" => StressCriteria::CONVERSION,
+                _ => return Err(Error::IllegalConfig),
+            };
+        }
+        if let Some(value) = var(MIN_GROUP_SIZE_ENV) {
+            self.min_group_size = value.trim().parse().map_err(|_| Error::IllegalConfig)?;
+        }
+        if let Some(value) = var(CONSENT_ENV) {
+            self.consent = match value.trim() {
+                "explicit" => ConsentHandling::Explicit,
+                "not_shared" => ConsentHandling::NotShared,
+                _ => return Err(Error::IllegalConfig),
+            };
+        }
+        if let Some(value) = var(REDACT_COLUMNS_ENV) {
+            self.redact_columns = value
+                .split(',')
+                .map(str::trim)
+                .filter(|column| !column.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// TOML文字列に変換する
+    pub fn to_toml_string(&self) -> Result<String, Error> {
+        toml::to_string(self).map_err(Error::ConfigWriteError)
+    }
+
+    /// 組み込みの別名に`header_aliases`を加えた別名表
+    pub fn header_aliases(&self) -> HeaderAliases {
+        HeaderAliases::default().with_aliases(&self.header_aliases)
+    }
+
+    /// 設定値を検証する
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.questionnaire_version != QUESTIONNAIRE_VERSION
+            || self.min_group_size == 0
+            || !self.header_aliases.values().all(|column| is_column(column))
+        {
+            return Err(Error::IllegalConfig);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_from_toml_str() {
+        let config = Config::from_toml_str(
+            r#"
+min_group_size = 20
+
+[criteria]
+method = "conversion"
+b = 12
+ac = 26
+b_with_ac = 17
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.questionnaire_version, "57");
+        assert_eq!(config.criteria, StressCriteria::CONVERSION);
+        assert_eq!(config.min_group_size, 20);
+        assert_eq!(config.consent, ConsentHandling::Explicit);
+
+        assert!(matches!(
+            Config::from_toml_str("questionnaire_version = \"80\""),
+            Err(Error::IllegalConfig)
+        ));
+        assert!(matches!(
+            Config::from_toml_str("[criteria]\nmethod = \"unknown\""),
+            Err(Error::ConfigReadError(_))
+        ));
+
+        let config = Config::from_toml_str("[header_aliases]\n\"職員コード\" = \"id\"").unwrap();
+        assert_eq!(
+            config.header_aliases().resolve("職員コード").as_deref(),
+            Some("id")
+        );
+        assert!(matches!(
+            Config::from_toml_str("[header_aliases]\n\"職員コード\" = \"employee\""),
+            Err(Error::IllegalConfig)
+        ));
+    }
+
+    #[test]
+    fn test_with_env_overrides() {
+        let vars = [
+            (CRITERIA_ENV, "conversion"),
+            (MIN_GROUP_SIZE_ENV, "20"),
+            (CONSENT_ENV, "not_shared"),
+            (REDACT_COLUMNS_ENV, "email, name"),
+        ];
+        let var = |name: &str| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        let config = Config::default().with_env_overrides(var).unwrap();
+        assert_eq!(config.criteria, StressCriteria::CONVERSION);
+        assert_eq!(config.min_group_size, 20);
+        assert_eq!(config.consent, ConsentHandling::NotShared);
+        assert_eq!(config.redact_columns, vec!["email", "name"]);
+
+        let config = Config::default()
+            .with_env_overrides(|_| Some(String::new()))
+            .unwrap();
+        assert_eq!(config, Config::default());
+        assert!(matches!(
+            Config::default().with_env_overrides(|name| {
+                (name == MIN_GROUP_SIZE_ENV).then(|| "0".to_string())
+            }),
+            Err(Error::IllegalConfig)
+        ));
+        assert!(matches!(
+            Config::default()
+                .with_env_overrides(|name| (name == CRITERIA_ENV).then(|| "other".to_string())),
+            Err(Error::IllegalConfig)
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let config = Config {
+            consent: ConsentHandling::NotShared,
+            redact_columns: vec!["email".to_string()],
+            locale: Locale::JAPANESE,
+            ..Config::default()
+        };
+        let value = config.to_toml_string().unwrap();
+        assert_eq!(Config::from_toml_str(&value).unwrap(), config);
+    }
+}
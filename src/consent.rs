@@ -0,0 +1,138 @@
+//! 事業者への結果提供に関する同意
+//!
+//! 結果を事業者へ提供するには、結果通知後に本人から個別に同意を得る必要がある。
+//! 同意の有無・取得日時・範囲を受検記録に付して保存し、事業者向けの出力から
+//! 同意のない記録を除外するために用いる。
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::config::ConsentHandling;
+use crate::CheckRecord;
+
+/// 同意の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentStatus {
+    /// 同意
+    Granted,
+    /// 不同意
+    Denied,
+}
+
+/// 同意の範囲
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsentScope {
+    /// 判定結果を事業者へ提供する
+    ShareWithEmployer,
+    /// 個人が特定されない形での統計・研究への利用
+    SecondaryUse,
+}
+
+/// 本人の同意の記録
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Consent {
+    pub status: ConsentStatus,
+    /// 同意・不同意の意思表示を受けた日時
+    pub timestamp: DateTime<FixedOffset>,
+    pub scope: ConsentScope,
+}
+
+impl Consent {
+    pub fn granted(timestamp: DateTime<FixedOffset>, scope: ConsentScope) -> Self {
+        Self {
+            status: ConsentStatus::Granted,
+            timestamp,
+            scope,
+        }
+    }
+
+    pub fn denied(timestamp: DateTime<FixedOffset>, scope: ConsentScope) -> Self {
+        Self {
+            status: ConsentStatus::Denied,
+            timestamp,
+            scope,
+        }
+    }
+
+    /// 指定の範囲について同意しているか
+    pub fn allows(&self, scope: ConsentScope) -> bool {
+        self.status == ConsentStatus::Granted && self.scope == scope
+    }
+}
+
+impl CheckRecord {
+    /// 結果の事業者への提供に同意しているか
+    /// 同意の記録がない場合は同意していないものとして扱う。
+    pub fn is_shared_with_employer(&self) -> bool {
+        self.consent
+            .iter()
+            .any(|consent| consent.allows(ConsentScope::ShareWithEmployer))
+    }
+}
+
+/// 事業者向けの出力に含めてよい記録のみを残す
+/// 実施設定で事業者へ提供しないとしている場合は全て除外する。
+pub fn filter_shared(records: &[CheckRecord], handling: ConsentHandling) -> Vec<&CheckRecord> {
+    match handling {
+        ConsentHandling::Explicit => records
+            .iter()
+            .filter(|record| record.is_shared_with_employer())
+            .collect(),
+        ConsentHandling::NotShared => vec![],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AnswerStore;
+
+    fn record(id: &str, consent: Option<Consent>) -> CheckRecord {
+        CheckRecord {
+            id: id.to_string(),
+            department: None,
+            demographics: None,
+            consent,
+            answers: AnswerStore::default(),
+            amendments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_filter_shared() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-06-01T10:00:00+09:00").unwrap();
+        let records = vec![
+            record(
+                "1",
+                Some(Consent::granted(timestamp, ConsentScope::ShareWithEmployer)),
+            ),
+            record(
+                "2",
+                Some(Consent::denied(timestamp, ConsentScope::ShareWithEmployer)),
+            ),
+            record(
+                "3",
+                Some(Consent::granted(timestamp, ConsentScope::SecondaryUse)),
+            ),
+            record("4", None),
+        ];
+        let shared = filter_shared(&records, ConsentHandling::Explicit);
+        assert_eq!(shared.len(), 1);
+        assert_eq!(shared[0].id, "1");
+        assert!(filter_shared(&records, ConsentHandling::NotShared).is_empty());
+    }
+
+    #[test]
+    fn test_serialize() {
+        let timestamp = DateTime::parse_from_rfc3339("2024-06-01T10:00:00+09:00").unwrap();
+        let consent = Consent::granted(timestamp, ConsentScope::ShareWithEmployer);
+        let value = serde_json::to_string(&consent).unwrap();
+        assert_eq!(
+            value,
+            r#"{"status":"granted","timestamp":"2024-06-01T10:00:00+09:00","scope":"share_with_employer"}"#
+        );
+        assert_eq!(serde_json::from_str::<Consent>(&value).unwrap(), consent);
+    }
+}
@@ -0,0 +1,220 @@
+//! 事業者向けダッシュボードのデータ
+//!
+//! 集団分析の結果を、部署ごとの健康リスク・高ストレス者率の階級・受検率にまとめたJSONとして
+//! 出力する。高ストレス者率は正確な人数が逆算されないよう階級でのみ示し、最小人数に満たない
+//! 部署は健康リスク・高ストレス者率とも示さない。
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+
+use crate::group::{Dimension, GroupAnalysis, HealthRisk};
+use crate::roster::{ResponseRate, Roster};
+use crate::wellbeing::WellbeingIndex;
+
+/// 高ストレス者率の階級の幅（%）
+pub const RATE_BAND_WIDTH: u32 = 10;
+
+/// 高ストレス者率の階級（`lower`%以上`upper`%未満。最上位の階級は100%を含む）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RateBand {
+    pub lower: u32,
+    pub upper: u32,
+}
+
+impl RateBand {
+    /// 高ストレス者率（0〜1）の属する階級
+    pub fn from_rate(rate: f64) -> Self {
+        // 0.3 * 100.0 が 29.999… となり下の階級に入らないよう誤差を許容する
+        let percent = ((rate * 100.0 + 1e-9).floor() as u32).min(99);
+        let lower = percent / RATE_BAND_WIDTH * RATE_BAND_WIDTH;
+        Self {
+            lower,
+            upper: lower + RATE_BAND_WIDTH,
+        }
+    }
+}
+
+/// 部署ごとの値
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DepartmentTile {
+    /// 部署名
+    pub department: String,
+    /// 受検者数
+    pub respondents: usize,
+    /// 少人数のため健康リスク・高ストレス者率を秘匿したか
+    pub suppressed: bool,
+    pub health_risk: Option<HealthRisk>,
+    pub high_stress_rate_band: Option<RateBand>,
+    /// 受検率（名簿を指定した場合のみ）
+    pub response_rate: Option<ResponseRate>,
+    /// 領域ごとのウェルビーイング指数（集団分析で`with_wellbeing_index`を指定した場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wellbeing_index: Option<WellbeingIndex>,
+}
+
+/// 事業者向けダッシュボードのデータ
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DashboardFeed {
+    /// 作成日時
+    pub generated_at: DateTime<FixedOffset>,
+    /// 集計値を表示する最小人数
+    pub min_group_size: usize,
+    /// 受検者数
+    pub respondents: usize,
+    /// 名簿全体の受検率（名簿を指定した場合のみ）
+    pub response_rate: Option<ResponseRate>,
+    pub departments: Vec<DepartmentTile>,
+}
+
+impl DashboardFeed {
+    /// 集団分析から作成する
+    /// `completed`は受検済みのトークン。受検率は名簿を指定した場合のみ求める。
+    pub fn new(
+        analysis: &GroupAnalysis,
+        roster: Option<&Roster>,
+        completed: &HashSet<String>,
+        generated_at: DateTime<FixedOffset>,
+    ) -> Self {
+        let mut response_rates: BTreeMap<String, ResponseRate> = roster
+            .map(|roster| {
+                roster
+                    .response_rates(completed)
+                    .into_iter()
+                    .map(|rate| (rate.department.clone(), rate))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut departments = analysis
+            .summarize(Dimension::Department)
+            .into_iter()
+            .map(|summary| DepartmentTile {
+                suppressed: summary.is_suppressed(),
+                respondents: summary.respondents,
+                health_risk: summary.health_risk,
+                high_stress_rate_band: summary.high_stress_rate.map(RateBand::from_rate),
+                response_rate: response_rates.remove(&summary.key),
+                wellbeing_index: summary.wellbeing_index,
+                department: summary.key,
+            })
+            .collect::<Vec<_>>();
+        // 名簿にあって受検者のいない部署も受検率を示す
+        departments.extend(response_rates.into_values().map(|rate| DepartmentTile {
+            department: rate.department.clone(),
+            respondents: 0,
+            suppressed: true,
+            health_risk: None,
+            high_stress_rate_band: None,
+            response_rate: Some(rate),
+            wellbeing_index: None,
+        }));
+        departments.sort_by(|a, b| a.department.cmp(&b.department));
+        Self {
+            generated_at,
+            min_group_size: analysis.min_group_size(),
+            respondents: analysis.respondents(),
+            response_rate: roster.map(|roster| roster.total_response_rate(completed)),
+            departments,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::roster::RosterEntry;
+    use crate::simulate::Population;
+    use crate::StressCriteria;
+
+    #[test]
+    fn test_rate_band() {
+        assert_eq!(
+            RateBand::from_rate(0.0),
+            RateBand {
+                lower: 0,
+                upper: 10
+            }
+        );
+        assert_eq!(
+            RateBand::from_rate(0.3),
+            RateBand {
+                lower: 30,
+                upper: 40
+            }
+        );
+        assert_eq!(
+            RateBand::from_rate(0.299),
+            RateBand {
+                lower: 20,
+                upper: 30
+            }
+        );
+        assert_eq!(
+            RateBand::from_rate(1.0),
+            RateBand {
+                lower: 90,
+                upper: 100
+            }
+        );
+    }
+
+    #[test]
+    fn test_dashboard_feed() {
+        let records = Population::new(300, 3).with_seed(4).generate();
+        let mut roster = Roster {
+            entries: records
+                .iter()
+                .map(|record| RosterEntry {
+                    department: record.department.clone(),
+                    ..RosterEntry::new(&record.id)
+                })
+                .collect(),
+        };
+        roster.entries.push(RosterEntry {
+            department: Some(crate::group_code::GroupCode::new("新設部").unwrap()),
+            ..RosterEntry::new("absent")
+        });
+        let completed = records.iter().map(|record| record.id.clone()).collect();
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP).with_min_group_size(50);
+        let generated_at = DateTime::parse_from_rfc3339("2024-08-01T09:00:00+09:00").unwrap();
+        let feed = DashboardFeed::new(&analysis, Some(&roster), &completed, generated_at);
+
+        assert_eq!(feed.respondents, 300);
+        assert_eq!(feed.response_rate.as_ref().unwrap().expected, 301);
+        let summaries = analysis.summarize(Dimension::Department);
+        assert_eq!(feed.departments.len(), summaries.len() + 1);
+        for summary in &summaries {
+            let tile = feed
+                .departments
+                .iter()
+                .find(|tile| tile.department == summary.key)
+                .unwrap();
+            assert_eq!(tile.suppressed, summary.respondents < 50);
+            assert_eq!(tile.health_risk.is_none(), tile.suppressed);
+            assert_eq!(tile.high_stress_rate_band.is_none(), tile.suppressed);
+            assert_eq!(
+                tile.response_rate.as_ref().unwrap().completed,
+                tile.respondents
+            );
+        }
+        let new_department = feed
+            .departments
+            .iter()
+            .find(|tile| tile.department == "新設部")
+            .unwrap();
+        assert_eq!(new_department.respondents, 0);
+        assert!(new_department.suppressed);
+        assert_eq!(
+            new_department
+                .response_rate
+                .as_ref()
+                .unwrap()
+                .completion_rate,
+            Some(0.0)
+        );
+
+        let json = serde_json::to_value(&feed).unwrap();
+        assert!(!json.to_string().contains("high_stress\""));
+    }
+}
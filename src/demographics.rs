@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+/// 年齢階層
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AgeBand {
+    /// 29歳以下
+    #[serde(rename = "under_30", alias = "under30")]
+    Under30,
+    /// 30〜39歳
+    Thirties,
+    /// 40〜49歳
+    Forties,
+    /// 50〜59歳
+    Fifties,
+    /// 60歳以上
+    SixtiesAndOver,
+}
+
+impl AgeBand {
+    pub const ALL: [AgeBand; 5] = [
+        AgeBand::Under30,
+        AgeBand::Thirties,
+        AgeBand::Forties,
+        AgeBand::Fifties,
+        AgeBand::SixtiesAndOver,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AgeBand::Under30 => "under_30",
+            AgeBand::Thirties => "thirties",
+            AgeBand::Forties => "forties",
+            AgeBand::Fifties => "fifties",
+            AgeBand::SixtiesAndOver => "sixties_and_over",
+        }
+    }
+}
+
+/// 雇用形態
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmploymentType {
+    /// 正社員
+    Regular,
+    /// 契約社員
+    Contract,
+    /// 派遣社員
+    Dispatched,
+    /// パート・アルバイト
+    PartTime,
+    /// その他
+    Other,
+}
+
+impl EmploymentType {
+    pub const ALL: [EmploymentType; 5] = [
+        EmploymentType::Regular,
+        EmploymentType::Contract,
+        EmploymentType::Dispatched,
+        EmploymentType::PartTime,
+        EmploymentType::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmploymentType::Regular => "regular",
+            EmploymentType::Contract => "contract",
+            EmploymentType::Dispatched => "dispatched",
+            EmploymentType::PartTime => "part_time",
+            EmploymentType::Other => "other",
+        }
+    }
+}
+
+/// 勤続年数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Tenure {
+    /// 1年未満
+    UnderOneYear,
+    /// 1年以上3年未満
+    OneToThreeYears,
+    /// 3年以上10年未満
+    ThreeToTenYears,
+    /// 10年以上
+    TenYearsAndOver,
+}
+
+impl Tenure {
+    pub const ALL: [Tenure; 4] = [
+        Tenure::UnderOneYear,
+        Tenure::OneToThreeYears,
+        Tenure::ThreeToTenYears,
+        Tenure::TenYearsAndOver,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Tenure::UnderOneYear => "under_one_year",
+            Tenure::OneToThreeYears => "one_to_three_years",
+            Tenure::ThreeToTenYears => "three_to_ten_years",
+            Tenure::TenYearsAndOver => "ten_years_and_over",
+        }
+    }
+}
+
+/// 回答者属性
+/// 集団分析で層別に高ストレス者率を算出するために用いる。いずれの項目も任意。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Demographics {
+    pub age_band: Option<AgeBand>,
+    pub employment_type: Option<EmploymentType>,
+    pub tenure: Option<Tenure>,
+}
+
+impl Demographics {
+    /// 全ての項目が未設定か
+    pub fn is_empty(&self) -> bool {
+        self.age_band.is_none() && self.employment_type.is_none() && self.tenure.is_none()
+    }
+}
@@ -0,0 +1,372 @@
+//! 面接指導用の資料
+//!
+//! 高ストレス者の面接指導を担当する医師に渡す資料を受検者ごとに作る。
+//! 尺度ごとにまとめた設問の回答、前回の実施回からの変化、面接指導の記録
+//! （厚生労働省の「面接指導結果報告書及び就業上の措置に係る意見書」の様式例の項目）の記入欄を持つ。
+
+use std::io::Write;
+
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::repository::{ResultRepository, StoredResult};
+use crate::{CheckResult, Error, Scale, QUESTIONS};
+
+/// 前回の実施回を探す範囲（実施回の数）
+const HISTORY_LIMIT: usize = 10;
+
+/// 満足度の設問番号（尺度に属さない）
+const SATISFACTION_QUESTIONS: [u8; 2] = [56, 57];
+
+/// 設問ごとの回答
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemAnswer {
+    /// 設問番号
+    pub question: u8,
+    pub text: String,
+    /// 回答番号
+    pub answer: u8,
+    /// 回答の選択肢の文言
+    pub choice: Option<String>,
+}
+
+impl ItemAnswer {
+    fn new(question: u8, answers: &[u8]) -> Self {
+        let master = QUESTIONS.question(question as u32);
+        let answer = answers
+            .get(question as usize - 1)
+            .copied()
+            .unwrap_or_default();
+        Self {
+            question,
+            text: master
+                .as_ref()
+                .map(|master| master.text.clone())
+                .unwrap_or_default(),
+            answer,
+            choice: master.and_then(|master| {
+                master
+                    .scores
+                    .iter()
+                    .find(|score| score.score == answer)
+                    .map(|score| score.text.clone())
+            }),
+        }
+    }
+}
+
+/// 尺度ごとの回答
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScaleSection {
+    pub scale: Scale,
+    /// 評価点
+    pub evaluation_point: u8,
+    /// 前回の評価点
+    pub previous_evaluation_point: Option<u8>,
+    pub items: Vec<ItemAnswer>,
+}
+
+/// 前回の実施回の結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PreviousPeriod {
+    pub period: String,
+    /// 領域Ａ・Ｂ・Ｃの点数
+    pub scores: (u8, u8, u8),
+    /// 高ストレス者に該当したか
+    pub has_stress: bool,
+}
+
+/// 面接指導の記録
+/// 面接指導の後に医師が記入する。
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InterviewRecord {
+    /// 面接指導の実施年月日
+    pub interviewed_on: Option<NaiveDate>,
+    /// 面接指導を実施した医師
+    pub physician: Option<String>,
+    /// 勤務の状況（労働時間、労働時間以外の要因）
+    pub working_conditions: Option<String>,
+    /// 心理的な負担の状況
+    pub psychological_burden: Option<String>,
+    /// その他の心身の状況
+    pub other_conditions: Option<String>,
+    /// 本人への指導区分（措置不要、要保健指導、要経過観察、要再面接、現病治療継続、医療機関紹介）
+    pub guidance: Option<String>,
+    /// 就業区分（通常勤務、就業制限・配慮、要休業）
+    pub work_classification: Option<String>,
+    /// 就業上の措置（労働時間の短縮、出張の制限、作業の転換、就業場所の変更等）
+    pub work_measures: Option<String>,
+    /// 措置期間
+    pub measure_period: Option<String>,
+    /// 職場環境の改善に関する意見
+    pub workplace_improvement: Option<String>,
+    /// 医療機関への受診配慮等
+    pub medical_referral: Option<String>,
+    /// その他（連絡事項等）
+    pub remarks: Option<String>,
+}
+
+impl InterviewRecord {
+    /// 記入欄の見出しと記入内容
+    pub fn fields(&self) -> [(&'static str, String); 12] {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        [
+            (
+                "実施年月日",
+                self.interviewed_on
+                    .map(|date| date.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("医師氏名", text(&self.physician)),
+            ("勤務の状況", text(&self.working_conditions)),
+            ("心理的な負担の状況", text(&self.psychological_burden)),
+            ("その他の心身の状況", text(&self.other_conditions)),
+            ("本人への指導区分", text(&self.guidance)),
+            ("就業区分", text(&self.work_classification)),
+            ("就業上の措置", text(&self.work_measures)),
+            ("措置期間", text(&self.measure_period)),
+            (
+                "職場環境の改善に関する意見",
+                text(&self.workplace_improvement),
+            ),
+            ("医療機関への受診配慮等", text(&self.medical_referral)),
+            ("その他", text(&self.remarks)),
+        ]
+    }
+}
+
+/// 面接指導用の資料
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Dossier {
+    /// 受検者のトークン
+    pub token: String,
+    pub period: String,
+    /// 所属部署
+    pub department: Option<String>,
+    /// 回答を受け付けた日時
+    pub submitted_at: DateTime<FixedOffset>,
+    pub result: CheckResult,
+    /// 前回の実施回の結果
+    pub previous: Option<PreviousPeriod>,
+    /// 尺度ごとの回答
+    pub scales: Vec<ScaleSection>,
+    /// 満足度の回答
+    pub satisfaction: Vec<ItemAnswer>,
+    pub interview: InterviewRecord,
+}
+
+impl Dossier {
+    /// 保存した判定結果から作成する
+    /// 設問ごとの回答を保存していない結果は`Error::NotFullfilled`とする。
+    pub fn new(current: &StoredResult, previous: Option<&StoredResult>) -> Result<Self, Error> {
+        let answers = current.answers.as_deref().ok_or(Error::NotFullfilled)?;
+        let scales = Scale::ALL
+            .iter()
+            .map(|&scale| ScaleSection {
+                scale,
+                evaluation_point: current
+                    .result
+                    .evaluation_points
+                    .get(&scale)
+                    .copied()
+                    .unwrap_or_default(),
+                previous_evaluation_point: previous
+                    .and_then(|previous| previous.result.evaluation_points.get(&scale).copied()),
+                items: scale
+                    .questions()
+                    .map(|question| ItemAnswer::new(question, answers))
+                    .collect(),
+            })
+            .collect();
+        Ok(Self {
+            token: current.token.clone(),
+            period: current.period.clone(),
+            department: current.department.clone(),
+            submitted_at: current.submitted_at,
+            result: current.result.clone(),
+            previous: previous.map(|previous| PreviousPeriod {
+                period: previous.period.clone(),
+                scores: previous.result.scores,
+                has_stress: previous.result.has_stress,
+            }),
+            scales,
+            satisfaction: SATISFACTION_QUESTIONS
+                .iter()
+                .map(|&question| ItemAnswer::new(question, answers))
+                .collect(),
+            interview: InterviewRecord::default(),
+        })
+    }
+
+    /// Markdownで書き出す
+    pub fn write_markdown<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        writeln!(writer, "# 面接指導用資料")?;
+        writeln!(writer)?;
+        writeln!(writer, "- トークン: {}", self.token)?;
+        writeln!(writer, "- 実施回: {}", self.period)?;
+        writeln!(
+            writer,
+            "- 所属部署: {}",
+            self.department.as_deref().unwrap_or("")
+        )?;
+        writeln!(writer, "- 受付日時: {}", self.submitted_at.to_rfc3339())?;
+        let (a, b, c) = self.result.scores;
+        writeln!(writer, "- 領域Ａ・Ｂ・Ｃ: {} / {} / {}", a, b, c)?;
+        if let Some(previous) = &self.previous {
+            let (a, b, c) = previous.scores;
+            writeln!(
+                writer,
+                "- 前回（{}）: {} / {} / {}（{}）",
+                previous.period,
+                a,
+                b,
+                c,
+                if previous.has_stress {
+                    "高ストレス"
+                } else {
+                    "非該当"
+                }
+            )?;
+        }
+        for section in &self.scales {
+            writeln!(writer)?;
+            write!(
+                writer,
+                "## {}（評価点 {}",
+                section.scale.label(),
+                section.evaluation_point
+            )?;
+            if let Some(previous) = section.previous_evaluation_point {
+                write!(writer, "、前回 {}", previous)?;
+            }
+            writeln!(writer, "）")?;
+            writeln!(writer)?;
+            write_items(&mut writer, &section.items)?;
+        }
+        writeln!(writer)?;
+        writeln!(writer, "## 満足度")?;
+        writeln!(writer)?;
+        write_items(&mut writer, &self.satisfaction)?;
+        writeln!(writer)?;
+        writeln!(writer, "## 面接指導の記録")?;
+        writeln!(writer)?;
+        for (label, value) in self.interview.fields() {
+            writeln!(writer, "- {}: {}", label, value)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_items<W: Write>(writer: &mut W, items: &[ItemAnswer]) -> Result<(), Error> {
+    writeln!(writer, "| 設問 | 内容 | 回答 |")?;
+    writeln!(writer, "| --- | --- | --- |")?;
+    for item in items {
+        writeln!(
+            writer,
+            "| {} | {} | {} {} |",
+            item.question,
+            item.text,
+            item.answer,
+            item.choice.as_deref().unwrap_or("")
+        )?;
+    }
+    Ok(())
+}
+
+/// 受付日時が`from`以上`to`未満の高ストレス者の資料を作成する
+/// 前回の実施回は、同じトークンの結果のうち直前に受け付けたものとする。
+pub fn dossiers<R: ResultRepository + ?Sized>(
+    repository: &R,
+    from: DateTime<FixedOffset>,
+    to: DateTime<FixedOffset>,
+) -> Result<Vec<Dossier>, Error> {
+    repository
+        .list(from, to)?
+        .iter()
+        .filter(|current| current.result.has_stress)
+        .map(|current| {
+            let history = repository.history(&current.token, HISTORY_LIMIT)?;
+            let previous = history.iter().rev().find(|stored| {
+                stored.period != current.period && stored.submitted_at < current.submitted_at
+            });
+            Dossier::new(current, previous)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::repository::MemoryRepository;
+    use crate::{AnswerStore, StressCriteria};
+
+    fn stored(token: &str, period: &str, value: u8) -> StoredResult {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(value).unwrap();
+        }
+        StoredResult {
+            token: token.to_string(),
+            period: period.to_string(),
+            department: Some("営業部".to_string()),
+            submitted_at: DateTime::parse_from_rfc3339(&format!("{}-06-01T09:00:00+09:00", period))
+                .unwrap(),
+            consent: None,
+            result: CheckResult::new(&store, StressCriteria::SUMUP).unwrap(),
+            answers: Some(store.values().to_vec()),
+            previous_results: vec![],
+        }
+    }
+
+    #[test]
+    fn test_dossiers() {
+        let mut repository = MemoryRepository::default();
+        repository.save(&stored("a", "2023", 2)).unwrap();
+        let mut current = stored("a", "2024", 2);
+        current.result.has_stress = true;
+        repository.save(&current).unwrap();
+        repository.save(&stored("b", "2024", 2)).unwrap();
+
+        let from = DateTime::parse_from_rfc3339("2024-04-01T00:00:00+09:00").unwrap();
+        let to = DateTime::parse_from_rfc3339("2025-04-01T00:00:00+09:00").unwrap();
+        let dossiers = dossiers(&repository, from, to).unwrap();
+        assert_eq!(dossiers.len(), 1);
+        let dossier = &dossiers[0];
+        assert_eq!(dossier.token, "a");
+        assert_eq!(dossier.previous.as_ref().unwrap().period, "2023");
+        assert_eq!(dossier.scales.len(), 18);
+        assert_eq!(
+            dossier.scales.iter().map(|s| s.items.len()).sum::<usize>(),
+            55
+        );
+        let depressed = dossier
+            .scales
+            .iter()
+            .find(|section| section.scale == Scale::Depressed)
+            .unwrap();
+        assert_eq!(depressed.items[0].question, 30);
+        assert_eq!(depressed.items[0].choice.as_deref(), Some("ときどきあった"));
+        assert_eq!(
+            depressed.previous_evaluation_point,
+            Some(depressed.evaluation_point)
+        );
+        assert_eq!(dossier.satisfaction.len(), 2);
+
+        let mut markdown = vec![];
+        dossier.write_markdown(&mut markdown).unwrap();
+        let markdown = String::from_utf8(markdown).unwrap();
+        assert!(markdown.contains("## 抑うつ感"));
+        assert!(markdown.contains("- 前回（2023）"));
+        assert!(markdown.contains("- 就業区分: "));
+    }
+
+    #[test]
+    fn test_dossier_without_answers() {
+        let mut current = stored("a", "2024", 2);
+        current.answers = None;
+        assert!(matches!(
+            Dossier::new(&current, None),
+            Err(Error::NotFullfilled)
+        ));
+    }
+}
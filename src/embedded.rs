@@ -0,0 +1,130 @@
+//! ビルド時に生成した設問マスタの定数データ
+//!
+//! `build.rs`が同梱の`resources/57.json`から生成する。[`STATIC_QUESTIONS`]・[`STATIC_THEMES`]・
+//! [`static_question`]は実行時にJSONを解析せず、初期化も割り当ても行わずに設問を参照できるため、
+//! 起動の速さが求められる場合や設問文の取得だけが必要な場合に用いる。
+//!
+//! 解析・初期化・割り当てを行わないのはこれらの定数の参照に限る。[`static_master`]は定数から
+//! [`SimpleStress`]を組み立てるため割り当てを行い、[`crate::QUESTIONS`]はリソースの場所の変更や
+//! 言い換えを反映するため、従来どおり初回の参照時に読み込む（`Lazy`）。
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use crate::{OuterQuestion, Question, Score, SimpleStress, Theme};
+
+/// 回答選択肢
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticScore {
+    pub score: u8,
+    pub text: &'static str,
+}
+
+/// 設問
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticQuestion {
+    pub id: u32,
+    pub text: &'static str,
+    /// ふりがな付きの設問文
+    pub furigana: Option<&'static str>,
+    pub reverse: bool,
+    pub scores: &'static [StaticScore],
+}
+
+/// サブ教示文ごとのまとまり
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticOuterQuestion {
+    pub title: Option<&'static str>,
+    /// [`STATIC_QUESTIONS`]の範囲
+    pub questions: Range<usize>,
+}
+
+/// 教示文ごとのまとまり
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticTheme {
+    pub theme: &'static str,
+    pub questions: &'static [StaticOuterQuestion],
+}
+
+include!(concat!(env!("OUT_DIR"), "/master.rs"));
+
+/// 設問番号を指定して設問を取得する
+pub const fn static_question(id: u32) -> Option<&'static StaticQuestion> {
+    if id >= 1 && id as usize <= STATIC_QUESTIONS.len() {
+        Some(&STATIC_QUESTIONS[id as usize - 1])
+    } else {
+        None
+    }
+}
+
+impl From<&StaticQuestion> for Question {
+    fn from(question: &StaticQuestion) -> Self {
+        Question {
+            id: question.id,
+            text: question.text.to_string(),
+            furigana: question.furigana.map(str::to_string),
+            variants: Default::default(),
+            reverse: question.reverse,
+            scores: question
+                .scores
+                .iter()
+                .map(|score| Score {
+                    score: score.score,
+                    text: score.text.to_string(),
+                })
+                .collect(),
+            condition: None,
+        }
+    }
+}
+
+/// 定数データから設問マスタを組み立てる
+/// JSONの解析は行わないが、設問文などを複製するため割り当てを行う。
+pub fn static_master() -> SimpleStress {
+    SimpleStress {
+        simple_stress: STATIC_THEMES
+            .iter()
+            .map(|theme| Theme {
+                theme: theme.theme.to_string(),
+                questions: theme
+                    .questions
+                    .iter()
+                    .map(|outer_question| OuterQuestion {
+                        title: outer_question.title.map(str::to_string),
+                        questions: STATIC_QUESTIONS[outer_question.questions.clone()]
+                            .iter()
+                            .map(|question| Arc::new(Question::from(question)))
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resource::EMBEDDED_MASTER;
+
+    #[test]
+    fn test_static_master() {
+        assert_eq!(STATIC_QUESTIONS.len(), 57);
+        assert!(STATIC_QUESTIONS
+            .iter()
+            .enumerate()
+            .all(|(index, question)| question.id as usize == index + 1));
+        const FIRST: Option<&StaticQuestion> = static_question(1);
+        assert_eq!(FIRST.map(|question| question.id), Some(1));
+        assert!(static_question(0).is_none());
+        assert!(static_question(58).is_none());
+
+        let master: SimpleStress = serde_json::from_str(EMBEDDED_MASTER).unwrap();
+        assert_eq!(static_master().canonical_text(), master.canonical_text());
+        for question in master.questions() {
+            let generated = static_question(question.id).unwrap();
+            assert_eq!(generated.text, question.text);
+            assert_eq!(generated.furigana, question.furigana.as_deref());
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! 入力ファイルの文字コードと表記の揺れの吸収
+//!
+//! 人事システムから出力したCSVはShift_JISやBOM付きのUTF-8であることが多く、数字が全角で
+//! 入力されていることもある。読み込む前に文字列へ変換し、全角数字を半角にそろえる。
+
+use std::io::Read;
+
+use encoding_rs::SHIFT_JIS;
+
+use crate::Error;
+
+/// UTF-8（BOM付きを含む）またはShift_JISのバイト列を文字列にする
+pub fn decode_text(bytes: &[u8]) -> Result<String, Error> {
+    let bytes = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(bytes);
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(text.to_string());
+    }
+    let (text, _, had_errors) = SHIFT_JIS.decode(bytes);
+    if had_errors {
+        return Err(Error::IllegalEncoding);
+    }
+    Ok(text.into_owned())
+}
+
+/// 全角数字（０〜９）を半角にする
+pub fn normalize_digits(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '０'..='９' => char::from(b'0' + (c as u32 - '０' as u32) as u8),
+            c => c,
+        })
+        .collect()
+}
+
+/// 全て読み込んで文字列にし、全角数字を半角にする
+pub fn read_text<R: Read>(mut reader: R) -> Result<String, Error> {
+    let mut bytes = vec![];
+    reader.read_to_end(&mut bytes)?;
+    Ok(normalize_digits(&decode_text(&bytes)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_text() {
+        let (shift_jis, _, _) = SHIFT_JIS.encode("id,q_1\n社員０１,３\n");
+        assert_eq!(read_text(&shift_jis[..]).unwrap(), "id,q_1\n社員01,3\n");
+        assert_eq!(
+            read_text("\u{FEFF}id,q_1\n１,４\n".as_bytes()).unwrap(),
+            "id,q_1\n1,4\n"
+        );
+        assert_eq!(normalize_digits("０１２３４５６７８９"), "0123456789");
+        assert!(matches!(
+            read_text(&[0x82, 0xFF][..]),
+            Err(Error::IllegalEncoding)
+        ));
+    }
+}
@@ -0,0 +1,214 @@
+//! 設問番号を指定した回答の入力
+//!
+//! 紙の調査票を転記する際に、`12 3`（設問12に回答3）のように設問番号と回答番号を
+//! 組にして任意の順で入力する。入力のたびに未回答の設問を示す。
+//!
+//! 速く転記するための[`QuickEntry`]では、Enterを押さずに1〜4のキーだけで設問を順に進める。
+//! 端末をrawモードにして1キーずつ読むのは`quick-entry` featureで有効になる。
+
+use crate::encoding::normalize_digits;
+use crate::{AnswerStore, Error};
+
+/// 入力された1行
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// 設問に回答する
+    Answer { question: u8, score: u8 },
+    /// 入力を終える
+    Finish,
+}
+
+/// 1行を解釈する
+/// 設問番号と回答番号は空白・`,`・`:`で区切る。全角数字も受け付ける。
+/// `q`（または`quit`）で入力を終える。
+pub fn parse_command(line: &str) -> Result<Command, Error> {
+    let line = normalize_digits(line.trim());
+    if line == "q" || line == "quit" {
+        return Ok(Command::Finish);
+    }
+    let mut parts = line
+        .split(|c: char| c.is_whitespace() || matches!(c, ',' | ':' | '：'))
+        .filter(|part| !part.is_empty());
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(question), Some(score), None) => Ok(Command::Answer {
+            question: question.parse().map_err(|_| Error::IllegalQuestion)?,
+            score: score.parse().map_err(|_| Error::IllegalAnswer)?,
+        }),
+        _ => Err(Error::IllegalAnswer),
+    }
+}
+
+impl AnswerStore {
+    /// 未回答の設問番号
+    pub fn unanswered(&self) -> Vec<u8> {
+        (1..=57)
+            .filter(|&question| self.values()[question as usize - 1] == 0)
+            .collect()
+    }
+}
+
+/// 設問番号の並びを`1-3, 7, 10-57`のように範囲でまとめる
+pub fn format_ranges(questions: &[u8]) -> String {
+    let mut ranges: Vec<(u8, u8)> = vec![];
+    for &question in questions {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == question => *end = question,
+            _ => ranges.push((question, question)),
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end)| {
+            if start == end {
+                start.to_string()
+            } else {
+                format!("{}-{}", start, end)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 連続入力のキー
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickKey {
+    /// 回答番号
+    Score(u8),
+    /// 前の設問に戻る
+    Back,
+    /// 入力を終える
+    Quit,
+    /// 割り当てのないキー
+    Other,
+}
+
+impl QuickKey {
+    /// 文字をキーに割り当てる（全角数字も受け付ける）
+    pub fn from_char(c: char) -> Self {
+        match c {
+            '1'..='4' => QuickKey::Score(c as u8 - b'0'),
+            '１'..='４' => QuickKey::Score((c as u32 - '０' as u32) as u8),
+            'b' | '-' => QuickKey::Back,
+            'q' => QuickKey::Quit,
+            _ => QuickKey::Other,
+        }
+    }
+}
+
+/// 連続入力の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuickState {
+    /// 次の設問を入力する
+    Continue,
+    /// 全設問を入力した
+    Complete,
+    /// 入力を中断した
+    Quit,
+}
+
+/// 1〜4のキーで設問1から順に回答する連続入力
+/// 回答すると次の設問に進み、`Back`で前の設問に戻って回答し直せる。
+#[derive(Debug, Clone, Default)]
+pub struct QuickEntry {
+    store: AnswerStore,
+    /// 入力中の設問（0始まり）
+    position: usize,
+}
+
+impl QuickEntry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 入力中の設問番号（全設問を入力した後は`None`）
+    pub fn question(&self) -> Option<u8> {
+        (self.position < 57).then(|| self.position as u8 + 1)
+    }
+
+    /// キーを1つ処理する
+    pub fn press(&mut self, key: QuickKey) -> QuickState {
+        match key {
+            QuickKey::Score(score) => {
+                if let Some(question) = self.question() {
+                    if self.store.insert(question, score).is_ok() {
+                        self.position += 1;
+                    }
+                }
+            }
+            QuickKey::Back => self.position = self.position.saturating_sub(1),
+            QuickKey::Quit => return QuickState::Quit,
+            QuickKey::Other => {}
+        }
+        if self.question().is_none() {
+            QuickState::Complete
+        } else {
+            QuickState::Continue
+        }
+    }
+
+    pub fn answers(&self) -> &AnswerStore {
+        &self.store
+    }
+
+    pub fn into_answers(self) -> AnswerStore {
+        self.store
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            parse_command("12 3").unwrap(),
+            Command::Answer {
+                question: 12,
+                score: 3
+            }
+        );
+        assert_eq!(
+            parse_command(" １２：３ ").unwrap(),
+            Command::Answer {
+                question: 12,
+                score: 3
+            }
+        );
+        assert_eq!(parse_command("q").unwrap(), Command::Finish);
+        assert!(matches!(parse_command("12"), Err(Error::IllegalAnswer)));
+        assert!(matches!(parse_command("x 3"), Err(Error::IllegalQuestion)));
+        assert!(matches!(parse_command("12 3 4"), Err(Error::IllegalAnswer)));
+    }
+
+    #[test]
+    fn test_unanswered() {
+        let mut store = AnswerStore::default();
+        assert_eq!(format_ranges(&store.unanswered()), "1-57");
+        store.insert(12, 3).unwrap();
+        store.insert(1, 1).unwrap();
+        store.insert(57, 2).unwrap();
+        assert_eq!(format_ranges(&store.unanswered()), "2-11, 13-56");
+        assert_eq!(format_ranges(&[]), "");
+    }
+
+    #[test]
+    fn test_quick_entry() {
+        let mut entry = QuickEntry::new();
+        assert_eq!(entry.question(), Some(1));
+        assert_eq!(entry.press(QuickKey::from_char('2')), QuickState::Continue);
+        assert_eq!(entry.press(QuickKey::from_char('9')), QuickState::Continue);
+        assert_eq!(entry.press(QuickKey::from_char('３')), QuickState::Continue);
+        assert_eq!(entry.question(), Some(3));
+        entry.press(QuickKey::from_char('b'));
+        entry.press(QuickKey::from_char('4'));
+        assert_eq!(&entry.answers().values()[..3], &[2, 4, 0]);
+        for _ in 2..56 {
+            assert_eq!(entry.press(QuickKey::Score(1)), QuickState::Continue);
+        }
+        assert_eq!(entry.press(QuickKey::Score(1)), QuickState::Complete);
+        assert_eq!(entry.question(), None);
+        assert_eq!(entry.press(QuickKey::Quit), QuickState::Quit);
+        assert!(entry.into_answers().unanswered().is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+//! 出力するCSVの無害化
+//!
+//! 表計算ソフトで開いた際に数式として解釈されないよう、`=` `+` `-` `@` などで
+//! 始まる値の先頭に`'`を付ける（CSVインジェクション対策）。また、指定した列の値を
+//! 伏字または鍵付きハッシュによる仮名に置き換える。CSVを書き出す処理はいずれもこの層を通す。
+
+use std::collections::HashSet;
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::pseudonym::Pseudonymizer;
+use crate::Error;
+
+/// 伏字
+pub const REDACTED: &str = "***";
+
+/// 数式として解釈されうる先頭の文字
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// 出力する値の無害化の設定
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sanitizer {
+    /// 伏字にする列
+    pub redact: HashSet<String>,
+    /// 仮名にする列。仮名化の鍵がない場合は伏字にする
+    pub pseudonymize: HashSet<String>,
+    pub pseudonymizer: Option<Pseudonymizer>,
+}
+
+impl Sanitizer {
+    pub fn new<I, S>(redact: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            redact: redact.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// 実施設定の伏字・仮名にする列を用いる
+    /// 仮名化の鍵は`with_pseudonymizer`で別に指定する。
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            pseudonymize: config.pseudonymize_columns.iter().cloned().collect(),
+            ..Self::new(config.redact_columns.iter().cloned())
+        }
+    }
+
+    /// 仮名化の鍵を指定する
+    pub fn with_pseudonymizer(mut self, pseudonymizer: Option<Pseudonymizer>) -> Self {
+        self.pseudonymizer = pseudonymizer;
+        self
+    }
+
+    /// 1つの値を無害化する
+    pub fn sanitize(&self, column: &str, value: &str) -> String {
+        if self.redact.contains(column) {
+            return REDACTED.to_string();
+        }
+        if self.pseudonymize.contains(column) {
+            return match self.pseudonymizer {
+                Some(ref pseudonymizer) => pseudonymizer.pseudonym(value),
+                None => REDACTED.to_string(),
+            };
+        }
+        escape_formula(value)
+    }
+}
+
+/// 数式として解釈されうる値の先頭に`'`を付ける
+/// 負の数など数値として解釈できる値はそのままとする。
+pub fn escape_formula(value: &str) -> String {
+    if value.starts_with(FORMULA_PREFIXES) && value.parse::<f64>().is_err() {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// 無害化しながら書き出すCSVライタ
+pub struct SanitizingWriter<W: Write> {
+    writer: csv::Writer<W>,
+    sanitizer: Sanitizer,
+    headers: Option<csv::StringRecord>,
+}
+
+impl<W: Write> SanitizingWriter<W> {
+    pub fn new(writer: W, sanitizer: &Sanitizer) -> Self {
+        Self {
+            writer: csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_writer(writer),
+            sanitizer: sanitizer.clone(),
+            headers: None,
+        }
+    }
+
+    /// 1行書き出す
+    /// 最初の行の前にヘッダを書き出す。
+    pub fn serialize<T: Serialize>(&mut self, row: T) -> Result<(), Error> {
+        let mut buffer = csv::Writer::from_writer(vec![]);
+        buffer.serialize(row).map_err(Error::CSVWriteError)?;
+        let buffer = buffer
+            .into_inner()
+            .map_err(|e| Error::IOError(e.into_error()))?;
+        let mut reader = csv::Reader::from_reader(buffer.as_slice());
+        let headers = reader.headers().map_err(Error::CSVReadError)?.clone();
+        let record = reader
+            .records()
+            .next()
+            .unwrap_or_else(|| Ok(csv::StringRecord::new()))
+            .map_err(Error::CSVReadError)?;
+        if self.headers.is_none() {
+            self.writer
+                .write_record(&headers)
+                .map_err(Error::CSVWriteError)?;
+            self.headers = Some(headers.clone());
+        }
+        let record = headers
+            .iter()
+            .zip(record.iter())
+            .map(|(column, value)| self.sanitizer.sanitize(column, value))
+            .collect::<Vec<_>>();
+        self.writer
+            .write_record(&record)
+            .map_err(Error::CSVWriteError)
+    }
+
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_escape_formula() {
+        assert_eq!(escape_formula("=1+2"), "'=1+2");
+        assert_eq!(escape_formula("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(escape_formula("-1.5"), "-1.5");
+        assert_eq!(escape_formula("営業部"), "営業部");
+    }
+
+    #[test]
+    fn test_sanitizing_writer() {
+        #[derive(Serialize)]
+        struct Row<'a> {
+            id: &'a str,
+            name: &'a str,
+            department: &'a str,
+        }
+
+        let mut output = vec![];
+        let mut writer = SanitizingWriter::new(&mut output, &Sanitizer::new(["name"]));
+        writer
+            .serialize(Row {
+                id: "1",
+                name: "山田太郎",
+                department: "=HYPERLINK(\"http://example.com\")",
+            })
+            .unwrap();
+        writer.flush().unwrap();
+        drop(writer);
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "id,name,department\n1,***,\"'=HYPERLINK(\"\"http://example.com\"\")\"\n"
+        );
+    }
+
+    #[test]
+    fn test_pseudonymize() {
+        let config = Config {
+            pseudonymize_columns: vec!["id".to_string()],
+            ..Config::default()
+        };
+        let pseudonymizer = Pseudonymizer::new(b"0123456789abcdef").unwrap();
+        let sanitizer =
+            Sanitizer::from_config(&config).with_pseudonymizer(Some(pseudonymizer.clone()));
+        assert_eq!(
+            sanitizer.sanitize("id", "0001"),
+            pseudonymizer.pseudonym("0001")
+        );
+        assert_eq!(sanitizer.sanitize("department", "営業部"), "営業部");
+        assert_eq!(
+            Sanitizer::from_config(&config).sanitize("id", "0001"),
+            REDACTED
+        );
+    }
+}
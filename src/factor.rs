@@ -0,0 +1,214 @@
+//! 因子分析のための項目データ
+//!
+//! 利用側で因子分析を行えるよう、集団の回答を設問ごとに中心化・標準化した行列を提供する。
+//! `pca`フィーチャーを有効にすると、ストレス反応（設問18〜46）などの主成分を求められる。
+
+use std::ops::RangeInclusive;
+
+use serde::Serialize;
+
+use crate::AnswerStore;
+
+/// ストレス反応の設問
+pub const STRESS_REACTION_QUESTIONS: RangeInclusive<u8> = 18..=46;
+
+/// 行列の変換方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Standardization {
+    /// 設問ごとに平均を引く
+    Centered,
+    /// 設問ごとに平均を引き、標準偏差で割る（分散が0の設問は0とする）
+    Standardized,
+}
+
+/// 回答者×設問の行列
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ItemMatrix {
+    /// 列の設問番号
+    pub questions: Vec<u8>,
+    pub standardization: Standardization,
+    /// 設問ごとの平均（回答番号、逆転前）
+    pub means: Vec<f64>,
+    /// 設問ごとの標準偏差（不偏）
+    pub std_devs: Vec<f64>,
+    /// 回答者ごとの行
+    pub rows: Vec<Vec<f64>>,
+}
+
+impl ItemMatrix {
+    /// 対象の設問に全て回答した者のみで行列を作る
+    pub fn new(
+        stores: &[AnswerStore],
+        questions: RangeInclusive<u8>,
+        standardization: Standardization,
+    ) -> Self {
+        let questions = questions
+            .filter(|question| (1..=57).contains(question))
+            .collect::<Vec<_>>();
+        let raw = stores
+            .iter()
+            .filter_map(|store| {
+                let values = store.values();
+                questions
+                    .iter()
+                    .map(|&question| match values[question as usize - 1] {
+                        0 => None,
+                        value => Some(value as f64),
+                    })
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Vec<_>>();
+        let n = raw.len() as f64;
+        let means = (0..questions.len())
+            .map(|j| raw.iter().map(|row| row[j]).sum::<f64>() / n)
+            .collect::<Vec<_>>();
+        let std_devs = (0..questions.len())
+            .map(|j| {
+                let sum = raw
+                    .iter()
+                    .map(|row| (row[j] - means[j]).powi(2))
+                    .sum::<f64>();
+                (sum / (n - 1.0).max(1.0)).sqrt()
+            })
+            .collect::<Vec<_>>();
+        let rows = raw
+            .into_iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(j, value)| {
+                        let centered = value - means[j];
+                        match standardization {
+                            Standardization::Centered => centered,
+                            Standardization::Standardized if std_devs[j] > 0.0 => {
+                                centered / std_devs[j]
+                            }
+                            Standardization::Standardized => 0.0,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            questions,
+            standardization,
+            means,
+            std_devs,
+            rows,
+        }
+    }
+
+    /// 行列の転置との積を(行数−1)で割ったもの（中心化なら共分散、標準化なら相関行列）
+    pub fn covariance(&self) -> Vec<Vec<f64>> {
+        let n = (self.rows.len() as f64 - 1.0).max(1.0);
+        (0..self.questions.len())
+            .map(|i| {
+                (0..self.questions.len())
+                    .map(|j| self.rows.iter().map(|row| row[i] * row[j]).sum::<f64>() / n)
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// 主成分
+#[cfg(feature = "pca")]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrincipalComponents {
+    /// 固有値（大きい順）
+    pub eigenvalues: Vec<f64>,
+    /// 寄与率
+    pub explained_variance_ratio: Vec<f64>,
+    /// 成分ごとの設問の係数（固有ベクトル）
+    pub loadings: Vec<Vec<f64>>,
+}
+
+/// 上位`components`個の主成分を求める
+/// 回答者が2人未満の場合は`None`。
+#[cfg(feature = "pca")]
+pub fn principal_components(matrix: &ItemMatrix, components: usize) -> Option<PrincipalComponents> {
+    use nalgebra::DMatrix;
+
+    if matrix.rows.len() < 2 || matrix.questions.is_empty() {
+        return None;
+    }
+    let size = matrix.questions.len();
+    let covariance = matrix.covariance();
+    let covariance = DMatrix::from_fn(size, size, |i, j| covariance[i][j]);
+    let eigen = covariance.symmetric_eigen();
+    let mut order = (0..size).collect::<Vec<_>>();
+    order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+    let total: f64 = eigen.eigenvalues.iter().sum();
+    let order = &order[..components.min(size)];
+    Some(PrincipalComponents {
+        eigenvalues: order.iter().map(|&i| eigen.eigenvalues[i]).collect(),
+        explained_variance_ratio: order
+            .iter()
+            .map(|&i| eigen.eigenvalues[i] / total)
+            .collect(),
+        loadings: order
+            .iter()
+            .map(|&i| eigen.eigenvectors.column(i).iter().copied().collect())
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simulate::Population;
+
+    fn stores() -> Vec<AnswerStore> {
+        let mut stores = Population::new(200, 1)
+            .with_seed(11)
+            .generate()
+            .into_iter()
+            .map(|record| record.answers)
+            .collect::<Vec<_>>();
+        stores.push(AnswerStore::default());
+        stores
+    }
+
+    #[test]
+    fn test_item_matrix() {
+        let matrix = ItemMatrix::new(
+            &stores(),
+            STRESS_REACTION_QUESTIONS,
+            Standardization::Standardized,
+        );
+        assert_eq!(matrix.questions.len(), 29);
+        assert_eq!(matrix.questions[0], 18);
+        assert_eq!(matrix.rows.len(), 200);
+        for j in 0..29 {
+            let mean = matrix.rows.iter().map(|row| row[j]).sum::<f64>() / 200.0;
+            assert!(mean.abs() < 1e-9);
+        }
+        let correlation = matrix.covariance();
+        assert!((correlation[3][3] - 1.0).abs() < 1e-9);
+
+        let centered = ItemMatrix::new(&stores(), 1..=3, Standardization::Centered);
+        assert_eq!(centered.rows[0].len(), 3);
+        assert!((centered.covariance()[0][0] - centered.std_devs[0].powi(2)).abs() < 1e-9);
+    }
+
+    #[cfg(feature = "pca")]
+    #[test]
+    fn test_principal_components() {
+        let matrix = ItemMatrix::new(
+            &stores(),
+            STRESS_REACTION_QUESTIONS,
+            Standardization::Standardized,
+        );
+        let components = principal_components(&matrix, 3).unwrap();
+        assert_eq!(components.eigenvalues.len(), 3);
+        assert!(components.eigenvalues[0] >= components.eigenvalues[1]);
+        assert!(components.explained_variance_ratio[0] > 0.0);
+        assert_eq!(components.loadings[0].len(), 29);
+        let norm = components.loadings[0].iter().map(|v| v * v).sum::<f64>();
+        assert!((norm - 1.0).abs() < 1e-9);
+
+        let empty = ItemMatrix::new(&[], STRESS_REACTION_QUESTIONS, Standardization::Centered);
+        assert_eq!(principal_components(&empty, 3), None);
+    }
+}
@@ -0,0 +1,490 @@
+//! FHIR（R4）形式での入出力
+//!
+//! 産業保健の記録システムとの連携のため、57設問マスタを`Questionnaire`リソースに、
+//! 回答を`QuestionnaireResponse`リソースに変換する。教示文・サブ教示文は`group`の項目とし、
+//! 設問は`linkId`を`q1`〜`q57`、回答番号を`valueCoding`のコード（`1`〜`4`）とする`choice`の項目とする。
+//!
+//! 判定結果は`Observation`リソースとして出力する。LOINCに該当するコードがないため、
+//! 独自のコード体系（[`OBSERVATION_SYSTEM`]）で尺度の評価点・領域ごとの点数・高ストレスの判定を表す。
+
+use std::sync::Arc;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::{AnswerStore, CheckResult, Error, Question, Scale, SimpleStress};
+
+/// `Questionnaire`の正規URL
+pub const QUESTIONNAIRE_URL: &str = "urn:stresscheck:questionnaire:57";
+
+/// 回答番号のコード体系
+pub const ANSWER_SYSTEM: &str = "urn:stresscheck:answer";
+
+/// 判定結果のコード体系
+pub const OBSERVATION_SYSTEM: &str = "urn:stresscheck:observation";
+
+/// `Observation`の分類のコード体系
+const OBSERVATION_CATEGORY_SYSTEM: &str =
+    "http://terminology.hl7.org/CodeSystem/observation-category";
+
+/// 項目の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemType {
+    Group,
+    Choice,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Coding {
+    pub system: String,
+    pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnswerOption {
+    pub value_coding: Coding,
+}
+
+/// `Questionnaire.item`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionnaireItem {
+    pub link_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(rename = "type")]
+    pub item_type: ItemType,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answer_option: Vec<AnswerOption>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub item: Vec<QuestionnaireItem>,
+}
+
+/// `Questionnaire`リソース
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Questionnaire {
+    pub resource_type: String,
+    pub url: String,
+    pub status: String,
+    pub title: String,
+    pub item: Vec<QuestionnaireItem>,
+}
+
+/// `QuestionnaireResponse.item`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseItem {
+    pub link_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub answer: Vec<AnswerOption>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub item: Vec<ResponseItem>,
+}
+
+/// `QuestionnaireResponse`リソース
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionnaireResponse {
+    pub resource_type: String,
+    pub questionnaire: String,
+    /// 全設問に回答していれば`completed`、それ以外は`in-progress`
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authored: Option<String>,
+    pub item: Vec<ResponseItem>,
+}
+
+fn theme_link_id(theme: usize) -> String {
+    format!("theme-{}", theme + 1)
+}
+
+fn group_link_id(theme: usize, group: usize) -> String {
+    format!("theme-{}.{}", theme + 1, group + 1)
+}
+
+fn question_link_id(id: u32) -> String {
+    format!("q{}", id)
+}
+
+fn answer_coding(question: &Question, score: u8) -> Coding {
+    Coding {
+        system: ANSWER_SYSTEM.to_string(),
+        code: score.to_string(),
+        display: question
+            .scores
+            .iter()
+            .find(|choice| choice.score == score)
+            .map(|choice| choice.text.clone()),
+    }
+}
+
+impl Questionnaire {
+    /// マスタから`Questionnaire`を作る
+    pub fn from_master(master: &SimpleStress) -> Self {
+        let question_item = |question: &Arc<Question>| QuestionnaireItem {
+            link_id: question_link_id(question.id),
+            text: Some(question.text.clone()),
+            item_type: ItemType::Choice,
+            required: true,
+            answer_option: question
+                .scores
+                .iter()
+                .map(|score| AnswerOption {
+                    value_coding: answer_coding(question, score.score),
+                })
+                .collect(),
+            item: vec![],
+        };
+        let item = master
+            .simple_stress
+            .iter()
+            .enumerate()
+            .map(|(theme_index, theme)| {
+                let mut items = vec![];
+                for (group_index, outer_question) in theme.questions.iter().enumerate() {
+                    let questions = outer_question.questions.iter().map(question_item);
+                    match &outer_question.title {
+                        Some(title) => items.push(QuestionnaireItem {
+                            link_id: group_link_id(theme_index, group_index),
+                            text: Some(title.clone()),
+                            item_type: ItemType::Group,
+                            required: false,
+                            answer_option: vec![],
+                            item: questions.collect(),
+                        }),
+                        None => items.extend(questions),
+                    }
+                }
+                QuestionnaireItem {
+                    link_id: theme_link_id(theme_index),
+                    text: Some(theme.theme.clone()),
+                    item_type: ItemType::Group,
+                    required: false,
+                    answer_option: vec![],
+                    item: items,
+                }
+            })
+            .collect();
+        Self {
+            resource_type: "Questionnaire".to_string(),
+            url: QUESTIONNAIRE_URL.to_string(),
+            status: "active".to_string(),
+            title: "職業性ストレス簡易調査票（57項目）".to_string(),
+            item,
+        }
+    }
+}
+
+impl QuestionnaireResponse {
+    /// 回答から`QuestionnaireResponse`を作る
+    /// 未回答の設問は項目に含めず、設問を含まないグループも省く。
+    pub fn new(
+        master: &SimpleStress,
+        store: &AnswerStore,
+        authored: Option<DateTime<FixedOffset>>,
+    ) -> Self {
+        let values = store.values();
+        let question_item = |question: &Arc<Question>| {
+            let score = *values.get((question.id as usize).checked_sub(1)?)?;
+            (score != 0).then(|| ResponseItem {
+                link_id: question_link_id(question.id),
+                text: Some(question.text.clone()),
+                answer: vec![AnswerOption {
+                    value_coding: answer_coding(question, score),
+                }],
+                item: vec![],
+            })
+        };
+        let mut item = vec![];
+        for (theme_index, theme) in master.simple_stress.iter().enumerate() {
+            let mut items = vec![];
+            for (group_index, outer_question) in theme.questions.iter().enumerate() {
+                let questions = outer_question.questions.iter().filter_map(question_item);
+                match &outer_question.title {
+                    Some(title) => {
+                        let questions = questions.collect::<Vec<_>>();
+                        if !questions.is_empty() {
+                            items.push(ResponseItem {
+                                link_id: group_link_id(theme_index, group_index),
+                                text: Some(title.clone()),
+                                answer: vec![],
+                                item: questions,
+                            });
+                        }
+                    }
+                    None => items.extend(questions),
+                }
+            }
+            if !items.is_empty() {
+                item.push(ResponseItem {
+                    link_id: theme_link_id(theme_index),
+                    text: Some(theme.theme.clone()),
+                    answer: vec![],
+                    item: items,
+                });
+            }
+        }
+        Self {
+            resource_type: "QuestionnaireResponse".to_string(),
+            questionnaire: QUESTIONNAIRE_URL.to_string(),
+            status: if values.contains(&0) {
+                "in-progress"
+            } else {
+                "completed"
+            }
+            .to_string(),
+            authored: authored.map(|authored| authored.to_rfc3339()),
+            item,
+        }
+    }
+
+    /// 回答を取り出す
+    /// `linkId`が`q1`〜`q57`でない回答は`IllegalQuestion`、コードが回答番号でなければ`IllegalAnswer`。
+    pub fn to_answers(&self) -> Result<AnswerStore, Error> {
+        fn collect(items: &[ResponseItem], store: &mut AnswerStore) -> Result<(), Error> {
+            for item in items {
+                if let Some(answer) = item.answer.first() {
+                    let question = item
+                        .link_id
+                        .strip_prefix('q')
+                        .and_then(|no| no.parse().ok())
+                        .ok_or(Error::IllegalQuestion)?;
+                    let score = answer
+                        .value_coding
+                        .code
+                        .parse()
+                        .map_err(|_| Error::IllegalAnswer)?;
+                    store.insert(question, score)?;
+                }
+                collect(&item.item, store)?;
+            }
+            Ok(())
+        }
+        let mut store = AnswerStore::default();
+        collect(&self.item, &mut store)?;
+        Ok(store)
+    }
+}
+
+/// JSONの`QuestionnaireResponse`から回答を読み込む
+pub fn read_questionnaire_response(value: &str) -> Result<AnswerStore, Error> {
+    let response: QuestionnaireResponse =
+        serde_json::from_str(value).map_err(Error::JSONReadError)?;
+    response.to_answers()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeableConcept {
+    pub coding: Vec<Coding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reference {
+    pub reference: String,
+}
+
+/// `Observation`リソース
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Observation {
+    pub resource_type: String,
+    pub status: String,
+    pub category: Vec<CodeableConcept>,
+    pub code: CodeableConcept,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<Reference>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effective_date_time: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_integer: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_boolean: Option<bool>,
+}
+
+impl Observation {
+    fn new(code: &str, text: &str) -> Self {
+        Self {
+            resource_type: "Observation".to_string(),
+            status: "final".to_string(),
+            category: vec![CodeableConcept {
+                coding: vec![Coding {
+                    system: OBSERVATION_CATEGORY_SYSTEM.to_string(),
+                    code: "survey".to_string(),
+                    display: Some("Survey".to_string()),
+                }],
+                text: None,
+            }],
+            code: CodeableConcept {
+                coding: vec![Coding {
+                    system: OBSERVATION_SYSTEM.to_string(),
+                    code: code.to_string(),
+                    display: Some(text.to_string()),
+                }],
+                text: Some(text.to_string()),
+            },
+            subject: None,
+            effective_date_time: None,
+            value_integer: None,
+            value_boolean: None,
+        }
+    }
+}
+
+/// 判定結果を`Observation`の並びにする
+/// 18尺度の評価点（コードは尺度名）、領域Ａ・Ｂ・Ｃの点数（`area_a`等）、高ストレスの判定（`high_stress`）の順。
+/// `subject`には受検者を指す参照（`Patient/123`等）を指定する。
+pub fn observations(
+    result: &CheckResult,
+    subject: Option<&str>,
+    effective: Option<DateTime<FixedOffset>>,
+) -> Vec<Observation> {
+    let (a, b, c) = result.scores;
+    let mut observations = Scale::ALL
+        .iter()
+        .filter_map(|scale| {
+            let point = result.evaluation_points.get(scale)?;
+            let mut observation = Observation::new(scale.as_str(), scale.label());
+            observation.value_integer = Some(i64::from(*point));
+            Some(observation)
+        })
+        .collect::<Vec<_>>();
+    for (code, text, value) in [
+        ("area_a", "領域Ａの点数", a),
+        ("area_b", "領域Ｂの点数", b),
+        ("area_c", "領域Ｃの点数", c),
+    ] {
+        let mut observation = Observation::new(code, text);
+        observation.value_integer = Some(i64::from(value));
+        observations.push(observation);
+    }
+    let mut observation = Observation::new("high_stress", "高ストレス者の判定");
+    observation.value_boolean = Some(result.has_stress);
+    observations.push(observation);
+    for observation in &mut observations {
+        observation.subject = subject.map(|subject| Reference {
+            reference: subject.to_string(),
+        });
+        observation.effective_date_time = effective.map(|effective| effective.to_rfc3339());
+    }
+    observations
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleEntry<T> {
+    pub resource: T,
+}
+
+/// `Bundle`リソース（`collection`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle<T> {
+    pub resource_type: String,
+    #[serde(rename = "type")]
+    pub bundle_type: String,
+    pub entry: Vec<BundleEntry<T>>,
+}
+
+impl<T> Bundle<T> {
+    /// リソースをまとめた`collection`の`Bundle`
+    pub fn collection(resources: Vec<T>) -> Self {
+        Self {
+            resource_type: "Bundle".to_string(),
+            bundle_type: "collection".to_string(),
+            entry: resources
+                .into_iter()
+                .map(|resource| BundleEntry { resource })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    #[test]
+    fn test_questionnaire() {
+        let questionnaire = Questionnaire::from_master(&QUESTIONS);
+        let value = serde_json::to_value(&questionnaire).unwrap();
+        assert_eq!(value["resourceType"], "Questionnaire");
+        assert_eq!(value["item"].as_array().unwrap().len(), 4);
+        let first = &value["item"][0]["item"][0];
+        assert_eq!(first["linkId"], "q1");
+        assert_eq!(first["type"], "choice");
+        assert_eq!(first["answerOption"][3]["valueCoding"]["code"], "4");
+        assert_eq!(first["answerOption"][0]["valueCoding"]["display"], "そうだ");
+        assert_eq!(value["item"][2]["item"][0]["linkId"], "theme-3.1");
+        assert_eq!(value["item"][2]["item"][0]["item"][0]["linkId"], "q47");
+    }
+
+    #[test]
+    fn test_questionnaire_response() {
+        let mut store = AnswerStore::default();
+        store.insert(1, 3).unwrap();
+        store.insert(48, 2).unwrap();
+        let response = QuestionnaireResponse::new(&QUESTIONS, &store, None);
+        assert_eq!(response.status, "in-progress");
+        assert_eq!(response.item.len(), 2);
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(!json.contains("authored"));
+        assert_eq!(
+            read_questionnaire_response(&json).unwrap().values(),
+            store.values()
+        );
+
+        let mut store = AnswerStore::default();
+        for i in 0..57 {
+            store.push(i % 4 + 1).unwrap();
+        }
+        let authored = DateTime::parse_from_rfc3339("2024-06-01T09:00:00+09:00").unwrap();
+        let response = QuestionnaireResponse::new(&QUESTIONS, &store, Some(authored));
+        assert_eq!(response.status, "completed");
+        assert_eq!(
+            response.authored.as_deref(),
+            Some("2024-06-01T09:00:00+09:00")
+        );
+        assert_eq!(response.to_answers().unwrap().values(), store.values());
+
+        let mut response = response;
+        response.item[0].item[0].link_id = "x1".to_string();
+        assert!(matches!(response.to_answers(), Err(Error::IllegalQuestion)));
+    }
+
+    #[test]
+    fn test_observations() {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(2).unwrap();
+        }
+        let result = CheckResult::new(&store, crate::StressCriteria::default()).unwrap();
+        let observations = observations(&result, Some("Patient/1"), None);
+        assert_eq!(observations.len(), 22);
+        let value = serde_json::to_value(Bundle::collection(observations)).unwrap();
+        assert_eq!(value["type"], "collection");
+        let first = &value["entry"][0]["resource"];
+        assert_eq!(first["resourceType"], "Observation");
+        assert_eq!(
+            first["code"]["coding"][0]["code"],
+            "mental_work_stress_volume"
+        );
+        assert_eq!(first["subject"]["reference"], "Patient/1");
+        assert!(first.get("effectiveDateTime").is_none());
+        assert_eq!(value["entry"][18]["resource"]["valueInteger"], 45);
+        let last = &value["entry"][21]["resource"];
+        assert_eq!(last["code"]["coding"][0]["code"], "high_stress");
+        assert_eq!(last["valueBoolean"], result.has_stress);
+        assert!(last.get("valueInteger").is_none());
+    }
+}
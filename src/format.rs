@@ -0,0 +1,131 @@
+use serde::Serialize;
+
+use crate::demographics::{AgeBand, EmploymentType, Tenure};
+
+/// 一括判定で受け付ける入力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkFormat {
+    /// `read_bulk`・`read_bulk_records`で読み込むCSV
+    Csv,
+}
+
+/// 列の型
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ColumnType {
+    /// 文字列
+    String,
+    /// 範囲が決まっている整数
+    Integer { min: u8, max: u8 },
+    /// 決められた値のいずれか
+    Enum { values: Vec<&'static str> },
+}
+
+/// 列の説明
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ColumnDescription {
+    /// 列名（ヘッダ）
+    pub name: String,
+    #[serde(flatten)]
+    pub column_type: ColumnType,
+    /// 必須の列か
+    pub required: bool,
+    /// 説明
+    pub description: String,
+}
+
+/// 入力形式の説明
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormatDescription {
+    pub format: BulkFormat,
+    pub columns: Vec<ColumnDescription>,
+}
+
+impl BulkFormat {
+    /// 受け付ける全ての入力形式
+    pub const ALL: [BulkFormat; 1] = [BulkFormat::Csv];
+
+    /// 列名・型・必須かどうかを機械可読な形で返す
+    /// アップロード用テンプレートの生成や、クライアント側での事前検証に用いる。
+    pub fn describe(&self) -> FormatDescription {
+        match self {
+            BulkFormat::Csv => {
+                let mut columns = vec![ColumnDescription {
+                    name: "id".to_string(),
+                    column_type: ColumnType::String,
+                    required: true,
+                    description: "ユーザ特定キー".to_string(),
+                }];
+                columns.extend((1..=57).map(|no| ColumnDescription {
+                    name: format!("q_{}", no),
+                    column_type: ColumnType::Integer { min: 1, max: 4 },
+                    required: true,
+                    description: format!("設問{}の回答番号", no),
+                }));
+                columns.push(ColumnDescription {
+                    name: "department".to_string(),
+                    column_type: ColumnType::String,
+                    required: false,
+                    description: "所属部署".to_string(),
+                });
+                columns.push(ColumnDescription {
+                    name: "age_band".to_string(),
+                    column_type: ColumnType::Enum {
+                        values: AgeBand::ALL.iter().map(|value| value.as_str()).collect(),
+                    },
+                    required: false,
+                    description: "年齢階層".to_string(),
+                });
+                columns.push(ColumnDescription {
+                    name: "employment_type".to_string(),
+                    column_type: ColumnType::Enum {
+                        values: EmploymentType::ALL
+                            .iter()
+                            .map(|value| value.as_str())
+                            .collect(),
+                    },
+                    required: false,
+                    description: "雇用形態".to_string(),
+                });
+                columns.push(ColumnDescription {
+                    name: "tenure".to_string(),
+                    column_type: ColumnType::Enum {
+                        values: Tenure::ALL.iter().map(|value| value.as_str()).collect(),
+                    },
+                    required: false,
+                    description: "勤続年数".to_string(),
+                });
+                FormatDescription {
+                    format: *self,
+                    columns,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let description = BulkFormat::Csv.describe();
+        assert_eq!(description.columns.len(), 62);
+        assert_eq!(description.columns[0].name, "id");
+        assert!(description.columns[0].required);
+        assert_eq!(
+            description.columns[57].column_type,
+            ColumnType::Integer { min: 1, max: 4 }
+        );
+        assert!(!description.columns[61].required);
+
+        let value = serde_json::to_value(&description).unwrap();
+        assert_eq!(value["format"], "csv");
+        assert_eq!(value["columns"][1]["name"], "q_1");
+        assert_eq!(value["columns"][1]["type"], "integer");
+        assert_eq!(value["columns"][1]["max"], 4);
+        assert_eq!(value["columns"][61]["values"][0], "under_one_year");
+    }
+}
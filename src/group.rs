@@ -0,0 +1,842 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::group_code::GroupCode;
+use crate::interval::{mean_interval, rate_interval, Interval, IntervalMethod};
+use crate::roster::{Roster, RosterEntry};
+use crate::wellbeing::WellbeingIndex;
+use crate::{score_slice, CheckRecord, Demographics, Error, Scale, StressCriteria};
+
+/// 集団分析の最小人数
+/// これを下回る集団は個人が特定されるおそれがあるため、集計値を表示しない。
+pub const DEFAULT_MIN_GROUP_SIZE: usize = 10;
+
+/// 分類軸の値が欠落している記録の集団名
+pub const UNKNOWN_GROUP: &str = "unknown";
+
+/// 集団分析の分類軸
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dimension {
+    /// 所属部署
+    Department,
+    /// 年齢階層
+    AgeBand,
+    /// 雇用形態
+    EmploymentType,
+    /// 勤続年数
+    Tenure,
+}
+
+impl Dimension {
+    fn key<'a>(&self, entry: &'a Entry) -> &'a str {
+        match self {
+            Dimension::Department => entry.department.as_deref(),
+            Dimension::AgeBand => entry.demographics.age_band.map(|band| band.as_str()),
+            Dimension::EmploymentType => entry
+                .demographics
+                .employment_type
+                .map(|employment_type| employment_type.as_str()),
+            Dimension::Tenure => entry.demographics.tenure.map(|tenure| tenure.as_str()),
+        }
+        .unwrap_or(UNKNOWN_GROUP)
+    }
+}
+
+struct Entry {
+    department: Option<GroupCode>,
+    demographics: Demographics,
+    has_stress: bool,
+    /// 領域Ａ・Ｂ・Ｃの点数
+    scores: (u8, u8, u8),
+    /// 尺度ごとの素点（`Scale::ALL`の順）
+    scales: [u8; 18],
+    /// 事後層化の重み
+    weight: f64,
+}
+
+/// 仕事のストレス判定図による健康リスク
+/// 全国平均を100とし、120であれば健康問題が起こる可能性が20%高いことを示す。
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HealthRisk {
+    /// 量－コントロール判定図
+    pub volume_control: f64,
+    /// 職場の支援判定図
+    pub support: f64,
+    /// 総合健康リスク
+    pub total: f64,
+}
+
+impl HealthRisk {
+    /// 尺度ごとの素点の平均から健康リスクを算出する
+    pub fn from_scale_means(scale_means: &BTreeMap<Scale, f64>) -> Self {
+        let mean = |scale| scale_means.get(&scale).copied().unwrap_or_default();
+        let volume_control = (0.076 * (mean(Scale::MentalWorkStressVolume) - 8.7)
+            - 0.089 * (mean(Scale::WorkControl) - 7.9))
+            .exp()
+            * 100.0;
+        let support = (-0.097 * (mean(Scale::BossSupport) - 7.6)
+            - 0.097 * (mean(Scale::ColleagueSupport) - 8.1))
+            .exp()
+            * 100.0;
+        Self {
+            volume_control,
+            support,
+            total: volume_control * support / 100.0,
+        }
+    }
+}
+
+/// 高ストレス者率・尺度の平均の95%信頼区間
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfidenceIntervals {
+    pub method: IntervalMethod,
+    pub high_stress_rate: Interval,
+    pub scale_means: BTreeMap<Scale, Interval>,
+}
+
+impl ConfidenceIntervals {
+    fn new(entries: &[&Entry], method: IntervalMethod) -> Option<Self> {
+        let high_stress = entries
+            .iter()
+            .map(|entry| (if entry.has_stress { 1.0 } else { 0.0 }, entry.weight))
+            .collect::<Vec<_>>();
+        let scale_means = Scale::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, &scale)| {
+                let values = entries
+                    .iter()
+                    .map(|entry| (entry.scales[index] as f64, entry.weight))
+                    .collect::<Vec<_>>();
+                Some((scale, mean_interval(&values, method)?))
+            })
+            .collect::<Option<BTreeMap<_, _>>>()?;
+        Some(Self {
+            method,
+            high_stress_rate: rate_interval(&high_stress, method)?,
+            scale_means,
+        })
+    }
+}
+
+/// 集計値
+/// 人数が最小人数に満たない場合は秘匿する。
+struct Statistics {
+    respondents: usize,
+    high_stress: Option<usize>,
+    high_stress_rate: Option<f64>,
+    scale_means: Option<BTreeMap<Scale, f64>>,
+    health_risk: Option<HealthRisk>,
+    confidence_intervals: Option<ConfidenceIntervals>,
+    wellbeing_index: Option<WellbeingIndex>,
+}
+
+impl Statistics {
+    /// `wellbeing`を指定した場合はその選定方法でウェルビーイング指数を求める
+    fn new(
+        entries: &[&Entry],
+        min_group_size: usize,
+        interval_method: Option<IntervalMethod>,
+        wellbeing: Option<StressCriteria>,
+    ) -> Self {
+        let respondents = entries.len();
+        if respondents < min_group_size || respondents == 0 {
+            return Self {
+                respondents,
+                high_stress: None,
+                high_stress_rate: None,
+                scale_means: None,
+                health_risk: None,
+                confidence_intervals: None,
+                wellbeing_index: None,
+            };
+        }
+        let high_stress = entries.iter().filter(|entry| entry.has_stress).count();
+        let total_weight: f64 = entries.iter().map(|entry| entry.weight).sum();
+        let weighted_high_stress: f64 = entries
+            .iter()
+            .filter(|entry| entry.has_stress)
+            .map(|entry| entry.weight)
+            .sum();
+        let scale_means = Scale::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, &scale)| {
+                let sum: f64 = entries
+                    .iter()
+                    .map(|entry| entry.scales[index] as f64 * entry.weight)
+                    .sum();
+                (scale, sum / total_weight)
+            })
+            .collect();
+        let wellbeing_index = wellbeing.map(|criteria| {
+            let mean = |score: fn(&(u8, u8, u8)) -> u8| {
+                entries
+                    .iter()
+                    .map(|entry| score(&entry.scores) as f64 * entry.weight)
+                    .sum::<f64>()
+                    / total_weight
+            };
+            WellbeingIndex::from_means(criteria, (mean(|s| s.0), mean(|s| s.1), mean(|s| s.2)))
+        });
+        Self {
+            respondents,
+            high_stress: Some(high_stress),
+            high_stress_rate: Some(weighted_high_stress / total_weight),
+            health_risk: Some(HealthRisk::from_scale_means(&scale_means)),
+            scale_means: Some(scale_means),
+            confidence_intervals: interval_method
+                .and_then(|method| ConfidenceIntervals::new(entries, method)),
+            wellbeing_index,
+        }
+    }
+}
+
+/// 集団ごとの集計結果
+/// 人数が最小人数に満たない集団は集計値が`None`となる。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupSummary {
+    /// 集団名
+    pub key: String,
+    /// 受検者数
+    pub respondents: usize,
+    /// 高ストレス者数
+    pub high_stress: Option<usize>,
+    /// 高ストレス者率
+    pub high_stress_rate: Option<f64>,
+    /// 尺度ごとの素点の平均
+    pub scale_means: Option<BTreeMap<Scale, f64>>,
+    /// 健康リスク
+    pub health_risk: Option<HealthRisk>,
+    /// 信頼区間（`with_confidence_intervals`を指定した場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence_intervals: Option<ConfidenceIntervals>,
+    /// 領域ごとのウェルビーイング指数（`with_wellbeing_index`を指定した場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wellbeing_index: Option<WellbeingIndex>,
+}
+
+impl GroupSummary {
+    /// 少人数のため集計値が秘匿されているか
+    pub fn is_suppressed(&self) -> bool {
+        self.high_stress.is_none()
+    }
+}
+
+/// 2つの分類軸によるクロス集計表
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrossTab {
+    /// 行の分類軸
+    pub rows: Dimension,
+    /// 列の分類軸
+    pub columns: Dimension,
+    pub cells: Vec<CrossTabCell>,
+}
+
+/// クロス集計表のセル
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrossTabCell {
+    /// 行の集団名
+    pub row: String,
+    /// 列の集団名
+    pub column: String,
+    /// 受検者数
+    pub respondents: usize,
+    /// 高ストレス者数
+    pub high_stress: Option<usize>,
+    /// 高ストレス者率
+    pub high_stress_rate: Option<f64>,
+    /// 尺度ごとの素点の平均
+    pub scale_means: Option<BTreeMap<Scale, f64>>,
+    /// 健康リスク
+    pub health_risk: Option<HealthRisk>,
+    /// 信頼区間（`with_confidence_intervals`を指定した場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence_intervals: Option<ConfidenceIntervals>,
+    /// 領域ごとのウェルビーイング指数（`with_wellbeing_index`を指定した場合のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wellbeing_index: Option<WellbeingIndex>,
+}
+
+impl CrossTab {
+    /// 行・列の集団名を指定してセルを取得する
+    pub fn cell(&self, row: &str, column: &str) -> Option<&CrossTabCell> {
+        self.cells
+            .iter()
+            .find(|cell| cell.row == row && cell.column == column)
+    }
+}
+
+/// 組織全体との比較に用いる指標
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Indicator {
+    /// 総合健康リスク
+    TotalHealthRisk,
+    /// 尺度ごとの素点の平均
+    Scale(Scale),
+}
+
+/// 組織全体からの乖離
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Deviation {
+    pub indicator: Indicator,
+    /// 集団の値
+    pub value: f64,
+    /// 組織全体の値
+    pub average: f64,
+    /// 集団間の標準偏差を単位とした乖離
+    pub z_score: f64,
+}
+
+/// 職場環境改善の優先対象となる集団
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Outlier {
+    /// 集団名
+    pub key: String,
+    /// 受検者数
+    pub respondents: usize,
+    /// 総合健康リスク
+    pub total_health_risk: f64,
+    /// 閾値を超えて乖離した指標
+    pub deviations: Vec<Deviation>,
+}
+
+impl Outlier {
+    fn priority(&self) -> f64 {
+        self.deviations
+            .iter()
+            .map(|deviation| deviation.z_score.abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+/// 集団分析
+pub struct GroupAnalysis {
+    entries: Vec<Entry>,
+    min_group_size: usize,
+    excluded: usize,
+    interval_method: Option<IntervalMethod>,
+    criteria: StressCriteria,
+    wellbeing_index: bool,
+}
+
+impl GroupAnalysis {
+    /// 受検記録を判定して集団分析を作成する
+    /// 回答欠落などで判定できない記録は集計から除外する。
+    pub fn new(records: &[CheckRecord], criteria: StressCriteria) -> Self {
+        let mut excluded = 0;
+        let entries = records
+            .iter()
+            .filter_map(|record| {
+                let entry = score_slice(record.answers.values(), criteria).and_then(|scores| {
+                    let intermediate = record.answers.to_intermediate_score()?;
+                    Ok(Entry {
+                        department: record.department.clone(),
+                        demographics: record.demographics.clone().unwrap_or_default(),
+                        has_stress: criteria.judge(scores),
+                        scores,
+                        scales: Scale::ALL.map(|scale| intermediate.get(scale)),
+                        weight: 1.0,
+                    })
+                });
+                if entry.is_err() {
+                    excluded += 1;
+                }
+                entry.ok()
+            })
+            .collect();
+        Self {
+            entries,
+            min_group_size: DEFAULT_MIN_GROUP_SIZE,
+            excluded,
+            interval_method: None,
+            criteria,
+            wellbeing_index: false,
+        }
+    }
+
+    /// 集計値を表示する最小人数を変更する
+    pub fn with_min_group_size(mut self, min_group_size: usize) -> Self {
+        self.min_group_size = min_group_size;
+        self
+    }
+
+    /// 集計対象の人数
+    pub fn respondents(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// 判定できずに除外した記録の数
+    pub fn excluded(&self) -> usize {
+        self.excluded
+    }
+
+    /// 分類軸ごとに高ストレス者率と尺度の平均を集計する
+    pub fn summarize(&self, dimension: Dimension) -> Vec<GroupSummary> {
+        let mut groups: BTreeMap<&str, Vec<&Entry>> = BTreeMap::new();
+        for entry in &self.entries {
+            groups.entry(dimension.key(entry)).or_default().push(entry);
+        }
+        groups
+            .into_iter()
+            .map(|(key, entries)| {
+                let statistics = self.statistics(&entries);
+                GroupSummary {
+                    key: key.to_string(),
+                    respondents: statistics.respondents,
+                    high_stress: statistics.high_stress,
+                    high_stress_rate: statistics.high_stress_rate,
+                    scale_means: statistics.scale_means,
+                    health_risk: statistics.health_risk,
+                    confidence_intervals: statistics.confidence_intervals,
+                    wellbeing_index: statistics.wellbeing_index,
+                }
+            })
+            .collect()
+    }
+
+    /// 分類軸ごとに尺度の素点（`Scale::ALL`の順）を集める
+    pub(crate) fn scale_values(&self, dimension: Dimension) -> BTreeMap<&str, Vec<[u8; 18]>> {
+        let mut groups: BTreeMap<&str, Vec<[u8; 18]>> = BTreeMap::new();
+        for entry in &self.entries {
+            groups
+                .entry(dimension.key(entry))
+                .or_default()
+                .push(entry.scales);
+        }
+        groups
+    }
+
+    /// 集計値に95%信頼区間を付ける
+    pub fn with_confidence_intervals(mut self, method: IntervalMethod) -> Self {
+        self.interval_method = Some(method);
+        self
+    }
+
+    /// 集計値に領域ごとのウェルビーイング指数を付ける
+    pub fn with_wellbeing_index(mut self) -> Self {
+        self.wellbeing_index = true;
+        self
+    }
+
+    fn statistics(&self, entries: &[&Entry]) -> Statistics {
+        Statistics::new(
+            entries,
+            self.min_group_size,
+            self.interval_method,
+            self.wellbeing_index.then_some(self.criteria),
+        )
+    }
+
+    /// 名簿の構成比に合わせて事後層化の重みを付ける
+    ///
+    /// 層（部署または年齢階層）ごとの重みを「名簿での構成比÷受検者での構成比」とし、
+    /// 受検率の低い層の回答を重く扱う。高ストレス者率と尺度の平均が重み付きとなり、
+    /// 高ストレス者数・受検者数は実数のまま。名簿にない層の受検者の重みは1とする。
+    pub fn with_post_stratification(
+        mut self,
+        roster: &Roster,
+        dimension: Dimension,
+    ) -> Result<Self, Error> {
+        let roster_key = |entry: &'_ RosterEntry| -> Result<String, Error> {
+            Ok(match dimension {
+                Dimension::Department => entry.department.as_deref(),
+                Dimension::AgeBand => entry.age_band.map(|band| band.as_str()),
+                _ => return Err(Error::IllegalConfig),
+            }
+            .unwrap_or(UNKNOWN_GROUP)
+            .to_string())
+        };
+        let mut population: BTreeMap<String, usize> = BTreeMap::new();
+        for entry in &roster.entries {
+            *population.entry(roster_key(entry)?).or_default() += 1;
+        }
+        let mut sample: BTreeMap<String, usize> = BTreeMap::new();
+        for entry in &self.entries {
+            *sample.entry(dimension.key(entry).to_string()).or_default() += 1;
+        }
+        let covered = sample
+            .iter()
+            .filter_map(|(key, &n)| Some((key, n, *population.get(key)?)))
+            .collect::<Vec<_>>();
+        let population_total: usize = covered.iter().map(|(_, _, count)| count).sum();
+        let sample_total: usize = covered.iter().map(|(_, n, _)| n).sum();
+        let weights = covered
+            .into_iter()
+            .map(|(key, n, count)| {
+                let weight =
+                    (count as f64 / population_total as f64) / (n as f64 / sample_total as f64);
+                (key.clone(), weight)
+            })
+            .collect::<BTreeMap<_, _>>();
+        for entry in &mut self.entries {
+            entry.weight = weights.get(dimension.key(entry)).copied().unwrap_or(1.0);
+        }
+        Ok(self)
+    }
+
+    /// 集計値を表示する最小人数
+    pub fn min_group_size(&self) -> usize {
+        self.min_group_size
+    }
+
+    /// 2つの分類軸でクロス集計する
+    /// 例えば部署×雇用形態のように、各セルで最小人数による秘匿を行う。
+    pub fn crosstab(&self, rows: Dimension, columns: Dimension) -> CrossTab {
+        let mut groups: BTreeMap<(&str, &str), Vec<&Entry>> = BTreeMap::new();
+        for entry in &self.entries {
+            groups
+                .entry((rows.key(entry), columns.key(entry)))
+                .or_default()
+                .push(entry);
+        }
+        let cells = groups
+            .into_iter()
+            .map(|((row, column), entries)| {
+                let statistics = self.statistics(&entries);
+                CrossTabCell {
+                    row: row.to_string(),
+                    column: column.to_string(),
+                    respondents: statistics.respondents,
+                    high_stress: statistics.high_stress,
+                    high_stress_rate: statistics.high_stress_rate,
+                    scale_means: statistics.scale_means,
+                    health_risk: statistics.health_risk,
+                    confidence_intervals: statistics.confidence_intervals,
+                    wellbeing_index: statistics.wellbeing_index,
+                }
+            })
+            .collect();
+        CrossTab {
+            rows,
+            columns,
+            cells,
+        }
+    }
+
+    /// 組織全体から大きく乖離した集団を抽出する
+    ///
+    /// 秘匿されていない集団について、総合健康リスクと尺度ごとの平均が組織全体の値から
+    /// 集団間の標準偏差の`threshold`倍を超えて乖離しているものを、乖離の大きい順に返す。
+    pub fn outliers(&self, dimension: Dimension, threshold: f64) -> Vec<Outlier> {
+        let all = self.entries.iter().collect::<Vec<_>>();
+        let Statistics {
+            scale_means: Some(average_means),
+            health_risk: Some(average_risk),
+            ..
+        } = Statistics::new(&all, 1, None, None)
+        else {
+            return vec![];
+        };
+        let groups = self
+            .summarize(dimension)
+            .into_iter()
+            .filter_map(|summary| {
+                Some((
+                    summary.key,
+                    summary.respondents,
+                    summary.scale_means?,
+                    summary.health_risk?,
+                ))
+            })
+            .collect::<Vec<_>>();
+        if groups.len() < 2 {
+            return vec![];
+        }
+
+        let indicators = std::iter::once(Indicator::TotalHealthRisk)
+            .chain(Scale::ALL.iter().map(|&scale| Indicator::Scale(scale)))
+            .collect::<Vec<_>>();
+        let deviations = indicators
+            .iter()
+            .map(|indicator| {
+                // 集団間の標準偏差
+                let values = groups
+                    .iter()
+                    .map(|(_, _, means, risk)| value_of(indicator, means, risk))
+                    .collect::<Vec<_>>();
+                let mean = values.iter().sum::<f64>() / values.len() as f64;
+                let variance =
+                    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+                variance.sqrt()
+            })
+            .collect::<Vec<_>>();
+
+        let mut outliers = groups
+            .iter()
+            .filter_map(|(key, respondents, means, risk)| {
+                let flagged = indicators
+                    .iter()
+                    .zip(&deviations)
+                    .filter(|(_, &sd)| sd > 0.0)
+                    .filter_map(|(indicator, sd)| {
+                        let value = value_of(indicator, means, risk);
+                        let average = value_of(indicator, &average_means, &average_risk);
+                        let z_score = (value - average) / sd;
+                        (z_score.abs() > threshold).then_some(Deviation {
+                            indicator: *indicator,
+                            value,
+                            average,
+                            z_score,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                (!flagged.is_empty()).then(|| Outlier {
+                    key: key.clone(),
+                    respondents: *respondents,
+                    total_health_risk: risk.total,
+                    deviations: flagged,
+                })
+            })
+            .collect::<Vec<_>>();
+        outliers.sort_by(|a, b| b.priority().total_cmp(&a.priority()));
+        outliers
+    }
+}
+
+fn value_of(indicator: &Indicator, means: &BTreeMap<Scale, f64>, risk: &HealthRisk) -> f64 {
+    match indicator {
+        Indicator::TotalHealthRisk => risk.total,
+        Indicator::Scale(scale) => means[scale],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::demographics::{AgeBand, EmploymentType};
+    use crate::{AnswerStore, Stress};
+
+    fn record(department: &str, age_band: Option<AgeBand>, answer: u8) -> CheckRecord {
+        let mut answers = AnswerStore::default();
+        for _ in 0..57 {
+            answers.push(answer).unwrap();
+        }
+        CheckRecord {
+            id: department.to_string(),
+            department: Some(GroupCode::new(department).unwrap()),
+            demographics: age_band.map(|age_band| Demographics {
+                age_band: Some(age_band),
+                employment_type: Some(EmploymentType::Regular),
+                tenure: None,
+            }),
+            consent: None,
+            answers,
+            amendments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_confidence_intervals() {
+        let mut records = (0..40)
+            .map(|i| record("営業部", None, if i % 4 == 0 { 4 } else { 1 }))
+            .collect::<Vec<_>>();
+        records.extend((0..10).map(|i| record("総務部", None, if i % 4 == 0 { 4 } else { 1 })));
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP);
+        assert!(analysis.summarize(Dimension::Department)[0]
+            .confidence_intervals
+            .is_none());
+
+        for method in [IntervalMethod::Normal, IntervalMethod::BOOTSTRAP] {
+            let summaries = GroupAnalysis::new(&records, StressCriteria::SUMUP)
+                .with_confidence_intervals(method)
+                .summarize(Dimension::Department);
+            let width = |summary: &GroupSummary| {
+                let intervals = summary.confidence_intervals.as_ref().unwrap();
+                assert_eq!(intervals.method, method);
+                assert!(intervals
+                    .high_stress_rate
+                    .contains(summary.high_stress_rate.unwrap()));
+                intervals.high_stress_rate.upper - intervals.high_stress_rate.lower
+            };
+            // 人数の少ない総務部の方が区間が広い
+            assert!(width(&summaries[1]) > width(&summaries[0]));
+        }
+    }
+
+    #[test]
+    fn test_wellbeing_index() {
+        let mut records = (0..10)
+            .map(|i| record("営業部", None, if i < 5 { 4 } else { 1 }))
+            .collect::<Vec<_>>();
+        records.push(record("総務部", None, 4));
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP);
+        assert!(analysis.summarize(Dimension::Department)[0]
+            .wellbeing_index
+            .is_none());
+
+        let summaries = analysis
+            .with_wellbeing_index()
+            .summarize(Dimension::Department);
+        let scores = |answer| {
+            let mut store = AnswerStore::default();
+            for _ in 0..57 {
+                store.push(answer).unwrap();
+            }
+            store.to_sumup_score().unwrap().scores()
+        };
+        let (high, low) = (scores(4), scores(1));
+        let mean = (
+            (high.0 as f64 + low.0 as f64) / 2.0,
+            (high.1 as f64 + low.1 as f64) / 2.0,
+            (high.2 as f64 + low.2 as f64) / 2.0,
+        );
+        assert_eq!(
+            summaries[0].wellbeing_index,
+            Some(WellbeingIndex::from_means(StressCriteria::SUMUP, mean))
+        );
+        assert!(summaries[1].wellbeing_index.is_none());
+    }
+
+    #[test]
+    fn test_post_stratification() {
+        // 営業部は10人中2人、総務部は10人中8人が受検
+        let mut records = (0..2)
+            .map(|_| record("営業部", Some(AgeBand::Under30), 4))
+            .collect::<Vec<_>>();
+        records.extend((0..8).map(|_| record("総務部", Some(AgeBand::Under30), 1)));
+        let mut csv = "token,department\n".to_string();
+        for i in 0..10 {
+            csv.push_str(&format!("s{},営業部\ng{},総務部\n", i, i));
+        }
+        let roster = Roster::from_reader(csv.as_bytes()).unwrap();
+
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP).with_min_group_size(2);
+        let summaries = analysis.summarize(Dimension::AgeBand);
+        assert_eq!(summaries[0].high_stress_rate, Some(0.2));
+
+        let weighted = analysis
+            .with_post_stratification(&roster, Dimension::Department)
+            .unwrap();
+        let summaries = weighted.summarize(Dimension::AgeBand);
+        assert_eq!(summaries[0].respondents, 10);
+        assert_eq!(summaries[0].high_stress, Some(2));
+        assert_eq!(summaries[0].high_stress_rate, Some(0.5));
+        // 層の中では重みが等しいため部署ごとの集計は変わらない
+        let summaries = weighted.summarize(Dimension::Department);
+        assert_eq!(summaries[0].high_stress_rate, Some(1.0));
+
+        assert!(matches!(
+            GroupAnalysis::new(&records, StressCriteria::SUMUP)
+                .with_post_stratification(&roster, Dimension::Tenure),
+            Err(Error::IllegalConfig)
+        ));
+    }
+
+    #[test]
+    fn test_summarize_department() {
+        let mut records = (0..10)
+            .map(|i| record("営業部", None, if i < 3 { 4 } else { 1 }))
+            .collect::<Vec<_>>();
+        records.push(record("総務部", None, 4));
+        records.push(CheckRecord {
+            answers: AnswerStore::default(),
+            amendments: vec![],
+            ..record("総務部", None, 1)
+        });
+
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP);
+        assert_eq!(analysis.respondents(), 11);
+        assert_eq!(analysis.excluded(), 1);
+
+        let summaries = analysis.summarize(Dimension::Department);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].key, "営業部");
+        assert_eq!(summaries[0].respondents, 10);
+        assert_eq!(summaries[0].high_stress, Some(3));
+        assert_eq!(summaries[0].high_stress_rate, Some(0.3));
+        assert_eq!(summaries[1].key, "総務部");
+        assert!(summaries[1].is_suppressed());
+    }
+
+    #[test]
+    fn test_summarize_demographics() {
+        let records = vec![
+            record("営業部", Some(AgeBand::Thirties), 4),
+            record("営業部", Some(AgeBand::Thirties), 1),
+            record("営業部", None, 1),
+        ];
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP).with_min_group_size(2);
+
+        let summaries = analysis.summarize(Dimension::AgeBand);
+        assert_eq!(summaries[0].key, "thirties");
+        assert_eq!(summaries[0].high_stress_rate, Some(0.5));
+        assert_eq!(summaries[1].key, UNKNOWN_GROUP);
+        assert!(summaries[1].is_suppressed());
+
+        let summaries = analysis.summarize(Dimension::EmploymentType);
+        assert_eq!(summaries[0].key, "regular");
+        assert_eq!(summaries[0].respondents, 2);
+        let means = summaries[0].scale_means.as_ref().unwrap();
+        assert_eq!(means[&Scale::MentalWorkStressVolume], 7.5);
+    }
+
+    #[test]
+    fn test_crosstab() {
+        let records = vec![
+            record("営業部", Some(AgeBand::Thirties), 4),
+            record("営業部", Some(AgeBand::Thirties), 4),
+            record("営業部", Some(AgeBand::Forties), 1),
+            record("総務部", Some(AgeBand::Thirties), 1),
+        ];
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP).with_min_group_size(2);
+        let table = analysis.crosstab(Dimension::Department, Dimension::AgeBand);
+        assert_eq!(table.cells.len(), 3);
+
+        let cell = table.cell("営業部", "thirties").unwrap();
+        assert_eq!(cell.respondents, 2);
+        assert_eq!(cell.high_stress_rate, Some(1.0));
+        assert!(table
+            .cell("営業部", "forties")
+            .unwrap()
+            .scale_means
+            .is_none());
+        assert!(table.cell("総務部", "forties").is_none());
+
+        let json = serde_json::to_value(&table).unwrap();
+        assert_eq!(json["rows"], "department");
+        assert!(json["cells"][0]["scale_means"].is_null());
+        assert_eq!(json["cells"][1]["scale_means"]["work_control"], 3.0);
+    }
+
+    #[test]
+    fn test_health_risk() {
+        let means = BTreeMap::from([
+            (Scale::MentalWorkStressVolume, 8.7),
+            (Scale::WorkControl, 7.9),
+            (Scale::BossSupport, 7.6),
+            (Scale::ColleagueSupport, 8.1),
+        ]);
+        let risk = HealthRisk::from_scale_means(&means);
+        assert!((risk.volume_control - 100.0).abs() < 1e-9);
+        assert!((risk.support - 100.0).abs() < 1e-9);
+        assert!((risk.total - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_outliers() {
+        let mut records = vec![];
+        for department in ["営業部", "総務部", "人事部", "経理部"] {
+            for _ in 0..2 {
+                records.push(record(department, None, 2));
+            }
+        }
+        records.push(record("開発部", None, 4));
+        records.push(record("開発部", None, 4));
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP).with_min_group_size(2);
+
+        let outliers = analysis.outliers(Dimension::Department, 1.5);
+        assert_eq!(outliers.len(), 1);
+        assert_eq!(outliers[0].key, "開発部");
+        assert!(outliers[0]
+            .deviations
+            .iter()
+            .any(|deviation| deviation.indicator == Indicator::TotalHealthRisk));
+        assert!(outliers[0].total_health_risk > 100.0);
+
+        assert!(analysis.outliers(Dimension::Department, 3.0).is_empty());
+    }
+}
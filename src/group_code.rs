@@ -0,0 +1,234 @@
+//! 部署などの集団コード
+//!
+//! 人事システムや調査業者のファイルでは、同じ部署が`営業部 `・`営業部`・`ＳＡＬＥＳ`のように
+//! 表記揺れすることが多く、そのまま集計すると別の集団になってしまう。読み込む際に[`GroupCode`]へ
+//! 正規化し、表記の違いだけの集団をまとめる。
+//!
+//! 正規化はNFKC（全角英数・半角カナの統一）、前後の空白の除去、連続する空白の1つへの置き換えとし、
+//! 英字の大文字・小文字は[`GroupCodePolicy`]の指定に従う。組織のコード体系に合わせた検証も追加できる。
+
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::Error;
+
+/// 正規化した集団コード
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct GroupCode(String);
+
+impl GroupCode {
+    /// 既定の規則（大文字・小文字はそのまま）で正規化する
+    /// 正規化の結果が空の場合は`Error::IllegalGroupCode`とする。
+    pub fn new(value: &str) -> Result<Self, Error> {
+        GroupCodePolicy::default().parse(value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for GroupCode {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for GroupCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl TryFrom<String> for GroupCode {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Error> {
+        GroupCode::new(&value)
+    }
+}
+
+impl TryFrom<&str> for GroupCode {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Error> {
+        GroupCode::new(value)
+    }
+}
+
+impl From<GroupCode> for String {
+    fn from(value: GroupCode) -> Self {
+        value.0
+    }
+}
+
+/// 英字の大文字・小文字の扱い
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseRule {
+    /// そのまま
+    #[default]
+    Preserve,
+    /// 大文字にそろえる
+    Upper,
+    /// 小文字にそろえる
+    Lower,
+}
+
+/// 集団コードを検証する関数
+/// 不正な場合は理由を返す。
+pub type GroupCodeValidator = Arc<dyn Fn(&GroupCode) -> Result<(), String> + Send + Sync>;
+
+/// 集団コードの正規化と検証の規則
+#[derive(Clone, Default)]
+pub struct GroupCodePolicy {
+    case: CaseRule,
+    validator: Option<GroupCodeValidator>,
+}
+
+impl fmt::Debug for GroupCodePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GroupCodePolicy")
+            .field("case", &self.case)
+            .field("validator", &self.validator.is_some())
+            .finish()
+    }
+}
+
+impl GroupCodePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_case(mut self, case: CaseRule) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// 正規化した後に呼ぶ検証を設定する
+    pub fn with_validator<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&GroupCode) -> Result<(), String> + Send + Sync + 'static,
+    {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// 正規化した文字列（空の場合もそのまま返す）
+    pub fn normalize(&self, value: &str) -> String {
+        let value = value
+            .nfkc()
+            .collect::<String>()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        match self.case {
+            CaseRule::Preserve => value,
+            CaseRule::Upper => value.to_uppercase(),
+            CaseRule::Lower => value.to_lowercase(),
+        }
+    }
+
+    /// 正規化して検証する
+    pub fn parse(&self, value: &str) -> Result<GroupCode, Error> {
+        let normalized = self.normalize(value);
+        if normalized.is_empty() {
+            return Err(Error::IllegalGroupCode(value.to_string()));
+        }
+        let code = GroupCode(normalized);
+        if let Some(validator) = &self.validator {
+            validator(&code)
+                .map_err(|reason| Error::IllegalGroupCode(format!("{}: {}", code, reason)))?;
+        }
+        Ok(code)
+    }
+
+    /// 値が空白だけの場合は`None`とする
+    pub fn parse_optional(&self, value: &str) -> Result<Option<GroupCode>, Error> {
+        if value.trim().is_empty() {
+            Ok(None)
+        } else {
+            self.parse(value).map(Some)
+        }
+    }
+}
+
+/// 空白だけの値を`None`とする、既定の規則での読み込み
+/// CSVの列に`#[serde(default, deserialize_with = "...")]`で指定する。
+pub fn deserialize_optional<'de, D>(deserializer: D) -> Result<Option<GroupCode>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = Option::<String>::deserialize(deserializer)?;
+    match value {
+        Some(value) => GroupCodePolicy::default()
+            .parse_optional(&value)
+            .map_err(serde::de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(GroupCode::new(" 営業部　").unwrap().as_str(), "営業部");
+        assert_eq!(
+            GroupCode::new("営業部").unwrap(),
+            GroupCode::new("営業部 ").unwrap()
+        );
+        assert_eq!(
+            GroupCode::new("ＳＡＬＥＳ  １課").unwrap().as_str(),
+            "SALES 1課"
+        );
+        assert_eq!(GroupCode::new("ｴｲｷﾞｮｳ").unwrap().as_str(), "エイギョウ");
+        assert!(matches!(
+            GroupCode::new(" 　"),
+            Err(Error::IllegalGroupCode(_))
+        ));
+
+        let policy = GroupCodePolicy::new()
+            .with_case(CaseRule::Upper)
+            .with_validator(|code| {
+                code.chars()
+                    .all(|c| c.is_ascii_alphanumeric())
+                    .then_some(())
+                    .ok_or_else(|| "英数字のみ".to_string())
+            });
+        assert_eq!(policy.parse("ｓａｌｅｓ1").unwrap().as_str(), "SALES1");
+        assert!(matches!(
+            policy.parse("営業部"),
+            Err(Error::IllegalGroupCode(e)) if e.contains("英数字のみ")
+        ));
+        assert_eq!(policy.parse_optional("  ").unwrap(), None);
+
+        let csv = format!(
+            "id,{},department\na,{},営業部 \nb,{},営業部\nc,{}, \n",
+            (1..=57)
+                .map(|i| format!("q_{}", i))
+                .collect::<Vec<_>>()
+                .join(","),
+            vec!["2"; 57].join(","),
+            vec!["2"; 57].join(","),
+            vec!["2"; 57].join(",")
+        );
+        let records = crate::read_bulk_records(csv.as_bytes())
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records[0].department, records[1].department);
+        assert_eq!(records[2].department, None);
+
+        let code: GroupCode = serde_json::from_str("\" 総務部\"").unwrap();
+        assert_eq!(serde_json::to_string(&code).unwrap(), "\"総務部\"");
+    }
+}
@@ -0,0 +1,158 @@
+//! CSVの列名の別名
+//!
+//! 顧客ごとに異なる列名（`設問1`・`Q1`・`社員番号`など）を、一括判定の列名（`q_1`・`id`など）に
+//! そろえてから読み込む。組み込みの別名に加え、実施設定の`header_aliases`で追加できる。
+
+use std::collections::BTreeMap;
+
+use crate::encoding::normalize_digits;
+
+/// 設問の列名の接頭辞（番号の前に付くもの）
+const QUESTION_PREFIXES: [&str; 5] = ["q", "question", "設問", "質問", "問"];
+
+/// 組み込みの別名と対応する列名
+const BUILTIN_ALIASES: [(&str, &str); 17] = [
+    ("id", "id"),
+    ("employee_id", "id"),
+    ("社員番号", "id"),
+    ("従業員番号", "id"),
+    ("個人id", "id"),
+    ("ユーザid", "id"),
+    ("department", "department"),
+    ("dept", "department"),
+    ("所属", "department"),
+    ("部署", "department"),
+    ("部署名", "department"),
+    ("age_band", "age_band"),
+    ("年代", "age_band"),
+    ("employment_type", "employment_type"),
+    ("雇用形態", "employment_type"),
+    ("tenure", "tenure"),
+    ("勤続年数", "tenure"),
+];
+
+/// 一括判定で読み込む列名か
+pub fn is_column(name: &str) -> bool {
+    matches!(
+        name,
+        "id" | "department" | "age_band" | "employment_type" | "tenure"
+    ) || question_no(name, "q_").is_some()
+}
+
+/// 大文字・小文字、空白・区切り記号、全角数字の違いを無視するための正規化
+fn normalize(name: &str) -> String {
+    normalize_digits(name.trim())
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '　' | '_' | '-'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 接頭辞に続く設問番号（1〜57）
+fn question_no(name: &str, prefix: &str) -> Option<u8> {
+    let no = name.strip_prefix(prefix)?.parse::<u8>().ok()?;
+    (1..=57).contains(&no).then_some(no)
+}
+
+/// 列名の別名表
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderAliases {
+    aliases: BTreeMap<String, String>,
+}
+
+impl Default for HeaderAliases {
+    fn default() -> Self {
+        Self {
+            aliases: BUILTIN_ALIASES
+                .iter()
+                .map(|(alias, column)| (normalize(alias), column.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl HeaderAliases {
+    /// 別名を追加する（別名→列名）
+    /// 組み込みの別名と重なる場合は追加したものを優先する。
+    pub fn with_aliases(mut self, aliases: &BTreeMap<String, String>) -> Self {
+        self.aliases.extend(
+            aliases
+                .iter()
+                .map(|(alias, column)| (normalize(alias), column.clone())),
+        );
+        self
+    }
+
+    /// 列名に対応する一括判定の列名
+    pub fn resolve(&self, header: &str) -> Option<String> {
+        let name = normalize(header);
+        if let Some(column) = self.aliases.get(&name) {
+            return Some(column.clone());
+        }
+        QUESTION_PREFIXES
+            .iter()
+            .find_map(|prefix| question_no(&name, prefix))
+            .map(|no| format!("q_{}", no))
+    }
+
+    /// 見出し行の列名を置き換える。対応しない列名はそのまま残す
+    pub fn rename(&self, headers: &csv::StringRecord) -> csv::StringRecord {
+        headers
+            .iter()
+            .map(|header| self.resolve(header).unwrap_or_else(|| header.to_string()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let aliases = HeaderAliases::default();
+        for header in [
+            "q_1",
+            "Q1",
+            "q 1",
+            "設問1",
+            "設問１",
+            "質問 1",
+            "問1",
+            "Question-1",
+        ] {
+            assert_eq!(
+                aliases.resolve(header).as_deref(),
+                Some("q_1"),
+                "{}",
+                header
+            );
+        }
+        assert_eq!(aliases.resolve("設問57").as_deref(), Some("q_57"));
+        assert_eq!(aliases.resolve("設問58"), None);
+        assert_eq!(aliases.resolve("社員番号").as_deref(), Some("id"));
+        assert_eq!(aliases.resolve("Employee ID").as_deref(), Some("id"));
+        assert_eq!(aliases.resolve("部署名").as_deref(), Some("department"));
+        assert_eq!(aliases.resolve("備考"), None);
+
+        let aliases = HeaderAliases::default().with_aliases(&BTreeMap::from([(
+            "職員コード".to_string(),
+            "id".to_string(),
+        )]));
+        assert_eq!(aliases.resolve("職員コード").as_deref(), Some("id"));
+        let headers = aliases.rename(&csv::StringRecord::from(vec![
+            "職員コード",
+            "設問2",
+            "備考",
+        ]));
+        assert_eq!(headers, csv::StringRecord::from(vec!["id", "q_2", "備考"]));
+    }
+
+    #[test]
+    fn test_is_column() {
+        assert!(is_column("id"));
+        assert!(is_column("q_57"));
+        assert!(!is_column("q_58"));
+        assert!(!is_column("q1"));
+    }
+}
@@ -0,0 +1,200 @@
+//! 名簿と受検記録のIDの照合支援
+//!
+//! 名簿と受検記録を突き合わせると、入力の誤りで一致しないIDが必ず出る。一致しなかった受検記録と
+//! 未受検の対象者の間で、表記揺れ（大文字・小文字、全角・半角）、先頭の0の有無、1文字の違い
+//! （レーベンシュタイン距離1）で一致する組を探し、確認用の候補として示す。
+//! 候補は自動では結び付けない。担当者が確認したうえで元のデータを直す。
+
+use std::io::Write;
+
+use serde::Serialize;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::export::{Sanitizer, SanitizingWriter};
+use crate::roster::{RosterEntry, RosterJoin};
+use crate::Error;
+
+/// 候補とした理由（確からしい順）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchReason {
+    /// 大文字・小文字、全角・半角、前後の空白だけが異なる
+    Normalized,
+    /// 先頭の0の有無だけが異なる
+    LeadingZeros,
+    /// 1文字の追加・削除・置き換えで一致する
+    OneEdit,
+}
+
+/// 照合の候補
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatchSuggestion<'a> {
+    /// 受検記録のID
+    pub record_id: &'a str,
+    /// 名簿のトークン
+    pub roster_token: &'a str,
+    pub reason: MatchReason,
+}
+
+/// 比較のために正規化したID（NFKC・小文字・前後の空白の除去）
+pub fn normalize_id(id: &str) -> String {
+    id.nfkc().collect::<String>().trim().to_lowercase()
+}
+
+/// 先頭の0を除いたID（全て0の場合は`0`）
+fn trim_leading_zeros(id: &str) -> &str {
+    let trimmed = id.trim_start_matches('0');
+    if trimmed.is_empty() && !id.is_empty() {
+        "0"
+    } else {
+        trimmed
+    }
+}
+
+/// レーベンシュタイン距離が1以下か
+fn within_one_edit(a: &str, b: &str) -> bool {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if long.len() - short.len() > 1 {
+        return false;
+    }
+    let prefix = short
+        .iter()
+        .zip(long.iter())
+        .take_while(|(x, y)| x == y)
+        .count();
+    if short.len() == long.len() {
+        short[prefix..]
+            .iter()
+            .skip(1)
+            .eq(long[prefix..].iter().skip(1))
+    } else {
+        short[prefix..].iter().eq(long[prefix..].iter().skip(1))
+    }
+}
+
+/// 2つのIDを候補とする理由
+pub fn match_reason(record_id: &str, roster_token: &str) -> Option<MatchReason> {
+    if record_id == roster_token {
+        return None;
+    }
+    let record_id = normalize_id(record_id);
+    let roster_token = normalize_id(roster_token);
+    if record_id == roster_token {
+        Some(MatchReason::Normalized)
+    } else if trim_leading_zeros(&record_id) == trim_leading_zeros(&roster_token) {
+        Some(MatchReason::LeadingZeros)
+    } else if within_one_edit(&record_id, &roster_token) {
+        Some(MatchReason::OneEdit)
+    } else {
+        None
+    }
+}
+
+impl<'a> RosterJoin<'a> {
+    /// 名簿にない受検記録と未受検の対象者の間の照合の候補
+    /// 受検記録ごとに最も確からしい理由の候補だけを示す（同じ理由の候補が複数あれば全て示す）。
+    pub fn suggestions(&self) -> Vec<MatchSuggestion<'a>> {
+        let mut suggestions = vec![];
+        for record in &self.unknown {
+            let candidates = self
+                .missing
+                .iter()
+                .filter_map(|entry: &&'a RosterEntry| {
+                    Some((match_reason(&record.id, &entry.token)?, *entry))
+                })
+                .collect::<Vec<_>>();
+            let Some(best) = candidates.iter().map(|(reason, _)| *reason).min() else {
+                continue;
+            };
+            suggestions.extend(
+                candidates
+                    .into_iter()
+                    .filter(|(reason, _)| *reason == best)
+                    .map(|(reason, entry)| MatchSuggestion {
+                        record_id: &record.id,
+                        roster_token: &entry.token,
+                        reason,
+                    }),
+            );
+        }
+        suggestions
+    }
+}
+
+/// 照合の候補をCSVで書き出す（列は`record_id, roster_token, reason`）
+pub fn write_suggestions_csv<W: Write>(
+    writer: W,
+    suggestions: &[MatchSuggestion],
+    sanitizer: &Sanitizer,
+) -> Result<(), Error> {
+    let mut writer = SanitizingWriter::new(writer, sanitizer);
+    for suggestion in suggestions {
+        writer.serialize(suggestion)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::roster::Roster;
+    use crate::CheckRecord;
+
+    #[test]
+    fn test_match_reason() {
+        assert_eq!(match_reason("A001", "A001"), None);
+        assert_eq!(
+            match_reason("ａ００１ ", "A001"),
+            Some(MatchReason::Normalized)
+        );
+        assert_eq!(match_reason("0123", "123"), Some(MatchReason::LeadingZeros));
+        assert_eq!(match_reason("000", "0"), Some(MatchReason::LeadingZeros));
+        assert_eq!(match_reason("A1234", "A1284"), Some(MatchReason::OneEdit));
+        assert_eq!(match_reason("A1234", "A124"), Some(MatchReason::OneEdit));
+        assert_eq!(match_reason("A1234", "A12345"), Some(MatchReason::OneEdit));
+        assert_eq!(match_reason("A1234", "A1243"), None);
+        assert_eq!(match_reason("A1234", "B9999"), None);
+    }
+
+    #[test]
+    fn test_suggestions() {
+        let roster =
+            Roster::from_reader("id\nE1001\nE1002\nE1003\n00777\nK5000\n".as_bytes()).unwrap();
+        let records = ["e1001", "E1003", "777", "E1004", "Z9999"]
+            .iter()
+            .map(|id| CheckRecord {
+                id: id.to_string(),
+                department: None,
+                demographics: None,
+                consent: None,
+                answers: Default::default(),
+                amendments: vec![],
+            })
+            .collect::<Vec<_>>();
+        let join = roster.join(&records);
+        let suggestions = join.suggestions();
+        assert_eq!(
+            suggestions
+                .iter()
+                .map(|s| (s.record_id, s.roster_token, s.reason))
+                .collect::<Vec<_>>(),
+            vec![
+                ("e1001", "E1001", MatchReason::Normalized),
+                ("777", "00777", MatchReason::LeadingZeros),
+                ("E1004", "E1001", MatchReason::OneEdit),
+                ("E1004", "E1002", MatchReason::OneEdit),
+            ]
+        );
+        // 候補は結び付けない
+        assert_eq!(join.summary().matched, 1);
+
+        let mut csv = vec![];
+        write_suggestions_csv(&mut csv, &suggestions[..1], &Sanitizer::default()).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "record_id,roster_token,reason\ne1001,E1001,normalized\n"
+        );
+    }
+}
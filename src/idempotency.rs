@@ -0,0 +1,157 @@
+//! 送信の冪等性キー
+//!
+//! 社内ネットワークの不調で同じ回答が再送信されても記録が重複しないよう、サーバ側で送信ごとの
+//! 冪等性キー（`Idempotency-Key`ヘッダなど）と判定結果を一定時間保持し、再送信には最初に
+//! 算出した結果を返す。同じキーで異なる内容が送られた場合は`IdempotencyConflict`（409）とする。
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::{CheckRecord, CheckResult, Error};
+
+/// 冪等性キーの最大長（バイト）
+pub const MAX_KEY_LENGTH: usize = 255;
+/// 結果を保持する既定の期間
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone)]
+struct Entry {
+    id: String,
+    answers: [u8; 57],
+    result: CheckResult,
+    created: Instant,
+}
+
+/// 受け付けた送信の結果
+#[derive(Debug, Clone, PartialEq)]
+pub struct Submission {
+    pub result: CheckResult,
+    /// 再送信に対して保持していた結果を返したか
+    pub replayed: bool,
+}
+
+/// 冪等性キーごとの判定結果
+/// 判定に失敗した送信は保持しないため、再送信では改めて判定する。
+#[derive(Debug)]
+pub struct IdempotencyCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl Default for IdempotencyCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_TTL)
+    }
+}
+
+impl IdempotencyCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 送信を受け付ける
+    /// 有効なキーで同じ内容が送られていれば`score`を呼ばずに保持していた結果を返す。
+    pub fn submit<F>(
+        &self,
+        key: &str,
+        record: &CheckRecord,
+        now: Instant,
+        score: F,
+    ) -> Result<Submission, Error>
+    where
+        F: FnOnce(&CheckRecord) -> Result<CheckResult, Error>,
+    {
+        if key.len() > MAX_KEY_LENGTH {
+            return Err(Error::PayloadTooLarge(format!(
+                "idempotency key {} bytes",
+                MAX_KEY_LENGTH
+            )));
+        }
+        let mut entries = match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some(entry) = entries.get(key) {
+            if now.saturating_duration_since(entry.created) < self.ttl {
+                if entry.id != record.id || &entry.answers != record.answers.values() {
+                    return Err(Error::IdempotencyConflict(key.to_string()));
+                }
+                return Ok(Submission {
+                    result: entry.result.clone(),
+                    replayed: true,
+                });
+            }
+        }
+        let result = score(record)?;
+        entries.insert(
+            key.to_string(),
+            Entry {
+                id: record.id.clone(),
+                answers: *record.answers.values(),
+                result: result.clone(),
+                created: now,
+            },
+        );
+        Ok(Submission {
+            result,
+            replayed: false,
+        })
+    }
+
+    /// 保持期間を過ぎた結果を破棄し、破棄した件数を返す
+    pub fn purge(&self, now: Instant) -> usize {
+        let mut entries = match self.entries.lock() {
+            Ok(entries) => entries,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let before = entries.len();
+        entries.retain(|_, entry| now.saturating_duration_since(entry.created) < self.ttl);
+        before - entries.len()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simulate::Population;
+    use crate::StressCriteria;
+
+    #[test]
+    fn test_submit() {
+        let cache = IdempotencyCache::new(Duration::from_secs(60));
+        let records = Population::new(2, 1).with_seed(5).generate();
+        let score = |record: &CheckRecord| CheckResult::new(&record.answers, StressCriteria::SUMUP);
+        let start = Instant::now();
+
+        let first = cache.submit("key-1", &records[0], start, score).unwrap();
+        assert!(!first.replayed);
+        let retried = cache
+            .submit("key-1", &records[0], start, |_| panic!("scored twice"))
+            .unwrap();
+        assert!(retried.replayed);
+        assert_eq!(retried.result, first.result);
+
+        let error = cache
+            .submit("key-1", &records[1], start, score)
+            .unwrap_err();
+        assert_eq!(error.code(), "E_IDEMPOTENCY_CONFLICT");
+        assert_eq!(error.http_status(), 409);
+
+        let later = start + Duration::from_secs(60);
+        assert!(
+            !cache
+                .submit("key-1", &records[1], later, score)
+                .unwrap()
+                .replayed
+        );
+        assert_eq!(cache.purge(later + Duration::from_secs(60)), 1);
+        assert!(matches!(
+            cache.submit(&"k".repeat(256), &records[0], start, score),
+            Err(Error::PayloadTooLarge(_))
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! 集計値の信頼区間
+//!
+//! 少人数の集団の差を実際以上に確かなものとして示さないよう、集団分析の高ストレス者率と
+//! 尺度の平均に95%信頼区間を付ける。正規近似とブートストラップ（パーセンタイル法）から
+//! 選べる。事後層化の重みがある場合は重み付きの推定量について区間を求める。
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// 信頼水準
+pub const CONFIDENCE_LEVEL: f64 = 0.95;
+/// 信頼水準95%の標準正規分布の分位点
+const Z: f64 = 1.959963984540054;
+/// ブートストラップの既定の反復回数
+pub const DEFAULT_RESAMPLES: usize = 1000;
+
+/// 信頼区間の算出方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum IntervalMethod {
+    /// 正規近似
+    Normal,
+    /// ブートストラップ（パーセンタイル法）。同じ`seed`であれば同じ区間となる
+    Bootstrap { resamples: usize, seed: u64 },
+}
+
+impl IntervalMethod {
+    /// 既定の反復回数によるブートストラップ
+    pub const BOOTSTRAP: IntervalMethod = IntervalMethod::Bootstrap {
+        resamples: DEFAULT_RESAMPLES,
+        seed: 0,
+    };
+}
+
+/// 信頼区間
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Interval {
+    pub fn contains(&self, value: f64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+
+    fn clamp(self, min: f64, max: f64) -> Self {
+        Self {
+            lower: self.lower.clamp(min, max),
+            upper: self.upper.clamp(min, max),
+        }
+    }
+}
+
+fn weighted_mean(values: &[(f64, f64)]) -> f64 {
+    let total: f64 = values.iter().map(|(_, weight)| weight).sum();
+    values
+        .iter()
+        .map(|(value, weight)| value * weight)
+        .sum::<f64>()
+        / total
+}
+
+fn normal(values: &[(f64, f64)]) -> Interval {
+    let n = values.len() as f64;
+    let total: f64 = values.iter().map(|(_, weight)| weight).sum();
+    let mean = weighted_mean(values);
+    // 重み付き平均の線形化による分散推定（重みが等しい場合は標本分散/n）
+    let variance = values
+        .iter()
+        .map(|(value, weight)| (weight * (value - mean)).powi(2))
+        .sum::<f64>()
+        / (total * total)
+        * n
+        / (n - 1.0).max(1.0);
+    let margin = Z * variance.sqrt();
+    Interval {
+        lower: mean - margin,
+        upper: mean + margin,
+    }
+}
+
+fn bootstrap(values: &[(f64, f64)], resamples: usize, seed: u64) -> Interval {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut means = (0..resamples.max(1))
+        .map(|_| {
+            let sample = (0..values.len())
+                .map(|_| values[rng.gen_range(0..values.len())])
+                .collect::<Vec<_>>();
+            weighted_mean(&sample)
+        })
+        .collect::<Vec<_>>();
+    means.sort_by(f64::total_cmp);
+    let alpha = (1.0 - CONFIDENCE_LEVEL) / 2.0;
+    let quantile = |p: f64| means[((means.len() - 1) as f64 * p).round() as usize];
+    Interval {
+        lower: quantile(alpha),
+        upper: quantile(1.0 - alpha),
+    }
+}
+
+/// 値と重みの組から平均の信頼区間を求める
+/// 値が空の場合は`None`。
+pub fn mean_interval(values: &[(f64, f64)], method: IntervalMethod) -> Option<Interval> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(match method {
+        IntervalMethod::Normal => normal(values),
+        IntervalMethod::Bootstrap { resamples, seed } => bootstrap(values, resamples, seed),
+    })
+}
+
+/// 該当の有無（1・0）と重みの組から割合の信頼区間を求める
+pub fn rate_interval(values: &[(f64, f64)], method: IntervalMethod) -> Option<Interval> {
+    mean_interval(values, method).map(|interval| interval.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn unweighted(values: &[f64]) -> Vec<(f64, f64)> {
+        values.iter().map(|&value| (value, 1.0)).collect()
+    }
+
+    #[test]
+    fn test_mean_interval() {
+        let values = unweighted(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+        let interval = mean_interval(&values, IntervalMethod::Normal).unwrap();
+        // 平均5、標本標準偏差約2.14
+        assert!((interval.lower - 3.518).abs() < 1e-3, "{:?}", interval);
+        assert!((interval.upper - 6.482).abs() < 1e-3, "{:?}", interval);
+
+        let bootstrap = mean_interval(&values, IntervalMethod::BOOTSTRAP).unwrap();
+        assert!(bootstrap.contains(5.0));
+        assert!(bootstrap.lower > 3.0 && bootstrap.upper < 7.0);
+        assert_eq!(
+            mean_interval(&values, IntervalMethod::BOOTSTRAP),
+            Some(bootstrap)
+        );
+        assert_eq!(mean_interval(&[], IntervalMethod::Normal), None);
+    }
+
+    #[test]
+    fn test_rate_interval() {
+        let small =
+            rate_interval(&unweighted(&[1.0, 0.0, 0.0, 0.0]), IntervalMethod::Normal).unwrap();
+        let large = rate_interval(
+            &unweighted(&[[1.0, 0.0, 0.0, 0.0]; 25].concat()),
+            IntervalMethod::Normal,
+        )
+        .unwrap();
+        assert_eq!(small.lower, 0.0);
+        assert!(small.upper - small.lower > large.upper - large.lower);
+        assert!(large.contains(0.25));
+    }
+}
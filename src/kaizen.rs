@@ -0,0 +1,272 @@
+//! 職場環境改善のためのヒント集（メンタルヘルスアクションチェックリスト）への対応付け
+//!
+//! 集団分析の結果から好ましくない尺度の組み合わせを検出し、ヒント集の6領域と
+//! 改善アクションの例を提示する。集団分析後の話し合いの材料とすることを想定している。
+//! 詳細 https://kokoro.mhlw.go.jp/manual/hint_shokuba_kaizen/
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::group::{Dimension, GroupAnalysis};
+use crate::Scale;
+
+/// 仕事のストレス判定図の全国平均
+const AVERAGE_VOLUME: f64 = 8.7;
+const AVERAGE_CONTROL: f64 = 7.9;
+const AVERAGE_BOSS_SUPPORT: f64 = 7.6;
+const AVERAGE_COLLEAGUE_SUPPORT: f64 = 8.1;
+
+/// 素点換算表で「やや高い/多い」以上となる素点の平均
+const ADVERSE_WORK_ENV_STRESS: f64 = 3.0;
+const ADVERSE_WORK_PEOPLE_STRESS: f64 = 8.0;
+const ADVERSE_PHYSICAL_STRESS: f64 = 3.0;
+
+/// ヒント集の領域
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HintCategory {
+    /// A 作業計画の参加と情報の共有
+    Participation,
+    /// B 勤務時間と作業編成
+    WorkingHours,
+    /// C 円滑な作業手順
+    WorkProcedure,
+    /// D 作業場環境
+    Workplace,
+    /// E 職場内の相互支援
+    MutualSupport,
+    /// F 安心できる職場のしくみ
+    SecureWorkplace,
+}
+
+impl HintCategory {
+    /// 領域名
+    pub fn title(&self) -> &'static str {
+        match self {
+            HintCategory::Participation => "作業計画の参加と情報の共有",
+            HintCategory::WorkingHours => "勤務時間と作業編成",
+            HintCategory::WorkProcedure => "円滑な作業手順",
+            HintCategory::Workplace => "作業場環境",
+            HintCategory::MutualSupport => "職場内の相互支援",
+            HintCategory::SecureWorkplace => "安心できる職場のしくみ",
+        }
+    }
+
+    /// 改善アクションの例
+    pub fn actions(&self) -> &'static [&'static str] {
+        match self {
+            HintCategory::Participation => &[
+                "作業の日程作成に参加する手順を定める",
+                "少人数単位の裁量範囲を増やす",
+                "個人あたりの過大な作業量があれば見直す",
+            ],
+            HintCategory::WorkingHours => &[
+                "ノー残業デーなどを運用する",
+                "繁忙期やピーク時の作業方法を改善する",
+                "休日・休暇が十分取れるようにする",
+            ],
+            HintCategory::WorkProcedure => &[
+                "物品と資材の取り扱い方法を改善する",
+                "作業ミス防止策を多面に講じる",
+                "個人ごとの作業場所を仕事しやすくする",
+            ],
+            HintCategory::Workplace => &[
+                "温熱環境や音環境、視環境を快適化する",
+                "有害環境源を隔離する",
+                "休憩設備を改善する",
+            ],
+            HintCategory::MutualSupport => &[
+                "上司に相談しやすい環境を整備する",
+                "同僚に相談でき、コミュニケーションがとりやすい環境を整備する",
+                "チームワークづくりをすすめる",
+            ],
+            HintCategory::SecureWorkplace => &[
+                "個人の健康や職場内の健康問題について相談できる窓口を設置する",
+                "セルフケアについて学ぶ機会を設ける",
+                "職場の将来計画や見通しについて、いつも周知されているようにする",
+            ],
+        }
+    }
+}
+
+/// 好ましくない尺度の組み合わせ
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Pattern {
+    /// 仕事の量的負担が高く、コントロールが低い
+    HighDemandLowControl,
+    /// 仕事の量的負担が高い
+    HighDemand,
+    /// 仕事のコントロールが低い
+    LowControl,
+    /// 上司の支援が低い
+    LowBossSupport,
+    /// 同僚の支援が低い
+    LowColleagueSupport,
+    /// 職場環境によるストレスが高い
+    PoorEnvironment,
+    /// 職場の対人関係でのストレスが高い
+    InterpersonalStress,
+    /// 身体的負担が高い
+    PhysicalBurden,
+}
+
+impl Pattern {
+    /// 対応するヒント集の領域
+    pub fn categories(&self) -> &'static [HintCategory] {
+        match self {
+            Pattern::HighDemandLowControl => {
+                &[HintCategory::Participation, HintCategory::WorkingHours]
+            }
+            Pattern::HighDemand => &[HintCategory::WorkingHours, HintCategory::WorkProcedure],
+            Pattern::LowControl => &[HintCategory::Participation],
+            Pattern::LowBossSupport => {
+                &[HintCategory::MutualSupport, HintCategory::SecureWorkplace]
+            }
+            Pattern::LowColleagueSupport => &[HintCategory::MutualSupport],
+            Pattern::PoorEnvironment => &[HintCategory::Workplace],
+            Pattern::InterpersonalStress => {
+                &[HintCategory::MutualSupport, HintCategory::SecureWorkplace]
+            }
+            Pattern::PhysicalBurden => &[HintCategory::Workplace, HintCategory::WorkProcedure],
+        }
+    }
+}
+
+/// 尺度ごとの素点の平均から該当する組み合わせを検出する
+pub fn detect(scale_means: &BTreeMap<Scale, f64>) -> Vec<Pattern> {
+    let mean = |scale| scale_means.get(&scale).copied().unwrap_or_default();
+    let high_demand = mean(Scale::MentalWorkStressVolume) > AVERAGE_VOLUME;
+    let low_control = mean(Scale::WorkControl) < AVERAGE_CONTROL;
+
+    let mut patterns = vec![];
+    match (high_demand, low_control) {
+        (true, true) => patterns.push(Pattern::HighDemandLowControl),
+        (true, false) => patterns.push(Pattern::HighDemand),
+        (false, true) => patterns.push(Pattern::LowControl),
+        (false, false) => {}
+    }
+    if mean(Scale::BossSupport) < AVERAGE_BOSS_SUPPORT {
+        patterns.push(Pattern::LowBossSupport);
+    }
+    if mean(Scale::ColleagueSupport) < AVERAGE_COLLEAGUE_SUPPORT {
+        patterns.push(Pattern::LowColleagueSupport);
+    }
+    if mean(Scale::WorkEnvStress) >= ADVERSE_WORK_ENV_STRESS {
+        patterns.push(Pattern::PoorEnvironment);
+    }
+    if mean(Scale::WorkPeopleStress) >= ADVERSE_WORK_PEOPLE_STRESS {
+        patterns.push(Pattern::InterpersonalStress);
+    }
+    if mean(Scale::AwarePhysicalStress) >= ADVERSE_PHYSICAL_STRESS {
+        patterns.push(Pattern::PhysicalBurden);
+    }
+    patterns
+}
+
+/// 改善の提案
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Suggestion {
+    pub category: HintCategory,
+    /// 領域名
+    pub title: &'static str,
+    /// 提案の根拠となった組み合わせ
+    pub patterns: Vec<Pattern>,
+    /// 改善アクションの例
+    pub actions: &'static [&'static str],
+}
+
+/// 尺度ごとの素点の平均から改善の提案を領域ごとにまとめる
+pub fn suggest(scale_means: &BTreeMap<Scale, f64>) -> Vec<Suggestion> {
+    let mut categories: BTreeMap<HintCategory, Vec<Pattern>> = BTreeMap::new();
+    for pattern in detect(scale_means) {
+        for &category in pattern.categories() {
+            categories.entry(category).or_default().push(pattern);
+        }
+    }
+    categories
+        .into_iter()
+        .map(|(category, patterns)| Suggestion {
+            category,
+            title: category.title(),
+            patterns,
+            actions: category.actions(),
+        })
+        .collect()
+}
+
+/// 集団ごとの改善の提案
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GroupSuggestions {
+    /// 集団名
+    pub key: String,
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// 集団分析の各集団について改善の提案を作成する
+/// 少人数のため秘匿された集団は対象外とする。
+pub fn suggest_for_groups(analysis: &GroupAnalysis, dimension: Dimension) -> Vec<GroupSuggestions> {
+    analysis
+        .summarize(dimension)
+        .into_iter()
+        .filter_map(|summary| {
+            let suggestions = suggest(summary.scale_means.as_ref()?);
+            Some(GroupSuggestions {
+                key: summary.key,
+                suggestions,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn means(volume: f64, control: f64, boss: f64, colleague: f64) -> BTreeMap<Scale, f64> {
+        BTreeMap::from([
+            (Scale::MentalWorkStressVolume, volume),
+            (Scale::WorkControl, control),
+            (Scale::BossSupport, boss),
+            (Scale::ColleagueSupport, colleague),
+            (Scale::WorkEnvStress, 2.0),
+            (Scale::WorkPeopleStress, 6.0),
+            (Scale::AwarePhysicalStress, 2.0),
+        ])
+    }
+
+    #[test]
+    fn test_detect() {
+        assert!(detect(&means(8.0, 9.0, 9.0, 9.0)).is_empty());
+        assert_eq!(
+            detect(&means(10.0, 6.0, 9.0, 9.0)),
+            vec![Pattern::HighDemandLowControl]
+        );
+        assert_eq!(
+            detect(&means(8.0, 6.0, 7.0, 9.0)),
+            vec![Pattern::LowControl, Pattern::LowBossSupport]
+        );
+    }
+
+    #[test]
+    fn test_suggest() {
+        let suggestions = suggest(&means(10.0, 6.0, 7.0, 9.0));
+        let categories = suggestions
+            .iter()
+            .map(|suggestion| suggestion.category)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            categories,
+            vec![
+                HintCategory::Participation,
+                HintCategory::WorkingHours,
+                HintCategory::MutualSupport,
+                HintCategory::SecureWorkplace,
+            ]
+        );
+        assert_eq!(suggestions[0].title, "作業計画の参加と情報の共有");
+        assert_eq!(suggestions[0].patterns, vec![Pattern::HighDemandLowControl]);
+        assert!(!suggestions[0].actions.is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+//! ハッシュチェーン形式の判定結果台帳
+//!
+//! 各記録に直前の記録のハッシュを含めてJSON Linesで書き出す。途中の記録を
+//! 書き換えると以降のハッシュが一致しなくなるため、事後の改ざんを検出できる。
+
+use std::io::{BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::verify::sha256_hex;
+use crate::{CheckResult, Error};
+
+/// 先頭の記録が参照する直前のハッシュ
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// 台帳の1記録
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// 通し番号（0始まり）
+    pub seq: u64,
+    /// ユーザ特定キー
+    pub id: String,
+    pub result: CheckResult,
+    /// 直前の記録のハッシュ
+    pub prev_hash: String,
+    /// この記録のハッシュ
+    pub hash: String,
+}
+
+/// ハッシュの対象となる部分
+#[derive(Serialize)]
+struct Payload<'a> {
+    seq: u64,
+    id: &'a str,
+    result: &'a CheckResult,
+    prev_hash: &'a str,
+}
+
+impl LedgerEntry {
+    /// 記録の内容からハッシュを算出する
+    pub fn compute_hash(&self) -> String {
+        let payload = Payload {
+            seq: self.seq,
+            id: &self.id,
+            result: &self.result,
+            prev_hash: &self.prev_hash,
+        };
+        sha256_hex(serde_json::to_string(&payload).unwrap().as_bytes())
+    }
+}
+
+/// 台帳の書き出し
+pub struct LedgerWriter<W> {
+    writer: W,
+    seq: u64,
+    last_hash: String,
+}
+
+impl<W: Write> LedgerWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            seq: 0,
+            last_hash: GENESIS_HASH.to_string(),
+        }
+    }
+
+    /// 既存の台帳の続きから書き出す
+    /// `existing`は追記先の台帳の内容。検証した上で最後の記録の番号・ハッシュから連鎖を続ける。
+    pub fn resume<R: BufRead>(writer: W, existing: R) -> Result<Self, Error> {
+        let (seq, last_hash) = chain_end(existing)?;
+        Ok(Self {
+            writer,
+            seq,
+            last_hash,
+        })
+    }
+
+    /// 判定結果を1記録として追記する
+    pub fn append(&mut self, id: &str, result: &CheckResult) -> Result<LedgerEntry, Error> {
+        let mut entry = LedgerEntry {
+            seq: self.seq,
+            id: id.to_string(),
+            result: result.clone(),
+            prev_hash: self.last_hash.clone(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        writeln!(self.writer, "{}", serde_json::to_string(&entry).unwrap())?;
+        self.seq += 1;
+        self.last_hash = entry.hash.clone();
+        Ok(entry)
+    }
+
+    /// 最後に書き出した記録のハッシュ
+    pub fn last_hash(&self) -> &str {
+        &self.last_hash
+    }
+
+    /// 書き出した記録を出力先に反映する
+    pub fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.writer.flush()?)
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// 台帳を検証し、記録数を返す
+/// 読み込めない記録やハッシュの連鎖が途切れた記録があれば、その行番号（1始まり）を
+/// `Error::TamperedLedger`として返す。
+pub fn verify_ledger<R: BufRead>(reader: R) -> Result<u64, Error> {
+    Ok(chain_end(reader)?.0)
+}
+
+/// 台帳を検証し、次の記録の番号と最後の記録のハッシュを返す
+fn chain_end<R: BufRead>(reader: R) -> Result<(u64, String), Error> {
+    let mut seq = 0;
+    let mut last_hash = GENESIS_HASH.to_string();
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let line_number = index + 1;
+        let entry: LedgerEntry =
+            serde_json::from_str(&line).map_err(|_| Error::TamperedLedger(line_number))?;
+        if entry.seq != seq || entry.prev_hash != last_hash || entry.hash != entry.compute_hash() {
+            return Err(Error::TamperedLedger(line_number));
+        }
+        seq += 1;
+        last_hash = entry.hash;
+    }
+    Ok((seq, last_hash))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, StressCriteria};
+
+    fn ledger() -> String {
+        let mut writer = LedgerWriter::new(vec![]);
+        for (id, answer) in [("1", 1), ("2", 4), ("3", 2)] {
+            let mut store = AnswerStore::default();
+            for _ in 0..57 {
+                store.push(answer).unwrap();
+            }
+            let result = CheckResult::new(&store, StressCriteria::SUMUP).unwrap();
+            writer.append(id, &result).unwrap();
+        }
+        String::from_utf8(writer.into_inner()).unwrap()
+    }
+
+    #[test]
+    fn test_verify_ledger() {
+        let value = ledger();
+        assert_eq!(verify_ledger(value.as_bytes()).unwrap(), 3);
+
+        let tampered = value.replace("\"id\":\"2\"", "\"id\":\"9\"");
+        assert!(matches!(
+            verify_ledger(tampered.as_bytes()),
+            Err(Error::TamperedLedger(2))
+        ));
+
+        let removed = value.lines().skip(1).collect::<Vec<_>>().join("\n");
+        assert!(matches!(
+            verify_ledger(removed.as_bytes()),
+            Err(Error::TamperedLedger(1))
+        ));
+    }
+
+    #[test]
+    fn test_resume() {
+        let value = ledger();
+        let (first, rest) = value.split_at(value.find('\n').unwrap() + 1);
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(4).unwrap();
+        }
+        let result = CheckResult::new(&store, StressCriteria::SUMUP).unwrap();
+        let mut writer = LedgerWriter::resume(vec![], first.as_bytes()).unwrap();
+        let entry = writer.append("2", &result).unwrap();
+        assert_eq!(entry.seq, 1);
+        assert_eq!(
+            String::from_utf8(writer.into_inner()).unwrap(),
+            rest.lines().next().unwrap().to_string() + "\n"
+        );
+        assert!(matches!(
+            LedgerWriter::resume(vec![], &b"{}\n"[..]),
+            Err(Error::TamperedLedger(1))
+        ));
+    }
+}
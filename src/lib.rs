@@ -1,13 +1,101 @@
+use std::collections::{BTreeMap, HashSet};
 use std::io::BufRead;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use once_cell::sync::Lazy;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-pub static QUESTIONS: Lazy<SimpleStress> = Lazy::new(|| {
-    let f = std::fs::File::open("resources/57.json").unwrap();
-    let reader = std::io::BufReader::new(f);
-    serde_json::from_reader(reader).unwrap()
-});
+pub mod amend;
+pub mod analysis;
+pub mod answer_file;
+#[cfg(feature = "async")]
+pub mod async_bulk;
+pub mod batch;
+pub mod checkpoint;
+pub mod cohort;
+#[cfg(feature = "arrow")]
+pub mod columnar;
+pub mod compact;
+pub mod compare;
+pub mod compress;
+pub mod config;
+pub mod consent;
+pub mod dashboard;
+pub mod demographics;
+pub mod dossier;
+pub mod embedded;
+pub mod encoding;
+pub mod entry;
+pub mod export;
+pub mod factor;
+pub mod fhir;
+pub mod format;
+pub mod group;
+pub mod group_code;
+pub mod header;
+pub mod id_match;
+pub mod idempotency;
+pub mod interval;
+pub mod kaizen;
+pub mod ledger;
+pub mod limit;
+pub mod locale;
+pub mod lock;
+#[cfg(feature = "mail")]
+pub mod mail;
+pub mod manual;
+pub mod markdown;
+pub mod memory;
+pub mod metrics;
+pub mod mhlw;
+pub mod migrate;
+pub mod missing;
+pub mod notify;
+pub mod outcome;
+pub mod pipeline;
+pub mod privacy;
+pub mod prompt;
+pub mod pseudonym;
+pub mod reload;
+pub mod reminder;
+pub mod repository;
+pub mod rescore;
+pub mod resource;
+pub mod result_code;
+pub mod result_sheet;
+pub mod retention;
+pub mod roster;
+pub mod ruby;
+pub mod schema;
+pub mod sheet;
+pub mod simulate;
+pub mod skip;
+pub mod snapshot;
+pub mod stamp;
+pub mod template;
+pub mod tenant;
+pub mod text_provider;
+pub mod trace;
+pub mod translation;
+pub mod trend;
+pub mod variant;
+pub mod verify;
+pub mod view;
+pub mod warning;
+pub mod web;
+#[cfg(feature = "webhook")]
+pub mod webhook;
+pub mod wellbeing;
+
+pub use consent::Consent;
+pub use demographics::Demographics;
+use demographics::{AgeBand, EmploymentType, Tenure};
+
+/// 設問マスタ（配置場所は[`resource`]を参照）
+/// 初回の参照時に読み込み、割り当てを行う。解析も割り当ても行わずに参照する場合は[`embedded`]の定数を用いる。
+pub static QUESTIONS: Lazy<SimpleStress> =
+    Lazy::new(|| resource::load_master().unwrap_or_else(|e| panic!("{}", e)));
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Score {
@@ -19,8 +107,17 @@ pub struct Score {
 pub struct Question {
     pub id: u32,
     pub text: String,
+    /// ふりがな付きの設問文（`{非常|ひじょう}に`の記法。[`ruby`]を参照）
+    #[serde(default)]
+    pub furigana: Option<String>,
+    /// 言い換えた設問文（[`variant`]を参照）
+    #[serde(default)]
+    pub variants: BTreeMap<variant::TextVariant, String>,
     pub reverse: bool,
     pub scores: Vec<Score>,
+    /// 提示する条件（拡張した調査票で用いる）
+    #[serde(default)]
+    pub condition: Option<skip::SkipRule>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -30,7 +127,8 @@ pub struct OuterQuestion {
     /// の調査ブロックは3つの設問サブセットに分解され、それぞれのサブセットに教示が内包されている。
     /// 詳細 https://www.mhlw.go.jp/bunya/roudoukijun/anzeneisei12/dl/stress-check_j.pdf
     pub title: Option<String>,
-    pub questions: Vec<Question>,
+    /// 設問。取得した設問を画面間で受け渡しても複製されないよう共有する
+    pub questions: Vec<Arc<Question>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -47,42 +145,44 @@ pub struct SimpleStress {
 }
 
 impl SimpleStress {
-    pub fn get(&self, index: usize) -> Option<Question> {
-        self.simple_stress
-            .iter()
-            .flat_map(|theme| {
-                theme
-                    .questions
-                    .iter()
-                    .flat_map(|outer_question| outer_question.questions.clone())
-            })
-            .nth(index)
+    /// JSONファイルからマスタを読み込む
+    /// 提示する条件が前の設問を参照していない（循環しうる）場合は`IllegalMaster`とする。
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        let master: Self = serde_json::from_reader(reader).map_err(Error::MasterReadError)?;
+        let discrepancies = master.verify_conditions();
+        if !discrepancies.is_empty() {
+            return Err(Error::IllegalMaster(discrepancies));
+        }
+        Ok(master)
+    }
+
+    /// 出現順に設問を列挙する
+    fn iter_questions(&self) -> impl Iterator<Item = &Arc<Question>> {
+        self.simple_stress.iter().flat_map(|theme| {
+            theme
+                .questions
+                .iter()
+                .flat_map(|outer_question| outer_question.questions.iter())
+        })
+    }
+
+    /// 出現順で`index`番目の設問を取得する
+    /// 設問は共有しているため、取得しても文言は複製されない。
+    pub fn get(&self, index: usize) -> Option<Arc<Question>> {
+        self.iter_questions().nth(index).cloned()
     }
 
     /// 設問番号を指定して設問を取得する
-    pub fn question(&self, id: u32) -> Option<Question> {
-        self.simple_stress
-            .iter()
-            .flat_map(|theme| {
-                theme
-                    .questions
-                    .iter()
-                    .flat_map(|outer_question| outer_question.questions.clone())
-            })
+    pub fn question(&self, id: u32) -> Option<Arc<Question>> {
+        self.iter_questions()
             .find(|question| question.id == id)
+            .cloned()
     }
 
     /// 57設問を全て取得する
-    pub fn questions(&self) -> Vec<Question> {
-        self.simple_stress
-            .iter()
-            .flat_map(|theme| {
-                theme
-                    .questions
-                    .iter()
-                    .flat_map(|outer_question| outer_question.questions.clone())
-            })
-            .collect::<Vec<Question>>()
+    pub fn questions(&self) -> Vec<Arc<Question>> {
+        self.iter_questions().cloned().collect()
     }
 }
 
@@ -90,6 +190,8 @@ impl SimpleStress {
 pub struct AnswerStore {
     values: [u8; 57],
     offset: usize,
+    /// 全設問の回答後の変更（設問番号, 変更前, 変更後）
+    changes: Vec<(u8, u8, u8)>,
 }
 
 impl Default for AnswerStore {
@@ -97,6 +199,7 @@ impl Default for AnswerStore {
         Self {
             values: [0; 57],
             offset: 0,
+            changes: vec![],
         }
     }
 }
@@ -126,6 +229,10 @@ impl AnswerStore {
         if (1..=4).contains(&score) {
             let offset: usize = (question_no - 1).into();
             if offset < 57 {
+                let before = self.values[offset];
+                if !self.values.contains(&0) && before != score {
+                    self.changes.push((question_no, before, score));
+                }
                 self.values[offset] = score;
                 Ok(())
             } else {
@@ -136,6 +243,11 @@ impl AnswerStore {
         }
     }
 
+    /// 格納済みの回答（未回答は0）
+    pub fn values(&self) -> &[u8; 57] {
+        &self.values
+    }
+
     /// 合計点数方式
     ///
     /// ○ まず、労働者が記入又は入力した調査票を元に、合計点数を算出します。
@@ -161,23 +273,7 @@ impl AnswerStore {
     /// ㋑ 領域ＡとＣの合算の合計点数が76点以上（最高点は４×17＋４×９＝104
     /// 点）であり、かつ領域Ｂの合計点数が 63 点以上であること
     pub fn to_sumup_score(&self) -> Result<SumupScore, Error> {
-        if self.values.iter().any(|&value| value == 0) {
-            return Err(Error::NotFullfilled);
-        }
-        if self.values.iter().any(|&value| value > 4) {
-            return Err(Error::IllegalAnswer);
-        }
-        let values = self
-            .values
-            .iter()
-            .enumerate()
-            .map(|(index, &value)| reverse_if((index + 1, value)))
-            .collect::<Vec<u8>>();
-        Ok(SumupScore {
-            sum_a: values.iter().take(17).sum(),
-            sum_b: values.iter().skip(17).take(29).sum(),
-            sum_c: values.iter().skip(46).take(9).sum(),
-        })
+        sumup_score_slice(&self.values)
     }
 
     /// ○ 素点換算表では、職業性ストレス簡易調査票の質問項目が、いくつかの
@@ -210,71 +306,379 @@ impl AnswerStore {
     /// ㋑ 領域ＡとＣの合算の評価点の合計が 26 点以下（最低点は１×９＋１×３
     /// ＝12 点）であり、かつ領域Ｂの評価点の合計が 17 点以下であること
     pub fn to_conversion_score(&self) -> Result<ConversionScore, Error> {
-        if self.values.iter().any(|&value| value == 0) {
-            return Err(Error::NotFullfilled);
-        }
-        if self.values.iter().any(|&value| value > 4) {
-            return Err(Error::IllegalAnswer);
-        }
-        IntermediateConversionScore {
-            mental_work_stress_volume: 15 - self.values.iter().take(3).sum::<u8>(),
-            mental_work_stress_quality: 15 - self.values.iter().skip(3).take(3).sum::<u8>(),
-            aware_physical_stress: 5 - self.values.get(6).ok_or(Error::NotFullfilled)?,
-            work_people_stress: 10 - self.values.iter().skip(11).take(2).sum::<u8>()
-                + self.values.get(13).ok_or(Error::NotFullfilled)?,
-            work_env_stress: 5 - self.values.get(14).ok_or(Error::NotFullfilled)?,
-            work_control: 15 - self.values.iter().skip(7).take(3).sum::<u8>(),
-            skill_apply: (*self.values.get(10).ok_or(Error::NotFullfilled)?),
-            work_apply: 5 - self.values.get(15).ok_or(Error::NotFullfilled)?,
-            decent_work: 5 - self.values.get(16).ok_or(Error::NotFullfilled)?,
-            vitality: self.values.iter().skip(17).take(3).sum::<u8>(),
-            iraira: self.values.iter().skip(20).take(3).sum::<u8>(),
-            tired: self.values.iter().skip(23).take(3).sum::<u8>(),
-            anxious: self.values.iter().skip(26).take(3).sum::<u8>(),
-            depressed: self.values.iter().skip(29).take(6).sum::<u8>(),
-            physical_complaint: self.values.iter().skip(35).take(11).sum::<u8>(),
-            boss_support: 15
-                - (self.values.get(46).ok_or(Error::NotFullfilled)?
-                    + self.values.get(49).ok_or(Error::NotFullfilled)?
-                    + self.values.get(52).ok_or(Error::NotFullfilled)?),
-            colleague_support: 15
-                - (self.values.get(47).ok_or(Error::NotFullfilled)?
-                    + self.values.get(50).ok_or(Error::NotFullfilled)?
-                    + self.values.get(53).ok_or(Error::NotFullfilled)?),
-            family_support: 15
-                - (self.values.get(48).ok_or(Error::NotFullfilled)?
-                    + self.values.get(51).ok_or(Error::NotFullfilled)?
-                    + self.values.get(54).ok_or(Error::NotFullfilled)?),
-        }
-        .try_into()
-    }
-}
-
-fn reverse_if(score: (usize, u8)) -> u8 {
+        self.to_intermediate_score()?.try_into()
+    }
+
+    /// 素点換算表に当てはめる前の尺度ごとの計算結果を算出する
+    pub fn to_intermediate_score(&self) -> Result<IntermediateConversionScore, Error> {
+        intermediate_score_slice(&self.values)
+    }
+}
+
+/// 設問番号と回答から、逆転項目であれば点数を置き換える
+const fn reverse_if(score: (usize, u8)) -> u8 {
     match score.0 {
-        ref id if (1..=7).contains(id) => 5 - score.1,
-        ref id if (11..=13).contains(id) => 5 - score.1,
-        15 => 5 - score.1,
-        ref id if (18..=20).contains(id) => 5 - score.1,
+        1..=7 | 11..=13 | 15 | 18..=20 => 5 - score.1,
         _ => score.1,
     }
 }
 
+/// 未回答・範囲外の回答がないことを確かめる
+fn check_answers(values: &[u8; 57]) -> Result<(), Error> {
+    if values.contains(&0) {
+        return Err(Error::NotFullfilled);
+    }
+    if values.iter().any(|&value| value > 4) {
+        return Err(Error::IllegalAnswer);
+    }
+    Ok(())
+}
+
+/// 設問番号の回答
+const fn answer(values: &[u8; 57], id: usize) -> u8 {
+    values[id - 1]
+}
+
+/// 尺度を構成する設問を`Direction`に従って置き換えた点数の合計
+const fn scale_sum(values: &[u8; 57], items: &[ScaleItem]) -> u8 {
+    let mut sum = 0;
+    let mut i = 0;
+    while i < items.len() {
+        sum += items[i]
+            .direction
+            .apply(answer(values, items[i].question as usize));
+        i += 1;
+    }
+    sum
+}
+
+/// 設問番号`from`〜`to`の逆転項目を置き換えた点数の合計
+const fn sum_reversed(values: &[u8; 57], from: usize, to: usize) -> u8 {
+    let mut sum = 0;
+    let mut id = from;
+    while id <= to {
+        sum += reverse_if((id, answer(values, id)));
+        id += 1;
+    }
+    sum
+}
+
+/// 検証済みの回答から合計点数方式の点数を算出する
+const fn sumup_unchecked(values: &[u8; 57]) -> SumupScore {
+    SumupScore {
+        sum_a: sum_reversed(values, 1, 17),
+        sum_b: sum_reversed(values, 18, 46),
+        // 満足度の56・57は含めない
+        sum_c: sum_reversed(values, 47, 55),
+    }
+}
+
+/// 検証済みの回答から尺度ごとの計算結果を算出する
+const fn intermediate_unchecked(values: &[u8; 57]) -> IntermediateConversionScore {
+    IntermediateConversionScore {
+        mental_work_stress_volume: scale_sum(values, Scale::MentalWorkStressVolume.items()),
+        mental_work_stress_quality: scale_sum(values, Scale::MentalWorkStressQuality.items()),
+        aware_physical_stress: scale_sum(values, Scale::AwarePhysicalStress.items()),
+        work_people_stress: scale_sum(values, Scale::WorkPeopleStress.items()),
+        work_env_stress: scale_sum(values, Scale::WorkEnvStress.items()),
+        work_control: scale_sum(values, Scale::WorkControl.items()),
+        skill_apply: scale_sum(values, Scale::SkillApply.items()),
+        work_apply: scale_sum(values, Scale::WorkApply.items()),
+        decent_work: scale_sum(values, Scale::DecentWork.items()),
+        vitality: scale_sum(values, Scale::Vitality.items()),
+        iraira: scale_sum(values, Scale::Iraira.items()),
+        tired: scale_sum(values, Scale::Tired.items()),
+        anxious: scale_sum(values, Scale::Anxious.items()),
+        depressed: scale_sum(values, Scale::Depressed.items()),
+        physical_complaint: scale_sum(values, Scale::PhysicalComplaint.items()),
+        boss_support: scale_sum(values, Scale::BossSupport.items()),
+        colleague_support: scale_sum(values, Scale::ColleagueSupport.items()),
+        family_support: scale_sum(values, Scale::FamilySupport.items()),
+    }
+}
+
+/// 回答の配列から合計点数方式の点数を算出する
+/// `AnswerStore`を介さずに、既に回答の配列を持っている呼び出し元向け。
+pub fn sumup_score_slice(values: &[u8; 57]) -> Result<SumupScore, Error> {
+    check_answers(values)?;
+    Ok(sumup_unchecked(values))
+}
+
+/// 回答の配列から素点換算表に当てはめる前の尺度ごとの計算結果を算出する
+pub fn intermediate_score_slice(values: &[u8; 57]) -> Result<IntermediateConversionScore, Error> {
+    check_answers(values)?;
+    Ok(intermediate_unchecked(values))
+}
+
+/// 回答の配列から素点換算表方式の評価点を算出する
+pub fn conversion_score_slice(values: &[u8; 57]) -> Result<ConversionScore, Error> {
+    intermediate_score_slice(values)?.try_into()
+}
+
+/// 回答の配列から選定方法に応じた領域Ａ・Ｂ・Ｃの点数を算出する
+pub fn score_slice(values: &[u8; 57], criteria: StressCriteria) -> Result<(u8, u8, u8), Error> {
+    match criteria {
+        StressCriteria::Sumup(_) => Ok(sumup_score_slice(values)?.scores()),
+        StressCriteria::Conversion(_) => Ok(conversion_score_slice(values)?.scores()),
+    }
+}
+
+/// 高ストレス者を選定する数値基準
+///
+/// 以下のいずれかを満たす場合に高ストレス者と選定する。
+/// ㋐ 領域Ｂの点数が`b`以上（素点換算表方式では以下）であること
+/// ㋑ 領域ＡとＣの合算の点数が`ac`以上（以下）であり、かつ領域Ｂの点数が`b_with_ac`以上（以下）であること
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Thresholds {
+    pub b: u8,
+    pub ac: u8,
+    pub b_with_ac: u8,
+}
+
+impl Thresholds {
+    /// 評価基準の設定例（その１）
+    pub const SUMUP: Thresholds = Thresholds {
+        b: 77,
+        ac: 76,
+        b_with_ac: 63,
+    };
+    /// 評価基準の設定例（その２）
+    pub const CONVERSION: Thresholds = Thresholds {
+        b: 12,
+        ac: 26,
+        b_with_ac: 17,
+    };
+}
+
+/// 高ストレス者の選定方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum StressCriteria {
+    /// 合計点数方式
+    Sumup(Thresholds),
+    /// 素点換算表方式
+    Conversion(Thresholds),
+}
+
+impl Default for StressCriteria {
+    fn default() -> Self {
+        StressCriteria::SUMUP
+    }
+}
+
+impl StressCriteria {
+    /// 合計点数方式（評価基準の設定例その１）
+    pub const SUMUP: StressCriteria = StressCriteria::Sumup(Thresholds::SUMUP);
+    /// 素点換算表方式（評価基準の設定例その２）
+    pub const CONVERSION: StressCriteria = StressCriteria::Conversion(Thresholds::CONVERSION);
+
+    /// 選定方法に応じた点数を算出する
+    /// 高ストレス者の判定と該当した基準には、この選定方法の数値基準を用いる。
+    pub fn score(&self, store: &AnswerStore) -> Result<CriteriaScore, Error> {
+        let score: Box<dyn StressReport> = match self {
+            StressCriteria::Sumup(_) => Box::new(store.to_sumup_score()?),
+            StressCriteria::Conversion(_) => Box::new(store.to_conversion_score()?),
+        };
+        Ok(CriteriaScore {
+            criteria: *self,
+            score,
+        })
+    }
+
+    /// 選定方法の名前（`sumup`・`conversion`）
+    pub fn method(&self) -> &'static str {
+        match self {
+            StressCriteria::Sumup(_) => "sumup",
+            StressCriteria::Conversion(_) => "conversion",
+        }
+    }
+
+    /// 領域ごとの点数を数値基準に照らし合わせる
+    pub fn judge(&self, scores: (u8, u8, u8)) -> bool {
+        self.criterion(scores).is_some()
+    }
+
+    /// 領域ごとの点数が該当する数値基準
+    /// ㋐・㋑のいずれにも該当する場合は㋐とする。該当しない場合は`None`。
+    pub fn criterion(&self, scores: (u8, u8, u8)) -> Option<Criterion> {
+        let (sum_a, sum_b, sum_c) = scores;
+        let (b, ac_with_b) = match self {
+            StressCriteria::Sumup(thresholds) => (
+                sum_b >= thresholds.b,
+                sum_a + sum_c >= thresholds.ac && sum_b >= thresholds.b_with_ac,
+            ),
+            StressCriteria::Conversion(thresholds) => (
+                sum_b <= thresholds.b,
+                sum_a + sum_c <= thresholds.ac && sum_b <= thresholds.b_with_ac,
+            ),
+        };
+        match (b, ac_with_b) {
+            (true, _) => Some(Criterion::B),
+            (false, true) => Some(Criterion::AcWithB),
+            (false, false) => None,
+        }
+    }
+
+    /// 回答から高ストレス者に該当するかを判定する
+    pub fn evaluate(&self, store: &AnswerStore) -> Result<bool, Error> {
+        Ok(self.judge(self.score(store)?.scores()))
+    }
+}
+
 pub trait Stress {
     fn scores(&self) -> (u8, u8, u8);
     fn has_stress(&self) -> bool;
 }
 
-#[derive(Debug)]
+/// 高ストレス者に該当した数値基準（`Thresholds`の㋐・㋑）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Criterion {
+    /// ㋐ 領域Ｂの点数による
+    B,
+    /// ㋑ 領域ＡとＣの合算の点数と、領域Ｂの点数による
+    AcWithB,
+}
+
+/// 選定方法・点数の内訳・該当した基準を含む判定の報告
+///
+/// トレイトオブジェクトとして扱え、`downcast_ref`で元の点数の型に戻せる。
+/// `dyn StressReport`は`{"method", "scores", "has_stress", "criterion", "detail"}`の形式で出力する。
+pub trait StressReport: Stress + std::any::Any {
+    /// 選定方法の名前（`StressCriteria::method`と同じ）
+    fn method(&self) -> &'static str;
+    /// 点数の内訳
+    fn detail(&self) -> serde_json::Value;
+    /// 該当した数値基準。該当しない場合は`None`
+    fn criterion(&self) -> Option<Criterion>;
+    fn as_any(&self) -> &dyn std::any::Any;
+}
+
+impl dyn StressReport {
+    /// 元の点数の型に戻す
+    pub fn downcast_ref<T: StressReport>(&self) -> Option<&T> {
+        self.as_any().downcast_ref()
+    }
+}
+
+impl Serialize for dyn StressReport {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("StressReport", 5)?;
+        state.serialize_field("method", self.method())?;
+        state.serialize_field("scores", &self.scores())?;
+        state.serialize_field("has_stress", &self.has_stress())?;
+        state.serialize_field("criterion", &self.criterion())?;
+        state.serialize_field("detail", &self.detail())?;
+        state.end()
+    }
+}
+
+/// 選定方法（数値基準を含む）に従って算出した点数
+///
+/// `SumupScore`・`ConversionScore`単体の`has_stress`・`criterion`は評価基準の設定例で判定するが、
+/// こちらは算出に用いた`StressCriteria`の数値基準で判定する。
+pub struct CriteriaScore {
+    criteria: StressCriteria,
+    score: Box<dyn StressReport>,
+}
+
+impl CriteriaScore {
+    /// 算出に用いた選定方法
+    pub fn criteria(&self) -> StressCriteria {
+        self.criteria
+    }
+
+    /// 元の点数の型に戻す
+    pub fn downcast_ref<T: StressReport>(&self) -> Option<&T> {
+        self.score.downcast_ref()
+    }
+}
+
+impl std::fmt::Debug for CriteriaScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CriteriaScore")
+            .field("criteria", &self.criteria)
+            .field("detail", &self.score.detail())
+            .finish()
+    }
+}
+
+impl Stress for CriteriaScore {
+    fn scores(&self) -> (u8, u8, u8) {
+        self.score.scores()
+    }
+
+    fn has_stress(&self) -> bool {
+        self.criteria.judge(self.scores())
+    }
+}
+
+impl StressReport for CriteriaScore {
+    fn method(&self) -> &'static str {
+        self.criteria.method()
+    }
+
+    fn detail(&self) -> serde_json::Value {
+        self.score.detail()
+    }
+
+    fn criterion(&self) -> Option<Criterion> {
+        self.criteria.criterion(self.scores())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+impl Serialize for CriteriaScore {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        (self as &dyn StressReport).serialize(serializer)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct SumupScore {
     sum_a: u8,
     sum_b: u8,
     sum_c: u8,
 }
 
+impl SumupScore {
+    /// 領域ごとの合計点数から作成する
+    /// 領域Ａは17〜68点、領域Ｂは29〜116点、領域Ｃは9〜36点の範囲であること。
+    pub fn new(sum_a: u8, sum_b: u8, sum_c: u8) -> Result<Self, Error> {
+        if !(17..=68).contains(&sum_a) || !(29..=116).contains(&sum_b) || !(9..=36).contains(&sum_c)
+        {
+            return Err(Error::IllegalScore);
+        }
+        Ok(Self {
+            sum_a,
+            sum_b,
+            sum_c,
+        })
+    }
+}
+
+impl TryFrom<&AnswerStore> for SumupScore {
+    type Error = Error;
+
+    fn try_from(store: &AnswerStore) -> Result<Self, Self::Error> {
+        store.to_sumup_score()
+    }
+}
+
+/// 評価基準の設定例（その１）で判定する。他の数値基準で判定する場合は`StressCriteria::score`を用いる
 impl Stress for SumupScore {
     fn has_stress(&self) -> bool {
-        self.sum_b >= 77 || (self.sum_a + self.sum_c >= 76 && self.sum_b >= 63)
+        StressCriteria::SUMUP.judge(self.scores())
     }
 
     fn scores(&self) -> (u8, u8, u8) {
@@ -282,6 +686,326 @@ impl Stress for SumupScore {
     }
 }
 
+impl StressReport for SumupScore {
+    fn method(&self) -> &'static str {
+        StressCriteria::SUMUP.method()
+    }
+
+    fn detail(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    fn criterion(&self) -> Option<Criterion> {
+        StressCriteria::SUMUP.criterion(self.scores())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// 素点換算表の尺度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scale {
+    /// 心理的な仕事の負担（量）
+    MentalWorkStressVolume,
+    /// 心理的な仕事の負担（質）
+    MentalWorkStressQuality,
+    /// 自覚的な身体的負担度
+    AwarePhysicalStress,
+    /// 職場の対人関係でのストレス
+    WorkPeopleStress,
+    /// 職場環境によるストレス
+    WorkEnvStress,
+    /// 仕事のコントロール
+    WorkControl,
+    /// 技能の活用度
+    SkillApply,
+    /// 仕事の適正度
+    WorkApply,
+    /// 働きがい
+    DecentWork,
+    /// 活気
+    Vitality,
+    /// イライラ感
+    Iraira,
+    /// 疲労感
+    Tired,
+    /// 不安感
+    Anxious,
+    /// 抑うつ感
+    Depressed,
+    /// 身体愁訴
+    PhysicalComplaint,
+    /// 上司からのサポート
+    BossSupport,
+    /// 同僚からのサポート
+    ColleagueSupport,
+    /// 家族友人からのサポート
+    FamilySupport,
+}
+
+impl Scale {
+    /// 全18尺度
+    pub const ALL: [Scale; 18] = [
+        Scale::MentalWorkStressVolume,
+        Scale::MentalWorkStressQuality,
+        Scale::AwarePhysicalStress,
+        Scale::WorkPeopleStress,
+        Scale::WorkEnvStress,
+        Scale::WorkControl,
+        Scale::SkillApply,
+        Scale::WorkApply,
+        Scale::DecentWork,
+        Scale::Vitality,
+        Scale::Iraira,
+        Scale::Tired,
+        Scale::Anxious,
+        Scale::Depressed,
+        Scale::PhysicalComplaint,
+        Scale::BossSupport,
+        Scale::ColleagueSupport,
+        Scale::FamilySupport,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scale::MentalWorkStressVolume => "mental_work_stress_volume",
+            Scale::MentalWorkStressQuality => "mental_work_stress_quality",
+            Scale::AwarePhysicalStress => "aware_physical_stress",
+            Scale::WorkPeopleStress => "work_people_stress",
+            Scale::WorkEnvStress => "work_env_stress",
+            Scale::WorkControl => "work_control",
+            Scale::SkillApply => "skill_apply",
+            Scale::WorkApply => "work_apply",
+            Scale::DecentWork => "decent_work",
+            Scale::Vitality => "vitality",
+            Scale::Iraira => "iraira",
+            Scale::Tired => "tired",
+            Scale::Anxious => "anxious",
+            Scale::Depressed => "depressed",
+            Scale::PhysicalComplaint => "physical_complaint",
+            Scale::BossSupport => "boss_support",
+            Scale::ColleagueSupport => "colleague_support",
+            Scale::FamilySupport => "family_support",
+        }
+    }
+
+    /// 尺度の名称
+    pub fn label(&self) -> &'static str {
+        match self {
+            Scale::MentalWorkStressVolume => "心理的な仕事の負担（量）",
+            Scale::MentalWorkStressQuality => "心理的な仕事の負担（質）",
+            Scale::AwarePhysicalStress => "自覚的な身体的負担度",
+            Scale::WorkPeopleStress => "職場の対人関係でのストレス",
+            Scale::WorkEnvStress => "職場環境によるストレス",
+            Scale::WorkControl => "仕事のコントロール度",
+            Scale::SkillApply => "技能の活用度",
+            Scale::WorkApply => "仕事の適性度",
+            Scale::DecentWork => "働きがい",
+            Scale::Vitality => "活気",
+            Scale::Iraira => "イライラ感",
+            Scale::Tired => "疲労感",
+            Scale::Anxious => "不安感",
+            Scale::Depressed => "抑うつ感",
+            Scale::PhysicalComplaint => "身体愁訴",
+            Scale::BossSupport => "上司からのサポート",
+            Scale::ColleagueSupport => "同僚からのサポート",
+            Scale::FamilySupport => "家族・友人からのサポート",
+        }
+    }
+
+    /// 尺度を構成する設問と点数の向き
+    pub const fn items(&self) -> &'static [ScaleItem] {
+        SCALE_ITEMS[*self as usize].1
+    }
+
+    /// 尺度を構成する設問番号
+    pub fn questions(&self) -> impl Iterator<Item = u8> {
+        self.items().iter().map(|item| item.question)
+    }
+}
+
+/// 尺度の計算で設問の点数をどう扱うか
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// 回答の点数をそのまま足す
+    Forward,
+    /// 5から回答の点数を引いて足す
+    Reverse,
+}
+
+impl Direction {
+    /// 回答の点数を置き換える
+    pub const fn apply(self, value: u8) -> u8 {
+        match self {
+            Direction::Forward => value,
+            Direction::Reverse => 5 - value,
+        }
+    }
+}
+
+/// 尺度を構成する設問
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScaleItem {
+    /// 設問番号
+    pub question: u8,
+    pub direction: Direction,
+}
+
+impl ScaleItem {
+    const fn forward(question: u8) -> Self {
+        Self {
+            question,
+            direction: Direction::Forward,
+        }
+    }
+
+    const fn reverse(question: u8) -> Self {
+        Self {
+            question,
+            direction: Direction::Reverse,
+        }
+    }
+}
+
+/// 尺度を構成する設問と点数の向きの表
+///
+/// 素点換算表に基づく評価点の算出方法の計算式をそのまま写したもの。
+/// 例えば「15-(1+2+3)」は設問1〜3の逆転、「10-(12+13)+14」は設問12・13の逆転と14の順方向になる。
+/// `Scale`の宣言順に並べる。
+const SCALE_ITEMS: [(Scale, &[ScaleItem]); 18] = [
+    (
+        Scale::MentalWorkStressVolume,
+        &[
+            ScaleItem::reverse(1),
+            ScaleItem::reverse(2),
+            ScaleItem::reverse(3),
+        ],
+    ),
+    (
+        Scale::MentalWorkStressQuality,
+        &[
+            ScaleItem::reverse(4),
+            ScaleItem::reverse(5),
+            ScaleItem::reverse(6),
+        ],
+    ),
+    (Scale::AwarePhysicalStress, &[ScaleItem::reverse(7)]),
+    (
+        Scale::WorkPeopleStress,
+        &[
+            ScaleItem::reverse(12),
+            ScaleItem::reverse(13),
+            ScaleItem::forward(14),
+        ],
+    ),
+    (Scale::WorkEnvStress, &[ScaleItem::reverse(15)]),
+    (
+        Scale::WorkControl,
+        &[
+            ScaleItem::reverse(8),
+            ScaleItem::reverse(9),
+            ScaleItem::reverse(10),
+        ],
+    ),
+    (Scale::SkillApply, &[ScaleItem::forward(11)]),
+    (Scale::WorkApply, &[ScaleItem::reverse(16)]),
+    (Scale::DecentWork, &[ScaleItem::reverse(17)]),
+    (
+        Scale::Vitality,
+        &[
+            ScaleItem::forward(18),
+            ScaleItem::forward(19),
+            ScaleItem::forward(20),
+        ],
+    ),
+    (
+        Scale::Iraira,
+        &[
+            ScaleItem::forward(21),
+            ScaleItem::forward(22),
+            ScaleItem::forward(23),
+        ],
+    ),
+    (
+        Scale::Tired,
+        &[
+            ScaleItem::forward(24),
+            ScaleItem::forward(25),
+            ScaleItem::forward(26),
+        ],
+    ),
+    (
+        Scale::Anxious,
+        &[
+            ScaleItem::forward(27),
+            ScaleItem::forward(28),
+            ScaleItem::forward(29),
+        ],
+    ),
+    (
+        Scale::Depressed,
+        &[
+            ScaleItem::forward(30),
+            ScaleItem::forward(31),
+            ScaleItem::forward(32),
+            ScaleItem::forward(33),
+            ScaleItem::forward(34),
+            ScaleItem::forward(35),
+        ],
+    ),
+    (
+        Scale::PhysicalComplaint,
+        &[
+            ScaleItem::forward(36),
+            ScaleItem::forward(37),
+            ScaleItem::forward(38),
+            ScaleItem::forward(39),
+            ScaleItem::forward(40),
+            ScaleItem::forward(41),
+            ScaleItem::forward(42),
+            ScaleItem::forward(43),
+            ScaleItem::forward(44),
+            ScaleItem::forward(45),
+            ScaleItem::forward(46),
+        ],
+    ),
+    (
+        Scale::BossSupport,
+        &[
+            ScaleItem::reverse(47),
+            ScaleItem::reverse(50),
+            ScaleItem::reverse(53),
+        ],
+    ),
+    (
+        Scale::ColleagueSupport,
+        &[
+            ScaleItem::reverse(48),
+            ScaleItem::reverse(51),
+            ScaleItem::reverse(54),
+        ],
+    ),
+    (
+        Scale::FamilySupport,
+        &[
+            ScaleItem::reverse(49),
+            ScaleItem::reverse(52),
+            ScaleItem::reverse(55),
+        ],
+    ),
+];
+
+const _: () = {
+    let mut i = 0;
+    while i < SCALE_ITEMS.len() {
+        assert!(SCALE_ITEMS[i].0 as usize == i);
+        i += 1;
+    }
+};
+
 pub struct IntermediateConversionScore {
     /// 心理的な仕事の負担（量）
     mental_work_stress_volume: u8,
@@ -325,6 +1049,32 @@ pub struct IntermediateConversionScore {
     family_support: u8,
 }
 
+impl IntermediateConversionScore {
+    /// 尺度ごとの計算結果を取得する
+    pub fn get(&self, scale: Scale) -> u8 {
+        match scale {
+            Scale::MentalWorkStressVolume => self.mental_work_stress_volume,
+            Scale::MentalWorkStressQuality => self.mental_work_stress_quality,
+            Scale::AwarePhysicalStress => self.aware_physical_stress,
+            Scale::WorkPeopleStress => self.work_people_stress,
+            Scale::WorkEnvStress => self.work_env_stress,
+            Scale::WorkControl => self.work_control,
+            Scale::SkillApply => self.skill_apply,
+            Scale::WorkApply => self.work_apply,
+            Scale::DecentWork => self.decent_work,
+            Scale::Vitality => self.vitality,
+            Scale::Iraira => self.iraira,
+            Scale::Tired => self.tired,
+            Scale::Anxious => self.anxious,
+            Scale::Depressed => self.depressed,
+            Scale::PhysicalComplaint => self.physical_complaint,
+            Scale::BossSupport => self.boss_support,
+            Scale::ColleagueSupport => self.colleague_support,
+            Scale::FamilySupport => self.family_support,
+        }
+    }
+}
+
 impl TryFrom<IntermediateConversionScore> for ConversionScore {
     type Error = Error;
 
@@ -473,6 +1223,7 @@ impl TryFrom<IntermediateConversionScore> for ConversionScore {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct ConversionScore {
     /// 心理的な仕事の負担（量）
     mental_work_stress_volume: u8,
@@ -516,10 +1267,72 @@ pub struct ConversionScore {
     family_support: u8,
 }
 
+impl TryFrom<&AnswerStore> for ConversionScore {
+    type Error = Error;
+
+    fn try_from(store: &AnswerStore) -> Result<Self, Self::Error> {
+        store.to_conversion_score()
+    }
+}
+
+impl ConversionScore {
+    /// 尺度ごとの評価点（`Scale::ALL`の順）から作成する
+    /// 評価点はいずれも1〜5の範囲であること。
+    pub fn new(points: [u8; 18]) -> Result<Self, Error> {
+        if points.iter().any(|point| !(1..=5).contains(point)) {
+            return Err(Error::IllegalScore);
+        }
+        Ok(Self {
+            mental_work_stress_volume: points[0],
+            mental_work_stress_quality: points[1],
+            aware_physical_stress: points[2],
+            work_people_stress: points[3],
+            work_env_stress: points[4],
+            work_control: points[5],
+            skill_apply: points[6],
+            work_apply: points[7],
+            decent_work: points[8],
+            vitality: points[9],
+            iraira: points[10],
+            tired: points[11],
+            anxious: points[12],
+            depressed: points[13],
+            physical_complaint: points[14],
+            boss_support: points[15],
+            colleague_support: points[16],
+            family_support: points[17],
+        })
+    }
+
+    /// 尺度ごとの評価点を取得する
+    pub fn get(&self, scale: Scale) -> u8 {
+        match scale {
+            Scale::MentalWorkStressVolume => self.mental_work_stress_volume,
+            Scale::MentalWorkStressQuality => self.mental_work_stress_quality,
+            Scale::AwarePhysicalStress => self.aware_physical_stress,
+            Scale::WorkPeopleStress => self.work_people_stress,
+            Scale::WorkEnvStress => self.work_env_stress,
+            Scale::WorkControl => self.work_control,
+            Scale::SkillApply => self.skill_apply,
+            Scale::WorkApply => self.work_apply,
+            Scale::DecentWork => self.decent_work,
+            Scale::Vitality => self.vitality,
+            Scale::Iraira => self.iraira,
+            Scale::Tired => self.tired,
+            Scale::Anxious => self.anxious,
+            Scale::Depressed => self.depressed,
+            Scale::PhysicalComplaint => self.physical_complaint,
+            Scale::BossSupport => self.boss_support,
+            Scale::ColleagueSupport => self.colleague_support,
+            Scale::FamilySupport => self.family_support,
+        }
+    }
+}
+
+/// 評価基準の設定例（その２）で判定する。他の数値基準で判定する場合は`StressCriteria::score`を用いる
 impl Stress for ConversionScore {
     fn has_stress(&self) -> bool {
-        let (sum_a, sum_b, sum_c) = self.scores();
-        sum_b <= 12 || (sum_a + sum_c <= 26 && sum_b <= 17)
+        StressCriteria::CONVERSION.judge(self.scores())
     }
 
     fn scores(&self) -> (u8, u8, u8) {
@@ -544,6 +1357,54 @@ impl Stress for ConversionScore {
     }
 }
 
+impl StressReport for ConversionScore {
+    fn method(&self) -> &'static str {
+        StressCriteria::CONVERSION.method()
+    }
+
+    fn detail(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap()
+    }
+
+    fn criterion(&self) -> Option<Criterion> {
+        StressCriteria::CONVERSION.criterion(self.scores())
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// 受検者一人分の判定結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CheckResult {
+    /// 高ストレス者の選定方法
+    pub criteria: StressCriteria,
+    /// 領域Ａ・Ｂ・Ｃの点数
+    pub scores: (u8, u8, u8),
+    /// 尺度ごとの評価点
+    pub evaluation_points: BTreeMap<Scale, u8>,
+    /// 高ストレス者に該当するか
+    pub has_stress: bool,
+}
+
+impl CheckResult {
+    /// 回答を選定方法に従って判定する
+    pub fn new(store: &AnswerStore, criteria: StressCriteria) -> Result<Self, Error> {
+        let conversion = store.to_conversion_score()?;
+        let scores = criteria.score(store)?.scores();
+        Ok(Self {
+            criteria,
+            scores,
+            evaluation_points: Scale::ALL
+                .iter()
+                .map(|&scale| (scale, conversion.get(scale)))
+                .collect(),
+            has_stress: criteria.judge(scores),
+        })
+    }
+}
+
 #[derive(Debug, Default, Deserialize)]
 struct BulkRow {
     /// ユーザ特定キー
@@ -605,6 +1466,15 @@ struct BulkRow {
     q_55: u8,
     q_56: u8,
     q_57: u8,
+    /// 所属部署
+    #[serde(default, deserialize_with = "group_code::deserialize_optional")]
+    department: Option<group_code::GroupCode>,
+    /// 年齢階層
+    age_band: Option<AgeBand>,
+    /// 雇用形態
+    employment_type: Option<EmploymentType>,
+    /// 勤続年数
+    tenure: Option<Tenure>,
 }
 
 impl From<BulkRow> for (String, AnswerStore) {
@@ -623,21 +1493,270 @@ impl From<BulkRow> for (String, AnswerStore) {
                     row.q_57,
                 ],
                 offset: 57,
+                changes: vec![],
             },
         )
     }
 }
 
+impl From<BulkRow> for CheckRecord {
+    fn from(mut row: BulkRow) -> Self {
+        let department = row.department.take();
+        let demographics = Demographics {
+            age_band: row.age_band.take(),
+            employment_type: row.employment_type.take(),
+            tenure: row.tenure.take(),
+        };
+        let (id, answers) = row.into();
+        CheckRecord {
+            id,
+            department,
+            demographics: (!demographics.is_empty()).then_some(demographics),
+            consent: None,
+            answers,
+            amendments: vec![],
+        }
+    }
+}
+
+/// 回答者一人分の受検記録
+#[derive(Debug, Clone)]
+pub struct CheckRecord {
+    /// ユーザ特定キー
+    pub id: String,
+    /// 所属部署（表記揺れを正規化したもの）
+    pub department: Option<group_code::GroupCode>,
+    /// 回答者属性
+    pub demographics: Option<Demographics>,
+    /// 事業者への結果提供に関する同意
+    pub consent: Option<Consent>,
+    pub answers: AnswerStore,
+    /// 受検後の回答の修正
+    pub amendments: Vec<amend::Amendment>,
+}
+
+/// CSVを読み込む
+/// 文字コードはUTF-8（BOM付きを含む）とShift_JISのいずれも受け付け、全角数字は半角とみなす。
+/// 列名は`設問1`・`社員番号`などの組み込みの別名も受け付ける（[`header`]を参照）。
 pub fn read_bulk<T>(reader: T) -> Vec<Result<(String, AnswerStore), Error>>
 where
     T: BufRead,
 {
-    let mut reader = csv::Reader::from_reader(reader);
+    read_bulk_rows(reader, &header::HeaderAliases::default())
+        .into_iter()
+        .map(|row| row.map(|row| row.into()))
+        .collect()
+}
+
+/// 所属部署・回答者属性の列を含めてCSVを読み込む
+/// department, age_band, employment_type, tenure の列はいずれも省略可能。
+/// 文字コード・全角数字・列名の扱いは`read_bulk`と同じ。
+pub fn read_bulk_records<T>(reader: T) -> Vec<Result<CheckRecord, Error>>
+where
+    T: BufRead,
+{
+    read_bulk_records_with_aliases(reader, &header::HeaderAliases::default())
+}
+
+/// 列名の別名表を指定して`read_bulk_records`と同様に読み込む
+pub fn read_bulk_records_with_aliases<T>(
+    reader: T,
+    aliases: &header::HeaderAliases,
+) -> Vec<Result<CheckRecord, Error>>
+where
+    T: BufRead,
+{
+    read_bulk_rows(reader, aliases)
+        .into_iter()
+        .map(|row| row.map(|row| row.into()))
+        .collect()
+}
+
+fn read_bulk_rows<T>(reader: T, aliases: &header::HeaderAliases) -> Vec<Result<BulkRow, Error>>
+where
+    T: BufRead,
+{
+    let text = match encoding::read_text(reader) {
+        Ok(text) => text,
+        Err(e) => return vec![Err(e)],
+    };
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let headers = match reader.headers() {
+        Ok(headers) => aliases.rename(headers),
+        Err(e) => return vec![Err(Error::CSVReadError(e))],
+    };
+    let id = headers.iter().position(|header| header == "id");
+    reader.set_headers(headers);
     reader
         .deserialize()
-        .map(|row: Result<BulkRow, _>| row.map(|row| row.into()))
-        .map(|row| row.map_err(Error::CSVReadError))
-        .collect::<Vec<Result<(String, AnswerStore), Error>>>()
+        .map(|row: Result<BulkRow, _>| row.map_err(|e| RowError::from_csv(e, &text, id)))
+        .collect()
+}
+
+/// 受検記録を`read_bulk_records`で読み込める形式のCSVで書き出す
+/// 読み戻した値が変わらないよう、数式の無害化は行わない。表計算ソフトで開く出力には`export::Sanitizer`を用いる。
+pub fn write_bulk_records<W>(writer: W, records: &[CheckRecord]) -> Result<(), Error>
+where
+    W: std::io::Write,
+{
+    let mut writer = csv::Writer::from_writer(writer);
+    let mut header = vec!["id".to_string()];
+    header.extend((1..=57).map(|i| format!("q_{}", i)));
+    header.extend(
+        ["department", "age_band", "employment_type", "tenure"]
+            .iter()
+            .map(|column| column.to_string()),
+    );
+    writer.write_record(&header).map_err(Error::CSVWriteError)?;
+    for record in records {
+        let demographics = record.demographics.clone().unwrap_or_default();
+        let mut row = vec![record.id.clone()];
+        row.extend(record.answers.values().iter().map(|value| match value {
+            0 => String::new(),
+            value => value.to_string(),
+        }));
+        row.push(record.department.as_deref().unwrap_or_default().to_string());
+        row.push(
+            demographics
+                .age_band
+                .map(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        );
+        row.push(
+            demographics
+                .employment_type
+                .map(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        );
+        row.push(
+            demographics
+                .tenure
+                .map(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+        );
+        writer.write_record(&row).map_err(Error::CSVWriteError)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// 回答ページでダウンロードしたJSONを読み込む
+/// 1件のオブジェクトまたはオブジェクトの配列を受け付ける。列名はCSVと同じ。
+pub fn read_bulk_json<T>(reader: T) -> Result<Vec<CheckRecord>, Error>
+where
+    T: std::io::Read,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Rows {
+        One(Box<BulkRow>),
+        Many(Vec<BulkRow>),
+    }
+    let rows = match serde_json::from_reader(reader).map_err(Error::JSONReadError)? {
+        Rows::One(row) => vec![*row],
+        Rows::Many(rows) => rows,
+    };
+    Ok(rows.into_iter().map(|row| row.into()).collect())
+}
+
+/// 読み込み元のファイルを付与した受検記録
+#[derive(Debug)]
+pub struct SourcedRecord {
+    /// 読み込み元のファイル
+    pub source: PathBuf,
+    pub record: Result<CheckRecord, Error>,
+}
+
+/// 拠点ごとのCSVなど複数のファイルをまとめて読み込む
+/// `.gz`・`.zst`のファイルは展開して読み込む。
+/// 既に読み込んだ記録とユーザ特定キーが重複する行は`Error::DuplicateId`とする。
+/// 記録はファイルを指定した順、ファイル内の行の順に返す。
+pub fn read_bulk_many<P>(paths: &[P]) -> Result<Vec<SourcedRecord>, Error>
+where
+    P: AsRef<Path>,
+{
+    read_bulk_many_with_aliases(paths, &header::HeaderAliases::default())
+}
+
+/// 列名の別名表を指定して`read_bulk_many`と同様に読み込む
+pub fn read_bulk_many_with_aliases<P>(
+    paths: &[P],
+    aliases: &header::HeaderAliases,
+) -> Result<Vec<SourcedRecord>, Error>
+where
+    P: AsRef<Path>,
+{
+    let mut ids = HashSet::new();
+    let mut records = vec![];
+    for path in paths {
+        let path = path.as_ref();
+        for record in read_bulk_records_with_aliases(compress::open_input(path)?, aliases) {
+            let record = record.and_then(|record| {
+                if ids.insert(record.id.clone()) {
+                    Ok(record)
+                } else {
+                    Err(Error::DuplicateId(record.id))
+                }
+            });
+            records.push(SourcedRecord {
+                source: path.to_path_buf(),
+                record,
+            });
+        }
+    }
+    Ok(records)
+}
+
+/// 読み込めなかった行の位置と内容
+#[derive(Debug)]
+pub struct RowError {
+    /// 行番号（見出し行を1行目とする）
+    pub line: u64,
+    /// 文字列に変換した後の先頭からのバイト位置
+    pub byte: u64,
+    /// 行の内容。ユーザ特定キーは伏字にする
+    pub raw: String,
+    pub error: csv::Error,
+}
+
+impl RowError {
+    /// 位置の分からないエラーは`CSVReadError`のままとする
+    fn from_csv(error: csv::Error, text: &str, id: Option<usize>) -> Error {
+        let Some(position) = error.position().cloned() else {
+            return Error::CSVReadError(error);
+        };
+        let line = text
+            .get(position.byte() as usize..)
+            .and_then(|rest| rest.lines().next())
+            .unwrap_or_default();
+        let raw = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .from_reader(line.as_bytes())
+            .records()
+            .next()
+            .and_then(Result::ok)
+            .map(|record| {
+                record
+                    .iter()
+                    .enumerate()
+                    .map(|(i, value)| match id {
+                        Some(id) if id == i => export::REDACTED,
+                        _ => value,
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .unwrap_or_default();
+        Error::InvalidRow(Box::new(RowError {
+            line: position.line(),
+            byte: position.byte(),
+            raw,
+            error,
+        }))
+    }
 }
 
 #[derive(Debug)]
@@ -646,12 +1765,76 @@ pub enum Error {
     IOError(std::io::Error),
     /// CSV Read Error
     CSVReadError(csv::Error),
+    /// CSVの書き出しエラー
+    CSVWriteError(csv::Error),
     /// 57設問ではない
     IllegalQuestion,
     /// 回答選択肢が違反
     IllegalAnswer,
     /// 回答欠落
     NotFullfilled,
+    /// 設定ファイルの読み込みエラー
+    ConfigReadError(toml::de::Error),
+    /// 設定ファイルの書き出しエラー
+    ConfigWriteError(toml::ser::Error),
+    /// 設定値が違反
+    IllegalConfig,
+    /// ユーザ特定キーの重複
+    DuplicateId(String),
+    /// 圧縮形式に未対応
+    UnsupportedCompression(compress::Compression),
+    /// 点数が範囲外
+    IllegalScore,
+    /// マスタの読み込みエラー
+    MasterReadError(serde_json::Error),
+    /// 台帳の改ざんを検出（行番号）
+    TamperedLedger(usize),
+    /// メールの送信エラー
+    MailError(String),
+    /// Webhookの送信エラー
+    WebhookError(String),
+    /// JSONの読み込みエラー
+    JSONReadError(serde_json::Error),
+    /// 回答用紙の版が異なる
+    UnknownSheetVersion(String),
+    /// 符号化された回答を復元できない
+    IllegalEncoding,
+    /// Arrow/Parquetの書き出しエラー
+    ColumnarWriteError(String),
+    /// 判定スレッドが終了している
+    ChannelClosed(String),
+    /// マスタの設問構成が不正
+    IllegalMaster(Vec<verify::Discrepancy>),
+    /// 送信頻度の制限を超えた（再送信までの秒数）
+    RateLimited(u64),
+    /// 本文サイズ・行数の上限を超えた
+    PayloadTooLarge(String),
+    /// テナントが登録されていない
+    UnknownTenant(String),
+    /// 保存先の読み書きエラー
+    StorageError(String),
+    /// 必要な列がない
+    MissingColumn(String),
+    /// 行を読み込めない（行番号・内容付き）
+    InvalidRow(Box<RowError>),
+    /// 再開しようとした進捗と実施設定が異なる（進捗の実施設定のハッシュ）
+    CheckpointMismatch(String),
+    /// 冪等性キーが異なる内容の送信に使われた
+    IdempotencyConflict(String),
+    /// リソースの場所が確定した後に変更しようとした（確定した場所）
+    ResourceDirLocked(PathBuf),
+    /// リソースが見つからない
+    ResourceNotFound(PathBuf),
+    /// 回答ファイル（TOML/YAML）の読み込みエラー
+    AnswerFileReadError(String),
+    /// 部署などの集団コードが不正
+    IllegalGroupCode(String),
+    /// 保存形式の版が不正、または読み込めない新しい版（記録の版）
+    UnsupportedSchemaVersion(String),
+    /// 他の実行がロックを取得している（ロックファイル・取得している実行の情報）
+    Locked(PathBuf, String),
+    /// 採点の自己診断に用いるマニュアルの回答例が同梱されていない
+    ManualExamplesMissing,
 }
 
 impl From<std::io::Error> for Error {
@@ -660,6 +1843,174 @@ impl From<std::io::Error> for Error {
     }
 }
 
+impl Error {
+    /// APIのエラー応答などに用いる安定したエラーコード
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::IOError(_) => "E_IO",
+            Error::CSVReadError(_) => "E_ROW_PARSE",
+            Error::CSVWriteError(_) => "E_CSV_WRITE",
+            Error::IllegalQuestion => "E_QUESTION_RANGE",
+            Error::IllegalAnswer => "E_ANSWER_RANGE",
+            Error::NotFullfilled => "E_INCOMPLETE",
+            Error::ConfigReadError(_) => "E_CONFIG_PARSE",
+            Error::ConfigWriteError(_) => "E_CONFIG_WRITE",
+            Error::IllegalConfig => "E_CONFIG_INVALID",
+            Error::DuplicateId(_) => "E_DUPLICATE_ID",
+            Error::UnsupportedCompression(_) => "E_UNSUPPORTED_COMPRESSION",
+            Error::IllegalScore => "E_SCORE_RANGE",
+            Error::MasterReadError(_) => "E_MASTER_PARSE",
+            Error::TamperedLedger(_) => "E_LEDGER_TAMPERED",
+            Error::MailError(_) => "E_MAIL",
+            Error::WebhookError(_) => "E_WEBHOOK",
+            Error::JSONReadError(_) => "E_JSON_PARSE",
+            Error::UnknownSheetVersion(_) => "E_SHEET_VERSION",
+            Error::IllegalEncoding => "E_ENCODING",
+            Error::ColumnarWriteError(_) => "E_COLUMNAR_WRITE",
+            Error::ChannelClosed(_) => "E_CHANNEL_CLOSED",
+            Error::IllegalMaster(_) => "E_MASTER_INVALID",
+            Error::RateLimited(_) => "E_RATE_LIMITED",
+            Error::PayloadTooLarge(_) => "E_PAYLOAD_TOO_LARGE",
+            Error::UnknownTenant(_) => "E_TENANT_UNKNOWN",
+            Error::StorageError(_) => "E_STORAGE",
+            Error::MissingColumn(_) => "E_COLUMN_MISSING",
+            Error::InvalidRow(_) => "E_ROW_PARSE",
+            Error::CheckpointMismatch(_) => "E_CHECKPOINT_MISMATCH",
+            Error::IdempotencyConflict(_) => "E_IDEMPOTENCY_CONFLICT",
+            Error::ResourceDirLocked(_) => "E_RESOURCE_DIR_LOCKED",
+            Error::ResourceNotFound(_) => "E_RESOURCE_NOT_FOUND",
+            Error::AnswerFileReadError(_) => "E_ANSWER_FILE_PARSE",
+            Error::IllegalGroupCode(_) => "E_GROUP_CODE",
+            Error::UnsupportedSchemaVersion(_) => "E_SCHEMA_VERSION",
+            Error::Locked(_, _) => "E_LOCKED",
+            Error::ManualExamplesMissing => "E_MANUAL_EXAMPLES_MISSING",
+        }
+    }
+
+    /// HTTPで応答する場合のステータスコード
+    pub fn http_status(&self) -> u16 {
+        match self {
+            Error::RateLimited(_) => 429,
+            Error::PayloadTooLarge(_) => 413,
+            Error::UnknownTenant(_) => 404,
+            Error::IdempotencyConflict(_) | Error::Locked(_, _) => 409,
+            Error::IOError(_)
+            | Error::ConfigWriteError(_)
+            | Error::CSVWriteError(_)
+            | Error::MailError(_)
+            | Error::WebhookError(_)
+            | Error::ColumnarWriteError(_)
+            | Error::ChannelClosed(_)
+            | Error::TamperedLedger(_)
+            | Error::StorageError(_)
+            | Error::ResourceDirLocked(_)
+            | Error::ResourceNotFound(_)
+            | Error::ManualExamplesMissing => 500,
+            _ => 400,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::IOError(e) => write!(f, "入出力エラー: {}", e),
+            Error::CSVReadError(e) => write!(f, "CSVの読み込みエラー: {}", e),
+            Error::CSVWriteError(e) => write!(f, "CSVの書き出しエラー: {}", e),
+            Error::IllegalQuestion => write!(f, "設問番号は1〜57で指定してください"),
+            Error::IllegalAnswer => write!(f, "回答は1〜4で指定してください"),
+            Error::NotFullfilled => write!(f, "未回答の設問があります"),
+            Error::ConfigReadError(e) => write!(f, "設定ファイルの読み込みエラー: {}", e),
+            Error::ConfigWriteError(e) => write!(f, "設定ファイルの書き出しエラー: {}", e),
+            Error::IllegalConfig => write!(f, "設定値が不正です"),
+            Error::DuplicateId(id) => write!(f, "ユーザ特定キーが重複しています: {}", id),
+            Error::UnsupportedCompression(compression) => {
+                write!(f, "未対応の圧縮形式です: {:?}", compression)
+            }
+            Error::IllegalScore => write!(f, "点数が範囲外です"),
+            Error::MasterReadError(e) => write!(f, "マスタの読み込みエラー: {}", e),
+            Error::TamperedLedger(line) => write!(f, "台帳の{}行目で改ざんを検出しました", line),
+            Error::MailError(e) => write!(f, "メールの送信エラー: {}", e),
+            Error::WebhookError(e) => write!(f, "Webhookの送信エラー: {}", e),
+            Error::JSONReadError(e) => write!(f, "JSONの読み込みエラー: {}", e),
+            Error::UnknownSheetVersion(e) => write!(f, "回答用紙を識別できません: {}", e),
+            Error::IllegalEncoding => write!(f, "符号化された回答を復元できません"),
+            Error::ColumnarWriteError(e) => write!(f, "Arrow/Parquetの書き出しエラー: {}", e),
+            Error::ChannelClosed(id) => {
+                write!(f, "判定スレッドが終了しているため送信できません: {}", id)
+            }
+            Error::IllegalMaster(e) => write!(f, "マスタの設問構成が不正です: {:?}", e),
+            Error::RateLimited(seconds) => {
+                write!(f, "送信が多すぎます。{}秒後に再送信してください", seconds)
+            }
+            Error::PayloadTooLarge(limit) => write!(f, "送信できる上限を超えています: {}", limit),
+            Error::UnknownTenant(id) => write!(f, "テナントが登録されていません: {}", id),
+            Error::StorageError(e) => write!(f, "保存先の読み書きエラー: {}", e),
+            Error::MissingColumn(column) => write!(f, "必要な列がありません: {}", column),
+            Error::CheckpointMismatch(hash) => write!(
+                f,
+                "進捗を記録した時と実施設定が異なるため再開できません: {}",
+                hash
+            ),
+            Error::IdempotencyConflict(key) => {
+                write!(f, "冪等性キーが異なる内容の送信に使われています: {}", key)
+            }
+            Error::InvalidRow(row) => write!(
+                f,
+                "CSVの{}行目（{}バイト目）を読み込めません: {}: {}",
+                row.line, row.byte, row.error, row.raw
+            ),
+            Error::ResourceDirLocked(dir) => write!(
+                f,
+                "リソースの場所は既に{}に確定しているため変更できません",
+                dir.display()
+            ),
+            Error::ResourceNotFound(path) => write!(
+                f,
+                "リソースが見つかりません: {}（環境変数{}で場所を指定できます）",
+                path.display(),
+                resource::RESOURCE_DIR_ENV
+            ),
+            Error::AnswerFileReadError(e) => write!(f, "回答ファイルの読み込みエラー: {}", e),
+            Error::IllegalGroupCode(e) => write!(f, "集団コードが不正です: {}", e),
+            Error::UnsupportedSchemaVersion(version) => {
+                write!(f, "読み込めない保存形式の版です: {}", version)
+            }
+            Error::Locked(path, holder) => {
+                write!(
+                    f,
+                    "他の実行がロックを取得しているため終了します: {}",
+                    path.display()
+                )?;
+                if !holder.is_empty() {
+                    write!(f, "（{}）", holder)?;
+                }
+                Ok(())
+            }
+            Error::ManualExamplesMissing => write!(
+                f,
+                "マニュアルの回答例が同梱されていないため、採点を自己診断できません"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// `{"code": "E_INCOMPLETE", "message": "..."}`の形式で出力する
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Error", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use std::io::{BufReader, Cursor};
@@ -671,6 +2022,11 @@ mod test {
         assert_eq!(Some(1), QUESTIONS.get(0).map(|q| q.id));
         assert_eq!(Some(57), QUESTIONS.get(56).map(|q| q.id));
         assert_eq!(None, QUESTIONS.get(57).map(|q| q.id));
+        // 取得した設問はマスタと共有する
+        assert!(Arc::ptr_eq(
+            &QUESTIONS.get(0).unwrap(),
+            &QUESTIONS.questions()[0]
+        ));
     }
 
     #[test]
@@ -684,7 +2040,7 @@ mod test {
     fn test_questions() {
         let questions = QUESTIONS.questions();
         assert_eq!(questions.len(), 57);
-        assert_eq!(questions.get(0).map(|q| q.id), Some(1));
+        assert_eq!(questions.first().map(|q| q.id), Some(1));
         assert_eq!(questions.get(56).map(|q| q.id), Some(57));
         assert_eq!(questions.get(57).map(|q| q.id), None);
     }
@@ -735,6 +2091,98 @@ mod test {
         assert_eq!(score.sum_c, 4 * 9);
     }
 
+    #[test]
+    fn test_score_slice() {
+        const SUMUP: SumupScore = sumup_unchecked(&[2; 57]);
+        assert_eq!(SUMUP.scores(), (45, 61, 18));
+        let values = std::array::from_fn(|i| (i * 5 % 4 + 1) as u8);
+        let mut store = AnswerStore::default();
+        for &value in &values {
+            store.push(value).unwrap();
+        }
+        assert_eq!(
+            sumup_score_slice(&values).unwrap(),
+            store.to_sumup_score().unwrap()
+        );
+        assert_eq!(
+            conversion_score_slice(&values).unwrap(),
+            store.to_conversion_score().unwrap()
+        );
+        for criteria in [StressCriteria::SUMUP, StressCriteria::CONVERSION] {
+            assert_eq!(
+                score_slice(&values, criteria).unwrap(),
+                criteria.score(&store).unwrap().scores()
+            );
+        }
+        let mut values = [1; 57];
+        values[56] = 0;
+        assert!(matches!(
+            sumup_score_slice(&values),
+            Err(Error::NotFullfilled)
+        ));
+        values[56] = 5;
+        assert!(matches!(
+            score_slice(&values, StressCriteria::CONVERSION),
+            Err(Error::IllegalAnswer)
+        ));
+    }
+
+    #[test]
+    fn test_stress_report() {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(4).unwrap();
+        }
+        let report = StressCriteria::SUMUP.score(&store).unwrap();
+        assert_eq!(report.method(), "sumup");
+        assert_eq!(report.criterion(), Some(Criterion::B));
+        assert_eq!(
+            report.downcast_ref::<SumupScore>(),
+            Some(&store.to_sumup_score().unwrap())
+        );
+        assert!(report.downcast_ref::<ConversionScore>().is_none());
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["method"], "sumup");
+        assert_eq!(value["criterion"], "b");
+        assert_eq!(value["detail"]["sum_b"], 107);
+
+        let report = StressCriteria::CONVERSION.score(&store).unwrap();
+        assert_eq!(report.method(), "conversion");
+        assert_eq!(report.criterion().is_some(), report.has_stress());
+        assert!(report.downcast_ref::<ConversionScore>().is_some());
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["detail"].as_object().unwrap().len(), 18);
+
+        // 独自の数値基準では、設定例ではなくその数値基準で判定する
+        let criteria = StressCriteria::Sumup(Thresholds {
+            b: 108,
+            ac: 71,
+            b_with_ac: 100,
+        });
+        let report = criteria.score(&store).unwrap();
+        assert_eq!(report.criteria(), criteria);
+        assert_eq!(report.scores(), (35, 107, 36));
+        assert_eq!(report.criterion(), Some(Criterion::AcWithB));
+        assert!(report.downcast_ref::<SumupScore>().unwrap().has_stress());
+        let criteria = StressCriteria::Sumup(Thresholds {
+            b: 108,
+            ac: 72,
+            b_with_ac: 100,
+        });
+        let report = criteria.score(&store).unwrap();
+        assert_eq!(report.criterion(), None);
+        assert!(!report.has_stress());
+        let value = serde_json::to_value(&report).unwrap();
+        assert_eq!(value["has_stress"], false);
+        assert_eq!(value["criterion"], serde_json::Value::Null);
+
+        assert_eq!(
+            StressCriteria::SUMUP.criterion((76, 63, 0)),
+            Some(Criterion::AcWithB)
+        );
+        assert_eq!(StressCriteria::SUMUP.criterion((76, 62, 0)), None);
+    }
+
     #[test]
     fn test_answer_not_fullfilled() {
         let mut store = AnswerStore::default();
@@ -834,10 +2282,112 @@ mod test {
         assert_eq!(store.colleague_support, 5);
 
         assert_eq!(store.scores(), (22, 26, 15));
+        assert_eq!(store.get(Scale::WorkControl), 5);
+        assert_eq!(store.get(Scale::FamilySupport), 5);
 
         assert!(!store.has_stress());
     }
 
+    #[test]
+    fn test_check_result() {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            assert!(store.push(4).is_ok());
+        }
+        let result = CheckResult::new(&store, StressCriteria::SUMUP).unwrap();
+        assert_eq!(result.scores, (35, 107, 36));
+        assert!(result.has_stress);
+        assert_eq!(result.evaluation_points.len(), 18);
+
+        let result = CheckResult::new(&store, StressCriteria::CONVERSION).unwrap();
+        assert_eq!(result.scores, store.to_conversion_score().unwrap().scores());
+        assert_eq!(result.evaluation_points[&Scale::WorkControl], 1);
+
+        assert!(CheckResult::new(&AnswerStore::default(), StressCriteria::SUMUP).is_err());
+    }
+
+    #[test]
+    fn test_score_new() {
+        let score = SumupScore::new(46, 63, 30).unwrap();
+        assert!(score.has_stress());
+        assert!(SumupScore::new(16, 63, 30).is_err());
+        assert!(SumupScore::new(46, 117, 30).is_err());
+        assert!(SumupScore::new(46, 63, 37).is_err());
+
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            assert!(store.push(1).is_ok());
+        }
+        assert_eq!(
+            SumupScore::try_from(&store).unwrap(),
+            store.to_sumup_score().unwrap()
+        );
+
+        let expected = store.to_conversion_score().unwrap();
+        let points = Scale::ALL.map(|scale| expected.get(scale));
+        assert_eq!(ConversionScore::new(points).unwrap(), expected);
+        assert_eq!(ConversionScore::try_from(&store).unwrap(), expected);
+        assert!(matches!(
+            ConversionScore::new([0; 18]),
+            Err(Error::IllegalScore)
+        ));
+    }
+
+    /// マニュアルの「素点換算表に基づく評価点の算出方法」の計算式
+    fn manual_formula(scale: Scale, values: &[u8; 57]) -> i32 {
+        let q = |id: usize| i32::from(values[id - 1]);
+        match scale {
+            Scale::MentalWorkStressVolume => 15 - (q(1) + q(2) + q(3)),
+            Scale::MentalWorkStressQuality => 15 - (q(4) + q(5) + q(6)),
+            Scale::AwarePhysicalStress => 5 - q(7),
+            Scale::WorkPeopleStress => 10 - (q(12) + q(13)) + q(14),
+            Scale::WorkEnvStress => 5 - q(15),
+            Scale::WorkControl => 15 - (q(8) + q(9) + q(10)),
+            Scale::SkillApply => q(11),
+            Scale::WorkApply => 5 - q(16),
+            Scale::DecentWork => 5 - q(17),
+            Scale::Vitality => q(18) + q(19) + q(20),
+            Scale::Iraira => q(21) + q(22) + q(23),
+            Scale::Tired => q(24) + q(25) + q(26),
+            Scale::Anxious => q(27) + q(28) + q(29),
+            Scale::Depressed => (30..=35).map(q).sum(),
+            Scale::PhysicalComplaint => (36..=46).map(q).sum(),
+            Scale::BossSupport => 15 - (q(47) + q(50) + q(53)),
+            Scale::ColleagueSupport => 15 - (q(48) + q(51) + q(54)),
+            Scale::FamilySupport => 15 - (q(49) + q(52) + q(55)),
+        }
+    }
+
+    #[test]
+    fn test_scale_items() {
+        let mut questions = Scale::ALL
+            .iter()
+            .flat_map(|scale| scale.questions())
+            .collect::<Vec<u8>>();
+        questions.sort();
+        assert_eq!(questions, (1..=55).collect::<Vec<u8>>());
+
+        // 1問だけ回答を変えた全ての組み合わせで計算式と一致すること
+        for base in 1..=4 {
+            for question in 0..55 {
+                for value in 1..=4 {
+                    let mut values = [base; 57];
+                    values[question] = value;
+                    let score = intermediate_score_slice(&values).unwrap();
+                    for scale in Scale::ALL {
+                        assert_eq!(
+                            i32::from(score.get(scale)),
+                            manual_formula(scale, &values),
+                            "{} q{} = {value}",
+                            scale.as_str(),
+                            question + 1
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_conversion_score_answer_not_fullfilled() {
         let mut store = AnswerStore::default();
@@ -864,7 +2414,198 @@ mod test {
         let line = iter.next().unwrap();
         assert!(line.is_err());
         let Err(e) = line else { panic!() };
-        assert!(matches!(e, Error::CSVReadError(_)));
+        assert_eq!(e.code(), "E_ROW_PARSE");
+        let Error::InvalidRow(row) = e else { panic!() };
+        assert_eq!(row.line, 4);
+        assert!(row.raw.starts_with("***,,2,3,1"));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn test_read_bulk_records() {
+        let cursor = Cursor::new(
+            r#"id,q_1,q_2,q_3,q_4,q_5,q_6,q_7,q_8,q_9,q_10,q_11,q_12,q_13,q_14,q_15,q_16,q_17,q_18,q_19,q_20,q_21,q_22,q_23,q_24,q_25,q_26,q_27,q_28,q_29,q_30,q_31,q_32,q_33,q_34,q_35,q_36,q_37,q_38,q_39,q_40,q_41,q_42,q_43,q_44,q_45,q_46,q_47,q_48,q_49,q_50,q_51,q_52,q_53,q_54,q_55,q_56,q_57,department,age_band,employment_type,tenure
+"1",1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,営業部,thirties,regular,
+"2",1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,1,2,3,,,,"#,
+        );
+        let mut iter = read_bulk_records(BufReader::new(cursor)).into_iter();
+        let record = iter.next().unwrap().unwrap();
+        assert_eq!(record.department.as_deref(), Some("営業部"));
+        let demographics = record.demographics.unwrap();
+        assert_eq!(demographics.age_band, Some(AgeBand::Thirties));
+        assert_eq!(demographics.employment_type, Some(EmploymentType::Regular));
+        assert_eq!(demographics.tenure, None);
+        let record = iter.next().unwrap().unwrap();
+        assert_eq!(record.department, None);
+        assert!(record.demographics.is_none());
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_read_bulk_records_shift_jis() {
+        let header = (1..=57)
+            .map(|no| format!("q_{}", no))
+            .collect::<Vec<_>>()
+            .join(",");
+        let text = format!(
+            "id,{},department\n０１,{},営業部\n",
+            header,
+            vec!["３"; 57].join(",")
+        );
+        let (bytes, _, _) = encoding_rs::SHIFT_JIS.encode(&text);
+        let records = read_bulk_records(&bytes[..]);
+        let record = records[0].as_ref().unwrap();
+        assert_eq!(record.id, "01");
+        assert_eq!(record.department.as_deref(), Some("営業部"));
+        assert_eq!(record.answers.values(), &[3; 57]);
+
+        let bom = format!("\u{FEFF}{}", text);
+        let records = read_bulk_records(bom.as_bytes());
+        assert_eq!(records[0].as_ref().unwrap().id, "01");
+        assert!(matches!(
+            read_bulk_records(&[0x82, 0xFF][..]).as_slice(),
+            [Err(Error::IllegalEncoding)]
+        ));
+    }
+
+    #[test]
+    fn test_read_bulk_records_aliases() {
+        let header = (1..=57)
+            .map(|no| format!("設問{}", no))
+            .collect::<Vec<_>>()
+            .join(",");
+        let text = format!(
+            "社員番号,{},部署名,職員区分\n0001,{},営業部,regular\n",
+            header,
+            vec!["2"; 57].join(",")
+        );
+        let records = read_bulk_records(text.as_bytes());
+        let record = records[0].as_ref().unwrap();
+        assert_eq!(record.id, "0001");
+        assert_eq!(record.department.as_deref(), Some("営業部"));
+        assert!(record.demographics.is_none());
+
+        let aliases = header::HeaderAliases::default().with_aliases(&BTreeMap::from([(
+            "職員区分".to_string(),
+            "employment_type".to_string(),
+        )]));
+        let records = read_bulk_records_with_aliases(text.as_bytes(), &aliases);
+        let demographics = records[0].as_ref().unwrap().demographics.clone().unwrap();
+        assert_eq!(demographics.employment_type, Some(EmploymentType::Regular));
+    }
+
+    #[test]
+    fn test_check_result_serialization_is_stable() {
+        // 下流のシステムが出力を比較するため、項目の順序を含めて固定する
+        let mut store = AnswerStore::default();
+        for i in 0..57 {
+            store.push((i % 4 + 1) as u8).unwrap();
+        }
+        let result = CheckResult::new(&store, StressCriteria::SUMUP).unwrap();
+        assert_eq!(
+            serde_json::to_string(&result).unwrap(),
+            concat!(
+                r#"{"criteria":{"method":"sumup","b":77,"ac":76,"b_with_ac":63},"#,
+                r#""scores":[42,69,23],"evaluation_points":{"#,
+                r#""mental_work_stress_volume":3,"mental_work_stress_quality":3,"#,
+                r#""aware_physical_stress":3,"work_people_stress":3,"work_env_stress":3,"#,
+                r#""work_control":3,"skill_apply":3,"work_apply":1,"decent_work":5,"#,
+                r#""vitality":4,"iraira":3,"tired":3,"anxious":2,"depressed":2,"#,
+                r#""physical_complaint":1,"boss_support":4,"colleague_support":2,"#,
+                r#""family_support":2},"has_stress":false}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_read_bulk_json() {
+        let row = (1..=57)
+            .map(|i| format!("\"q_{}\":2", i))
+            .collect::<Vec<_>>()
+            .join(",");
+        let records = read_bulk_json(format!("{{\"id\":\"1\",{}}}", row).as_bytes()).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].id, "1");
+        assert!(records[0].answers.to_sumup_score().is_ok());
+
+        let records = read_bulk_json(
+            format!("[{{\"id\":\"1\",{}}},{{\"id\":\"2\",{}}}]", row, row).as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(records[1].id, "2");
+        assert!(matches!(
+            read_bulk_json("{\"id\":\"1\"}".as_bytes()),
+            Err(Error::JSONReadError(_))
+        ));
+    }
+
+    #[test]
+    fn test_error_code() {
+        assert_eq!(Error::NotFullfilled.code(), "E_INCOMPLETE");
+        assert_eq!(Error::IllegalAnswer.code(), "E_ANSWER_RANGE");
+        let value = serde_json::to_value(Error::DuplicateId("1".to_string())).unwrap();
+        assert_eq!(value["code"], "E_DUPLICATE_ID");
+        assert_eq!(value["message"], "ユーザ特定キーが重複しています: 1");
+    }
+
+    #[test]
+    fn test_write_bulk_records_raw() {
+        let mut answers = AnswerStore::default();
+        for _ in 0..57 {
+            answers.push(2).unwrap();
+        }
+        let records = ["-A12", "+81312345678", "@abc", "=1+2"]
+            .map(|id| CheckRecord {
+                id: id.to_string(),
+                department: None,
+                demographics: None,
+                consent: None,
+                answers: answers.clone(),
+                amendments: vec![],
+            })
+            .to_vec();
+        let mut output = vec![];
+        write_bulk_records(&mut output, &records).unwrap();
+        let ids = read_bulk_records(output.as_slice())
+            .into_iter()
+            .map(|record| record.unwrap().id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["-A12", "+81312345678", "@abc", "=1+2"]);
+    }
+
+    #[test]
+    fn test_read_bulk_many() {
+        let header = (1..=57)
+            .map(|no| format!("q_{}", no))
+            .collect::<Vec<_>>()
+            .join(",");
+        let answers = vec!["1"; 57].join(",");
+        let dir = std::env::temp_dir().join("stresscheck_test_read_bulk_many");
+        std::fs::create_dir_all(&dir).unwrap();
+        let tokyo = dir.join("tokyo.csv");
+        let osaka = dir.join("osaka.csv");
+        std::fs::write(
+            &tokyo,
+            format!("id,{}\n\"1\",{}\n\"2\",{}\n", header, answers, answers),
+        )
+        .unwrap();
+        std::fs::write(
+            &osaka,
+            format!("id,{}\n\"3\",{}\n\"1\",{}\n", header, answers, answers),
+        )
+        .unwrap();
+
+        let records = read_bulk_many(&[&tokyo, &osaka]).unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0].source, tokyo);
+        assert_eq!(records[2].source, osaka);
+        assert_eq!(records[2].record.as_ref().unwrap().id, "3");
+        assert!(matches!(&records[3].record, Err(Error::DuplicateId(id)) if id == "1"));
+
+        assert!(matches!(
+            read_bulk_many(&[dir.join("missing.csv")]),
+            Err(Error::IOError(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,147 @@
+//! 受付の流量・サイズ制限
+//!
+//! 全社一斉の受検期間に、一部の利用者からの大量送信でサービスが停止しないよう、サーバ側で
+//! トークンごとの送信頻度と一括登録の本文サイズ・行数を制限するために用いる。制限を超えた場合の
+//! エラーは`Error::http_status`で429・413に対応付ける。
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::Error;
+
+/// 一括登録の本文サイズの既定の上限（バイト）
+pub const DEFAULT_MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+/// 一括登録の行数の既定の上限
+pub const DEFAULT_MAX_ROWS: usize = 50_000;
+
+/// 一括登録の本文サイズ・行数の上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_body_bytes: usize,
+    pub max_rows: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_rows: DEFAULT_MAX_ROWS,
+        }
+    }
+}
+
+impl Limits {
+    /// 上限までの本文を読み込む
+    /// 上限を超える場合は残りを読まずに`PayloadTooLarge`を返す。
+    pub fn read_body<R: Read>(&self, reader: R) -> Result<Vec<u8>, Error> {
+        let mut body = vec![];
+        reader
+            .take(self.max_body_bytes as u64 + 1)
+            .read_to_end(&mut body)?;
+        if body.len() > self.max_body_bytes {
+            return Err(Error::PayloadTooLarge(format!(
+                "{} bytes",
+                self.max_body_bytes
+            )));
+        }
+        Ok(body)
+    }
+
+    /// 行数が上限以内か確認する
+    pub fn check_rows(&self, rows: usize) -> Result<(), Error> {
+        if rows > self.max_rows {
+            return Err(Error::PayloadTooLarge(format!("{} rows", self.max_rows)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// トークンごとの送信頻度の制限（トークンバケット方式）
+/// 最大`burst`回まで連続して送信でき、その後は`interval`ごとに1回分回復する。
+#[derive(Debug)]
+pub struct RateLimiter {
+    burst: u32,
+    interval: Duration,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(burst: u32, interval: Duration) -> Self {
+        Self {
+            burst: burst.max(1),
+            interval,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 送信を1回分消費する
+    /// 制限を超えた場合は次に送信できるまでの時間を`RateLimited`で返す。
+    pub fn check(&self, token: &str, now: Instant) -> Result<(), Error> {
+        let mut buckets = match self.buckets.lock() {
+            Ok(buckets) => buckets,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let bucket = buckets.entry(token.to_string()).or_insert(Bucket {
+            tokens: self.burst as f64,
+            updated: now,
+        });
+        let elapsed = now.saturating_duration_since(bucket.updated);
+        let refill = if self.interval.is_zero() {
+            self.burst as f64
+        } else {
+            elapsed.as_secs_f64() / self.interval.as_secs_f64()
+        };
+        bucket.tokens = (bucket.tokens + refill).min(self.burst as f64);
+        bucket.updated = now;
+        if bucket.tokens < 1.0 {
+            let retry_after = self.interval.mul_f64(1.0 - bucket.tokens);
+            return Err(Error::RateLimited(retry_after.as_secs().max(1)));
+        }
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_limits() {
+        let limits = Limits {
+            max_body_bytes: 4,
+            max_rows: 2,
+        };
+        assert_eq!(limits.read_body(&b"abcd"[..]).unwrap(), b"abcd");
+        let error = limits.read_body(&b"abcde"[..]).unwrap_err();
+        assert_eq!(error.code(), "E_PAYLOAD_TOO_LARGE");
+        assert_eq!(error.http_status(), 413);
+        assert!(limits.check_rows(2).is_ok());
+        assert!(matches!(
+            limits.check_rows(3),
+            Err(Error::PayloadTooLarge(_))
+        ));
+    }
+
+    #[test]
+    fn test_rate_limiter() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(10));
+        let start = Instant::now();
+        assert!(limiter.check("a", start).is_ok());
+        assert!(limiter.check("a", start).is_ok());
+        let error = limiter.check("a", start).unwrap_err();
+        assert!(matches!(error, Error::RateLimited(10)));
+        assert_eq!(error.http_status(), 429);
+        assert!(limiter.check("b", start).is_ok());
+        assert!(limiter.check("a", start + Duration::from_secs(10)).is_ok());
+        assert!(limiter.check("a", start + Duration::from_secs(10)).is_err());
+    }
+}
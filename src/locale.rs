@@ -0,0 +1,141 @@
+//! 帳票の数値・日付の書式
+//!
+//! 印刷する結果通知書では和暦の日付や全角数字が求められることがあるため、
+//! `Locale`に従って書式を切り替える。調査票の言語もここで選ぶ（[`crate::translation`]）。
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::translation::QuestionnaireLanguage;
+
+/// 日付の書式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DateStyle {
+    /// 2024-06-01
+    #[default]
+    Iso,
+    /// 令和6年6月1日
+    JapaneseEra,
+}
+
+/// 元号と開始日
+const ERAS: [(&str, (i32, u32, u32)); 5] = [
+    ("令和", (2019, 5, 1)),
+    ("平成", (1989, 1, 8)),
+    ("昭和", (1926, 12, 25)),
+    ("大正", (1912, 7, 30)),
+    ("明治", (1868, 1, 25)),
+];
+
+/// 帳票の書式設定
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Locale {
+    pub date_style: DateStyle,
+    /// 数字を全角にする
+    pub full_width_digits: bool,
+    /// 調査票の言語
+    pub language: QuestionnaireLanguage,
+}
+
+impl Locale {
+    /// ISO形式・半角数字
+    pub const ISO: Locale = Locale {
+        date_style: DateStyle::Iso,
+        full_width_digits: false,
+        language: QuestionnaireLanguage::Ja,
+    };
+
+    /// 和暦・全角数字
+    pub const JAPANESE: Locale = Locale {
+        date_style: DateStyle::JapaneseEra,
+        full_width_digits: true,
+        language: QuestionnaireLanguage::Ja,
+    };
+
+    /// 日付を書式化する
+    /// 明治より前の日付は和暦の指定があってもISO形式とする。
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        let value = match self.date_style {
+            DateStyle::Iso => date.format("%Y-%m-%d").to_string(),
+            DateStyle::JapaneseEra => match japanese_era(date) {
+                Some((era, year)) => {
+                    let year = match year {
+                        1 => "元".to_string(),
+                        year => year.to_string(),
+                    };
+                    format!("{}{}年{}月{}日", era, year, date.month(), date.day())
+                }
+                None => date.format("%Y-%m-%d").to_string(),
+            },
+        };
+        self.digits(&value)
+    }
+
+    /// 小数点以下の桁数を指定して数値を書式化する
+    pub fn format_number(&self, value: f64, precision: usize) -> String {
+        self.digits(&format!("{:.*}", precision, value))
+    }
+
+    /// 整数を書式化する
+    pub fn format_integer<T: ToString>(&self, value: T) -> String {
+        self.digits(&value.to_string())
+    }
+
+    fn digits(&self, value: &str) -> String {
+        if !self.full_width_digits {
+            return value.to_string();
+        }
+        value
+            .chars()
+            .map(|c| match c {
+                '0'..='9' => char::from_u32(c as u32 - '0' as u32 + '０' as u32).unwrap(),
+                '.' => '．',
+                '-' => '－',
+                c => c,
+            })
+            .collect()
+    }
+}
+
+/// 元号と年
+pub fn japanese_era(date: NaiveDate) -> Option<(&'static str, i32)> {
+    ERAS.iter().find_map(|&(era, (year, month, day))| {
+        let start = NaiveDate::from_ymd_opt(year, month, day)?;
+        (date >= start).then(|| (era, date.year() - year + 1))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_format_date() {
+        let locale = Locale {
+            date_style: DateStyle::JapaneseEra,
+            full_width_digits: false,
+            ..Locale::ISO
+        };
+        assert_eq!(locale.format_date(date(2024, 6, 1)), "令和6年6月1日");
+        assert_eq!(locale.format_date(date(2019, 5, 1)), "令和元年5月1日");
+        assert_eq!(locale.format_date(date(2019, 4, 30)), "平成31年4月30日");
+        assert_eq!(Locale::ISO.format_date(date(2024, 6, 1)), "2024-06-01");
+        assert_eq!(
+            Locale::JAPANESE.format_date(date(2024, 12, 1)),
+            "令和６年１２月１日"
+        );
+    }
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(Locale::ISO.format_number(12.345, 1), "12.3");
+        assert_eq!(Locale::JAPANESE.format_number(-0.5, 2), "－０．５０");
+        assert_eq!(Locale::JAPANESE.format_integer(77), "７７");
+    }
+}
@@ -0,0 +1,114 @@
+//! 多重起動を防ぐロックファイル
+//!
+//! 夜間の一括判定などをジョブスケジューラから起動すると、前回の実行が終わらないうちに次の実行が
+//! 始まり、同じ出力先へ二重に書き込むことがある。実行の間だけロックファイルにOSの排他ロック
+//! （アドバイザリロック）を掛け、既に他の実行が掛けている場合は何もせずに終了する。
+//!
+//! ロックはプロセスの終了時にOSが解放するため、強制終了された場合も次の実行を妨げない。
+//! ロックファイル自体は削除しない。削除すると、削除の前に開いた実行と後に作成した実行が
+//! 別々のファイルにロックを掛け、同時に動いてしまうため。
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use crate::Error;
+
+/// 取得したロック。破棄すると解放する
+#[derive(Debug)]
+pub struct LockFile {
+    path: PathBuf,
+    _file: File,
+}
+
+impl LockFile {
+    /// ロックを取得する
+    /// 既に他の実行が取得している場合は`None`を返す。ロックファイルには取得したプロセスのIDと日時を書き込む。
+    pub fn acquire<P: AsRef<Path>>(path: P) -> Result<Option<Self>, Error> {
+        let path = path.as_ref();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+        match file.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(None),
+            Err(TryLockError::Error(e)) => return Err(e.into()),
+        }
+        // ロックを取得してから前の実行の記録を消す
+        file.set_len(0)?;
+        writeln!(
+            file,
+            "pid = {}, acquired = {}",
+            std::process::id(),
+            Local::now().to_rfc3339()
+        )?;
+        Ok(Some(Self {
+            path: path.to_path_buf(),
+            _file: file,
+        }))
+    }
+
+    /// 取得している実行の情報（ロックファイルの内容）
+    /// 読み込めない場合は空とする。
+    pub fn holder<P: AsRef<Path>>(path: P) -> String {
+        std::fs::read_to_string(path)
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// コマンドの`--lock`の指定に従ってロックを取得する
+/// 指定がない場合は`None`を返す。他の実行が取得している場合は`Error::Locked`（終了コード4）とする。
+pub fn acquire_if_requested(path: Option<&str>) -> Result<Option<LockFile>, Error> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    match LockFile::acquire(path)? {
+        Some(lock) => Ok(Some(lock)),
+        None => Err(Error::Locked(PathBuf::from(path), LockFile::holder(path))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::outcome::Outcome;
+
+    #[test]
+    fn test_acquire() {
+        let path = std::env::temp_dir().join("stresscheck_test_lock.lock");
+        let _ = std::fs::remove_file(&path);
+        let lock = LockFile::acquire(&path).unwrap().unwrap();
+        assert!(LockFile::acquire(&path).unwrap().is_none());
+        assert!(LockFile::holder(&path).starts_with(&format!("pid = {},", std::process::id())));
+        drop(lock);
+        assert!(path.exists());
+        let lock = LockFile::acquire(&path).unwrap();
+        assert!(lock.is_some());
+        drop(lock);
+        // 強制終了などで残った内容はロックの有無に関係しない
+        std::fs::write(&path, "pid = 0, acquired = 2026-01-01T00:00:00+09:00\n").unwrap();
+        assert!(LockFile::acquire(&path).unwrap().is_some());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_acquire_if_requested() {
+        assert!(acquire_if_requested(None).unwrap().is_none());
+        let path = std::env::temp_dir().join("stresscheck_test_lock_requested.lock");
+        let path = path.to_str().unwrap();
+        let _lock = acquire_if_requested(Some(path)).unwrap().unwrap();
+        let e = acquire_if_requested(Some(path)).unwrap_err();
+        assert!(matches!(e, Error::Locked(_, ref holder) if holder.starts_with("pid = ")));
+        assert_eq!(e.outcome(), Outcome::Locked);
+    }
+}
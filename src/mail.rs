@@ -0,0 +1,185 @@
+//! 結果通知メールの直接送信
+//!
+//! `notify`で作成したメールをSMTPまたはsendmailで送信する。
+//! 送信せずに宛先を確認するドライランと、送信間隔による流量制限に対応する。
+
+use std::time::Duration;
+
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{SendmailTransport, SmtpTransport, Transport as _};
+use serde::{Deserialize, Serialize};
+
+use crate::notify::Message;
+use crate::Error;
+
+/// 標準の送信間隔
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 送信方法
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Transport {
+    /// SMTPサーバに中継する（TLS）
+    Smtp {
+        host: String,
+        port: Option<u16>,
+        username: Option<String>,
+        password: Option<String>,
+    },
+    /// ローカルのsendmailコマンドに渡す
+    Sendmail { command: Option<String> },
+}
+
+enum Sender {
+    Smtp(SmtpTransport),
+    Sendmail(SendmailTransport),
+}
+
+/// 通知メールの送信
+pub struct Mailer {
+    sender: Option<Sender>,
+    interval: Duration,
+}
+
+/// 1通分の送信結果
+#[derive(Debug)]
+pub struct Delivery {
+    /// ユーザ特定キー
+    pub id: String,
+    pub to: String,
+    /// 送信したか（ドライランの場合は`false`）
+    pub sent: bool,
+    pub result: Result<(), Error>,
+}
+
+impl Mailer {
+    pub fn new(transport: &Transport) -> Result<Self, Error> {
+        let sender = match transport {
+            Transport::Smtp {
+                host,
+                port,
+                username,
+                password,
+            } => {
+                let mut builder =
+                    SmtpTransport::relay(host).map_err(|e| Error::MailError(e.to_string()))?;
+                if let Some(port) = port {
+                    builder = builder.port(*port);
+                }
+                if let (Some(username), Some(password)) = (username, password) {
+                    builder = builder
+                        .credentials(Credentials::new(username.to_string(), password.to_string()));
+                }
+                Sender::Smtp(builder.build())
+            }
+            Transport::Sendmail { command } => Sender::Sendmail(match command {
+                Some(command) => SendmailTransport::new_with_command(command),
+                None => SendmailTransport::new(),
+            }),
+        };
+        Ok(Self {
+            sender: Some(sender),
+            interval: DEFAULT_INTERVAL,
+        })
+    }
+
+    /// 送信せずに宛先のみを確認する
+    pub fn dry_run() -> Self {
+        Self {
+            sender: None,
+            interval: Duration::ZERO,
+        }
+    }
+
+    /// 送信間隔を指定する
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.sender.is_none()
+    }
+
+    /// 1通送信する
+    /// ドライランの場合もメールの組み立てまでは行い、宛先の誤りを検出する。
+    pub fn send(&self, message: &Message) -> Result<bool, Error> {
+        let email = build(message)?;
+        match self.sender {
+            Some(Sender::Smtp(ref transport)) => transport
+                .send(&email)
+                .map(|_| ())
+                .map_err(|e| Error::MailError(e.to_string()))?,
+            Some(Sender::Sendmail(ref transport)) => transport
+                .send(&email)
+                .map_err(|e| Error::MailError(e.to_string()))?,
+            None => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// 送信間隔を空けながら全て送信する
+    /// 送信に失敗したメールがあっても残りの送信を続ける。
+    pub fn send_all(&self, messages: &[Message]) -> Vec<Delivery> {
+        let mut deliveries = vec![];
+        self.send_each(messages, |delivery| deliveries.push(delivery));
+        deliveries
+    }
+
+    /// `send_all`と同様に送信し、1通ごとに結果を渡す
+    /// 途中で中断した場合に送信済みの宛先を記録するために用いる。
+    pub fn send_each<F: FnMut(Delivery)>(&self, messages: &[Message], mut f: F) {
+        for (index, message) in messages.iter().enumerate() {
+            if index > 0 && !self.is_dry_run() {
+                std::thread::sleep(self.interval);
+            }
+            let result = self.send(message);
+            f(Delivery {
+                id: message.id.clone(),
+                to: message.to.clone(),
+                sent: matches!(result, Ok(true)),
+                result: result.map(|_| ()),
+            });
+        }
+    }
+}
+
+fn build(message: &Message) -> Result<lettre::Message, Error> {
+    let parse = |address: &str| {
+        address
+            .parse()
+            .map_err(|_| Error::MailError(format!("不正なメールアドレスです: {}", address)))
+    };
+    lettre::Message::builder()
+        .from(parse(&message.from)?)
+        .to(parse(&message.to)?)
+        .subject(message.subject.as_str())
+        .header(ContentType::TEXT_PLAIN)
+        .body(message.body.clone())
+        .map_err(|e| Error::MailError(e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn message(to: &str) -> Message {
+        Message {
+            id: "1".to_string(),
+            from: "stresscheck@example.com".to_string(),
+            to: to.to_string(),
+            subject: "結果".to_string(),
+            body: "本文".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let mailer = Mailer::dry_run();
+        let deliveries = mailer.send_all(&[message("taro@example.com"), message("invalid")]);
+        assert!(!deliveries[0].sent);
+        assert!(deliveries[0].result.is_ok());
+        assert!(matches!(deliveries[1].result, Err(Error::MailError(_))));
+    }
+}
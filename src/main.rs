@@ -1,28 +1,156 @@
 use std::io::stdin;
+use std::process::ExitCode;
 
-use simple_stresscheck::Stress;
-use simple_stresscheck::{AnswerStore, Error, QUESTIONS};
+use clap::Parser;
+use simple_stresscheck::config::Config;
+use simple_stresscheck::entry::{format_ranges, parse_command, Command};
+use simple_stresscheck::outcome::{exit_with, Outcome};
+use simple_stresscheck::prompt::PromptStyle;
+use simple_stresscheck::result_sheet::render_result_sheet;
+use simple_stresscheck::variant::TextVariant;
+use simple_stresscheck::{result_code, AnswerStore, CheckResult, Error, Question, SimpleStress};
 
-fn main() {
-    let mut buffer = String::new();
+#[derive(Parser)]
+struct Args {
+    /// 実施設定ファイル(TOML)。項目は環境変数`STRESSCHECK_*`でも上書きできる
+    #[arg(long, env = "STRESSCHECK_CONFIG")]
+    config: Option<String>,
+    /// 既定の実施設定(TOML)を出力して終了する
+    #[arg(long)]
+    print_default_config: bool,
+    /// 設問番号と回答番号の組（`12 3`）を任意の順に入力する。紙の調査票の転記用
+    #[arg(long)]
+    transcribe: bool,
+    /// 画面読み上げ向けに、設問番号・選択肢番号を明示して選択肢を1行ずつ示す
+    #[arg(long, env = "STRESSCHECK_ACCESSIBLE")]
+    accessible: bool,
+    /// 設問文にふりがなを括弧書きで添える
+    #[arg(long)]
+    furigana: bool,
+    /// 設問文を「やさしい日本語」で示す
+    #[arg(long)]
+    plain: bool,
+    /// 判定後に結果参照コードを表示する。結果解説のページでコードを入力すると評価点を確認できる
+    #[arg(long)]
+    result_code: bool,
+    /// 個人結果のシート(HTML)を書き出す。`qr` featureでは結果参照コードのQRコードを添える
+    #[arg(long)]
+    result_sheet: Option<String>,
+    /// 1〜4のキーだけで設問を順に入力する（Enter不要）。bで前の設問に戻り、qで中断する
+    #[cfg(feature = "quick-entry")]
+    #[arg(long, conflicts_with = "transcribe")]
+    quick: bool,
+    /// 判定結果を送信するWebhookのURL。署名の鍵は環境変数`STRESSCHECK_WEBHOOK_SECRET`で指定する
+    #[cfg(feature = "webhook")]
+    #[arg(long)]
+    webhook_url: Option<String>,
+    /// ユーザ特定キー。結果票に記載し、Webhookでも送信する
+    #[arg(long)]
+    id: Option<String>,
+}
+
+fn main() -> ExitCode {
+    exit_with(run(Args::parse()))
+}
+
+fn run(args: Args) -> Result<Outcome, Error> {
+    if args.print_default_config {
+        print!("{}", Config::default().to_toml_string()?);
+        return Ok(Outcome::Success);
+    }
+    let config = Config::resolve(args.config.as_ref())?;
+    let master = config.locale.master()?;
+    #[cfg(feature = "quick-entry")]
+    let store = if args.quick {
+        quick_entry(&master)?
+    } else {
+        read_answers(&args, &master)
+    };
+    #[cfg(not(feature = "quick-entry"))]
+    let store = read_answers(&args, &master);
+
+    match config.criteria.evaluate(&store)? {
+        true => println!("あなたは高ストレス状態です。"),
+        false => println!("あなたは高ストレスではありません。"),
+    }
+    if args.result_code {
+        let result = CheckResult::new(&store, config.criteria)?;
+        println!("結果参照コード: {}", result_code::encode(&result)?);
+    }
+    if let Some(ref path) = args.result_sheet {
+        let result = CheckResult::new(&store, config.criteria)?;
+        let html = render_result_sheet(
+            args.id.as_deref().unwrap_or_default(),
+            &result,
+            &config.locale,
+        )?;
+        std::fs::write(path, html)?;
+    }
+
+    #[cfg(feature = "webhook")]
+    if let Some(ref url) = args.webhook_url {
+        use simple_stresscheck::webhook::WebhookSink;
+        let secret = std::env::var("STRESSCHECK_WEBHOOK_SECRET").unwrap_or_default();
+        let result = CheckResult::new(&store, config.criteria)?;
+        WebhookSink::new(url, secret.as_bytes())
+            .emit(args.id.as_deref().unwrap_or_default(), &result)?;
+    }
+
+    // dbg!("{} {}", score, store);
+    Ok(Outcome::Success)
+}
+
+fn prompt_style(args: &Args) -> PromptStyle {
+    if args.accessible {
+        PromptStyle::Accessible
+    } else {
+        PromptStyle::Standard
+    }
+}
+
+fn read_answers(args: &Args, master: &SimpleStress) -> AnswerStore {
     let mut store = AnswerStore::default();
+    if args.transcribe {
+        transcribe(&mut store, master);
+    } else {
+        ask_in_order(&mut store, master, prompt_style(args), |question| {
+            if args.plain {
+                question.text_for(TextVariant::Plain).to_string()
+            } else if args.furigana {
+                question.text_with_furigana()
+            } else {
+                question.text.clone()
+            }
+        });
+    }
+    store
+}
 
-    for theme in &QUESTIONS.simple_stress {
+/// 設問を順に示して回答を入力する
+/// `text`で設問文の示し方（ふりがな・言い換え）を選ぶ
+fn ask_in_order<F>(store: &mut AnswerStore, master: &SimpleStress, style: PromptStyle, text: F)
+where
+    F: Fn(&Question) -> String,
+{
+    let mut buffer = String::new();
+    let total = master.questions().len();
+    for theme in &master.simple_stress {
         println!("{}", theme.theme);
         for outer_question in &theme.questions {
             if let Some(ref title) = outer_question.title {
                 println!("{}", title);
             }
             for inner_question in &outer_question.questions {
-                println!("{}", inner_question.text);
-                for score in &inner_question.scores {
-                    print!("  {} => {}", score.score, score.text);
-                }
+                let inner_question = &Question {
+                    text: text(inner_question),
+                    ..Question::clone(inner_question)
+                };
+                print!("{}", style.question(inner_question, total));
                 loop {
                     println!();
                     stdin().read_line(&mut buffer).unwrap();
-                    if store_answer(buffer.trim(), &mut store).is_err() {
-                        println!("回答は半角英数1〜4で入力してください。");
+                    if store_answer(buffer.trim(), store).is_err() {
+                        println!("{}", style.invalid_answer(inner_question));
                         buffer.clear();
                     } else {
                         buffer.clear();
@@ -33,14 +161,104 @@ fn main() {
             }
         }
     }
+}
 
-    let score = store.to_sumup_score().unwrap();
-    match score.has_stress() {
-        true => println!("あなたは高ストレス状態です。"),
-        false => println!("あなたは高ストレスではありません。"),
+/// 設問番号と回答番号の組を入力する
+/// 全設問に回答するか`q`・入力の終わりで終える。
+fn transcribe(store: &mut AnswerStore, master: &SimpleStress) {
+    let mut buffer = String::new();
+    println!("設問番号と回答番号を空白で区切って入力してください（例: 12 3）。qで終了します。");
+    loop {
+        let unanswered = store.unanswered();
+        if unanswered.is_empty() {
+            break;
+        }
+        println!("未回答: {}", format_ranges(&unanswered));
+        buffer.clear();
+        if stdin().read_line(&mut buffer).unwrap_or_default() == 0 {
+            break;
+        }
+        match parse_command(&buffer) {
+            Ok(Command::Answer { question, score }) => match store.insert(question, score) {
+                Ok(()) => {
+                    if let Some(question) = master.question(question as u32) {
+                        let choice = question
+                            .scores
+                            .iter()
+                            .find(|choice| choice.score == score)
+                            .map(|choice| choice.text.as_str())
+                            .unwrap_or_default();
+                        println!("{}. {} => {} {}", question.id, question.text, score, choice);
+                    }
+                }
+                Err(Error::IllegalQuestion) => {
+                    println!("設問番号は1〜57で入力してください。")
+                }
+                Err(_) => println!("回答は半角英数1〜4で入力してください。"),
+            },
+            Ok(Command::Finish) => break,
+            Err(Error::IllegalQuestion) => println!("設問番号は1〜57で入力してください。"),
+            Err(_) => println!("設問番号と回答番号を空白で区切って入力してください。"),
+        }
+    }
+}
+
+/// 端末をrawモードにして1〜4のキーだけで設問を順に入力する
+#[cfg(feature = "quick-entry")]
+fn quick_entry(master: &SimpleStress) -> Result<AnswerStore, Error> {
+    use std::io::Write;
+
+    use crossterm::event::{read, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+    use simple_stresscheck::entry::{QuickEntry, QuickKey, QuickState};
+
+    /// 中断やエラーで抜けても端末を元に戻す
+    struct RawMode;
+
+    impl Drop for RawMode {
+        fn drop(&mut self) {
+            let _ = disable_raw_mode();
+        }
     }
 
-    // dbg!("{} {}", score, store);
+    let questions = master.questions();
+    let mut entry = QuickEntry::new();
+    let mut shown = None;
+    println!("1〜4のキーで回答してください。bで前の設問に戻り、qで中断します。");
+    enable_raw_mode()?;
+    let _raw_mode = RawMode;
+    loop {
+        if entry.question() != shown {
+            shown = entry.question();
+            if let Some(question) = shown.and_then(|no| questions.get(no as usize - 1)) {
+                let choices = question
+                    .scores
+                    .iter()
+                    .map(|score| format!("{} {}", score.score, score.text))
+                    .collect::<Vec<_>>()
+                    .join(" / ");
+                // rawモードでは改行で行頭に戻らない
+                print!("{}. {} [{}]\r\n", question.id, question.text, choices);
+                std::io::stdout().flush()?;
+            }
+        }
+        let key = match read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    QuickKey::Quit
+                }
+                KeyCode::Char(c) => QuickKey::from_char(c),
+                KeyCode::Backspace | KeyCode::Up => QuickKey::Back,
+                KeyCode::Esc => QuickKey::Quit,
+                _ => QuickKey::Other,
+            },
+            _ => continue,
+        };
+        if entry.press(key) != QuickState::Continue {
+            break;
+        }
+    }
+    Ok(entry.into_answers())
 }
 
 fn store_answer(value: &str, store: &mut AnswerStore) -> Result<(), Error> {
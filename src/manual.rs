@@ -0,0 +1,385 @@
+//! マニュアルの回答例による採点の自己診断
+//!
+//! 厚生労働省のマニュアルに掲載された回答例と、そこに示された点数・評価点・判定を
+//! `resources/manual_examples.json`に収め、採点の実装がそれと一致することを確かめる。
+//! 組み込み先では起動時に`verify_against_manual`を呼び出して利用できる。
+//!
+//! 期待値はこの実装で計算せず、必ずマニュアルから書き写し、`source`に掲載箇所（ページ・表）を記す。
+//! 実装から求めた値を期待値にすると、採点の誤りを検出できないため。
+//! 合成した回答による境界値の確認は、このモジュールの単体試験で行う。
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{AnswerStore, CheckResult, Error, Scale, StressCriteria};
+
+/// 同梱している回答例
+pub const EMBEDDED_MANUAL_EXAMPLES: &str = include_str!("../resources/manual_examples.json");
+
+/// 合計点数方式の期待値
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct SumupExpectation {
+    /// 領域Ａ・Ｂ・Ｃの合計点数
+    pub scores: (u8, u8, u8),
+    pub has_stress: bool,
+}
+
+/// 素点換算表方式の期待値
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ConversionExpectation {
+    /// 領域Ａ・Ｂ・Ｃの評価点の合計
+    pub scores: (u8, u8, u8),
+    /// 尺度ごとの評価点
+    pub evaluation_points: BTreeMap<Scale, u8>,
+    pub has_stress: bool,
+}
+
+/// 回答例
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ManualExample {
+    pub name: String,
+    /// 例の説明
+    pub note: String,
+    /// 出典（マニュアルの名称・ページ・表）
+    pub source: String,
+    /// 設問1〜57の回答
+    pub answers: Vec<u8>,
+    pub sumup: SumupExpectation,
+    pub conversion: ConversionExpectation,
+}
+
+impl ManualExample {
+    /// 回答を格納する
+    pub fn store(&self) -> Result<AnswerStore, Error> {
+        if self.answers.len() != 57 {
+            return Err(Error::IllegalQuestion);
+        }
+        let mut store = AnswerStore::default();
+        for &answer in &self.answers {
+            store.push(answer)?;
+        }
+        Ok(store)
+    }
+
+    /// 採点の結果が期待値と一致しない項目
+    pub fn mismatches(&self) -> Result<Vec<ManualMismatch>, Error> {
+        let store = self.store()?;
+        let sumup = CheckResult::new(&store, StressCriteria::SUMUP)?;
+        let conversion = CheckResult::new(&store, StressCriteria::CONVERSION)?;
+        let mut mismatches = vec![];
+        let mut check = |field: String, expected: String, actual: String| {
+            if expected != actual {
+                mismatches.push(ManualMismatch {
+                    example: self.name.clone(),
+                    field,
+                    expected,
+                    actual,
+                });
+            }
+        };
+        check(
+            "sumup.scores".to_string(),
+            format!("{:?}", self.sumup.scores),
+            format!("{:?}", sumup.scores),
+        );
+        check(
+            "sumup.has_stress".to_string(),
+            self.sumup.has_stress.to_string(),
+            sumup.has_stress.to_string(),
+        );
+        check(
+            "conversion.scores".to_string(),
+            format!("{:?}", self.conversion.scores),
+            format!("{:?}", conversion.scores),
+        );
+        for scale in Scale::ALL {
+            check(
+                format!("conversion.evaluation_points.{}", scale.as_str()),
+                format!("{:?}", self.conversion.evaluation_points.get(&scale)),
+                format!("{:?}", conversion.evaluation_points.get(&scale)),
+            );
+        }
+        check(
+            "conversion.has_stress".to_string(),
+            self.conversion.has_stress.to_string(),
+            conversion.has_stress.to_string(),
+        );
+        Ok(mismatches)
+    }
+}
+
+/// 回答例との不一致
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ManualMismatch {
+    /// 回答例の名前
+    pub example: String,
+    /// 一致しなかった項目
+    pub field: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// 同梱している回答例を読み込む
+pub fn manual_examples() -> Result<Vec<ManualExample>, Error> {
+    serde_json::from_str(EMBEDDED_MANUAL_EXAMPLES).map_err(Error::JSONReadError)
+}
+
+/// 同梱している回答例で採点を自己診断する
+/// 全ての例が一致すれば空を返す。回答例が同梱されていない場合は何も確かめられないため、
+/// 一致したとはせずに`Error::ManualExamplesMissing`とする。
+pub fn verify_against_manual() -> Result<Vec<ManualMismatch>, Error> {
+    let examples = manual_examples()?;
+    if examples.is_empty() {
+        return Err(Error::ManualExamplesMissing);
+    }
+    let mut mismatches = vec![];
+    for example in examples {
+        mismatches.extend(example.mismatches()?);
+    }
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Stress;
+
+    #[test]
+    fn test_verify_against_manual() {
+        let examples = manual_examples().unwrap();
+        assert!(examples.iter().all(|example| !example.source.is_empty()
+            && example.conversion.evaluation_points.len() == 18));
+        // マニュアルの回答例を書き写すまでは、自己診断が通ったように見せない
+        match verify_against_manual() {
+            Err(Error::ManualExamplesMissing) => assert!(examples.is_empty()),
+            result => assert_eq!(result.unwrap(), vec![]),
+        }
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let mut example = ManualExample {
+            name: "all_1".to_string(),
+            note: String::new(),
+            source: String::new(),
+            answers: vec![1; 57],
+            sumup: SumupExpectation {
+                scores: (50, 38, 9),
+                has_stress: false,
+            },
+            conversion: ConversionExpectation {
+                scores: (22, 26, 15),
+                evaluation_points: CheckResult::new(&answers(1, &[]), StressCriteria::CONVERSION)
+                    .unwrap()
+                    .evaluation_points,
+                has_stress: false,
+            },
+        };
+        assert_eq!(example.mismatches().unwrap(), vec![]);
+        example.sumup.scores.1 += 1;
+        let mismatches = example.mismatches().unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!(mismatches[0].field, "sumup.scores");
+        assert_eq!(mismatches[0].actual, "(50, 38, 9)");
+        example.answers.pop();
+        assert!(matches!(example.mismatches(), Err(Error::IllegalQuestion)));
+    }
+
+    /// 全ての設問に`base`と回答し、`overrides`の設問（1始まり）だけ回答を変える
+    fn answers(base: u8, overrides: &[(usize, u8)]) -> AnswerStore {
+        let mut values = [base; 57];
+        for &(no, answer) in overrides {
+            values[no - 1] = answer;
+        }
+        let mut store = AnswerStore::default();
+        for value in values {
+            store.push(value).unwrap();
+        }
+        store
+    }
+
+    /// 合成した回答の例と、選定方法ごとの領域Ａ・Ｂ・Ｃの点数・判定
+    struct Case {
+        note: &'static str,
+        base: u8,
+        overrides: &'static [(usize, u8)],
+        sumup: Option<((u8, u8, u8), bool)>,
+        conversion: Option<((u8, u8, u8), bool)>,
+    }
+
+    const CASES: &[Case] = &[
+        Case {
+            note: "全ての設問に1と回答",
+            base: 1,
+            overrides: &[],
+            sumup: Some(((50, 38, 9), false)),
+            conversion: Some(((22, 26, 15), false)),
+        },
+        Case {
+            note: "全ての設問に2と回答",
+            base: 2,
+            overrides: &[],
+            sumup: Some(((45, 61, 18), false)),
+            conversion: Some(((24, 17, 10), false)),
+        },
+        Case {
+            note: "全ての設問に3と回答",
+            base: 3,
+            overrides: &[],
+            sumup: Some(((40, 84, 27), true)),
+            conversion: Some(((26, 12, 5), true)),
+        },
+        Case {
+            note: "全ての設問に4と回答",
+            base: 4,
+            overrides: &[],
+            sumup: Some(((35, 107, 36), true)),
+            conversion: Some(((28, 10, 3), true)),
+        },
+        Case {
+            note: "合計点数方式の基準㋐の境界（領域Ｂが77点）",
+            base: 2,
+            overrides: &[
+                (21, 4),
+                (22, 4),
+                (23, 4),
+                (24, 4),
+                (25, 4),
+                (26, 4),
+                (27, 4),
+                (28, 4),
+            ],
+            sumup: Some(((45, 77, 18), true)),
+            conversion: None,
+        },
+        Case {
+            note: "合計点数方式の基準㋐の境界の直下（領域Ｂが76点）",
+            base: 2,
+            overrides: &[
+                (21, 4),
+                (22, 4),
+                (23, 4),
+                (24, 4),
+                (25, 4),
+                (26, 4),
+                (27, 4),
+                (28, 4),
+                (46, 1),
+            ],
+            sumup: Some(((45, 76, 18), false)),
+            conversion: None,
+        },
+        Case {
+            note: "合計点数方式の基準㋑の境界（領域ＡとＣの合算が76点、領域Ｂが63点）",
+            base: 2,
+            overrides: &[
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (5, 1),
+                (21, 4),
+                (47, 4),
+                (48, 4),
+                (49, 4),
+                (50, 4),
+            ],
+            sumup: Some(((50, 63, 26), true)),
+            conversion: None,
+        },
+        Case {
+            note: "合計点数方式の基準㋑の境界の直下（領域ＡとＣの合算が75点）",
+            base: 2,
+            overrides: &[
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (5, 1),
+                (21, 4),
+                (47, 4),
+                (48, 4),
+                (49, 4),
+                (50, 3),
+            ],
+            sumup: Some(((50, 63, 25), false)),
+            conversion: None,
+        },
+        Case {
+            note: "合計点数方式の基準㋑の境界の直下（領域Ｂが62点）",
+            base: 2,
+            overrides: &[
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (5, 1),
+                (21, 3),
+                (47, 4),
+                (48, 4),
+                (49, 4),
+                (50, 4),
+            ],
+            sumup: Some(((50, 62, 26), false)),
+            conversion: None,
+        },
+        Case {
+            note: "素点換算表方式の基準㋐の境界（領域Ｂが12点）",
+            base: 2,
+            overrides: &[(18, 1), (21, 4), (22, 4), (24, 4), (27, 4)],
+            sumup: None,
+            conversion: Some(((24, 12, 10), true)),
+        },
+        Case {
+            note: "素点換算表方式の基準㋐の境界の直上（領域Ｂが13点）",
+            base: 2,
+            overrides: &[(21, 4), (22, 4), (24, 4), (27, 4)],
+            sumup: None,
+            conversion: Some(((24, 13, 10), false)),
+        },
+        Case {
+            note: "素点換算表方式の基準㋑の境界（領域ＡとＣの合算が26点、領域Ｂが17点）",
+            base: 2,
+            overrides: &[
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (4, 1),
+                (5, 1),
+                (11, 1),
+                (21, 3),
+                (47, 4),
+                (48, 4),
+                (49, 4),
+                (50, 4),
+            ],
+            sumup: None,
+            conversion: Some(((20, 17, 6), true)),
+        },
+    ];
+
+    #[test]
+    fn test_boundaries() {
+        for case in CASES {
+            let store = answers(case.base, case.overrides);
+            for (criteria, expected) in [
+                (StressCriteria::SUMUP, case.sumup),
+                (StressCriteria::CONVERSION, case.conversion),
+            ] {
+                let Some((scores, has_stress)) = expected else {
+                    continue;
+                };
+                let score = criteria.score(&store).unwrap();
+                assert_eq!(score.scores(), scores, "{}", case.note);
+                assert_eq!(criteria.judge(scores), has_stress, "{}", case.note);
+                assert_eq!(
+                    criteria.evaluate(&store).unwrap(),
+                    has_stress,
+                    "{}",
+                    case.note
+                );
+            }
+        }
+    }
+}
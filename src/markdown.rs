@@ -0,0 +1,162 @@
+//! Markdownの調査票
+//!
+//! 設問マスタから記入用のMarkdownを生成し、記入済みのMarkdownを回答として読み込む。
+//! 回答欄はチェックボックス（`- [x] 2 まあそうだ`）か、回答番号を書き込む空欄（`回答: 2`）とする。
+
+use crate::{AnswerStore, Error, SimpleStress};
+
+/// 回答欄の形式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnswerField {
+    /// 選択肢ごとのチェックボックス
+    #[default]
+    Checkbox,
+    /// 回答番号を書き込む空欄
+    Blank,
+}
+
+/// 空欄形式の回答欄の見出し
+const BLANK_LABEL: &str = "回答:";
+
+impl SimpleStress {
+    /// チェックボックス形式の記入用Markdown
+    pub fn to_markdown(&self) -> String {
+        self.to_markdown_with(AnswerField::Checkbox)
+    }
+
+    /// 回答欄の形式を指定して記入用Markdownを生成する
+    pub fn to_markdown_with(&self, field: AnswerField) -> String {
+        let mut markdown = String::from("# 職業性ストレス簡易調査票\n");
+        for theme in &self.simple_stress {
+            markdown.push_str(&format!("\n## {}\n", theme.theme));
+            for outer_question in &theme.questions {
+                if let Some(title) = &outer_question.title {
+                    markdown.push_str(&format!("\n### {}\n", title));
+                }
+                for question in &outer_question.questions {
+                    markdown.push_str(&format!("\n**{}. {}**\n\n", question.id, question.text));
+                    match field {
+                        AnswerField::Checkbox => {
+                            for score in &question.scores {
+                                markdown
+                                    .push_str(&format!("- [ ] {} {}\n", score.score, score.text));
+                            }
+                        }
+                        AnswerField::Blank => {
+                            let choices = question
+                                .scores
+                                .iter()
+                                .map(|score| format!("{} {}", score.score, score.text))
+                                .collect::<Vec<_>>()
+                                .join(" / ");
+                            markdown.push_str(&format!("{}\n\n{}\n", choices, BLANK_LABEL));
+                        }
+                    }
+                }
+            }
+        }
+        markdown
+    }
+}
+
+impl AnswerStore {
+    /// 記入済みのMarkdownを読み込む
+    /// どちらの形式の回答欄も読み込める。記入のない設問は未回答のまま残す。
+    /// 1つの設問に複数の回答がある場合は`Error::IllegalAnswer`とする。
+    pub fn from_markdown_str(markdown: &str) -> Result<Self, Error> {
+        let mut store = AnswerStore::default();
+        let mut current: Option<u8> = None;
+        let mut answered: Option<u8> = None;
+        for line in markdown.lines().map(str::trim) {
+            if let Some(question) = parse_question(line) {
+                current = Some(question?);
+                continue;
+            }
+            let score = if let Some(rest) = line.strip_prefix("- [") {
+                match rest.split_once(']') {
+                    Some(("x" | "X", choice)) => choice.split_whitespace().next(),
+                    _ => None,
+                }
+            } else if let Some(rest) = line
+                .strip_prefix(BLANK_LABEL)
+                .or_else(|| line.strip_prefix("回答："))
+            {
+                Some(rest.trim()).filter(|rest| !rest.is_empty())
+            } else {
+                None
+            };
+            let Some(score) = score else {
+                continue;
+            };
+            let question = current.ok_or(Error::IllegalQuestion)?;
+            if answered == Some(question) {
+                return Err(Error::IllegalAnswer);
+            }
+            store.insert(question, score.parse().map_err(|_| Error::IllegalAnswer)?)?;
+            answered = Some(question);
+        }
+        Ok(store)
+    }
+}
+
+/// `**12. 設問文**`の行から設問番号を取り出す
+fn parse_question(line: &str) -> Option<Result<u8, Error>> {
+    let (number, _) = line.strip_prefix("**")?.split_once(". ")?;
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(number.parse().map_err(|_| Error::IllegalQuestion))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    fn fill(markdown: &str, field: AnswerField, score: u8) -> String {
+        markdown
+            .lines()
+            .map(|line| match field {
+                AnswerField::Checkbox if line.starts_with(&format!("- [ ] {} ", score)) => {
+                    line.replacen("[ ]", "[x]", 1)
+                }
+                AnswerField::Blank if line.starts_with(BLANK_LABEL) => {
+                    format!("{} {}", BLANK_LABEL, score)
+                }
+                _ => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        for field in [AnswerField::Checkbox, AnswerField::Blank] {
+            let markdown = QUESTIONS.to_markdown_with(field);
+            assert!(markdown.contains("**57. 家庭生活に満足だ**"));
+            let empty = AnswerStore::from_markdown_str(&markdown).unwrap();
+            assert_eq!(empty.values(), &[0; 57]);
+            let store = AnswerStore::from_markdown_str(&fill(&markdown, field, 3)).unwrap();
+            assert_eq!(store.values(), &[3; 57]);
+        }
+    }
+
+    #[test]
+    fn test_markdown_errors() {
+        let markdown = "**1. 非常にたくさんの仕事をしなければならない**\n\n- [x] 1 そうだ\n- [X] 2 まあそうだ\n";
+        assert!(matches!(
+            AnswerStore::from_markdown_str(markdown),
+            Err(Error::IllegalAnswer)
+        ));
+        assert!(matches!(
+            AnswerStore::from_markdown_str("**58. 追加**\n\n回答: 1\n"),
+            Err(Error::IllegalQuestion)
+        ));
+        assert!(matches!(
+            AnswerStore::from_markdown_str("**1. 設問**\n\n回答: 5\n"),
+            Err(Error::IllegalAnswer)
+        ));
+        let store = AnswerStore::from_markdown_str("**12. 設問**\n\n回答：4\n").unwrap();
+        assert_eq!(store.values()[11], 4);
+    }
+}
@@ -0,0 +1,168 @@
+//! ファイルを介さない一括処理
+//!
+//! CSVの読み込みから判定・集団分析までを、メモリ上の入力に対してまとめて行う。作成日時と
+//! 差分プライバシーの乱数の種を外から与えるため、同じ入力からは常に同じ結果が得られ、
+//! 利用する側の結合試験でファイルや時計に依存せずに一連の処理を確かめられる。
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::config::Config;
+use crate::dashboard::DashboardFeed;
+use crate::group::{Dimension, GroupAnalysis, GroupSummary};
+use crate::outcome::Outcome;
+use crate::privacy::LaplaceMechanism;
+use crate::stamp::ResultDocument;
+use crate::{read_bulk_records_with_aliases, CheckRecord, CheckResult, Error};
+
+/// 一括処理の結果
+#[derive(Debug)]
+pub struct MemoryRun {
+    /// 判定できた受検者の結果（入力の順）
+    pub results: Vec<ResultDocument>,
+    /// 判定できなかった行（0始まりのデータ行の番号とエラー）
+    pub errors: Vec<(usize, Error)>,
+    /// 部署ごとの集計。差分プライバシーを指定した場合は雑音を加えたもの
+    pub departments: Vec<GroupSummary>,
+    pub dashboard: DashboardFeed,
+}
+
+impl MemoryRun {
+    /// コマンドとして実行した場合の実行結果
+    pub fn outcome(&self) -> Outcome {
+        Outcome::from_counts(self.results.len(), self.errors.len())
+    }
+}
+
+/// メモリ上で読み込み・判定・集計を行う
+#[derive(Debug, Clone)]
+pub struct MemoryPipeline {
+    config: Config,
+    generated_at: DateTime<FixedOffset>,
+    privacy: Option<(f64, u64)>,
+}
+
+impl MemoryPipeline {
+    /// `generated_at`はダッシュボードの作成日時とする
+    pub fn new(config: Config, generated_at: DateTime<FixedOffset>) -> Self {
+        Self {
+            config,
+            generated_at,
+            privacy: None,
+        }
+    }
+
+    /// 部署ごとの集計に差分プライバシーの雑音を加える
+    /// 雑音は`seed`から生成するため、同じ種からは同じ集計が得られる。
+    pub fn with_privacy(mut self, epsilon: f64, seed: u64) -> Self {
+        self.privacy = Some((epsilon, seed));
+        self
+    }
+
+    /// CSVを読み込み、判定して集計する
+    /// 判定できない行は`errors`に記録して続ける。実施設定やプライバシー予算が不正な場合は`Err`とする。
+    pub fn run(&self, input: &[u8]) -> Result<MemoryRun, Error> {
+        self.config.validate()?;
+        let mut mechanism = self
+            .privacy
+            .map(|(epsilon, seed)| LaplaceMechanism::new(epsilon).map(|m| m.with_seed(seed)))
+            .transpose()?;
+        let mut records: Vec<CheckRecord> = vec![];
+        let mut results = vec![];
+        let mut errors = vec![];
+        let rows = read_bulk_records_with_aliases(input, &self.config.header_aliases());
+        for (row, record) in rows.into_iter().enumerate() {
+            match record.and_then(|record| {
+                let result = CheckResult::new(&record.answers, self.config.criteria)?;
+                Ok((record, result))
+            }) {
+                Ok((record, result)) => {
+                    results.push(ResultDocument {
+                        id: record.id.clone(),
+                        result,
+                    });
+                    records.push(record);
+                }
+                Err(e) => errors.push((row, e)),
+            }
+        }
+        let analysis = GroupAnalysis::new(&records, self.config.criteria)
+            .with_min_group_size(self.config.min_group_size);
+        let summaries = analysis.summarize(Dimension::Department);
+        let departments = match mechanism {
+            Some(ref mut mechanism) => mechanism.apply(&summaries),
+            None => summaries,
+        };
+        let completed = records
+            .iter()
+            .map(|record| record.id.clone())
+            .collect::<HashSet<_>>();
+        Ok(MemoryRun {
+            results,
+            errors,
+            departments,
+            dashboard: DashboardFeed::new(&analysis, None, &completed, self.generated_at),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simulate::Population;
+
+    fn csv(records: &[CheckRecord]) -> Vec<u8> {
+        let header = (1..=57)
+            .map(|no| format!("q_{}", no))
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut value = format!("id,department,{}\n", header);
+        for record in records {
+            let answers = record
+                .answers
+                .values()
+                .iter()
+                .map(|answer| answer.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            value.push_str(&format!(
+                "\"{}\",{},{}\n",
+                record.id,
+                record.department.as_ref().unwrap(),
+                answers
+            ));
+        }
+        value.into_bytes()
+    }
+
+    #[test]
+    fn test_run() {
+        let records = Population::new(60, 2).with_seed(5).generate();
+        let mut input = csv(&records);
+        input.extend_from_slice(format!("\"x\",部署1,{}\n", vec!["1"; 56].join(",")).as_bytes());
+        let now = DateTime::parse_from_rfc3339("2026-04-01T09:00:00+09:00").unwrap();
+        let pipeline = MemoryPipeline::new(Config::default(), now).with_privacy(1.0, 7);
+        let run = pipeline.run(&input).unwrap();
+        assert_eq!(run.results.len(), 60);
+        assert_eq!(run.results[0].id, records[0].id);
+        assert_eq!(run.errors.len(), 1);
+        assert_eq!(run.errors[0].0, 60);
+        assert_eq!(run.outcome(), Outcome::PartialErrors);
+        assert_eq!(run.dashboard.generated_at, now);
+        assert_eq!(run.dashboard.respondents, 60);
+        assert_eq!(run.departments.len(), 2);
+
+        let again = pipeline.run(&input).unwrap();
+        assert_eq!(again.results, run.results);
+        assert_eq!(again.departments, run.departments);
+        assert_eq!(again.dashboard, run.dashboard);
+
+        assert!(matches!(
+            MemoryPipeline::new(Config::default(), now)
+                .with_privacy(0.0, 7)
+                .run(&input),
+            Err(Error::IllegalConfig)
+        ));
+    }
+}
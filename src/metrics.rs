@@ -0,0 +1,177 @@
+//! 稼働監視用の指標
+//!
+//! サーバ側の`/healthz`・`/metrics`の応答を組み立てるために用いる。高ストレス者数は
+//! 受検者数が集団分析の最小人数に満たない間は出力しない（少人数での個人の推測を防ぐ）。
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::reload::Settings;
+use crate::{CheckResult, Error};
+
+/// 判定時間のヒストグラムの区切り（秒）
+pub const LATENCY_BUCKETS: [f64; 8] = [0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1];
+
+#[derive(Debug, Default)]
+struct Counters {
+    scored: u64,
+    high_stress: u64,
+    errors: BTreeMap<&'static str, u64>,
+    latency_buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_count: u64,
+    latency_sum: f64,
+}
+
+/// 判定件数・エラー件数・判定時間の指標
+#[derive(Debug)]
+pub struct Metrics {
+    min_group_size: usize,
+    counters: Mutex<Counters>,
+}
+
+impl Metrics {
+    /// `min_group_size`未満の間は高ストレス者数を出力しない
+    pub fn new(min_group_size: usize) -> Self {
+        Self {
+            min_group_size,
+            counters: Mutex::new(Counters::default()),
+        }
+    }
+
+    /// 判定1件の結果と所要時間を記録する
+    pub fn record(&self, result: &Result<CheckResult, Error>, elapsed: Duration) {
+        let mut counters = match self.counters.lock() {
+            Ok(counters) => counters,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        match result {
+            Ok(result) => {
+                counters.scored += 1;
+                if result.has_stress {
+                    counters.high_stress += 1;
+                }
+            }
+            Err(e) => *counters.errors.entry(e.code()).or_default() += 1,
+        }
+        let seconds = elapsed.as_secs_f64();
+        for (bucket, &le) in counters.latency_buckets.iter_mut().zip(&LATENCY_BUCKETS) {
+            if seconds <= le {
+                *bucket += 1;
+            }
+        }
+        counters.latency_count += 1;
+        counters.latency_sum += seconds;
+    }
+
+    /// Prometheusのテキスト形式で出力する
+    pub fn render(&self) -> String {
+        let counters = match self.counters.lock() {
+            Ok(counters) => counters,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let mut text = String::new();
+        let _ = writeln!(
+            text,
+            "# HELP stresscheck_submissions_scored_total Number of submissions scored.\n\
+             # TYPE stresscheck_submissions_scored_total counter\n\
+             stresscheck_submissions_scored_total {}",
+            counters.scored
+        );
+        let _ = writeln!(
+            text,
+            "# HELP stresscheck_errors_total Number of submissions rejected, by error code.\n\
+             # TYPE stresscheck_errors_total counter"
+        );
+        for (code, count) in &counters.errors {
+            let _ = writeln!(
+                text,
+                "stresscheck_errors_total{{code=\"{}\"}} {}",
+                code, count
+            );
+        }
+        let _ = writeln!(
+            text,
+            "# HELP stresscheck_scoring_duration_seconds Time spent scoring a submission.\n\
+             # TYPE stresscheck_scoring_duration_seconds histogram"
+        );
+        for (count, le) in counters.latency_buckets.iter().zip(&LATENCY_BUCKETS) {
+            let _ = writeln!(
+                text,
+                "stresscheck_scoring_duration_seconds_bucket{{le=\"{}\"}} {}",
+                le, count
+            );
+        }
+        let _ = writeln!(
+            text,
+            "stresscheck_scoring_duration_seconds_bucket{{le=\"+Inf\"}} {}\n\
+             stresscheck_scoring_duration_seconds_sum {}\n\
+             stresscheck_scoring_duration_seconds_count {}",
+            counters.latency_count, counters.latency_sum, counters.latency_count
+        );
+        let _ = writeln!(
+            text,
+            "# HELP stresscheck_high_stress Number of respondents judged as high stress.\n\
+             # TYPE stresscheck_high_stress gauge"
+        );
+        if counters.scored as usize >= self.min_group_size {
+            let _ = writeln!(text, "stresscheck_high_stress {}", counters.high_stress);
+        }
+        text
+    }
+}
+
+/// `/healthz`の応答
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Health {
+    pub status: &'static str,
+    /// 調査票の版
+    pub questionnaire_version: String,
+    /// 読み込んでいるマスタのチェックサム
+    pub master_checksum: String,
+}
+
+impl Health {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            status: "ok",
+            questionnaire_version: settings.config.questionnaire_version.clone(),
+            master_checksum: settings.master.checksum(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, StressCriteria};
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::new(2);
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(4).unwrap();
+        }
+        let high = CheckResult::new(&store, StressCriteria::SUMUP);
+        metrics.record(&high, Duration::from_micros(300));
+        metrics.record(
+            &CheckResult::new(&AnswerStore::default(), StressCriteria::SUMUP),
+            Duration::from_millis(3),
+        );
+
+        let text = metrics.render();
+        assert!(text.contains("stresscheck_submissions_scored_total 1\n"));
+        assert!(text.contains("stresscheck_errors_total{code=\"E_INCOMPLETE\"} 1\n"));
+        assert!(text.contains("stresscheck_scoring_duration_seconds_bucket{le=\"0.001\"} 1\n"));
+        assert!(text.contains("stresscheck_scoring_duration_seconds_bucket{le=\"0.005\"} 2\n"));
+        assert!(text.contains("stresscheck_scoring_duration_seconds_count 2\n"));
+        assert!(!text.contains("\nstresscheck_high_stress "));
+
+        metrics.record(&high, Duration::from_micros(300));
+        assert!(metrics.render().contains("stresscheck_high_stress 2\n"));
+    }
+}
@@ -0,0 +1,241 @@
+//! 厚生労働省版ストレスチェック実施プログラムとのデータ交換（列の構成は利用者が指定する）
+//!
+//! 実施プログラムなどから出力したCSV（Shift_JIS）を受検記録として読み込み、過去の結果を
+//! 再判定・引き継げるようにする。逆に、委託先へ渡すためのShift_JISのCSVも書き出す。
+//!
+//! 実施プログラムの出力・取り込みの列の構成は、見本のファイルや仕様書と照合していない。
+//! `MhlwLayout::default`の列名（`個人ID`・`所属`・区分ごとの番号`A1`〜`D2`）は仮に定めた例であり、
+//! 実施プログラムの形式ではない。利用する版の出力の見出し行に合わせて`MhlwLayout`を指定すること。
+
+use std::io::{Read, Write};
+
+use encoding_rs::SHIFT_JIS;
+
+use crate::encoding::read_text;
+use crate::group_code::GroupCode;
+use crate::{AnswerStore, CheckRecord, Error};
+
+/// 調査票の区分ごとの設問数（Ａ：仕事、Ｂ：心身の反応、Ｃ：周囲のサポート、Ｄ：満足度）
+const SECTIONS: [(char, usize); 4] = [('A', 17), ('B', 29), ('C', 9), ('D', 2)];
+
+/// CSVの列名
+/// 実施プログラムとやり取りする場合は、その版の出力の見出し行に合わせて指定する。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MhlwLayout {
+    /// ユーザ特定キーの列
+    pub id_column: String,
+    /// 所属部署の列
+    pub department_column: String,
+    /// 設問1〜57の回答の列
+    pub answer_columns: Vec<String>,
+}
+
+/// 仮に定めた列名の例（`個人ID`・`所属`・調査票の区分ごとの番号`A1`〜`D2`）
+/// 実施プログラムの形式と照合したものではない。
+impl Default for MhlwLayout {
+    fn default() -> Self {
+        Self {
+            id_column: "個人ID".to_string(),
+            department_column: "所属".to_string(),
+            answer_columns: SECTIONS
+                .iter()
+                .flat_map(|&(section, count)| {
+                    (1..=count).map(move |no| format!("{}{}", section, no))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// `layout`の列名でShift_JISまたはUTF-8のCSVを読み込む
+/// 文字コードはShift_JISとUTF-8のいずれも受け付け、全角数字は半角とみなす。必要な列が見つからない場合はエラーとし、
+/// 行ごとの回答の誤りは行ごとのエラーとして返す。未回答の設問は空欄とする。
+pub fn read_mhlw<R: Read>(
+    reader: R,
+    layout: &MhlwLayout,
+) -> Result<Vec<Result<CheckRecord, Error>>, Error> {
+    let text = read_text(reader)?;
+    let mut reader = csv::Reader::from_reader(text.as_bytes());
+    let headers = reader.headers().map_err(Error::CSVReadError)?.clone();
+    let position = |name: &str| {
+        headers
+            .iter()
+            .position(|header| header.trim() == name)
+            .ok_or_else(|| Error::MissingColumn(name.to_string()))
+    };
+    let id = position(&layout.id_column)?;
+    let department = position(&layout.department_column).ok();
+    let answers = layout
+        .answer_columns
+        .iter()
+        .map(|name| position(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    if answers.len() != 57 {
+        return Err(Error::IllegalConfig);
+    }
+    Ok(reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(Error::CSVReadError)?;
+            let mut store = AnswerStore::default();
+            for (index, &column) in answers.iter().enumerate() {
+                let value = record.get(column).unwrap_or_default().trim();
+                if value.is_empty() {
+                    continue;
+                }
+                let score = value.parse::<u8>().map_err(|_| Error::IllegalAnswer)?;
+                store.insert(index as u8 + 1, score)?;
+            }
+            Ok(CheckRecord {
+                id: record.get(id).unwrap_or_default().trim().to_string(),
+                department: department
+                    .and_then(|column| record.get(column))
+                    .and_then(|value| GroupCode::new(value).ok()),
+                demographics: None,
+                consent: None,
+                answers: store,
+                amendments: vec![],
+            })
+        })
+        .collect())
+}
+
+/// `layout`の列名でShift_JISのCSVとして書き出す
+/// 実施プログラムに取り込む場合は、その版の取り込み形式に合わせた`layout`を指定する。列は`MhlwLayout`のユーザ特定キー・所属部署・設問1〜57の順とし、未回答は空欄とする。
+/// 取り込んだ値が変わらないよう、`export::Sanitizer`による数式の無害化・伏字・仮名化は行わない
+/// （`write_bulk_records`と同じ例外）。表計算ソフトで開く出力や委託先以外に渡す出力には用いない。
+/// Shift_JISで表せない文字を含む場合は`IllegalEncoding`とする。
+pub fn write_mhlw<W: Write>(
+    mut writer: W,
+    records: &[CheckRecord],
+    layout: &MhlwLayout,
+) -> Result<(), Error> {
+    let mut csv = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(vec![]);
+    let mut header = vec![layout.id_column.as_str(), layout.department_column.as_str()];
+    header.extend(layout.answer_columns.iter().map(String::as_str));
+    csv.write_record(&header).map_err(Error::CSVWriteError)?;
+    for record in records {
+        let mut row = vec![
+            record.id.clone(),
+            record
+                .department
+                .clone()
+                .map(String::from)
+                .unwrap_or_default(),
+        ];
+        row.extend(record.answers.values().iter().map(|value| match value {
+            0 => String::new(),
+            value => value.to_string(),
+        }));
+        csv.write_record(&row).map_err(Error::CSVWriteError)?;
+    }
+    let bytes = csv
+        .into_inner()
+        .map_err(|e| Error::IOError(e.into_error()))?;
+    let text = String::from_utf8(bytes).map_err(|_| Error::IllegalEncoding)?;
+    let (bytes, _, had_errors) = SHIFT_JIS.encode(&text);
+    if had_errors {
+        return Err(Error::IllegalEncoding);
+    }
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{CheckResult, StressCriteria};
+
+    fn csv(rows: &[(&str, &str, &str)]) -> String {
+        let layout = MhlwLayout::default();
+        let mut text = format!("個人ID,氏名,所属,{}\n", layout.answer_columns.join(","));
+        for (id, department, answers) in rows {
+            text.push_str(&format!("{},山田太郎,{},{}\n", id, department, answers));
+        }
+        text
+    }
+
+    #[test]
+    fn test_read_mhlw() {
+        let all = |answer: &str| vec![answer; 57].join(",");
+        let mut partial = vec!["2"; 57];
+        partial[56] = "";
+        let text = csv(&[
+            ("0001", "営業部", &all("4")),
+            ("0002", "", &partial.join(",")),
+            ("0003", "総務部", &all("5")),
+        ]);
+        let (bytes, _, _) = SHIFT_JIS.encode(&text);
+
+        let records = read_mhlw(&bytes[..], &MhlwLayout::default()).unwrap();
+        assert_eq!(records.len(), 3);
+        let record = records[0].as_ref().unwrap();
+        assert_eq!(record.id, "0001");
+        assert_eq!(record.department.as_deref(), Some("営業部"));
+        assert!(
+            CheckResult::new(&record.answers, StressCriteria::SUMUP)
+                .unwrap()
+                .has_stress
+        );
+        let record = records[1].as_ref().unwrap();
+        assert_eq!(record.department, None);
+        assert!(matches!(
+            record.answers.to_sumup_score(),
+            Err(Error::NotFullfilled)
+        ));
+        assert!(matches!(records[2], Err(Error::IllegalAnswer)));
+
+        // UTF-8でも読み込める
+        assert_eq!(
+            read_mhlw(text.as_bytes(), &MhlwLayout::default())
+                .unwrap()
+                .len(),
+            3
+        );
+    }
+
+    /// 見本は仮の列名から作成しており、実施プログラムの出力との互換性は確かめていない
+    #[test]
+    fn test_write_mhlw() {
+        let all = |answer: &str| vec![answer; 57].join(",");
+        let text = csv(&[("0001", "営業部", &all("4")), ("0002", "", &all("1"))]);
+        let records = read_mhlw(text.as_bytes(), &MhlwLayout::default())
+            .unwrap()
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let mut bytes = vec![];
+        write_mhlw(&mut bytes, &records, &MhlwLayout::default()).unwrap();
+        assert!(std::str::from_utf8(&bytes).is_err());
+        let (text, _, _) = SHIFT_JIS.decode(&bytes);
+        assert!(text.starts_with("個人ID,所属,A1,A2,"));
+        assert!(text.contains("\r\n0001,営業部,4,4,"));
+
+        let read = read_mhlw(&bytes[..], &MhlwLayout::default()).unwrap();
+        assert_eq!(read.len(), 2);
+        let record = read[1].as_ref().unwrap();
+        assert_eq!(record.id, "0002");
+        assert_eq!(record.answers.values(), records[1].answers.values());
+
+        let mut unencodable = records[0].clone();
+        unencodable.department = Some(GroupCode::new("営業部🏢").unwrap());
+        assert!(matches!(
+            write_mhlw(vec![], &[unencodable], &MhlwLayout::default()),
+            Err(Error::IllegalEncoding)
+        ));
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let result = read_mhlw("個人ID,所属,A1\n".as_bytes(), &MhlwLayout::default());
+        assert!(matches!(result, Err(Error::MissingColumn(ref column)) if column == "A2"));
+        assert!(matches!(
+            read_mhlw(&b"\x82\xa0\xff\xff"[..], &MhlwLayout::default()),
+            Err(Error::IllegalEncoding)
+        ));
+    }
+}
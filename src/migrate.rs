@@ -0,0 +1,238 @@
+//! 保存形式の版と移行
+//!
+//! 保存する記録に`schema_version`（"主版.副版"）を埋め込み、古い版で保存された記録を
+//! 読み込む際に現在の形式へ移行する。副版の変更は項目の追加のみで、古い版でも読み込める。
+//! 主版の変更は項目の意味・型の変更で、移行の手順を`Versioned::MIGRATIONS`に加える。
+//! 版のない記録は最初の形式（1.0）として扱う。
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::repository::StoredResult;
+use crate::Error;
+
+/// 記録に埋め込む版の項目名
+pub const SCHEMA_VERSION_FIELD: &str = "schema_version";
+
+/// 保存形式の版
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SchemaVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl SchemaVersion {
+    /// 版のない記録の版
+    pub const LEGACY: SchemaVersion = SchemaVersion::new(1, 0);
+
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl fmt::Display for SchemaVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for SchemaVersion {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let illegal = || Error::UnsupportedSchemaVersion(value.to_string());
+        let (major, minor) = value.split_once('.').ok_or_else(illegal)?;
+        Ok(Self {
+            major: major.parse().map_err(|_| illegal())?,
+            minor: minor.parse().map_err(|_| illegal())?,
+        })
+    }
+}
+
+impl Serialize for SchemaVersion {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SchemaVersion {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// 版の間の移行の手順
+pub struct Migration {
+    /// 移行前の版
+    pub from: SchemaVersion,
+    /// 移行後の版
+    pub to: SchemaVersion,
+    /// 記録を書き換える
+    pub apply: fn(&mut Map<String, Value>) -> Result<(), Error>,
+}
+
+/// 版を付けて保存する記録
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// 現在の版
+    const SCHEMA_VERSION: SchemaVersion;
+    /// 古い版から順に並べた移行の手順
+    const MIGRATIONS: &'static [Migration];
+}
+
+/// 判定結果の保存形式
+/// 1.1で実施回（`period`）、1.2で回答（`answers`）と再判定前の結果（`previous_results`）を加えた。
+impl Versioned for StoredResult {
+    const SCHEMA_VERSION: SchemaVersion = SchemaVersion::new(1, 2);
+    const MIGRATIONS: &'static [Migration] = &[
+        Migration {
+            from: SchemaVersion::new(1, 0),
+            to: SchemaVersion::new(1, 1),
+            apply: |record| {
+                record
+                    .entry("period")
+                    .or_insert_with(|| Value::String(String::new()));
+                Ok(())
+            },
+        },
+        Migration {
+            from: SchemaVersion::new(1, 1),
+            to: SchemaVersion::new(1, 2),
+            apply: |_| Ok(()),
+        },
+    ];
+}
+
+/// 記録の版
+/// 版のない記録は`SchemaVersion::LEGACY`とする。
+pub fn schema_version(value: &Value) -> Result<SchemaVersion, Error> {
+    match value.get(SCHEMA_VERSION_FIELD) {
+        None => Ok(SchemaVersion::LEGACY),
+        Some(Value::String(version)) => version.parse(),
+        Some(other) => Err(Error::UnsupportedSchemaVersion(other.to_string())),
+    }
+}
+
+/// 記録を現在の版に移行する
+/// 現在より新しい主版の記録は読み込めないためエラーとする。新しい副版の記録はそのまま読み込む。
+pub fn migrate<T: Versioned>(value: Value) -> Result<Value, Error> {
+    let mut version = schema_version(&value)?;
+    let Value::Object(mut record) = value else {
+        return Err(Error::UnsupportedSchemaVersion(version.to_string()));
+    };
+    if version.major > T::SCHEMA_VERSION.major {
+        return Err(Error::UnsupportedSchemaVersion(version.to_string()));
+    }
+    for migration in T::MIGRATIONS {
+        if migration.from == version {
+            (migration.apply)(&mut record)?;
+            version = migration.to;
+        }
+    }
+    if version.major != T::SCHEMA_VERSION.major {
+        return Err(Error::UnsupportedSchemaVersion(version.to_string()));
+    }
+    record.insert(
+        SCHEMA_VERSION_FIELD.to_string(),
+        Value::String(version.max(T::SCHEMA_VERSION).to_string()),
+    );
+    Ok(Value::Object(record))
+}
+
+/// 記録を現在の版を付けたJSONにする
+pub fn to_versioned<T: Versioned>(record: &T) -> Result<Value, Error> {
+    let mut value = serde_json::to_value(record).map_err(Error::JSONReadError)?;
+    if let Value::Object(ref mut map) = value {
+        map.insert(
+            SCHEMA_VERSION_FIELD.to_string(),
+            Value::String(T::SCHEMA_VERSION.to_string()),
+        );
+    }
+    Ok(value)
+}
+
+/// 版を付けたJSONから記録を読み込む
+/// 古い版の記録は現在の版に移行してから読み込む。
+pub fn from_versioned<T: Versioned>(value: Value) -> Result<T, Error> {
+    serde_json::from_value(migrate::<T>(value)?).map_err(Error::JSONReadError)
+}
+
+/// 版を付けたJSONの文字列から記録を読み込む
+pub fn from_versioned_str<T: Versioned>(value: &str) -> Result<T, Error> {
+    from_versioned(serde_json::from_str(value).map_err(Error::JSONReadError)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, CheckResult, StressCriteria};
+    use serde_json::json;
+
+    fn stored() -> StoredResult {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(2).unwrap();
+        }
+        StoredResult {
+            token: "t1".to_string(),
+            period: "2024".to_string(),
+            department: None,
+            submitted_at: "2024-06-01T09:00:00+09:00".parse().unwrap(),
+            consent: None,
+            result: CheckResult::new(&store, StressCriteria::SUMUP).unwrap(),
+            answers: None,
+            previous_results: vec![],
+        }
+    }
+
+    #[test]
+    fn test_schema_version() {
+        assert_eq!(
+            "1.2".parse::<SchemaVersion>().unwrap(),
+            SchemaVersion::new(1, 2)
+        );
+        assert!("1".parse::<SchemaVersion>().is_err());
+        assert!("a.b".parse::<SchemaVersion>().is_err());
+        assert_eq!(schema_version(&json!({})).unwrap(), SchemaVersion::LEGACY);
+        assert!(schema_version(&json!({"schema_version": 1})).is_err());
+    }
+
+    #[test]
+    fn test_migrate() {
+        let record = stored();
+        let value = to_versioned(&record).unwrap();
+        assert_eq!(value["schema_version"], "1.2");
+        assert_eq!(
+            from_versioned::<StoredResult>(value.clone()).unwrap(),
+            record
+        );
+
+        // 実施回のない版のない記録
+        let mut legacy = value.clone();
+        let map = legacy.as_object_mut().unwrap();
+        map.remove("schema_version");
+        map.remove("period");
+        let migrated = migrate::<StoredResult>(legacy.clone()).unwrap();
+        assert_eq!(migrated["schema_version"], "1.2");
+        assert_eq!(migrated["period"], "");
+        let read = from_versioned::<StoredResult>(legacy).unwrap();
+        assert_eq!(read.period, "");
+        assert_eq!(read.result, record.result);
+
+        // 新しい副版は読み込み、新しい主版は読み込まない
+        let mut newer = value.clone();
+        newer["schema_version"] = json!("1.9");
+        newer["extra"] = json!(true);
+        assert_eq!(from_versioned::<StoredResult>(newer).unwrap(), record);
+        let mut newer = value;
+        newer["schema_version"] = json!("2.0");
+        assert!(matches!(
+            from_versioned::<StoredResult>(newer),
+            Err(Error::UnsupportedSchemaVersion(version)) if version == "2.0"
+        ));
+    }
+}
@@ -0,0 +1,215 @@
+//! 設問ごとの欠損の集計
+//!
+//! 一括判定の入力で、どの設問が未回答・不正な値になりやすいかを集計する。分かりにくい
+//! 設問や、回答フォームの不具合（特定の設問が送信されないなど）の発見に用いる。
+
+use std::io::BufRead;
+
+use serde::Serialize;
+
+use crate::header::HeaderAliases;
+use crate::{AnswerStore, Error};
+
+/// 設問ごとの欠損数
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct QuestionMissing {
+    /// 設問番号
+    pub question: u8,
+    /// 未回答（空欄）の数
+    pub skipped: usize,
+    /// 1〜4以外の値の数
+    pub invalid: usize,
+    /// 欠損（未回答＋不正）の割合
+    pub rate: f64,
+}
+
+/// 一括判定の入力全体の欠損の集計
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MissingReport {
+    /// 集計した行数
+    pub rows: usize,
+    /// 欠損のある行数
+    pub incomplete_rows: usize,
+    /// 欠損の多い順に並べた設問ごとの集計（欠損のない設問は含めない）
+    pub questions: Vec<QuestionMissing>,
+}
+
+/// 欠損の集計
+#[derive(Debug, Clone)]
+pub struct MissingTally {
+    rows: usize,
+    incomplete_rows: usize,
+    skipped: [usize; 57],
+    invalid: [usize; 57],
+    aliases: HeaderAliases,
+}
+
+impl Default for MissingTally {
+    fn default() -> Self {
+        Self {
+            rows: 0,
+            incomplete_rows: 0,
+            skipped: [0; 57],
+            invalid: [0; 57],
+            aliases: HeaderAliases::default(),
+        }
+    }
+}
+
+impl MissingTally {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// CSVの列名の別名表を指定する
+    pub fn with_header_aliases(mut self, aliases: HeaderAliases) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// 回答を集計に加える。未回答の設問を数える
+    pub fn add_answers(&mut self, store: &AnswerStore) {
+        self.add_cells(store.values().iter().map(|&value| match value {
+            0 => Cell::Skipped,
+            _ => Cell::Answered,
+        }));
+    }
+
+    /// `q_1`〜`q_57`列（またはその別名）を含むCSVを集計に加える
+    /// 一括判定では行ごと読み込みエラーとなる値も、設問ごとに数える。
+    pub fn add_csv<R: BufRead>(&mut self, reader: R) -> Result<(), Error> {
+        let text = crate::encoding::read_text(reader)?;
+        let mut reader = csv::Reader::from_reader(text.as_bytes());
+        let headers = self
+            .aliases
+            .rename(reader.headers().map_err(Error::CSVReadError)?);
+        let columns = (1..=57)
+            .map(|no| {
+                let name = format!("q_{}", no);
+                headers
+                    .iter()
+                    .position(|header| header == name)
+                    .ok_or(Error::MissingColumn(name))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        for record in reader.records() {
+            let record = record.map_err(Error::CSVReadError)?;
+            self.add_cells(columns.iter().map(|&column| {
+                match record.get(column).unwrap_or_default().trim() {
+                    "" => Cell::Skipped,
+                    "1" | "2" | "3" | "4" => Cell::Answered,
+                    _ => Cell::Invalid,
+                }
+            }));
+        }
+        Ok(())
+    }
+
+    fn add_cells<I: Iterator<Item = Cell>>(&mut self, cells: I) {
+        self.rows += 1;
+        let mut incomplete = false;
+        for (index, cell) in cells.enumerate() {
+            match cell {
+                Cell::Answered => continue,
+                Cell::Skipped => self.skipped[index] += 1,
+                Cell::Invalid => self.invalid[index] += 1,
+            }
+            incomplete = true;
+        }
+        if incomplete {
+            self.incomplete_rows += 1;
+        }
+    }
+
+    /// 集計結果
+    pub fn report(&self) -> MissingReport {
+        let mut questions = (0..57)
+            .filter(|&index| self.skipped[index] + self.invalid[index] > 0)
+            .map(|index| QuestionMissing {
+                question: index as u8 + 1,
+                skipped: self.skipped[index],
+                invalid: self.invalid[index],
+                rate: (self.skipped[index] + self.invalid[index]) as f64 / self.rows as f64,
+            })
+            .collect::<Vec<_>>();
+        questions.sort_by(|a, b| {
+            (b.skipped + b.invalid)
+                .cmp(&(a.skipped + a.invalid))
+                .then(a.question.cmp(&b.question))
+        });
+        MissingReport {
+            rows: self.rows,
+            incomplete_rows: self.incomplete_rows,
+            questions,
+        }
+    }
+}
+
+enum Cell {
+    Answered,
+    Skipped,
+    Invalid,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_add_csv() {
+        let header = (1..=57)
+            .map(|no| format!("q_{}", no))
+            .collect::<Vec<_>>()
+            .join(",");
+        let row = |edit: &dyn Fn(&mut Vec<&str>)| {
+            let mut cells = vec!["2"; 57];
+            edit(&mut cells);
+            cells.join(",")
+        };
+        let csv = [
+            format!("id,{}", header),
+            format!("a,{}", row(&|_| {})),
+            format!("b,{}", row(&|cells| cells[9] = "")),
+            format!(
+                "c,{}",
+                row(&|cells| {
+                    cells[9] = "5";
+                    cells[56] = "";
+                })
+            ),
+            format!("d,{}", row(&|cells| cells[9] = "")),
+        ]
+        .join("\n");
+
+        let mut tally = MissingTally::new();
+        tally.add_csv(csv.as_bytes()).unwrap();
+        let mut store = AnswerStore::default();
+        store.insert(1, 1).unwrap();
+        tally.add_answers(&store);
+
+        let report = tally.report();
+        assert_eq!(report.rows, 5);
+        assert_eq!(report.incomplete_rows, 4);
+        assert_eq!(
+            report.questions[0],
+            QuestionMissing {
+                question: 10,
+                skipped: 3,
+                invalid: 1,
+                rate: 0.8,
+            }
+        );
+        assert_eq!(report.questions[1].question, 57);
+        assert_eq!(report.questions[1].skipped, 2);
+        assert_eq!(report.questions.len(), 56);
+        assert!(report
+            .questions
+            .iter()
+            .all(|question| question.question != 1));
+
+        assert!(matches!(
+            MissingTally::new().add_csv("id,q_1\n".as_bytes()),
+            Err(Error::MissingColumn(ref column)) if column == "q_2"
+        ));
+    }
+}
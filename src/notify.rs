@@ -0,0 +1,258 @@
+//! 個人結果の通知メール
+//!
+//! 受検者ごとの結果通知メール（件名・本文）をテンプレートから作成する。
+//! 作成したメールは.emlファイルとして保存するか、外部のメール配信システム向けの
+//! 一覧としてCSVで書き出す。
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::export::{Sanitizer, SanitizingWriter};
+use crate::{CheckResult, Error};
+
+/// 通知の言語
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    #[default]
+    Ja,
+    En,
+}
+
+/// 通知メールのテンプレート
+/// 件名・本文中の次のプレースホルダを置き換える。
+/// `{{id}}` `{{score_a}}` `{{score_b}}` `{{score_c}}` `{{judgement}}` `{{interview}}`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    pub subject: String,
+    pub body: String,
+}
+
+impl Template {
+    /// 標準のテンプレート
+    pub fn default_for(language: Language) -> Self {
+        match language {
+            Language::Ja => Self {
+                subject: "ストレスチェック結果のお知らせ".to_string(),
+                body: "ストレスチェックの結果をお知らせします。\n\n\
+                       受検番号: {{id}}\n\
+                       ストレスの原因と考えられる因子(A): {{score_a}}点\n\
+                       ストレスによって起こる心身の反応(B): {{score_b}}点\n\
+                       ストレス反応に影響を与える他の因子(C): {{score_c}}点\n\n\
+                       判定: {{judgement}}\n\
+                       {{interview}}\n"
+                    .to_string(),
+            },
+            Language::En => Self {
+                subject: "Your stress check results".to_string(),
+                body: "Here are the results of your stress check.\n\n\
+                       ID: {{id}}\n\
+                       Causes of stress (A): {{score_a}}\n\
+                       Stress reactions (B): {{score_b}}\n\
+                       Other factors affecting stress reactions (C): {{score_c}}\n\n\
+                       Result: {{judgement}}\n\
+                       {{interview}}\n"
+                    .to_string(),
+            },
+        }
+    }
+
+    fn fill(value: &str, placeholders: &[(&str, String)]) -> String {
+        placeholders
+            .iter()
+            .fold(value.to_string(), |value, (name, replacement)| {
+                value.replace(&format!("{{{{{}}}}}", name), replacement)
+            })
+    }
+}
+
+/// 通知メールの作成
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notifier {
+    pub language: Language,
+    pub template: Template,
+    /// 送信元アドレス
+    pub from: String,
+    /// 高ストレス者への面接指導の申出方法の案内
+    pub interview_instructions: String,
+}
+
+impl Notifier {
+    pub fn new(language: Language, from: &str) -> Self {
+        let interview_instructions = match language {
+            Language::Ja => {
+                "医師による面接指導を受けることをおすすめします。\
+                             希望される場合は実施事務従事者までお申し出ください。"
+            }
+            Language::En => {
+                "We recommend an interview with a physician. \
+                             Please contact the stress check staff to request one."
+            }
+        };
+        Self {
+            language,
+            template: Template::default_for(language),
+            from: from.to_string(),
+            interview_instructions: interview_instructions.to_string(),
+        }
+    }
+
+    pub fn with_template(mut self, template: Template) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// 受検者一人分の通知メールを作成する
+    pub fn render(&self, id: &str, to: &str, result: &CheckResult) -> Message {
+        let judgement = match (self.language, result.has_stress) {
+            (Language::Ja, true) => "高ストレス者に該当します",
+            (Language::Ja, false) => "高ストレス者に該当しません",
+            (Language::En, true) => "High stress",
+            (Language::En, false) => "Not high stress",
+        };
+        let interview = if result.has_stress {
+            self.interview_instructions.clone()
+        } else {
+            String::new()
+        };
+        let (a, b, c) = result.scores;
+        let placeholders = [
+            ("id", id.to_string()),
+            ("score_a", a.to_string()),
+            ("score_b", b.to_string()),
+            ("score_c", c.to_string()),
+            ("judgement", judgement.to_string()),
+            ("interview", interview),
+        ];
+        Message {
+            id: id.to_string(),
+            from: self.from.clone(),
+            to: to.to_string(),
+            subject: Template::fill(&self.template.subject, &placeholders),
+            body: Template::fill(&self.template.body, &placeholders),
+        }
+    }
+}
+
+/// 通知メール
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Message {
+    /// ユーザ特定キー
+    pub id: String,
+    pub from: String,
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+impl Message {
+    /// RFC 5322形式のメール
+    /// 件名はRFC 2047でエンコードし、本文はBase64で符号化する。
+    pub fn to_eml(&self) -> String {
+        let engine = base64::engine::general_purpose::STANDARD;
+        let body = engine
+            .encode(self.body.as_bytes())
+            .as_bytes()
+            .chunks(76)
+            .map(|line| String::from_utf8_lossy(line).into_owned())
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        format!(
+            "From: {}\r\nTo: {}\r\nSubject: =?UTF-8?B?{}?=\r\nMIME-Version: 1.0\r\n\
+             Content-Type: text/plain; charset=UTF-8\r\n\
+             Content-Transfer-Encoding: base64\r\n\r\n{}\r\n",
+            self.from,
+            self.to,
+            engine.encode(self.subject.as_bytes()),
+            body
+        )
+    }
+}
+
+/// 通知メールを1通ずつ.emlファイルとして保存する
+/// ファイル名はユーザ特定キーの英数字以外を`_`に置き換えたもの。
+pub fn write_eml_files<P: AsRef<Path>>(
+    dir: P,
+    messages: &[Message],
+) -> Result<Vec<PathBuf>, Error> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    let mut paths = vec![];
+    for message in messages {
+        let name = message
+            .id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        let path = dir.join(format!("{}.eml", name));
+        std::fs::write(&path, message.to_eml())?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+/// 外部のメール配信システム向けにCSVで書き出す
+pub fn write_mail_list<W: Write>(
+    writer: W,
+    messages: &[Message],
+    sanitizer: &Sanitizer,
+) -> Result<(), Error> {
+    let mut writer = SanitizingWriter::new(writer, sanitizer);
+    for message in messages {
+        writer.serialize(message)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, StressCriteria};
+
+    fn result(answer: u8) -> CheckResult {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(answer).unwrap();
+        }
+        CheckResult::new(&store, StressCriteria::SUMUP).unwrap()
+    }
+
+    #[test]
+    fn test_render() {
+        let notifier = Notifier::new(Language::Ja, "stresscheck@example.com");
+        let message = notifier.render("1", "taro@example.com", &result(4));
+        assert_eq!(message.subject, "ストレスチェック結果のお知らせ");
+        assert!(message.body.contains("受検番号: 1"));
+        assert!(message.body.contains("高ストレス者に該当します"));
+        assert!(message.body.contains("面接指導"));
+        assert!(!message.body.contains("{{"));
+
+        let notifier =
+            Notifier::new(Language::En, "stresscheck@example.com").with_template(Template {
+                subject: "Result {{id}}".to_string(),
+                body: "{{judgement}}{{interview}}".to_string(),
+            });
+        let message = notifier.render("2", "hanako@example.com", &result(1));
+        assert_eq!(message.subject, "Result 2");
+        assert_eq!(message.body, "Not high stress");
+    }
+
+    #[test]
+    fn test_to_eml() {
+        let message = Message {
+            id: "1".to_string(),
+            from: "a@example.com".to_string(),
+            to: "b@example.com".to_string(),
+            subject: "結果".to_string(),
+            body: "本文".to_string(),
+        };
+        let eml = message.to_eml();
+        assert!(eml.starts_with("From: a@example.com\r\nTo: b@example.com\r\n"));
+        assert!(eml.contains("Subject: =?UTF-8?B?57WQ5p6c?=\r\n"));
+        assert!(eml.ends_with("\r\n\r\n5pys5paH\r\n"));
+    }
+}
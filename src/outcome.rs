@@ -0,0 +1,108 @@
+//! コマンドの終了コード
+//!
+//! シェルのパイプラインやジョブスケジューラが結果に応じて分岐できるよう、
+//! 各コマンドは次の終了コードで終了する。
+//!
+//! | コード | 意味 |
+//! |---|---|
+//! | 0 | 成功 |
+//! | 1 | 内部エラー（入出力・保存先・送信の失敗など） |
+//! | 2 | 入力の検証に失敗した（引数・設定・回答の誤り。全件が判定できなかった場合を含む） |
+//! | 3 | 一部の行が判定できなかった（判定できた行は出力済み） |
+//! | 4 | 他の実行がロックを取得しているため何もせずに終了した（[`crate::lock`]を参照） |
+
+use std::process::ExitCode;
+
+use crate::Error;
+
+/// コマンドの実行結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    InternalError,
+    InvalidInput,
+    PartialErrors,
+    Locked,
+}
+
+impl Outcome {
+    /// 終了コード
+    pub fn code(&self) -> u8 {
+        match self {
+            Outcome::Success => 0,
+            Outcome::InternalError => 1,
+            Outcome::InvalidInput => 2,
+            Outcome::PartialErrors => 3,
+            Outcome::Locked => 4,
+        }
+    }
+
+    /// 行ごとの判定の件数から実行結果を決める
+    pub fn from_counts(succeeded: usize, failed: usize) -> Self {
+        match (succeeded, failed) {
+            (_, 0) => Outcome::Success,
+            (0, _) => Outcome::InvalidInput,
+            _ => Outcome::PartialErrors,
+        }
+    }
+}
+
+impl From<Outcome> for ExitCode {
+    fn from(outcome: Outcome) -> Self {
+        ExitCode::from(outcome.code())
+    }
+}
+
+impl Error {
+    /// エラーで終了する場合の実行結果
+    /// ロックを取得できなかった場合を除き、HTTPで500を返すエラーを内部エラー、それ以外を入力の誤りとする。
+    pub fn outcome(&self) -> Outcome {
+        if let Error::Locked(_, _) = self {
+            return Outcome::Locked;
+        }
+        match self.http_status() {
+            500 => Outcome::InternalError,
+            _ => Outcome::InvalidInput,
+        }
+    }
+}
+
+/// コマンドの処理結果を標準エラーに示して終了コードにする
+pub fn exit_with(result: Result<Outcome, Error>) -> ExitCode {
+    match result {
+        Ok(outcome) => outcome.into(),
+        Err(e) => {
+            eprintln!("{} ({})", e, e.code());
+            e.outcome().into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_outcome() {
+        assert_eq!(Outcome::from_counts(3, 0), Outcome::Success);
+        assert_eq!(Outcome::from_counts(0, 0), Outcome::Success);
+        assert_eq!(Outcome::from_counts(0, 2), Outcome::InvalidInput);
+        assert_eq!(Outcome::from_counts(3, 2), Outcome::PartialErrors);
+        assert_eq!(Error::NotFullfilled.outcome(), Outcome::InvalidInput);
+        assert_eq!(
+            Error::StorageError("down".to_string()).outcome(),
+            Outcome::InternalError
+        );
+        assert_eq!(
+            [
+                Outcome::Success,
+                Outcome::InternalError,
+                Outcome::InvalidInput,
+                Outcome::PartialErrors,
+                Outcome::Locked
+            ]
+            .map(|outcome| outcome.code()),
+            [0, 1, 2, 3, 4]
+        );
+    }
+}
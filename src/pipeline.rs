@@ -0,0 +1,211 @@
+//! 受信と判定を分離するチャネル
+//!
+//! サーバが受け付けた回答を`ScoringSink`に送り、判定済みの結果を`ScoredStream`から
+//! 受け取る。チャネルは容量付きで、判定が追いつかない場合は送信側が待たされる
+//! （`try_send`では即座に`Full`が返る）。判定は複数のスレッドで並行して行うが、
+//! 結果は送った順に返す。
+
+use std::collections::BTreeMap;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use crate::{CheckRecord, CheckResult, Error, StressCriteria};
+
+/// 判定済みの結果
+#[derive(Debug)]
+pub struct Scored {
+    /// 送った順の番号（0始まり）
+    pub sequence: u64,
+    /// ユーザ特定キー
+    pub id: String,
+    pub result: Result<CheckResult, Error>,
+}
+
+/// 判定待ちの回答を送る側
+/// 複製した送る側から送った場合も、番号は全体で送った順に振る。
+#[derive(Clone)]
+pub struct ScoringSink {
+    sender: SyncSender<(u64, CheckRecord)>,
+    /// 次に振る番号。送信に成功した場合のみ進める
+    next: Arc<Mutex<u64>>,
+}
+
+/// 判定済みの結果を受け取る側
+/// 全ての`ScoringSink`が破棄され、残りの判定が終わると反復が終了する。
+pub struct ScoredStream {
+    receiver: Receiver<Scored>,
+    workers: Vec<JoinHandle<()>>,
+    /// 先に判定が終わった結果
+    pending: BTreeMap<u64, Scored>,
+    next: u64,
+}
+
+impl ScoringSink {
+    /// 判定スレッドを起動する
+    /// `capacity`は送信側・受信側それぞれのチャネルの容量。
+    pub fn spawn(
+        criteria: StressCriteria,
+        workers: usize,
+        capacity: usize,
+    ) -> (ScoringSink, ScoredStream) {
+        let (sender, submissions) = mpsc::sync_channel::<(u64, CheckRecord)>(capacity);
+        let (results, receiver) = mpsc::sync_channel::<Scored>(capacity);
+        let submissions = Arc::new(Mutex::new(submissions));
+        let workers = (0..workers.max(1))
+            .map(|_| {
+                let submissions = Arc::clone(&submissions);
+                let results = results.clone();
+                std::thread::spawn(move || loop {
+                    let record = match submissions.lock() {
+                        Ok(submissions) => submissions.recv(),
+                        Err(_) => return,
+                    };
+                    let Ok((sequence, record)) = record else {
+                        return;
+                    };
+                    let scored = Scored {
+                        sequence,
+                        result: CheckResult::new(&record.answers, criteria),
+                        id: record.id,
+                    };
+                    if results.send(scored).is_err() {
+                        return;
+                    }
+                })
+            })
+            .collect();
+        (
+            ScoringSink {
+                sender,
+                next: Arc::new(Mutex::new(0)),
+            },
+            ScoredStream {
+                receiver,
+                workers,
+                pending: BTreeMap::new(),
+                next: 0,
+            },
+        )
+    }
+
+    /// 回答を送る
+    /// チャネルが満杯の場合は空きが出るまで待つ。
+    pub fn send(&self, record: CheckRecord) -> Result<(), Error> {
+        let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+        self.sender
+            .send((*next, record))
+            .map_err(|e| Error::ChannelClosed((e.0).1.id))?;
+        *next += 1;
+        Ok(())
+    }
+
+    /// 待たずに回答を送る
+    /// 満杯の場合は`TrySendError::Full`で回答を返す。
+    pub fn try_send(&self, record: CheckRecord) -> Result<(), Box<TrySendError<CheckRecord>>> {
+        let mut next = self.next.lock().unwrap_or_else(|e| e.into_inner());
+        self.sender.try_send((*next, record)).map_err(|e| {
+            Box::new(match e {
+                TrySendError::Full((_, record)) => TrySendError::Full(record),
+                TrySendError::Disconnected((_, record)) => TrySendError::Disconnected(record),
+            })
+        })?;
+        *next += 1;
+        Ok(())
+    }
+}
+
+impl Iterator for ScoredStream {
+    type Item = Scored;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(scored) = self.pending.remove(&self.next) {
+                self.next += 1;
+                return Some(scored);
+            }
+            match self.receiver.recv() {
+                Ok(scored) => {
+                    self.pending.insert(scored.sequence, scored);
+                }
+                Err(_) => {
+                    for worker in self.workers.drain(..) {
+                        let _ = worker.join();
+                    }
+                    // 判定スレッドが異常終了して欠番がある場合も、残りは順に返す
+                    let (sequence, scored) = self.pending.pop_first()?;
+                    self.next = sequence + 1;
+                    return Some(scored);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::simulate::Population;
+    use crate::AnswerStore;
+
+    #[test]
+    fn test_scoring_sink() {
+        let mut records = Population::new(100, 2).generate();
+        records[7].answers = AnswerStore::default();
+        let (sink, stream) = ScoringSink::spawn(StressCriteria::SUMUP, 4, 8);
+        let producer = std::thread::spawn(move || {
+            for record in records {
+                sink.send(record).unwrap();
+            }
+        });
+        let scored = stream.collect::<Vec<_>>();
+        producer.join().unwrap();
+        assert_eq!(scored.len(), 100);
+        assert!(matches!(scored[7].result, Err(Error::NotFullfilled)));
+        assert_eq!(
+            scored.iter().filter(|scored| scored.result.is_ok()).count(),
+            99
+        );
+    }
+
+    #[test]
+    fn test_scoring_sink_order() {
+        let records = Population::new(500, 2).with_seed(3).generate();
+        let ids = records
+            .iter()
+            .map(|record| record.id.clone())
+            .collect::<Vec<_>>();
+        let (sink, stream) = ScoringSink::spawn(StressCriteria::CONVERSION, 8, 4);
+        let producer = std::thread::spawn(move || {
+            for record in records {
+                sink.send(record).unwrap();
+            }
+        });
+        let scored = stream.collect::<Vec<_>>();
+        producer.join().unwrap();
+        assert_eq!(
+            scored
+                .iter()
+                .map(|scored| scored.id.clone())
+                .collect::<Vec<_>>(),
+            ids
+        );
+        assert!(scored
+            .iter()
+            .enumerate()
+            .all(|(i, scored)| scored.sequence == i as u64));
+    }
+
+    #[test]
+    fn test_try_send_full() {
+        let (sink, stream) = ScoringSink::spawn(StressCriteria::SUMUP, 1, 1);
+        let records = Population::new(10, 1).generate();
+        let full = records
+            .into_iter()
+            .map(|record| sink.try_send(record))
+            .any(|result| matches!(result.map_err(|e| *e), Err(TrySendError::Full(_))));
+        assert!(full);
+        drop(sink);
+        assert!(stream.count() >= 1);
+    }
+}
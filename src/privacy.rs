@@ -0,0 +1,140 @@
+//! 公表する集計値への差分プライバシーの付与（任意）
+//!
+//! 社内イントラネットへの掲載など、実施者以外に集団分析の結果を示す場合に、受検者数・
+//! 高ストレス者数にラプラス雑音を加える。雑音の大きさはプライバシー予算`epsilon`で決まり、
+//! 小さいほど保護が強く値は不正確になる。高ストレス者率は雑音を加えた人数から求め直す。
+//! 尺度の平均・健康リスク・信頼区間・ウェルビーイング指数は保護されないため、雑音を加えた集計からは除く。
+//! 実施者が事後措置に用いる集計には適用しない。
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::group::GroupSummary;
+use crate::Error;
+
+/// ラプラス機構
+#[derive(Debug, Clone)]
+pub struct LaplaceMechanism {
+    epsilon: f64,
+    rng: StdRng,
+}
+
+impl LaplaceMechanism {
+    /// `epsilon`が正の有限値でない場合は`IllegalConfig`
+    pub fn new(epsilon: f64) -> Result<Self, Error> {
+        if !(epsilon.is_finite() && epsilon > 0.0) {
+            return Err(Error::IllegalConfig);
+        }
+        Ok(Self {
+            epsilon,
+            rng: StdRng::from_entropy(),
+        })
+    }
+
+    /// 乱数の種を固定する（試験用。公表する集計では用いない）
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// 平均0・尺度`scale`のラプラス分布に従う雑音
+    pub fn noise(&mut self, scale: f64) -> f64 {
+        // -0.5では対数が発散するため引き直す
+        let u = loop {
+            let u: f64 = self.rng.gen_range(-0.5..0.5);
+            if u > -0.5 {
+                break u;
+            }
+        };
+        -scale * u.signum() * (1.0 - 2.0 * u.abs()).ln()
+    }
+
+    /// 人数に雑音を加える（感度1、予算`epsilon`）
+    /// 整数に丸め、負の値は0とする。
+    pub fn count(&mut self, count: usize) -> usize {
+        let epsilon = self.epsilon;
+        self.count_with_budget(count, epsilon)
+    }
+
+    fn count_with_budget(&mut self, count: usize, epsilon: f64) -> usize {
+        (count as f64 + self.noise(1.0 / epsilon)).round().max(0.0) as usize
+    }
+
+    /// 集団ごとの集計に雑音を加える
+    /// 受検者数と高ストレス者数で予算を半分ずつ用いる。集団は互いに重ならないため、
+    /// 全体の予算も`epsilon`となる。
+    pub fn apply(&mut self, summaries: &[GroupSummary]) -> Vec<GroupSummary> {
+        let half = self.epsilon / 2.0;
+        summaries
+            .iter()
+            .map(|summary| {
+                let respondents = self.count_with_budget(summary.respondents, half);
+                let high_stress = summary
+                    .high_stress
+                    .map(|high_stress| self.count_with_budget(high_stress, half));
+                GroupSummary {
+                    key: summary.key.clone(),
+                    respondents,
+                    high_stress,
+                    high_stress_rate: high_stress.map(|high_stress| match respondents {
+                        0 => 0.0,
+                        respondents => (high_stress as f64 / respondents as f64).min(1.0),
+                    }),
+                    scale_means: None,
+                    health_risk: None,
+                    confidence_intervals: None,
+                    wellbeing_index: None,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::{Dimension, GroupAnalysis};
+    use crate::simulate::Population;
+    use crate::StressCriteria;
+
+    #[test]
+    fn test_noise() {
+        let mut mechanism = LaplaceMechanism::new(1.0).unwrap().with_seed(1);
+        let samples = (0..20_000)
+            .map(|_| mechanism.noise(2.0))
+            .collect::<Vec<_>>();
+        let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+        let mean_abs = samples.iter().map(|v| v.abs()).sum::<f64>() / samples.len() as f64;
+        // ラプラス分布の平均絶対偏差は尺度に等しい
+        assert!(mean.abs() < 0.1, "{}", mean);
+        assert!((mean_abs - 2.0).abs() < 0.1, "{}", mean_abs);
+        assert!(matches!(
+            LaplaceMechanism::new(0.0),
+            Err(Error::IllegalConfig)
+        ));
+        assert!(LaplaceMechanism::new(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        let records = Population::new(300, 3).with_seed(4).generate();
+        let summaries =
+            GroupAnalysis::new(&records, StressCriteria::SUMUP).summarize(Dimension::Department);
+        let mut mechanism = LaplaceMechanism::new(1.0).unwrap().with_seed(2);
+        let noisy = mechanism.apply(&summaries);
+        assert_eq!(noisy.len(), summaries.len());
+        for (noisy, exact) in noisy.iter().zip(&summaries) {
+            assert_eq!(noisy.key, exact.key);
+            assert_eq!(noisy.is_suppressed(), exact.is_suppressed());
+            assert!(noisy.respondents.abs_diff(exact.respondents) < 30);
+            assert!(noisy.scale_means.is_none());
+            if let Some(rate) = noisy.high_stress_rate {
+                assert!((0.0..=1.0).contains(&rate));
+            }
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! 対話形式の受検での設問の示し方
+//!
+//! 画面読み上げソフトで受検する人のため、選択肢を1行ずつ番号付きで読み上げ、
+//! 毎回同じ文言で回答を求める形式を用意する。表や罫線は用いない。
+
+use crate::Question;
+
+/// 設問の示し方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PromptStyle {
+    /// 選択肢を1行に並べる
+    #[default]
+    Standard,
+    /// 画面読み上げ向け。設問番号・選択肢番号を明示し、選択肢を1行ずつ示す
+    Accessible,
+}
+
+impl PromptStyle {
+    /// 設問と選択肢（末尾の改行なし）
+    pub fn question(&self, question: &Question, total: usize) -> String {
+        match self {
+            PromptStyle::Standard => {
+                let mut text = question.text.clone();
+                text.push('\n');
+                for score in &question.scores {
+                    text.push_str(&format!("  {} => {}", score.score, score.text));
+                }
+                text
+            }
+            PromptStyle::Accessible => {
+                let mut text = format!("設問{}（全{}問）: {}", question.id, total, question.text);
+                for score in &question.scores {
+                    text.push_str(&format!("\n選択肢{}: {}", score.score, score.text));
+                }
+                text.push('\n');
+                text.push_str(&self.answer_prompt(question));
+                text
+            }
+        }
+    }
+
+    /// 回答を読み取れなかった場合の案内
+    pub fn invalid_answer(&self, question: &Question) -> String {
+        match self {
+            PromptStyle::Standard => "回答は半角英数1〜4で入力してください。".to_string(),
+            PromptStyle::Accessible => {
+                format!(
+                    "回答を読み取れませんでした。{}",
+                    self.answer_prompt(question)
+                )
+            }
+        }
+    }
+
+    fn answer_prompt(&self, question: &Question) -> String {
+        let first = question.scores.first().map(|score| score.score);
+        let last = question.scores.last().map(|score| score.score);
+        format!(
+            "回答の番号を{}から{}の数字で入力してください。",
+            first.unwrap_or(1),
+            last.unwrap_or(4)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    #[test]
+    fn test_prompt_style() {
+        let question = QUESTIONS.question(1).unwrap();
+        assert_eq!(
+            PromptStyle::Standard.question(&question, 57),
+            "非常にたくさんの仕事をしなければならない\n  1 => そうだ  2 => まあそうだ  3 => ややちがう  4 => ちがう"
+        );
+        assert_eq!(
+            PromptStyle::Accessible.question(&question, 57),
+            "設問1（全57問）: 非常にたくさんの仕事をしなければならない\n\
+             選択肢1: そうだ\n選択肢2: まあそうだ\n選択肢3: ややちがう\n選択肢4: ちがう\n\
+             回答の番号を1から4の数字で入力してください。"
+        );
+        assert!(PromptStyle::Accessible
+            .invalid_answer(&question)
+            .ends_with("回答の番号を1から4の数字で入力してください。"));
+    }
+}
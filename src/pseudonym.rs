@@ -0,0 +1,96 @@
+//! 鍵付きハッシュによる仮名化
+//!
+//! 分析担当者に渡す出力では、ユーザ特定キーを鍵付きハッシュ（HMAC-SHA256）による仮名に置き換える。
+//! 同じ鍵を用いる限り同じ受検者は年度をまたいで同じ仮名になるため、個人を特定せずに経年の
+//! 集団分析ができる。鍵は実施設定には書かず、環境変数`STRESSCHECK_PSEUDONYM_KEY`（Base64）で渡す。
+
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::Error;
+
+/// 仮名化の鍵を指定する環境変数（Base64）
+pub const PSEUDONYM_KEY_ENV: &str = "STRESSCHECK_PSEUDONYM_KEY";
+/// 鍵の最小長（バイト）
+pub const MIN_KEY_LENGTH: usize = 16;
+/// 仮名の接頭辞
+const PREFIX: &str = "p_";
+
+/// 仮名化
+#[derive(Clone, PartialEq, Eq)]
+pub struct Pseudonymizer {
+    key: Vec<u8>,
+}
+
+/// 鍵を出力しない
+impl std::fmt::Debug for Pseudonymizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pseudonymizer").finish_non_exhaustive()
+    }
+}
+
+impl Pseudonymizer {
+    /// 鍵が短すぎる場合は`IllegalConfig`
+    pub fn new(key: &[u8]) -> Result<Self, Error> {
+        if key.len() < MIN_KEY_LENGTH {
+            return Err(Error::IllegalConfig);
+        }
+        Ok(Self { key: key.to_vec() })
+    }
+
+    /// 環境変数の鍵を用いる。指定がない場合は`None`
+    pub fn from_env() -> Result<Option<Self>, Error> {
+        match std::env::var(PSEUDONYM_KEY_ENV) {
+            Ok(value) if !value.trim().is_empty() => Self::from_base64(value.trim()).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// Base64で表した鍵を用いる
+    pub fn from_base64(value: &str) -> Result<Self, Error> {
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(|_| Error::IllegalConfig)?;
+        Self::new(&key)
+    }
+
+    /// 仮名（`p_`に続く32桁の16進数）
+    pub fn pseudonym(&self, id: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC accepts any key");
+        mac.update(id.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let hex = digest[..16]
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<String>();
+        format!("{}{}", PREFIX, hex)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_pseudonym() {
+        let pseudonymizer = Pseudonymizer::new(b"0123456789abcdef").unwrap();
+        let pseudonym = pseudonymizer.pseudonym("0001");
+        assert_eq!(pseudonym.len(), 34);
+        assert!(pseudonym.starts_with("p_"));
+        assert_eq!(pseudonymizer.pseudonym("0001"), pseudonym);
+        assert_ne!(pseudonymizer.pseudonym("0002"), pseudonym);
+
+        let other = Pseudonymizer::new(b"fedcba9876543210").unwrap();
+        assert_ne!(other.pseudonym("0001"), pseudonym);
+        assert_eq!(
+            Pseudonymizer::from_base64("MDEyMzQ1Njc4OWFiY2RlZg==").unwrap(),
+            pseudonymizer
+        );
+        assert!(!format!("{:?}", pseudonymizer).contains("0123"));
+        assert!(matches!(
+            Pseudonymizer::new(b"short"),
+            Err(Error::IllegalConfig)
+        ));
+    }
+}
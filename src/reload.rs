@@ -0,0 +1,141 @@
+//! 稼働中のマスタ・実施設定の差し替え
+//!
+//! サーバ側で再起動せずに設問マスタと実施設定を読み直すために用いる。新しいマスタと設定は
+//! 検証を通過した場合にのみまとめて差し替え、検証に失敗した場合は現在の内容を維持する。
+//! 差し替え前に`current`で取得した内容は、処理中の要求が終わるまでそのまま利用できる。
+
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use crate::config::Config;
+use crate::verify::Discrepancy;
+use crate::{Error, SimpleStress};
+
+/// 判定に用いるマスタと実施設定の組
+#[derive(Debug)]
+pub struct Settings {
+    pub master: SimpleStress,
+    pub config: Config,
+}
+
+impl Settings {
+    /// マスタと設定を検証して組にする
+    /// チェックサムの不一致（文言の改訂）は許容し、設問構成の不整合は拒否する。
+    pub fn new(master: SimpleStress, config: Config) -> Result<Self, Error> {
+        let discrepancies = master
+            .verify()
+            .into_iter()
+            .filter(|discrepancy| !matches!(discrepancy, Discrepancy::Checksum { .. }))
+            .collect::<Vec<_>>();
+        if !discrepancies.is_empty() {
+            return Err(Error::IllegalMaster(discrepancies));
+        }
+        config.validate()?;
+        Ok(Self { master, config })
+    }
+}
+
+/// 読み直し可能なマスタと実施設定
+pub struct Reloadable {
+    master_path: PathBuf,
+    config_path: Option<PathBuf>,
+    current: RwLock<Arc<Settings>>,
+}
+
+impl Reloadable {
+    /// マスタと設定を読み込む
+    /// 設定ファイルを省略した場合は既定値を用いる。
+    pub fn load<P: Into<PathBuf>>(master_path: P, config_path: Option<P>) -> Result<Self, Error> {
+        let master_path = master_path.into();
+        let config_path = config_path.map(Into::into);
+        let settings = Self::read(&master_path, config_path.as_ref())?;
+        Ok(Self {
+            master_path,
+            config_path,
+            current: RwLock::new(Arc::new(settings)),
+        })
+    }
+
+    fn read(master_path: &PathBuf, config_path: Option<&PathBuf>) -> Result<Settings, Error> {
+        let master = SimpleStress::load(master_path)?;
+        let config = match config_path {
+            Some(path) => Config::load(path)?,
+            None => Config::default(),
+        };
+        Settings::new(master, config)
+    }
+
+    /// 現在のマスタと設定
+    pub fn current(&self) -> Arc<Settings> {
+        match self.current.read() {
+            Ok(current) => Arc::clone(&current),
+            Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+        }
+    }
+
+    /// ファイルを読み直して差し替える
+    /// 読み込み・検証に失敗した場合は差し替えずにエラーを返す。
+    pub fn reload(&self) -> Result<Arc<Settings>, Error> {
+        let settings = Arc::new(Self::read(&self.master_path, self.config_path.as_ref())?);
+        let mut current = match self.current.write() {
+            Ok(current) => current,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        *current = Arc::clone(&settings);
+        Ok(settings)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::StressCriteria;
+
+    #[test]
+    fn test_reload() {
+        let dir = std::env::temp_dir().join(format!("stresscheck-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let master = dir.join("57.json");
+        let config = dir.join("config.toml");
+        std::fs::copy("resources/57.json", &master).unwrap();
+        std::fs::write(&config, "min_group_size = 10\n").unwrap();
+
+        let reloadable = Reloadable::load(&master, Some(&config)).unwrap();
+        let before = reloadable.current();
+        assert_eq!(before.config.min_group_size, 10);
+
+        std::fs::write(
+            &config,
+            "[criteria]\nmethod = \"conversion\"\nb = 12\nac = 26\nb_with_ac = 17\n",
+        )
+        .unwrap();
+        reloadable.reload().unwrap();
+        assert_eq!(
+            reloadable.current().config.criteria,
+            StressCriteria::CONVERSION
+        );
+        assert_eq!(before.config.min_group_size, 10);
+
+        // 設問番号が不正なマスタには差し替えない
+        let edited =
+            std::fs::read_to_string(&master)
+                .unwrap()
+                .replacen("\"id\": 1,", "\"id\": 100,", 1);
+        std::fs::write(&master, edited).unwrap();
+        assert!(matches!(reloadable.reload(), Err(Error::IllegalMaster(_))));
+        assert_eq!(
+            reloadable.current().config.criteria,
+            StressCriteria::CONVERSION
+        );
+
+        std::fs::copy("resources/57.json", &master).unwrap();
+        std::fs::write(&config, "min_group_size = 0\n").unwrap();
+        assert!(matches!(reloadable.reload(), Err(Error::IllegalConfig)));
+        assert_eq!(
+            reloadable.current().config.criteria,
+            StressCriteria::CONVERSION
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+use std::io::Write;
+
+use chrono::{Duration, NaiveDate};
+use serde::Serialize;
+
+use crate::export::{Sanitizer, SanitizingWriter};
+use crate::roster::{Roster, RosterEntry};
+use crate::Error;
+
+/// 標準の督促時期（締切の何日前か）
+pub const DEFAULT_STAGES: [u32; 2] = [7, 3];
+
+/// 督促の送信予定
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReminderSchedule {
+    /// 実施期間の締切日
+    pub deadline: NaiveDate,
+    /// 締切の何日前に督促するか
+    pub stages: Vec<u32>,
+}
+
+impl ReminderSchedule {
+    pub fn new(deadline: NaiveDate) -> Self {
+        Self {
+            deadline,
+            stages: DEFAULT_STAGES.to_vec(),
+        }
+    }
+
+    pub fn with_stages(mut self, stages: &[u32]) -> Self {
+        self.stages = stages.to_vec();
+        self
+    }
+
+    /// 未受検者の督促をまとめる
+    /// `today`より前の時期は作成しない。送信日の早い順に並べる。
+    pub fn batches(
+        &self,
+        roster: &Roster,
+        completed: &HashSet<String>,
+        today: NaiveDate,
+    ) -> Vec<ReminderBatch> {
+        let outstanding = roster
+            .outstanding(completed)
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut stages = self.stages.clone();
+        stages.sort_unstable_by(|a, b| b.cmp(a));
+        stages.dedup();
+        stages
+            .into_iter()
+            .map(|days_before| ReminderBatch {
+                send_on: self.deadline - Duration::days(days_before.into()),
+                days_before,
+                entries: outstanding.clone(),
+            })
+            .filter(|batch| batch.send_on >= today)
+            .collect()
+    }
+}
+
+/// 1回分の督促
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReminderBatch {
+    /// 送信日
+    pub send_on: NaiveDate,
+    /// 締切の何日前か
+    pub days_before: u32,
+    /// 督促の対象者
+    pub entries: Vec<RosterEntry>,
+}
+
+#[derive(Serialize)]
+struct ReminderRow<'a> {
+    send_on: NaiveDate,
+    days_before: u32,
+    token: &'a str,
+    department: Option<&'a str>,
+}
+
+/// 督促の一覧をCSVで書き出す
+pub fn write_reminders<W: Write>(
+    writer: W,
+    batches: &[ReminderBatch],
+    sanitizer: &Sanitizer,
+) -> Result<(), Error> {
+    let mut writer = SanitizingWriter::new(writer, sanitizer);
+    for batch in batches {
+        for entry in &batch.entries {
+            writer.serialize(ReminderRow {
+                send_on: batch.send_on,
+                days_before: batch.days_before,
+                token: &entry.token,
+                department: entry.department.as_deref(),
+            })?;
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_batches() {
+        let roster =
+            Roster::from_reader("token,department\na,営業部\nb,総務部\n".as_bytes()).unwrap();
+        let completed = HashSet::from(["a".to_string()]);
+        let deadline = NaiveDate::from_ymd_opt(2024, 7, 31).unwrap();
+        let schedule = ReminderSchedule::new(deadline);
+
+        let batches = schedule.batches(
+            &roster,
+            &completed,
+            NaiveDate::from_ymd_opt(2024, 7, 20).unwrap(),
+        );
+        assert_eq!(batches.len(), 2);
+        assert_eq!(
+            batches[0].send_on,
+            NaiveDate::from_ymd_opt(2024, 7, 24).unwrap()
+        );
+        assert_eq!(batches[1].days_before, 3);
+        assert_eq!(batches[1].entries[0].token, "b");
+
+        let batches = schedule.batches(
+            &roster,
+            &completed,
+            NaiveDate::from_ymd_opt(2024, 7, 26).unwrap(),
+        );
+        assert_eq!(batches.len(), 1);
+
+        let mut output = vec![];
+        write_reminders(&mut output, &batches, &Sanitizer::default()).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "send_on,days_before,token,department\n2024-07-28,3,b,総務部\n"
+        );
+    }
+}
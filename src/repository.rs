@@ -0,0 +1,294 @@
+//! 判定結果の保存先
+//!
+//! サーバ・各コマンドから保存先を差し替えられるよう、判定結果の保存・取得を
+//! `ResultRepository`にまとめる。メモリ上の実装と、`sqlite`フィーチャーでSQLiteの実装を提供する。
+//! PostgreSQLやオブジェクトストレージなどの実装は利用側で追加できる。
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+
+use crate::consent::ConsentScope;
+use crate::{CheckResult, Consent, Error};
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresRepository;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteRepository;
+
+/// 保存する判定結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredResult {
+    /// 受検者のトークン（ユーザ特定キー）。年度をまたいで同じ受検者には同じトークンを用いる
+    pub token: String,
+    /// 実施回（`"2024"`など）
+    #[serde(default)]
+    pub period: String,
+    /// 所属部署
+    pub department: Option<String>,
+    /// 回答を受け付けた日時
+    pub submitted_at: DateTime<FixedOffset>,
+    /// 事業者への結果提供に関する同意
+    pub consent: Option<Consent>,
+    pub result: CheckResult,
+    /// 回答（未回答は0）。保存しておくと判定基準の変更後に判定し直せる
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub answers: Option<Vec<u8>>,
+    /// 判定し直す前の結果（古い順）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous_results: Vec<PreviousResult>,
+}
+
+/// 判定し直す前の結果
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreviousResult {
+    pub result: CheckResult,
+    /// 判定し直した日時
+    pub replaced_at: DateTime<FixedOffset>,
+}
+
+impl StoredResult {
+    /// 結果の事業者への提供に同意しているか
+    pub fn is_shared_with_employer(&self) -> bool {
+        self.consent
+            .iter()
+            .any(|consent| consent.allows(ConsentScope::ShareWithEmployer))
+    }
+}
+
+/// 判定結果の保存先
+pub trait ResultRepository {
+    /// 判定結果を保存する
+    /// 同じトークン・実施回の結果が既にある場合は置き換える（再受検・回答の修正）。
+    fn save(&mut self, result: &StoredResult) -> Result<(), Error>;
+
+    /// トークンで最新の判定結果を取得する
+    fn fetch(&self, token: &str) -> Result<Option<StoredResult>, Error>;
+
+    /// トークンの直近`limit`回分の判定結果を古い順に列挙する
+    fn history(&self, token: &str, limit: usize) -> Result<Vec<StoredResult>, Error>;
+
+    /// 受付日時が`cutoff`より前の判定結果を削除し、削除した件数を返す
+    /// 保存期間（5年）を過ぎた記録の廃棄に用いる。
+    fn purge_before(&mut self, cutoff: DateTime<FixedOffset>) -> Result<usize, Error>;
+
+    /// 受付日時が`from`以上`to`未満の判定結果を受付順に列挙する
+    fn list(
+        &self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> Result<Vec<StoredResult>, Error>;
+
+    /// 期間内の高ストレス者のうち、事業者への結果提供に同意した者を列挙する
+    /// 面接指導の勧奨で最も頻繁に行う抽出のため、索引を使える実装では上書きする。
+    fn list_high_stress_shared(
+        &self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> Result<Vec<StoredResult>, Error> {
+        Ok(self
+            .list(from, to)?
+            .into_iter()
+            .filter(|result| result.result.has_stress && result.is_shared_with_employer())
+            .collect())
+    }
+}
+
+/// メモリ上の保存先
+/// 試験やコマンドでの一時的な集計に用いる。
+#[derive(Debug, Clone, Default)]
+pub struct MemoryRepository {
+    results: HashMap<(String, String), StoredResult>,
+}
+
+impl MemoryRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.results.is_empty()
+    }
+}
+
+impl ResultRepository for MemoryRepository {
+    fn save(&mut self, result: &StoredResult) -> Result<(), Error> {
+        self.results.insert(
+            (result.token.clone(), result.period.clone()),
+            result.clone(),
+        );
+        Ok(())
+    }
+
+    fn fetch(&self, token: &str) -> Result<Option<StoredResult>, Error> {
+        Ok(self.history(token, 1)?.pop())
+    }
+
+    fn history(&self, token: &str, limit: usize) -> Result<Vec<StoredResult>, Error> {
+        let mut results = self
+            .results
+            .values()
+            .filter(|result| result.token == token)
+            .cloned()
+            .collect::<Vec<_>>();
+        results.sort_by_key(|result| result.submitted_at);
+        let skip = results.len().saturating_sub(limit);
+        Ok(results.split_off(skip))
+    }
+
+    fn purge_before(&mut self, cutoff: DateTime<FixedOffset>) -> Result<usize, Error> {
+        let before = self.results.len();
+        self.results
+            .retain(|_, result| result.submitted_at >= cutoff);
+        Ok(before - self.results.len())
+    }
+
+    fn list(
+        &self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> Result<Vec<StoredResult>, Error> {
+        let mut results = self
+            .results
+            .values()
+            .filter(|result| from <= result.submitted_at && result.submitted_at < to)
+            .cloned()
+            .collect::<Vec<_>>();
+        results.sort_by(|a, b| {
+            a.submitted_at
+                .cmp(&b.submitted_at)
+                .then_with(|| a.token.cmp(&b.token))
+        });
+        Ok(results)
+    }
+}
+
+/// 保存先の実装に共通の動作を確認する
+#[cfg(test)]
+pub(crate) fn check_repository<R: ResultRepository>(repository: &mut R) {
+    use crate::{AnswerStore, StressCriteria};
+
+    let stored_in = |token: &str, period: &str, answer: u8, submitted_at: &str| {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(answer).unwrap();
+        }
+        let submitted_at = DateTime::parse_from_rfc3339(submitted_at).unwrap();
+        StoredResult {
+            token: token.to_string(),
+            period: period.to_string(),
+            department: Some("営業部".to_string()),
+            submitted_at,
+            consent: Some(Consent::granted(
+                submitted_at,
+                ConsentScope::ShareWithEmployer,
+            )),
+            result: CheckResult::new(&store, StressCriteria::SUMUP).unwrap(),
+            answers: Some(store.values().to_vec()),
+            previous_results: vec![],
+        }
+    };
+    let stored = |token: &str, answer: u8, submitted_at: &str| {
+        stored_in(token, "2024", answer, submitted_at)
+    };
+    let time = |value: &str| DateTime::parse_from_rfc3339(value).unwrap();
+
+    repository
+        .save(&stored("a", 1, "2024-06-03T09:00:00+09:00"))
+        .unwrap();
+    repository
+        .save(&stored("b", 4, "2024-06-02T23:30:00Z"))
+        .unwrap();
+    repository
+        .save(&stored("c", 2, "2024-07-01T00:00:00+09:00"))
+        .unwrap();
+    assert!(repository.fetch("z").unwrap().is_none());
+    assert_eq!(
+        repository.fetch("b").unwrap(),
+        Some(stored("b", 4, "2024-06-02T23:30:00Z"))
+    );
+
+    let june = repository
+        .list(
+            time("2024-06-01T00:00:00+09:00"),
+            time("2024-07-01T00:00:00+09:00"),
+        )
+        .unwrap();
+    assert_eq!(
+        june.iter()
+            .map(|result| result.token.as_str())
+            .collect::<Vec<_>>(),
+        ["b", "a"]
+    );
+    assert!(june[0].result.has_stress);
+    assert!(june[0].is_shared_with_employer());
+
+    let high_stress = repository
+        .list_high_stress_shared(
+            time("2024-06-01T00:00:00+09:00"),
+            time("2024-08-01T00:00:00+09:00"),
+        )
+        .unwrap();
+    assert_eq!(high_stress.len(), 1);
+    assert_eq!(high_stress[0].token, "b");
+
+    // 再受検は置き換える
+    repository
+        .save(&stored("a", 2, "2024-07-02T09:00:00+09:00"))
+        .unwrap();
+    let june = repository
+        .list(
+            time("2024-06-01T00:00:00+09:00"),
+            time("2024-07-01T00:00:00+09:00"),
+        )
+        .unwrap();
+    assert_eq!(june.len(), 1);
+
+    // 別の実施回は履歴として残す
+    repository
+        .save(&stored_in("a", "2022", 4, "2022-06-01T09:00:00+09:00"))
+        .unwrap();
+    repository
+        .save(&stored_in("a", "2023", 3, "2023-06-01T09:00:00+09:00"))
+        .unwrap();
+    let history = repository.history("a", 2).unwrap();
+    assert_eq!(
+        history
+            .iter()
+            .map(|result| result.period.as_str())
+            .collect::<Vec<_>>(),
+        ["2023", "2024"]
+    );
+    assert_eq!(repository.history("a", 5).unwrap().len(), 3);
+    assert_eq!(repository.fetch("a").unwrap().unwrap().period, "2024");
+    assert!(repository.history("z", 5).unwrap().is_empty());
+
+    assert_eq!(
+        repository
+            .purge_before(time("2023-01-01T00:00:00+09:00"))
+            .unwrap(),
+        1
+    );
+    assert_eq!(repository.history("a", 5).unwrap().len(), 2);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_memory_repository() {
+        let mut repository = MemoryRepository::new();
+        check_repository(&mut repository);
+        assert_eq!(repository.len(), 4);
+    }
+}
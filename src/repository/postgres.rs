@@ -0,0 +1,200 @@
+use chrono::{DateTime, FixedOffset, Utc};
+use serde_json::Value;
+use sqlx::postgres::PgPool;
+use sqlx::types::Json;
+use tokio::runtime::Runtime;
+
+use super::{ResultRepository, StoredResult};
+use crate::migrate::{from_versioned, to_versioned};
+use crate::Error;
+
+/// テーブル・索引の作成
+pub const MIGRATIONS: [&str; 2] = [
+    include_str!("../../migrations/postgres/0001_results.sql"),
+    include_str!("../../migrations/postgres/0002_results_by_period.sql"),
+];
+
+fn storage_error(e: sqlx::Error) -> Error {
+    Error::StorageError(e.to_string())
+}
+
+/// PostgreSQLの保存先
+/// 判定結果はJSONBで保存し、受付日時と「高ストレスかつ同意あり」の部分索引を張る。
+/// `ResultRepository`は同期的なため、内部のランタイムで問い合わせを実行する。非同期の
+/// 処理の中からは`tokio::task::spawn_blocking`などで呼び出すこと。
+pub struct PostgresRepository {
+    pool: PgPool,
+    runtime: Runtime,
+}
+
+impl PostgresRepository {
+    /// 接続してテーブル・索引を作成する
+    pub fn connect(url: &str) -> Result<Self, Error> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        let pool = runtime
+            .block_on(PgPool::connect(url))
+            .map_err(storage_error)?;
+        let repository = Self { pool, runtime };
+        repository.migrate()?;
+        Ok(repository)
+    }
+
+    /// マイグレーションを適用する（何度適用してもよい）
+    pub fn migrate(&self) -> Result<(), Error> {
+        self.runtime.block_on(async {
+            for migration in MIGRATIONS {
+                sqlx::raw_sql(migration)
+                    .execute(&self.pool)
+                    .await
+                    .map_err(storage_error)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn query_documents(
+        &self,
+        sql: &str,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> Result<Vec<StoredResult>, Error> {
+        let rows = self
+            .runtime
+            .block_on(
+                sqlx::query_scalar::<_, Json<Value>>(sql)
+                    .bind(from.with_timezone(&Utc))
+                    .bind(to.with_timezone(&Utc))
+                    .fetch_all(&self.pool),
+            )
+            .map_err(storage_error)?;
+        rows.into_iter()
+            .map(|Json(value)| from_versioned(value))
+            .collect()
+    }
+}
+
+impl ResultRepository for PostgresRepository {
+    fn save(&mut self, result: &StoredResult) -> Result<(), Error> {
+        self.runtime
+            .block_on(
+                sqlx::query(
+                    "INSERT INTO results \
+                     (token, period, department, submitted_at, has_stress, \
+                     shared_with_employer, document) \
+                     VALUES ($1, $2, $3, $4, $5, $6, $7) \
+                     ON CONFLICT (token, period) DO UPDATE SET \
+                     department = EXCLUDED.department, \
+                     submitted_at = EXCLUDED.submitted_at, \
+                     has_stress = EXCLUDED.has_stress, \
+                     shared_with_employer = EXCLUDED.shared_with_employer, \
+                     document = EXCLUDED.document",
+                )
+                .bind(&result.token)
+                .bind(&result.period)
+                .bind(&result.department)
+                .bind(result.submitted_at.with_timezone(&Utc))
+                .bind(result.result.has_stress)
+                .bind(result.is_shared_with_employer())
+                .bind(Json(to_versioned(result)?))
+                .execute(&self.pool),
+            )
+            .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn fetch(&self, token: &str) -> Result<Option<StoredResult>, Error> {
+        let row = self
+            .runtime
+            .block_on(
+                sqlx::query_scalar::<_, Json<Value>>(
+                    "SELECT document FROM results WHERE token = $1 \
+                     ORDER BY submitted_at DESC LIMIT 1",
+                )
+                .bind(token)
+                .fetch_optional(&self.pool),
+            )
+            .map_err(storage_error)?;
+        row.map(|Json(value)| from_versioned(value)).transpose()
+    }
+
+    fn history(&self, token: &str, limit: usize) -> Result<Vec<StoredResult>, Error> {
+        let rows = self
+            .runtime
+            .block_on(
+                sqlx::query_scalar::<_, Json<Value>>(
+                    "SELECT document FROM results WHERE token = $1 \
+                     ORDER BY submitted_at DESC LIMIT $2",
+                )
+                .bind(token)
+                .bind(limit as i64)
+                .fetch_all(&self.pool),
+            )
+            .map_err(storage_error)?;
+        rows.into_iter()
+            .rev()
+            .map(|Json(value)| from_versioned(value))
+            .collect()
+    }
+
+    fn purge_before(&mut self, cutoff: DateTime<FixedOffset>) -> Result<usize, Error> {
+        let done = self
+            .runtime
+            .block_on(
+                sqlx::query("DELETE FROM results WHERE submitted_at < $1")
+                    .bind(cutoff.with_timezone(&Utc))
+                    .execute(&self.pool),
+            )
+            .map_err(storage_error)?;
+        Ok(done.rows_affected() as usize)
+    }
+
+    fn list(
+        &self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> Result<Vec<StoredResult>, Error> {
+        self.query_documents(
+            "SELECT document FROM results WHERE submitted_at >= $1 AND submitted_at < $2 \
+             ORDER BY submitted_at, token",
+            from,
+            to,
+        )
+    }
+
+    fn list_high_stress_shared(
+        &self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> Result<Vec<StoredResult>, Error> {
+        self.query_documents(
+            "SELECT document FROM results \
+             WHERE has_stress AND shared_with_employer \
+             AND submitted_at >= $1 AND submitted_at < $2 \
+             ORDER BY submitted_at, token",
+            from,
+            to,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::repository::check_repository;
+
+    /// 試験用のデータベースを`STRESSCHECK_TEST_POSTGRES_URL`で指定した場合のみ実行する
+    #[test]
+    fn test_postgres_repository() {
+        let Ok(url) = std::env::var("STRESSCHECK_TEST_POSTGRES_URL") else {
+            return;
+        };
+        let mut repository = PostgresRepository::connect(&url).unwrap();
+        repository
+            .runtime
+            .block_on(sqlx::query("DELETE FROM results").execute(&repository.pool))
+            .unwrap();
+        check_repository(&mut repository);
+    }
+}
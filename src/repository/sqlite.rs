@@ -0,0 +1,192 @@
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::{ResultRepository, StoredResult};
+use crate::migrate::{from_versioned_str, to_versioned};
+use crate::Error;
+
+/// スキーマの変更。適用済みの数を`user_version`に記録する
+const MIGRATIONS: [&str; 2] = [
+    "
+CREATE TABLE IF NOT EXISTS results (
+    token TEXT PRIMARY KEY,
+    submitted_at INTEGER NOT NULL,
+    document TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS results_submitted_at ON results (submitted_at);
+",
+    "
+CREATE TABLE results_by_period (
+    token TEXT NOT NULL,
+    period TEXT NOT NULL,
+    submitted_at INTEGER NOT NULL,
+    document TEXT NOT NULL,
+    PRIMARY KEY (token, period)
+);
+INSERT INTO results_by_period SELECT token, '', submitted_at, document FROM results;
+DROP TABLE results;
+ALTER TABLE results_by_period RENAME TO results;
+CREATE INDEX results_submitted_at ON results (submitted_at);
+CREATE INDEX results_token_submitted_at ON results (token, submitted_at);
+",
+];
+
+fn storage_error(e: rusqlite::Error) -> Error {
+    Error::StorageError(e.to_string())
+}
+
+/// SQLiteの保存先
+/// 判定結果はJSONで保存し、受付日時（UTCのマイクロ秒）に索引を張る。
+pub struct SqliteRepository {
+    connection: Connection,
+}
+
+impl SqliteRepository {
+    /// データベースファイルを開く。テーブルがなければ作成する
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_connection(Connection::open(path).map_err(storage_error)?)
+    }
+
+    /// メモリ上のデータベースを開く
+    pub fn open_in_memory() -> Result<Self, Error> {
+        Self::from_connection(Connection::open_in_memory().map_err(storage_error)?)
+    }
+
+    fn from_connection(mut connection: Connection) -> Result<Self, Error> {
+        let version: usize = connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(storage_error)?;
+        for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let transaction = connection.transaction().map_err(storage_error)?;
+            transaction
+                .execute_batch(migration)
+                .map_err(storage_error)?;
+            transaction
+                .pragma_update(None, "user_version", index + 1)
+                .map_err(storage_error)?;
+            transaction.commit().map_err(storage_error)?;
+        }
+        Ok(Self { connection })
+    }
+
+    fn decode(document: String) -> Result<StoredResult, Error> {
+        from_versioned_str(&document)
+    }
+}
+
+impl ResultRepository for SqliteRepository {
+    fn save(&mut self, result: &StoredResult) -> Result<(), Error> {
+        let document = to_versioned(result)?.to_string();
+        self.connection
+            .execute(
+                "INSERT OR REPLACE INTO results (token, period, submitted_at, document) \
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    result.token,
+                    result.period,
+                    result.submitted_at.timestamp_micros(),
+                    document
+                ],
+            )
+            .map_err(storage_error)?;
+        Ok(())
+    }
+
+    fn fetch(&self, token: &str) -> Result<Option<StoredResult>, Error> {
+        self.connection
+            .query_row(
+                "SELECT document FROM results WHERE token = ?1 \
+                 ORDER BY submitted_at DESC LIMIT 1",
+                params![token],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(storage_error)?
+            .map(Self::decode)
+            .transpose()
+    }
+
+    fn history(&self, token: &str, limit: usize) -> Result<Vec<StoredResult>, Error> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT document FROM results WHERE token = ?1 \
+                 ORDER BY submitted_at DESC LIMIT ?2",
+            )
+            .map_err(storage_error)?;
+        let documents = statement
+            .query_map(params![token, limit as i64], |row| row.get::<_, String>(0))
+            .map_err(storage_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(storage_error)?;
+        documents.into_iter().rev().map(Self::decode).collect()
+    }
+
+    fn purge_before(&mut self, cutoff: DateTime<FixedOffset>) -> Result<usize, Error> {
+        self.connection
+            .execute(
+                "DELETE FROM results WHERE submitted_at < ?1",
+                params![cutoff.timestamp_micros()],
+            )
+            .map_err(storage_error)
+    }
+
+    fn list(
+        &self,
+        from: DateTime<FixedOffset>,
+        to: DateTime<FixedOffset>,
+    ) -> Result<Vec<StoredResult>, Error> {
+        let mut statement = self
+            .connection
+            .prepare(
+                "SELECT document FROM results WHERE submitted_at >= ?1 AND submitted_at < ?2 \
+                 ORDER BY submitted_at, token",
+            )
+            .map_err(storage_error)?;
+        let documents = statement
+            .query_map(
+                params![from.timestamp_micros(), to.timestamp_micros()],
+                |row| row.get::<_, String>(0),
+            )
+            .map_err(storage_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(storage_error)?;
+        documents.into_iter().map(Self::decode).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::repository::check_repository;
+
+    #[test]
+    fn test_sqlite_repository() {
+        let mut repository = SqliteRepository::open_in_memory().unwrap();
+        check_repository(&mut repository);
+    }
+
+    #[test]
+    fn test_migrate_from_token_key() {
+        let path =
+            std::env::temp_dir().join(format!("stresscheck-sqlite-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        {
+            let connection = Connection::open(&path).unwrap();
+            connection.execute_batch(MIGRATIONS[0]).unwrap();
+            connection
+                .execute(
+                    "INSERT INTO results VALUES ('a', 0, ?1)",
+                    params![r#"{"token":"a","department":null,"submitted_at":"1970-01-01T00:00:00Z","consent":null,"result":{"criteria":{"method":"sumup","b":77,"ac":76,"b_with_ac":63},"scores":[0,0,0],"evaluation_points":{},"has_stress":false}}"#],
+                )
+                .unwrap();
+        }
+        let repository = SqliteRepository::open(&path).unwrap();
+        let result = repository.fetch("a").unwrap().unwrap();
+        assert_eq!(result.period, "");
+        drop(repository);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,231 @@
+//! 判定基準の変更後の再判定
+//!
+//! 衛生委員会で判定基準を見直した場合などに、保存済みの回答を現在の実施設定で判定し直す。
+//! 元の結果は`previous_results`に残したまま新しい結果を保存し、高ストレス者の判定が
+//! 変わった受検者を差分として報告する。回答を保存していない結果は判定し直せないため数えるのみとする。
+
+use std::io::Write;
+
+use chrono::{DateTime, FixedOffset};
+use serde::Serialize;
+
+use crate::export::{Sanitizer, SanitizingWriter};
+use crate::repository::{PreviousResult, ResultRepository, StoredResult};
+use crate::{AnswerStore, CheckResult, Error, StressCriteria};
+
+/// 判定が変わった受検者
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct JudgementChange {
+    pub token: String,
+    pub period: String,
+    pub department: Option<String>,
+    pub previous_scores: (u8, u8, u8),
+    pub scores: (u8, u8, u8),
+    pub previous_has_stress: bool,
+    pub has_stress: bool,
+}
+
+/// 再判定の結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RescoreReport {
+    /// 判定し直した件数
+    pub rescored: usize,
+    /// 結果が変わり、新しい結果を保存した件数
+    pub updated: usize,
+    /// 回答を保存していないため判定し直せなかった件数
+    pub without_answers: usize,
+    /// 高ストレス者の判定が変わった受検者
+    pub changes: Vec<JudgementChange>,
+}
+
+#[derive(Serialize)]
+struct ChangeRow<'a> {
+    token: &'a str,
+    period: &'a str,
+    department: &'a str,
+    previous_scores: String,
+    scores: String,
+    previous_has_stress: bool,
+    has_stress: bool,
+}
+
+impl RescoreReport {
+    /// 新たに高ストレス者に該当した人数
+    pub fn newly_high_stress(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.has_stress)
+            .count()
+    }
+
+    /// 高ストレス者に該当しなくなった人数
+    pub fn no_longer_high_stress(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| !change.has_stress)
+            .count()
+    }
+
+    /// 判定が変わった受検者をCSVで書き出す
+    /// 実施設定の伏字・仮名にする列（`token`・`department`など）は`sanitizer`に従って置き換える。
+    pub fn write_csv<W: Write>(&self, writer: W, sanitizer: &Sanitizer) -> Result<(), Error> {
+        let mut writer = SanitizingWriter::new(writer, sanitizer);
+        let scores = |(a, b, c): (u8, u8, u8)| format!("{}/{}/{}", a, b, c);
+        for change in &self.changes {
+            writer.serialize(ChangeRow {
+                token: &change.token,
+                period: &change.period,
+                department: change.department.as_deref().unwrap_or_default(),
+                previous_scores: scores(change.previous_scores),
+                scores: scores(change.scores),
+                previous_has_stress: change.previous_has_stress,
+                has_stress: change.has_stress,
+            })?;
+        }
+        writer.flush()
+    }
+}
+
+/// 保存済みの結果を判定し直す
+/// 回答を保存していない場合は`None`。結果が変わった場合は元の結果を`previous_results`に加える。
+pub fn rescore(
+    stored: &StoredResult,
+    criteria: StressCriteria,
+    replaced_at: DateTime<FixedOffset>,
+) -> Result<Option<StoredResult>, Error> {
+    let Some(ref values) = stored.answers else {
+        return Ok(None);
+    };
+    if values.len() != 57 {
+        return Err(Error::IllegalQuestion);
+    }
+    let mut answers = AnswerStore::default();
+    for (no, &value) in (1..).zip(values) {
+        if value != 0 {
+            answers.insert(no, value)?;
+        }
+    }
+    let result = CheckResult::new(&answers, criteria)?;
+    let mut rescored = stored.clone();
+    if result != stored.result {
+        rescored.previous_results.push(PreviousResult {
+            result: std::mem::replace(&mut rescored.result, result),
+            replaced_at,
+        });
+    }
+    Ok(Some(rescored))
+}
+
+/// 受付日時が`from`以上`to`未満の結果を判定し直し、結果が変わったものを保存する
+/// `dry_run`の場合は保存せずに報告のみを作る。
+pub fn rescore_repository<R: ResultRepository + ?Sized>(
+    repository: &mut R,
+    from: DateTime<FixedOffset>,
+    to: DateTime<FixedOffset>,
+    criteria: StressCriteria,
+    replaced_at: DateTime<FixedOffset>,
+    dry_run: bool,
+) -> Result<RescoreReport, Error> {
+    let mut report = RescoreReport::default();
+    for stored in repository.list(from, to)? {
+        let Some(rescored) = rescore(&stored, criteria, replaced_at)? else {
+            report.without_answers += 1;
+            continue;
+        };
+        report.rescored += 1;
+        if rescored.result == stored.result {
+            continue;
+        }
+        if rescored.result.has_stress != stored.result.has_stress {
+            report.changes.push(JudgementChange {
+                token: stored.token.clone(),
+                period: stored.period.clone(),
+                department: stored.department.clone(),
+                previous_scores: stored.result.scores,
+                scores: rescored.result.scores,
+                previous_has_stress: stored.result.has_stress,
+                has_stress: rescored.result.has_stress,
+            });
+        }
+        if !dry_run {
+            repository.save(&rescored)?;
+        }
+        report.updated += 1;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::repository::MemoryRepository;
+    use crate::Thresholds;
+
+    fn stored(token: &str, answer: u8, with_answers: bool) -> StoredResult {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(answer).unwrap();
+        }
+        StoredResult {
+            token: token.to_string(),
+            period: "2024".to_string(),
+            department: None,
+            submitted_at: DateTime::parse_from_rfc3339("2024-06-01T09:00:00+09:00").unwrap(),
+            consent: None,
+            result: CheckResult::new(&store, StressCriteria::SUMUP).unwrap(),
+            answers: with_answers.then(|| store.values().to_vec()),
+            previous_results: vec![],
+        }
+    }
+
+    #[test]
+    fn test_rescore_repository() {
+        let mut repository = MemoryRepository::default();
+        // 回答2は合計点数方式の既定の基準では該当しない（領域Ｂ 61点）
+        repository.save(&stored("a", 2, true)).unwrap();
+        repository.save(&stored("b", 1, true)).unwrap();
+        repository.save(&stored("c", 2, false)).unwrap();
+        let stricter = StressCriteria::Sumup(Thresholds {
+            b: 60,
+            ..Thresholds::SUMUP
+        });
+        let from = DateTime::parse_from_rfc3339("2024-01-01T00:00:00+09:00").unwrap();
+        let to = DateTime::parse_from_rfc3339("2025-01-01T00:00:00+09:00").unwrap();
+        let at = DateTime::parse_from_rfc3339("2024-09-01T09:00:00+09:00").unwrap();
+
+        let report = rescore_repository(&mut repository, from, to, stricter, at, true).unwrap();
+        assert_eq!(report.rescored, 2);
+        assert_eq!(report.without_answers, 1);
+        assert_eq!(report.newly_high_stress(), 1);
+        assert_eq!(report.changes[0].token, "a");
+        assert!(repository
+            .fetch("a")
+            .unwrap()
+            .unwrap()
+            .previous_results
+            .is_empty());
+
+        rescore_repository(&mut repository, from, to, stricter, at, false).unwrap();
+        let a = repository.fetch("a").unwrap().unwrap();
+        assert!(a.result.has_stress);
+        assert_eq!(a.result.criteria, stricter);
+        assert_eq!(a.previous_results.len(), 1);
+        assert!(!a.previous_results[0].result.has_stress);
+        assert_eq!(a.previous_results[0].replaced_at, at);
+
+        let mut csv = vec![];
+        report.write_csv(&mut csv, &Sanitizer::default()).unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap().lines().nth(1),
+            Some("a,2024,,45/61/18,45/61/18,false,true")
+        );
+        let mut csv = vec![];
+        report
+            .write_csv(&mut csv, &Sanitizer::new(["token"]))
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap().lines().nth(1),
+            Some("***,2024,,45/61/18,45/61/18,false,true")
+        );
+    }
+}
@@ -0,0 +1,132 @@
+//! 設問マスタなどのリソースの配置場所
+//!
+//! 既定ではカレントディレクトリの`resources`を参照する。
+//! deb/rpmやコンテナで`/usr/share`などに配置する場合は、環境変数`STRESSCHECK_RESOURCE_DIR`か、
+//! 最初にリソースを参照する前の[`set_resource_dir`]で場所を指定する。
+//! 場所を指定せず既定の場所にもファイルがない場合は、バイナリに組み込んだマスタを用いるため、
+//! リソースを配置しないコンテナ（scratch）でも動作する。
+
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::OnceCell;
+
+use crate::variant::{self, TextVariant};
+use crate::{Error, SimpleStress};
+
+/// リソースの場所を指定する環境変数
+pub const RESOURCE_DIR_ENV: &str = "STRESSCHECK_RESOURCE_DIR";
+/// 既定のリソースの場所
+pub const DEFAULT_RESOURCE_DIR: &str = "resources";
+/// 設問マスタのファイル名
+pub const MASTER_FILE: &str = "57.json";
+
+/// バイナリに組み込んだ設問マスタ
+pub const EMBEDDED_MASTER: &str = include_str!("../resources/57.json");
+
+static RESOURCE_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// リソースの場所を指定する
+/// ディレクトリが存在しない場合は`ResourceNotFound`、
+/// 既に場所が確定している（リソースを参照した、または指定済み）場合は`ResourceDirLocked`。
+pub fn set_resource_dir<P: Into<PathBuf>>(path: P) -> Result<(), Error> {
+    let path = path.into();
+    if !path.is_dir() {
+        return Err(Error::ResourceNotFound(path));
+    }
+    if let Some(current) = RESOURCE_DIR.get() {
+        return Err(Error::ResourceDirLocked(current.clone()));
+    }
+    RESOURCE_DIR
+        .set(path)
+        .map_err(|_| Error::ResourceDirLocked(resource_dir().to_path_buf()))
+}
+
+/// リソースの場所
+/// 最初の呼び出しで確定し、以降は変更できない。
+pub fn resource_dir() -> &'static Path {
+    RESOURCE_DIR.get_or_init(|| resolve(std::env::var_os(RESOURCE_DIR_ENV)))
+}
+
+/// リソースの場所が既定のままか
+/// 既定の場所でファイルがない場合に限り、組み込みのリソースを用いる。
+pub fn is_default_resource_dir() -> bool {
+    resource_dir() == Path::new(DEFAULT_RESOURCE_DIR)
+}
+
+/// リソースのファイルの場所
+pub fn resource_path<P: AsRef<Path>>(name: P) -> PathBuf {
+    resource_dir().join(name)
+}
+
+/// リソースの場所から設問マスタを読み込む
+/// 既定の場所にファイルがない場合は組み込みのマスタを用いる。
+/// 場所を指定したにもかかわらずファイルがない場合は`ResourceNotFound`。
+/// 「やさしい日本語」の言い換え（[`variant`]）も加える。
+pub fn load_master() -> Result<SimpleStress, Error> {
+    let path = resource_path(MASTER_FILE);
+    let mut master = if path.is_file() {
+        SimpleStress::load(path)?
+    } else if is_default_resource_dir() {
+        embedded_master()?
+    } else {
+        return Err(Error::ResourceNotFound(path));
+    };
+    master.add_variant(TextVariant::Plain, &variant::load_plain_texts()?);
+    Ok(master)
+}
+
+/// 組み込みの設問マスタ
+/// ビルド時に生成した定数データから組み立てるため、JSONの解析は行わない。
+pub fn embedded_master() -> Result<SimpleStress, Error> {
+    Ok(crate::embedded::static_master())
+}
+
+fn resolve(env: Option<OsString>) -> PathBuf {
+    env.filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_RESOURCE_DIR))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve(None), PathBuf::from("resources"));
+        assert_eq!(resolve(Some(OsString::new())), PathBuf::from("resources"));
+        assert_eq!(
+            resolve(Some(OsString::from("/usr/share/stresscheck"))),
+            PathBuf::from("/usr/share/stresscheck")
+        );
+    }
+
+    #[test]
+    fn test_set_resource_dir() {
+        assert!(matches!(
+            set_resource_dir("does/not/exist"),
+            Err(Error::ResourceNotFound(_))
+        ));
+        assert_eq!(resource_dir(), Path::new("resources"));
+        assert!(matches!(
+            set_resource_dir("resources"),
+            Err(Error::ResourceDirLocked(_))
+        ));
+        assert_eq!(
+            resource_path(MASTER_FILE),
+            PathBuf::from("resources/57.json")
+        );
+        assert!(load_master().is_ok());
+    }
+
+    #[test]
+    fn test_embedded_master() {
+        let master = embedded_master().unwrap();
+        assert_eq!(master.questions().len(), 57);
+        assert_eq!(
+            EMBEDDED_MASTER,
+            std::fs::read_to_string("resources/57.json").unwrap()
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! 結果参照コード
+//!
+//! 18尺度の評価点（1〜5）と検査用のビットを、手で入力しやすい11文字（`XXXX-XXXX-XXX`）に符号化する。
+//! キオスク端末で受検した人が、後から結果解説のWebページでコードを入力して自分の結果を見られるようにする。
+//! コードは評価点だけから作るため、受検者とコードの対応はどこにも保存しない。
+//!
+//! 文字はCrockfordのBase32（`I`・`L`・`O`・`U`を含まない）とし、入力時の大文字・小文字、
+//! 区切りの`-`や空白、`I`・`L`と`1`、`O`と`0`の取り違えは許す。
+
+use std::collections::BTreeMap;
+
+use sha2::{Digest, Sha256};
+
+use crate::{CheckResult, Error, Scale};
+
+/// 符号化の版
+pub const VERSION: u8 = 1;
+
+/// 符号化に用いる文字
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// コードの文字数（区切りを除く）
+const CODE_LEN: usize = 11;
+
+/// 評価点を詰めた部分のビット数（5^18 < 2^42）
+const POINTS_BITS: u32 = 42;
+
+/// 検査用のビット数
+const CHECK_BITS: u32 = 10;
+
+fn check(value: u64) -> u64 {
+    let digest = Sha256::digest(value.to_be_bytes());
+    (u64::from(digest[0]) << 2 | u64::from(digest[1]) >> 6) & ((1 << CHECK_BITS) - 1)
+}
+
+/// 判定結果から結果参照コードを作る
+pub fn encode(result: &CheckResult) -> Result<String, Error> {
+    encode_points(&result.evaluation_points)
+}
+
+/// 評価点から結果参照コードを作る
+/// 18尺度の評価点が揃っていない場合や1〜5でない場合は`Error::IllegalScore`とする。
+pub fn encode_points(points: &BTreeMap<Scale, u8>) -> Result<String, Error> {
+    let mut packed = 0u64;
+    for scale in Scale::ALL {
+        match points.get(&scale) {
+            Some(&point @ 1..=5) => packed = packed * 5 + u64::from(point - 1),
+            _ => return Err(Error::IllegalScore),
+        }
+    }
+    let body = u64::from(VERSION) << POINTS_BITS | packed;
+    let value = body << CHECK_BITS | check(body);
+    let chars = (0..CODE_LEN)
+        .rev()
+        .map(|index| ALPHABET[(value >> (index * 5)) as usize & 0b11111] as char)
+        .collect::<String>();
+    Ok(format!("{}-{}-{}", &chars[..4], &chars[4..8], &chars[8..]))
+}
+
+/// 結果参照コードから評価点を復元する
+/// 形式・版・検査用ビットが一致しない場合は`Error::IllegalEncoding`とする。
+pub fn decode(code: &str) -> Result<BTreeMap<Scale, u8>, Error> {
+    let mut value = 0u64;
+    let mut len = 0;
+    for c in code.chars().filter(|c| !c.is_whitespace() && *c != '-') {
+        let c = match c.to_ascii_uppercase() {
+            'I' | 'L' => '1',
+            'O' => '0',
+            c => c,
+        };
+        let digit = ALPHABET
+            .iter()
+            .position(|&a| a as char == c)
+            .ok_or(Error::IllegalEncoding)?;
+        value = value << 5 | digit as u64;
+        len += 1;
+        if len > CODE_LEN {
+            return Err(Error::IllegalEncoding);
+        }
+    }
+    if len != CODE_LEN {
+        return Err(Error::IllegalEncoding);
+    }
+    let body = value >> CHECK_BITS;
+    if check(body) != value & ((1 << CHECK_BITS) - 1) || body >> POINTS_BITS != u64::from(VERSION) {
+        return Err(Error::IllegalEncoding);
+    }
+    let mut packed = body & ((1 << POINTS_BITS) - 1);
+    let mut points = BTreeMap::new();
+    for scale in Scale::ALL.iter().rev() {
+        points.insert(*scale, (packed % 5) as u8 + 1);
+        packed /= 5;
+    }
+    if packed != 0 {
+        return Err(Error::IllegalEncoding);
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, StressCriteria};
+
+    #[test]
+    fn test_round_trip() {
+        let mut store = AnswerStore::default();
+        for i in 0..57 {
+            store.push(i % 4 + 1).unwrap();
+        }
+        let result = CheckResult::new(&store, StressCriteria::default()).unwrap();
+        let code = encode(&result).unwrap();
+        assert_eq!(code.len(), CODE_LEN + 2);
+        assert_eq!(decode(&code).unwrap(), result.evaluation_points);
+
+        let typed = code.to_lowercase().replace('-', " ").replace('0', "o");
+        assert_eq!(decode(&typed).unwrap(), result.evaluation_points);
+
+        let points = Scale::ALL.iter().map(|&scale| (scale, 5)).collect();
+        let code = encode_points(&points).unwrap();
+        assert_eq!(decode(&code).unwrap(), points);
+
+        let mut tampered = code.into_bytes();
+        tampered[0] = if tampered[0] == b'2' { b'3' } else { b'2' };
+        assert!(matches!(
+            decode(&String::from_utf8(tampered).unwrap()),
+            Err(Error::IllegalEncoding)
+        ));
+        assert!(matches!(decode("ABCD-EFGH"), Err(Error::IllegalEncoding)));
+        assert!(matches!(
+            decode("ABCD-EFGH-JKU"),
+            Err(Error::IllegalEncoding)
+        ));
+        assert!(matches!(
+            encode_points(&BTreeMap::new()),
+            Err(Error::IllegalScore)
+        ));
+    }
+}
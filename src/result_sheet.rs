@@ -0,0 +1,214 @@
+//! 個人結果の印刷用シート
+//!
+//! 受検者に渡す結果（判定・領域ごとの点数・18尺度の評価点）を1枚のHTMLにする。
+//! 末尾には結果参照コード（[`result_code`]）を載せ、`qr` featureではこれをQRコードにして添える。
+//! QRコードを読み取れば、個人の健康管理アプリ等に評価点を取り込める。
+//! QRコードの画像はSVGのほか、単体で保存するためのPNGも生成できる。
+
+use crate::locale::Locale;
+use crate::web::escape_html;
+use crate::{result_code, CheckResult, Error, Scale};
+
+/// 結果のQRコードに埋め込む文字列の接頭辞
+pub const RESULT_QR_PREFIX: &str = "stresscheck:result:";
+
+/// 結果のQRコードに埋め込む文字列（`stresscheck:result:<結果参照コード>`）
+pub fn result_qr_payload(result: &CheckResult) -> Result<String, Error> {
+    Ok(format!(
+        "{}{}",
+        RESULT_QR_PREFIX,
+        result_code::encode(result)?
+    ))
+}
+
+/// QRコードの画像形式
+#[cfg(feature = "qr")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrImageFormat {
+    Svg,
+    Png,
+}
+
+#[cfg(feature = "qr")]
+fn result_qr(result: &CheckResult) -> Result<qrcode::QrCode, Error> {
+    qrcode::QrCode::new(result_qr_payload(result)?.as_bytes()).map_err(|_| Error::IllegalEncoding)
+}
+
+/// 結果のQRコードの画像
+/// PNGは1セルを`module_size`ピクセルとし、周囲に4セルの余白を付ける。
+#[cfg(feature = "qr")]
+pub fn result_qr_image(
+    result: &CheckResult,
+    format: QrImageFormat,
+    module_size: u32,
+) -> Result<Vec<u8>, Error> {
+    use qrcode::render::svg;
+
+    let code = result_qr(result)?;
+    match format {
+        QrImageFormat::Svg => Ok(code
+            .render::<svg::Color>()
+            .min_dimensions(120, 120)
+            .build()
+            .into_bytes()),
+        QrImageFormat::Png => encode_png(&code, module_size.max(1)),
+    }
+}
+
+/// 8ビットグレースケールのPNGにする
+#[cfg(feature = "qr")]
+fn encode_png(code: &qrcode::QrCode, module_size: u32) -> Result<Vec<u8>, Error> {
+    use std::io::Write;
+
+    use flate2::write::ZlibEncoder;
+    use flate2::{Compression, Crc};
+
+    const QUIET_ZONE: u32 = 4;
+
+    fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let mut crc = Crc::new();
+        crc.update(kind);
+        crc.update(data);
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        png.extend_from_slice(&crc.sum().to_be_bytes());
+    }
+
+    let width = code.width() as u32;
+    let colors = code.to_colors();
+    let size = (width + QUIET_ZONE * 2) * module_size;
+    let mut pixels = Vec::with_capacity(((size + 1) * size) as usize);
+    for y in 0..size {
+        // 行ごとのフィルタ（なし）
+        pixels.push(0);
+        let row = (y / module_size).checked_sub(QUIET_ZONE);
+        for x in 0..size {
+            let column = (x / module_size).checked_sub(QUIET_ZONE);
+            let dark = match (row, column) {
+                (Some(row), Some(column)) if row < width && column < width => {
+                    colors[(row * width + column) as usize] == qrcode::Color::Dark
+                }
+                _ => false,
+            };
+            pixels.push(if dark { 0 } else { 255 });
+        }
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(&pixels)?;
+    let data = encoder.finish()?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&size.to_be_bytes());
+    header.extend_from_slice(&size.to_be_bytes());
+    // ビット深度8、グレースケール、圧縮・フィルタ・インターレースは既定
+    header.extend_from_slice(&[8, 0, 0, 0, 0]);
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &data);
+    chunk(&mut png, b"IEND", &[]);
+    Ok(png)
+}
+
+/// 個人結果のシート（HTML）を生成する
+/// `id`が空の場合は受検番号を載せない。`qr` featureでは結果参照コードのQRコードを末尾に添える。
+pub fn render_result_sheet(
+    id: &str,
+    result: &CheckResult,
+    locale: &Locale,
+) -> Result<String, Error> {
+    let code = result_code::encode(result)?;
+    let (a, b, c) = result.scores;
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>ストレスチェック結果</title>\n");
+    html.push_str(
+        "<style>table{border-collapse:collapse}td,th{border:1px solid #000;padding:2px 6px}\
+         .point{text-align:center;width:3em}</style>\n",
+    );
+    html.push_str("</head>\n<body>\n<h1>ストレスチェック結果</h1>\n");
+    if !id.is_empty() {
+        html.push_str(&format!("<p>受検番号 {}</p>\n", escape_html(id)));
+    }
+    html.push_str(&format!(
+        "<p class=\"judgement\">{}</p>\n",
+        if result.has_stress {
+            "高ストレスに該当します。"
+        } else {
+            "高ストレスに該当しません。"
+        }
+    ));
+    html.push_str(&format!(
+        "<p>領域Ａ {} / 領域Ｂ {} / 領域Ｃ {}</p>\n",
+        locale.format_integer(a),
+        locale.format_integer(b),
+        locale.format_integer(c)
+    ));
+    html.push_str("<table>\n<tr><th>尺度</th><th>評価点</th></tr>\n");
+    for scale in Scale::ALL {
+        if let Some(point) = result.evaluation_points.get(&scale) {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"point\">{}</td></tr>\n",
+                scale.label(),
+                locale.format_integer(point)
+            ));
+        }
+    }
+    html.push_str("</table>\n");
+    html.push_str(&format!("<p>結果参照コード {}</p>\n", code));
+    #[cfg(feature = "qr")]
+    {
+        let image = result_qr_image(result, QrImageFormat::Svg, 1)?;
+        html.push_str(&format!(
+            "<div class=\"qr\">{}</div>\n",
+            String::from_utf8_lossy(&image)
+        ));
+    }
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, StressCriteria};
+
+    fn result() -> CheckResult {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(2).unwrap();
+        }
+        CheckResult::new(&store, StressCriteria::default()).unwrap()
+    }
+
+    #[test]
+    fn test_render_result_sheet() {
+        let result = result();
+        let payload = result_qr_payload(&result).unwrap();
+        let code = payload.strip_prefix(RESULT_QR_PREFIX).unwrap();
+        assert_eq!(result_code::decode(code).unwrap(), result.evaluation_points);
+        let html = render_result_sheet("<1>", &result, &Locale::JAPANESE).unwrap();
+        assert!(html.contains("受検番号 &lt;1&gt;"));
+        assert!(html.contains(&format!("結果参照コード {}", code)));
+        assert!(html.contains("領域Ａ ４５"));
+        assert_eq!(html.matches("class=\"point\"").count(), 18);
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_result_qr_image() {
+        let result = result();
+        let svg = result_qr_image(&result, QrImageFormat::Svg, 1).unwrap();
+        assert!(String::from_utf8(svg).unwrap().contains("<svg"));
+        let png = result_qr_image(&result, QrImageFormat::Png, 3).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(&png[12..16], b"IHDR");
+        let size = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        assert_eq!(size % 3, 0);
+        assert!(png.ends_with(&[0xae, 0x42, 0x60, 0x82]));
+        assert!(render_result_sheet("1", &result, &Locale::ISO)
+            .unwrap()
+            .contains("<svg"));
+    }
+}
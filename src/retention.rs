@@ -0,0 +1,178 @@
+//! 保存期間を過ぎた記録の廃棄
+//!
+//! 結果の記録は5年間保存することが望ましいとされており、その後の廃棄は実施者の責任で行う。
+//! 保存期間を過ぎた結果を列挙して確認（ドライラン）したうえで削除し、何をいつ廃棄したかを
+//! 廃棄記録として残す。
+
+use std::io::Write;
+
+use chrono::{DateTime, FixedOffset, Months};
+use serde::Serialize;
+
+use crate::export::{Sanitizer, SanitizingWriter};
+use crate::repository::ResultRepository;
+use crate::Error;
+
+/// 既定の保存期間（年）
+pub const RETENTION_YEARS: u32 = 5;
+
+/// 列挙の起点（これより前の受付日時はありえない）
+const EPOCH: &str = "1900-01-01T00:00:00Z";
+
+/// 廃棄した（ドライランでは廃棄する）結果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DestructionEntry {
+    pub token: String,
+    pub period: String,
+    pub submitted_at: DateTime<FixedOffset>,
+}
+
+/// 廃棄記録
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DestructionLog {
+    /// 実行日時
+    pub executed_at: DateTime<FixedOffset>,
+    /// この日時より前に受け付けた結果を廃棄の対象とした
+    pub cutoff: DateTime<FixedOffset>,
+    /// 削除せずに列挙のみを行ったか
+    pub dry_run: bool,
+    pub entries: Vec<DestructionEntry>,
+    /// 削除した件数（ドライランでは0）
+    pub purged: usize,
+}
+
+#[derive(Serialize)]
+struct DestructionRow<'a> {
+    token: &'a str,
+    period: &'a str,
+    submitted_at: String,
+    cutoff: String,
+    executed_at: String,
+    dry_run: bool,
+}
+
+impl DestructionLog {
+    /// 廃棄記録をCSVで書き出す
+    /// 実施設定の伏字・仮名にする列（`token`など）は`sanitizer`に従って置き換える。
+    pub fn write_csv<W: Write>(&self, writer: W, sanitizer: &Sanitizer) -> Result<(), Error> {
+        let mut writer = SanitizingWriter::new(writer, sanitizer);
+        for entry in &self.entries {
+            writer.serialize(DestructionRow {
+                token: &entry.token,
+                period: &entry.period,
+                submitted_at: entry.submitted_at.to_rfc3339(),
+                cutoff: self.cutoff.to_rfc3339(),
+                executed_at: self.executed_at.to_rfc3339(),
+                dry_run: self.dry_run,
+            })?;
+        }
+        writer.flush()
+    }
+}
+
+/// 保存期間の起点となる日時（`now`の`period`前）
+pub fn retention_cutoff(
+    now: DateTime<FixedOffset>,
+    period: Months,
+) -> Result<DateTime<FixedOffset>, Error> {
+    now.checked_sub_months(period).ok_or(Error::IllegalConfig)
+}
+
+/// 受付から`period`を過ぎた結果を廃棄する
+/// `dry_run`の場合は削除せずに対象を列挙する。
+pub fn purge_older_than<R: ResultRepository + ?Sized>(
+    repository: &mut R,
+    period: Months,
+    now: DateTime<FixedOffset>,
+    dry_run: bool,
+) -> Result<DestructionLog, Error> {
+    let cutoff = retention_cutoff(now, period)?;
+    let epoch = DateTime::parse_from_rfc3339(EPOCH).expect("valid epoch");
+    let entries = repository
+        .list(epoch, cutoff)?
+        .into_iter()
+        .map(|stored| DestructionEntry {
+            token: stored.token,
+            period: stored.period,
+            submitted_at: stored.submitted_at,
+        })
+        .collect();
+    let purged = if dry_run {
+        0
+    } else {
+        repository.purge_before(cutoff)?
+    };
+    Ok(DestructionLog {
+        executed_at: now,
+        cutoff,
+        dry_run,
+        entries,
+        purged,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::repository::{MemoryRepository, StoredResult};
+    use crate::{AnswerStore, CheckResult, StressCriteria};
+
+    fn stored(token: &str, submitted_at: &str) -> StoredResult {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(2).unwrap();
+        }
+        StoredResult {
+            token: token.to_string(),
+            period: submitted_at[..4].to_string(),
+            department: None,
+            submitted_at: DateTime::parse_from_rfc3339(submitted_at).unwrap(),
+            consent: None,
+            result: CheckResult::new(&store, StressCriteria::SUMUP).unwrap(),
+            answers: None,
+            previous_results: vec![],
+        }
+    }
+
+    #[test]
+    fn test_purge_older_than() {
+        let mut repository = MemoryRepository::default();
+        repository
+            .save(&stored("a", "2018-06-01T09:00:00+09:00"))
+            .unwrap();
+        repository
+            .save(&stored("b", "2019-06-30T09:00:00+09:00"))
+            .unwrap();
+        repository
+            .save(&stored("c", "2019-07-02T09:00:00+09:00"))
+            .unwrap();
+        let now = DateTime::parse_from_rfc3339("2024-07-01T09:00:00+09:00").unwrap();
+        let period = Months::new(12 * RETENTION_YEARS);
+
+        let log = purge_older_than(&mut repository, period, now, true).unwrap();
+        assert_eq!(
+            log.cutoff,
+            DateTime::parse_from_rfc3339("2019-07-01T09:00:00+09:00").unwrap()
+        );
+        assert_eq!(
+            log.entries
+                .iter()
+                .map(|entry| entry.token.as_str())
+                .collect::<Vec<_>>(),
+            vec!["a", "b"]
+        );
+        assert_eq!(log.purged, 0);
+        assert!(repository.fetch("a").unwrap().is_some());
+
+        let log = purge_older_than(&mut repository, period, now, false).unwrap();
+        assert_eq!(log.purged, 2);
+        assert!(repository.fetch("a").unwrap().is_none());
+        assert!(repository.fetch("c").unwrap().is_some());
+
+        let mut csv = vec![];
+        log.write_csv(&mut csv, &Sanitizer::default()).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.contains("a,2018,2018-06-01T09:00:00+09:00,2019-07-01T09:00:00+09:00"));
+    }
+}
@@ -0,0 +1,379 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::demographics::{AgeBand, EmploymentType};
+use crate::export::{Sanitizer, SanitizingWriter};
+use crate::group::UNKNOWN_GROUP;
+use crate::group_code::GroupCode;
+use crate::{compress, CheckRecord, Error};
+
+/// 対象者名簿の1行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RosterEntry {
+    /// 受検用トークン（ユーザ特定キー）。列名は`id`でもよい
+    #[serde(alias = "id")]
+    pub token: String,
+    /// 氏名
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 所属部署
+    #[serde(default, deserialize_with = "crate::group_code::deserialize_optional")]
+    pub department: Option<GroupCode>,
+    /// 結果通知の送付先
+    #[serde(default)]
+    pub email: Option<String>,
+    /// 年齢階層
+    #[serde(default)]
+    pub age_band: Option<AgeBand>,
+    /// 雇用形態
+    #[serde(default)]
+    pub employment_type: Option<EmploymentType>,
+    /// 受検の対象か（休職中などで対象外の場合は`false`）
+    #[serde(default = "default_expected", deserialize_with = "deserialize_flag")]
+    pub expected: bool,
+}
+
+fn default_expected() -> bool {
+    true
+}
+
+/// `true`/`false`のほか`1`/`0`・`yes`/`no`を受け付け、空欄は対象とみなす
+fn deserialize_flag<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" | "true" | "1" | "yes" => Ok(true),
+        "false" | "0" | "no" => Ok(false),
+        _ => Err(serde::de::Error::custom(format!(
+            "expected must be true or false: {}",
+            value
+        ))),
+    }
+}
+
+impl RosterEntry {
+    /// トークンだけの対象者
+    pub fn new(token: &str) -> Self {
+        Self {
+            token: token.to_string(),
+            name: None,
+            department: None,
+            email: None,
+            age_band: None,
+            employment_type: None,
+            expected: true,
+        }
+    }
+}
+
+/// ストレスチェックの対象者名簿
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Roster {
+    pub entries: Vec<RosterEntry>,
+}
+
+impl Roster {
+    /// CSVから名簿を読み込む
+    /// token（またはid）列は必須、name・department・email・age_band・employment_type・expected列は省略可能。
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self, Error> {
+        let entries = csv::Reader::from_reader(reader)
+            .deserialize()
+            .collect::<Result<Vec<RosterEntry>, _>>()
+            .map_err(Error::CSVReadError)?;
+        Ok(Self { entries })
+    }
+
+    /// 名簿ファイルを読み込む
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_reader(compress::open_input(path)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 受検の対象者
+    pub fn expected(&self) -> impl Iterator<Item = &RosterEntry> {
+        self.entries.iter().filter(|entry| entry.expected)
+    }
+
+    /// 未受検の対象者
+    /// `completed`は受検済みのトークン。対象外の者は含めない。
+    pub fn outstanding(&self, completed: &HashSet<String>) -> Vec<&RosterEntry> {
+        self.expected()
+            .filter(|entry| !completed.contains(&entry.token))
+            .collect()
+    }
+}
+
+/// 部署ごとの受検率
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResponseRate {
+    /// 部署名
+    pub department: String,
+    /// 対象者数
+    pub expected: usize,
+    /// 受検者数
+    pub completed: usize,
+    /// 受検率
+    pub completion_rate: Option<f64>,
+}
+
+impl ResponseRate {
+    fn new(department: String, expected: usize, completed: usize) -> Self {
+        Self {
+            department,
+            expected,
+            completed,
+            completion_rate: (expected > 0).then(|| completed as f64 / expected as f64),
+        }
+    }
+}
+
+impl Roster {
+    /// 名簿に対する部署ごとの受検率
+    /// 名簿にないトークンと対象外の者の受検は数えない。部署が未設定の対象者は`unknown`にまとめる。
+    pub fn response_rates(&self, completed: &HashSet<String>) -> Vec<ResponseRate> {
+        let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+        for entry in self.expected() {
+            let department = entry.department.as_deref().unwrap_or(UNKNOWN_GROUP);
+            let count = counts.entry(department).or_default();
+            count.0 += 1;
+            if completed.contains(&entry.token) {
+                count.1 += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .map(|(department, (expected, completed))| {
+                ResponseRate::new(department.to_string(), expected, completed)
+            })
+            .collect()
+    }
+
+    /// 名簿全体の受検率
+    pub fn total_response_rate(&self, completed: &HashSet<String>) -> ResponseRate {
+        let count = self
+            .expected()
+            .filter(|entry| completed.contains(&entry.token))
+            .count();
+        ResponseRate::new("total".to_string(), self.expected().count(), count)
+    }
+}
+
+/// 名簿と受検記録の突き合わせ
+#[derive(Debug, Clone, Default)]
+pub struct RosterJoin<'a> {
+    /// 名簿の対象者の受検記録
+    pub matched: Vec<(&'a RosterEntry, &'a CheckRecord)>,
+    /// 受検記録のない対象者
+    pub missing: Vec<&'a RosterEntry>,
+    /// 名簿にない受検記録
+    pub unknown: Vec<&'a CheckRecord>,
+    /// 対象外とした者の受検記録
+    pub not_expected: Vec<(&'a RosterEntry, &'a CheckRecord)>,
+}
+
+/// 突き合わせの件数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct JoinSummary {
+    pub matched: usize,
+    pub missing: usize,
+    pub unknown: usize,
+    pub not_expected: usize,
+}
+
+impl Roster {
+    /// 名簿と受検記録をトークン（ユーザ特定キー）で突き合わせる
+    /// 名簿の同じトークンが複数ある場合は先の行を用いる。
+    pub fn join<'a>(&'a self, records: &'a [CheckRecord]) -> RosterJoin<'a> {
+        let mut entries: HashMap<&str, &RosterEntry> = HashMap::new();
+        for entry in &self.entries {
+            entries.entry(entry.token.as_str()).or_insert(entry);
+        }
+        let mut join = RosterJoin::default();
+        let mut responded = HashSet::new();
+        for record in records {
+            match entries.get(record.id.as_str()) {
+                Some(entry) if entry.expected => join.matched.push((entry, record)),
+                Some(entry) => join.not_expected.push((entry, record)),
+                None => join.unknown.push(record),
+            }
+            responded.insert(record.id.as_str());
+        }
+        join.missing = self
+            .expected()
+            .filter(|entry| !responded.contains(entry.token.as_str()))
+            .collect();
+        join
+    }
+}
+
+impl RosterJoin<'_> {
+    pub fn summary(&self) -> JoinSummary {
+        JoinSummary {
+            matched: self.matched.len(),
+            missing: self.missing.len(),
+            unknown: self.unknown.len(),
+            not_expected: self.not_expected.len(),
+        }
+    }
+
+    /// 突き合わなかった行の一覧をCSVで書き出す
+    /// `status`は`missing`（未受検）・`unknown`（名簿にない）・`not_expected`（対象外）のいずれか。
+    pub fn write_unmatched_csv<W: Write>(
+        &self,
+        writer: W,
+        sanitizer: &Sanitizer,
+    ) -> Result<(), Error> {
+        let mut writer = SanitizingWriter::new(writer, sanitizer);
+        for entry in &self.missing {
+            writer.serialize(UnmatchedRow::from_entry("missing", entry))?;
+        }
+        for record in &self.unknown {
+            writer.serialize(UnmatchedRow {
+                status: "unknown",
+                id: &record.id,
+                name: None,
+                department: record.department.as_deref(),
+            })?;
+        }
+        for (entry, _) in &self.not_expected {
+            writer.serialize(UnmatchedRow::from_entry("not_expected", entry))?;
+        }
+        writer.flush()
+    }
+}
+
+#[derive(Serialize)]
+struct UnmatchedRow<'a> {
+    status: &'static str,
+    id: &'a str,
+    name: Option<&'a str>,
+    department: Option<&'a str>,
+}
+
+impl<'a> UnmatchedRow<'a> {
+    fn from_entry(status: &'static str, entry: &'a RosterEntry) -> Self {
+        Self {
+            status,
+            id: &entry.token,
+            name: entry.name.as_deref(),
+            department: entry.department.as_deref(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_outstanding() {
+        let roster =
+            Roster::from_reader("token,department\na,営業部\nb,\nc,総務部\n".as_bytes()).unwrap();
+        assert_eq!(roster.len(), 3);
+        assert_eq!(roster.entries[1].department, None);
+
+        let completed = HashSet::from(["a".to_string(), "x".to_string()]);
+        let outstanding = roster.outstanding(&completed);
+        assert_eq!(
+            outstanding
+                .iter()
+                .map(|entry| entry.token.as_str())
+                .collect::<Vec<_>>(),
+            vec!["b", "c"]
+        );
+    }
+
+    #[test]
+    fn test_response_rates() {
+        let roster =
+            Roster::from_reader("token,department\na,営業部\nb,営業部\nc,総務部\nd,\n".as_bytes())
+                .unwrap();
+        let completed = HashSet::from(["a".to_string(), "c".to_string(), "x".to_string()]);
+        let rates = roster.response_rates(&completed);
+        assert_eq!(rates.len(), 3);
+        assert_eq!(rates[0].department, UNKNOWN_GROUP);
+        assert_eq!(rates[0].completion_rate, Some(0.0));
+        assert_eq!(rates[1].department, "営業部");
+        assert_eq!((rates[1].expected, rates[1].completed), (2, 1));
+        assert_eq!(rates[2].completion_rate, Some(1.0));
+        assert_eq!(
+            roster.total_response_rate(&completed).completion_rate,
+            Some(0.5)
+        );
+    }
+
+    #[test]
+    fn test_join() {
+        let roster = Roster::from_reader(
+            "id,name,department,employment_type,expected\n\
+             a,山田,営業部,regular,\n\
+             b,佐藤,営業部 ,contract,true\n\
+             c,鈴木,総務部,,false\n\
+             d,田中,総務部,,0\n\
+             e,,=総務部,,yes\n"
+                .as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(roster.entries[0].name.as_deref(), Some("山田"));
+        assert_eq!(
+            roster.entries[1].employment_type,
+            Some(EmploymentType::Contract)
+        );
+        assert_eq!(roster.entries[0].department, roster.entries[1].department);
+        assert_eq!(roster.expected().count(), 3);
+
+        let records = ["a", "c", "x"]
+            .iter()
+            .map(|id| CheckRecord {
+                id: id.to_string(),
+                department: None,
+                demographics: None,
+                consent: None,
+                answers: Default::default(),
+                amendments: vec![],
+            })
+            .collect::<Vec<_>>();
+        let join = roster.join(&records);
+        assert_eq!(
+            join.summary(),
+            JoinSummary {
+                matched: 1,
+                missing: 2,
+                unknown: 1,
+                not_expected: 1
+            }
+        );
+        assert_eq!(join.matched[0].1.id, "a");
+
+        let mut csv = vec![];
+        join.write_unmatched_csv(&mut csv, &Sanitizer::default())
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "status,id,name,department\n\
+             missing,b,佐藤,営業部\n\
+             missing,e,,'=総務部\n\
+             unknown,x,,\n\
+             not_expected,c,鈴木,総務部\n"
+        );
+        assert!(Roster::from_reader("token,expected\na,maybe\n".as_bytes()).is_err());
+        assert_eq!(
+            roster
+                .total_response_rate(&HashSet::from(["a".to_string()]))
+                .expected,
+            3
+        );
+    }
+}
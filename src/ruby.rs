@@ -0,0 +1,153 @@
+//! ふりがな（ルビ）
+//!
+//! マスタでは設問文のふりがなを`{非常|ひじょう}に`のように`{親文字|ふりがな}`の記法で書く。
+//! 端末には括弧書き（`非常（ひじょう）に`）、HTMLには`<ruby>`要素として出力する。
+
+use crate::web::escape_html;
+use crate::Question;
+
+/// ふりがな付きの文の区切り
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Segment<'a> {
+    /// ふりがなのない部分
+    Text(&'a str),
+    /// ふりがなを付けた部分
+    Ruby { base: &'a str, reading: &'a str },
+}
+
+/// `{親文字|ふりがな}`の記法を区切る
+/// 記法として閉じていない`{`はそのまま文字として扱う。
+pub fn parse(value: &str) -> Vec<Segment<'_>> {
+    let mut segments = vec![];
+    let mut rest = value;
+    while let Some(open) = rest.find('{') {
+        let ruby = rest[open + 1..].split_once('}').and_then(|(inner, after)| {
+            let (base, reading) = inner.split_once('|')?;
+            (!base.is_empty() && !reading.is_empty() && !base.contains('{'))
+                .then_some((base, reading, after))
+        });
+        match ruby {
+            Some((base, reading, after)) => {
+                if open > 0 {
+                    segments.push(Segment::Text(&rest[..open]));
+                }
+                segments.push(Segment::Ruby { base, reading });
+                rest = after;
+            }
+            None => {
+                segments.push(Segment::Text(&rest[..open + 1]));
+                rest = &rest[open + 1..];
+            }
+        }
+    }
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// ふりがなを除いた文
+pub fn base_text(value: &str) -> String {
+    parse(value)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text,
+            Segment::Ruby { base, .. } => base,
+        })
+        .collect()
+}
+
+/// ふりがなを括弧書きにした文
+pub fn to_parenthesized(value: &str) -> String {
+    parse(value)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => text.to_string(),
+            Segment::Ruby { base, reading } => format!("{}（{}）", base, reading),
+        })
+        .collect()
+}
+
+/// ふりがなを`<ruby>`要素にしたHTML
+pub fn to_html(value: &str) -> String {
+    parse(value)
+        .into_iter()
+        .map(|segment| match segment {
+            Segment::Text(text) => escape_html(text),
+            Segment::Ruby { base, reading } => format!(
+                "<ruby>{}<rt>{}</rt></ruby>",
+                escape_html(base),
+                escape_html(reading)
+            ),
+        })
+        .collect()
+}
+
+impl Question {
+    /// ふりがなを括弧書きで添えた設問文（ふりがながなければ設問文）
+    pub fn text_with_furigana(&self) -> String {
+        match &self.furigana {
+            Some(furigana) => to_parenthesized(furigana),
+            None => self.text.clone(),
+        }
+    }
+
+    /// 設問文のHTML
+    /// `furigana`を指定した場合はふりがなを`<ruby>`要素で添える。
+    pub fn text_html(&self, furigana: bool) -> String {
+        match (&self.furigana, furigana) {
+            (Some(value), true) => to_html(value),
+            _ => escape_html(&self.text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    #[test]
+    fn test_parse() {
+        let value = "{非常|ひじょう}にたくさんの{仕事|しごと}";
+        assert_eq!(
+            parse(value),
+            vec![
+                Segment::Ruby {
+                    base: "非常",
+                    reading: "ひじょう"
+                },
+                Segment::Text("にたくさんの"),
+                Segment::Ruby {
+                    base: "仕事",
+                    reading: "しごと"
+                },
+            ]
+        );
+        assert_eq!(base_text(value), "非常にたくさんの仕事");
+        assert_eq!(
+            to_parenthesized(value),
+            "非常（ひじょう）にたくさんの仕事（しごと）"
+        );
+        assert_eq!(to_html("{<a>|b}&"), "<ruby>&lt;a&gt;<rt>b</rt></ruby>&amp;");
+        assert_eq!(base_text("{a} {b|}c{"), "{a} {b|}c{");
+    }
+
+    #[test]
+    fn test_master_furigana() {
+        for question in QUESTIONS.questions() {
+            if let Some(furigana) = &question.furigana {
+                assert_eq!(base_text(furigana), question.text, "{}", question.id);
+            }
+        }
+        let question = QUESTIONS.question(1).unwrap();
+        assert_eq!(
+            question.text_with_furigana(),
+            "非常（ひじょう）にたくさんの仕事（しごと）をしなければならない"
+        );
+        assert!(question
+            .text_html(true)
+            .starts_with("<ruby>非常<rt>ひじょう</rt></ruby>"));
+        assert_eq!(question.text_html(false), question.text);
+    }
+}
@@ -0,0 +1,288 @@
+//! 出力文書のJSON Schema
+//!
+//! 出力を取り込む外部システムがモデルを生成できるよう、結果・集計の文書型について
+//! JSON Schema（draft 2020-12）を提供する。
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use crate::Scale;
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// スキーマを提供する文書型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentType {
+    /// `CheckResult`
+    CheckResult,
+    /// `group::GroupSummary`
+    GroupSummary,
+    /// `Error`
+    Error,
+}
+
+impl DocumentType {
+    /// 全ての文書型
+    pub const ALL: [DocumentType; 3] = [
+        DocumentType::CheckResult,
+        DocumentType::GroupSummary,
+        DocumentType::Error,
+    ];
+
+    /// JSON Schemaを返す
+    pub fn json_schema(&self) -> Value {
+        let mut schema = match self {
+            DocumentType::CheckResult => check_result(),
+            DocumentType::GroupSummary => group_summary(),
+            DocumentType::Error => json!({
+                "type": "object",
+                "properties": {
+                    "code": {"type": "string", "pattern": "^E_[A-Z_]+$"},
+                    "message": {"type": "string"},
+                },
+                "required": ["code", "message"],
+                "additionalProperties": false,
+            }),
+        };
+        let object = schema.as_object_mut().unwrap();
+        object.insert("$schema".to_string(), json!(DRAFT));
+        object.insert(
+            "title".to_string(),
+            json!(serde_json::to_value(self).unwrap()),
+        );
+        schema
+    }
+}
+
+fn nullable(schema: Value) -> Value {
+    json!({"anyOf": [schema, {"type": "null"}]})
+}
+
+fn criteria() -> Value {
+    let thresholds = |method: &str| {
+        json!({
+            "type": "object",
+            "properties": {
+                "method": {"const": method},
+                "b": {"type": "integer", "minimum": 0, "maximum": 255},
+                "ac": {"type": "integer", "minimum": 0, "maximum": 255},
+                "b_with_ac": {"type": "integer", "minimum": 0, "maximum": 255},
+            },
+            "required": ["method", "b", "ac", "b_with_ac"],
+            "additionalProperties": false,
+        })
+    };
+    json!({"oneOf": [thresholds("sumup"), thresholds("conversion")]})
+}
+
+fn scale_map(value: Value) -> Value {
+    let properties = Scale::ALL
+        .iter()
+        .map(|scale| (scale.as_str().to_string(), value.clone()))
+        .collect::<Map<_, _>>();
+    json!({
+        "type": "object",
+        "properties": properties,
+        "additionalProperties": false,
+    })
+}
+
+fn check_result() -> Value {
+    let score = json!({"type": "integer", "minimum": 0, "maximum": 255});
+    let mut evaluation_points = scale_map(json!({"type": "integer", "minimum": 1, "maximum": 5}));
+    evaluation_points["required"] = json!(Scale::ALL
+        .iter()
+        .map(|scale| scale.as_str())
+        .collect::<Vec<_>>());
+    json!({
+        "type": "object",
+        "properties": {
+            "criteria": criteria(),
+            "scores": {
+                "type": "array",
+                "prefixItems": [score, score, score],
+                "minItems": 3,
+                "maxItems": 3,
+            },
+            "evaluation_points": evaluation_points,
+            "has_stress": {"type": "boolean"},
+        },
+        "required": ["criteria", "scores", "evaluation_points", "has_stress"],
+        "additionalProperties": false,
+    })
+}
+
+fn group_summary() -> Value {
+    let count = json!({"type": "integer", "minimum": 0});
+    let rate = json!({"type": "number", "minimum": 0, "maximum": 1});
+    let interval = json!({
+        "type": "object",
+        "properties": {
+            "lower": {"type": "number"},
+            "upper": {"type": "number"},
+        },
+        "required": ["lower", "upper"],
+        "additionalProperties": false,
+    });
+    json!({
+        "type": "object",
+        "properties": {
+            "key": {"type": "string"},
+            "respondents": count,
+            "high_stress": nullable(count.clone()),
+            "high_stress_rate": nullable(rate),
+            "scale_means": nullable(scale_map(json!({"type": "number"}))),
+            "health_risk": nullable(json!({
+                "type": "object",
+                "properties": {
+                    "volume_control": {"type": "number"},
+                    "support": {"type": "number"},
+                    "total": {"type": "number"},
+                },
+                "required": ["volume_control", "support", "total"],
+                "additionalProperties": false,
+            })),
+            "confidence_intervals": {
+                "type": "object",
+                "properties": {
+                    "method": {
+                        "type": "object",
+                        "properties": {
+                            "method": {"type": "string", "enum": ["normal", "bootstrap"]},
+                            "resamples": count,
+                            "seed": count,
+                        },
+                        "required": ["method"],
+                        "additionalProperties": false,
+                    },
+                    "high_stress_rate": interval,
+                    "scale_means": scale_map(interval.clone()),
+                },
+                "required": ["method", "high_stress_rate", "scale_means"],
+                "additionalProperties": false,
+            },
+        },
+        "required": [
+            "key", "respondents", "high_stress", "high_stress_rate", "scale_means", "health_risk"
+        ],
+        "additionalProperties": false,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::group::{Dimension, GroupAnalysis};
+    use crate::interval::IntervalMethod;
+    use crate::simulate::Population;
+    use crate::{CheckResult, Error, StressCriteria};
+
+    /// テストで用いる範囲のキーワードのみを扱う簡易な検証
+    fn validate(schema: &Value, value: &Value) -> Result<(), String> {
+        let fail = || Err(format!("{} does not match {}", value, schema));
+        if let Some(options) = schema.get("anyOf").or(schema.get("oneOf")) {
+            let matched = options
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|option| validate(option, value).is_ok())
+                .count();
+            let ok = if schema.get("oneOf").is_some() {
+                matched == 1
+            } else {
+                matched > 0
+            };
+            return if ok { Ok(()) } else { fail() };
+        }
+        if let Some(expected) = schema.get("const") {
+            if expected != value {
+                return fail();
+            }
+        }
+        let type_matches = match schema.get("type").and_then(Value::as_str) {
+            Some("object") => value.is_object(),
+            Some("array") => value.is_array(),
+            Some("string") => value.is_string(),
+            Some("integer") => value.is_u64() || value.is_i64(),
+            Some("number") => value.is_number(),
+            Some("boolean") => value.is_boolean(),
+            Some("null") => value.is_null(),
+            _ => true,
+        };
+        if !type_matches {
+            return fail();
+        }
+        if let Some(number) = value.as_f64() {
+            let minimum = schema.get("minimum").and_then(Value::as_f64);
+            let maximum = schema.get("maximum").and_then(Value::as_f64);
+            if minimum.is_some_and(|minimum| number < minimum)
+                || maximum.is_some_and(|maximum| number > maximum)
+            {
+                return fail();
+            }
+        }
+        if let Some(object) = value.as_object() {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for required in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if !object.contains_key(required.as_str().unwrap()) {
+                    return fail();
+                }
+            }
+            for (key, item) in object {
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(property) => validate(property, item)?,
+                    None if schema.get("additionalProperties") == Some(&json!(false)) => {
+                        return fail()
+                    }
+                    None => {}
+                }
+            }
+        }
+        if let (Some(items), Some(array)) = (
+            schema.get("prefixItems").and_then(Value::as_array),
+            value.as_array(),
+        ) {
+            if items.len() != array.len() {
+                return fail();
+            }
+            for (item, value) in items.iter().zip(array) {
+                validate(item, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_schema() {
+        let records = Population::new(30, 1).with_seed(3).generate();
+        for criteria in [StressCriteria::SUMUP, StressCriteria::CONVERSION] {
+            let result = CheckResult::new(&records[0].answers, criteria).unwrap();
+            let value = serde_json::to_value(&result).unwrap();
+            validate(&DocumentType::CheckResult.json_schema(), &value).unwrap();
+        }
+
+        let analysis = GroupAnalysis::new(&records, StressCriteria::SUMUP).with_min_group_size(40);
+        let schema = DocumentType::GroupSummary.json_schema();
+        for summary in analysis.summarize(Dimension::Department).into_iter().chain(
+            GroupAnalysis::new(&records, StressCriteria::SUMUP)
+                .with_confidence_intervals(IntervalMethod::Normal)
+                .summarize(Dimension::Department),
+        ) {
+            validate(&schema, &serde_json::to_value(&summary).unwrap()).unwrap();
+        }
+
+        let value = serde_json::to_value(Error::NotFullfilled).unwrap();
+        validate(&DocumentType::Error.json_schema(), &value).unwrap();
+        assert!(validate(&DocumentType::Error.json_schema(), &json!({"code": "E_IO"})).is_err());
+        assert_eq!(
+            DocumentType::CheckResult.json_schema()["title"],
+            "check_result"
+        );
+    }
+}
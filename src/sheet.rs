@@ -0,0 +1,168 @@
+//! 紙の回答用紙
+//!
+//! 受検用トークンと用紙の版を埋め込んだQRコード付きの回答用紙を生成し、
+//! OMR等で読み取ったCSVを受検記録に変換する。QRコードの生成は`qr` featureで有効になる。
+//!
+//! 読み取りCSVの列は`qr, q_1〜q_57`とする。`qr`はQRコードの読み取り結果、
+//! 各設問はマークした回答番号（未記入は空欄、複数マークは`|`区切り）。
+
+use std::io::BufRead;
+
+use crate::{AnswerStore, CheckRecord, Error};
+
+/// 回答用紙の版
+pub const SHEET_VERSION: &str = "57.1";
+
+/// QRコードに埋め込む文字列の接頭辞
+const PAYLOAD_PREFIX: &str = "stresscheck";
+
+/// QRコードに埋め込む文字列（`stresscheck:<版>:<トークン>`）
+pub fn qr_payload(token: &str) -> String {
+    format!("{}:{}:{}", PAYLOAD_PREFIX, SHEET_VERSION, token)
+}
+
+/// QRコードの読み取り結果からトークンを取り出す
+/// 版が異なる用紙は`Error::UnknownSheetVersion`とする。
+pub fn parse_qr_payload(value: &str) -> Result<String, Error> {
+    let mut parts = value.trim().splitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(PAYLOAD_PREFIX), Some(SHEET_VERSION), Some(token)) if !token.is_empty() => {
+            Ok(token.to_string())
+        }
+        _ => Err(Error::UnknownSheetVersion(value.to_string())),
+    }
+}
+
+/// 読み取りCSVを受検記録として読み込む
+/// 未記入の設問は未回答のまま残し、判定時に`Error::NotFullfilled`となる。
+pub fn read_scanned<R: BufRead>(reader: R) -> Vec<Result<CheckRecord, Error>> {
+    let mut reader = csv::Reader::from_reader(reader);
+    reader
+        .records()
+        .map(|record| {
+            let record = record.map_err(Error::CSVReadError)?;
+            let id = parse_qr_payload(record.get(0).unwrap_or_default())?;
+            let mut answers = AnswerStore::default();
+            for (index, mark) in record.iter().skip(1).enumerate() {
+                let mark = mark.trim();
+                if mark.is_empty() {
+                    continue;
+                }
+                let score = mark.parse::<u8>().map_err(|_| Error::IllegalAnswer)?;
+                let question_no = u8::try_from(index + 1).map_err(|_| Error::IllegalQuestion)?;
+                answers.insert(question_no, score)?;
+            }
+            Ok(CheckRecord {
+                id,
+                department: None,
+                demographics: None,
+                consent: None,
+                answers,
+                amendments: vec![],
+            })
+        })
+        .collect()
+}
+
+/// 印刷用の回答用紙（HTML）を生成する
+#[cfg(feature = "qr")]
+pub fn render_answer_sheet(master: &crate::SimpleStress, token: &str) -> Result<String, Error> {
+    use crate::web::escape_html;
+    use qrcode::render::svg;
+    use qrcode::QrCode;
+
+    let code = QrCode::new(qr_payload(token).as_bytes())
+        .map_err(|e| Error::UnknownSheetVersion(e.to_string()))?;
+    let image = code.render::<svg::Color>().min_dimensions(120, 120).build();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>ストレスチェック回答用紙</title>\n");
+    html.push_str(
+        "<style>table{border-collapse:collapse}td{border:1px solid #000;padding:2px 6px}\
+         .mark{text-align:center;width:2.5em}</style>\n",
+    );
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!(
+        "<div class=\"qr\">{}</div>\n<p>用紙 {} / 受検番号 {}</p>\n",
+        image,
+        SHEET_VERSION,
+        escape_html(token)
+    ));
+    for theme in &master.simple_stress {
+        html.push_str(&format!(
+            "<h2>{}</h2>\n<table>\n",
+            escape_html(&theme.theme)
+        ));
+        for outer_question in &theme.questions {
+            for question in &outer_question.questions {
+                html.push_str(&format!(
+                    "<tr><td>{}</td><td>{}</td>",
+                    question.id,
+                    escape_html(&question.text)
+                ));
+                for score in &question.scores {
+                    html.push_str(&format!("<td class=\"mark\">{}</td>", score.score));
+                }
+                html.push_str("</tr>\n");
+            }
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_qr_payload() {
+        assert_eq!(qr_payload("abc"), "stresscheck:57.1:abc");
+        assert_eq!(parse_qr_payload("stresscheck:57.1:abc").unwrap(), "abc");
+        assert!(matches!(
+            parse_qr_payload("stresscheck:80.1:abc"),
+            Err(Error::UnknownSheetVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_scanned() {
+        let header = (1..=57)
+            .map(|i| format!("q_{}", i))
+            .collect::<Vec<_>>()
+            .join(",");
+        let full = vec!["2"; 57].join(",");
+        let mut blank = vec!["2"; 57];
+        blank[10] = "";
+        let mut double = vec!["2"; 57];
+        double[3] = "1|3";
+        let input = format!(
+            "qr,{}\nstresscheck:57.1:a,{}\nstresscheck:57.1:b,{}\nstresscheck:57.1:c,{}\n",
+            header,
+            full,
+            blank.join(","),
+            double.join(",")
+        );
+        let records = read_scanned(input.as_bytes());
+        assert_eq!(records.len(), 3);
+        let record = records[0].as_ref().unwrap();
+        assert_eq!(record.id, "a");
+        assert!(record.answers.to_sumup_score().is_ok());
+        assert!(matches!(
+            records[1].as_ref().unwrap().answers.to_sumup_score(),
+            Err(Error::NotFullfilled)
+        ));
+        assert!(matches!(records[2], Err(Error::IllegalAnswer)));
+    }
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_render_answer_sheet() {
+        let html = render_answer_sheet(&crate::QUESTIONS, "abc").unwrap();
+        assert!(html.contains("<svg"));
+        assert!(html.contains("受検番号 abc"));
+        assert_eq!(html.matches("<tr>").count(), 57);
+    }
+}
@@ -0,0 +1,165 @@
+//! 模擬集団の生成
+//!
+//! 実データを使わずに取り込み処理の負荷試験や集団分析のデモを行えるよう、
+//! 部署構成と高ストレス者の割合を指定して一括読み込み形式の受検記録を生成する。
+//! 高ストレス者の割合の既定値は全国の実施状況（おおむね1割）に合わせている。
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::demographics::{AgeBand, EmploymentType, Tenure};
+use crate::group_code::GroupCode;
+use crate::{reverse_if, AnswerStore, CheckRecord, Demographics};
+
+/// 高ストレス者の割合の既定値
+pub const DEFAULT_HIGH_STRESS_RATE: f64 = 0.1;
+
+/// 模擬集団の設定
+#[derive(Debug, Clone, PartialEq)]
+pub struct Population {
+    /// 人数
+    pub size: usize,
+    /// 部署名と人数の比
+    pub departments: Vec<(String, f64)>,
+    /// 高ストレス状態にある人の割合
+    pub high_stress_rate: f64,
+    /// 乱数の種。同じ種からは同じ集団を生成する
+    pub seed: u64,
+}
+
+impl Population {
+    /// `departments`個の部署に均等に割り振る
+    pub fn new(size: usize, departments: usize) -> Self {
+        Self {
+            size,
+            departments: (1..=departments.max(1))
+                .map(|i| (format!("部署{}", i), 1.0))
+                .collect(),
+            high_stress_rate: DEFAULT_HIGH_STRESS_RATE,
+            seed: 0,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_high_stress_rate(mut self, rate: f64) -> Self {
+        self.high_stress_rate = rate;
+        self
+    }
+
+    /// 受検記録を生成する
+    pub fn generate(&self) -> Vec<CheckRecord> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let total_weight = self
+            .departments
+            .iter()
+            .map(|(_, weight)| weight)
+            .sum::<f64>();
+        (0..self.size)
+            .map(|index| {
+                let mut pick = rng.gen_range(0.0..total_weight.max(f64::MIN_POSITIVE));
+                let department = self
+                    .departments
+                    .iter()
+                    .find(|(_, weight)| {
+                        pick -= weight;
+                        pick < 0.0
+                    })
+                    .or(self.departments.last())
+                    .and_then(|(name, _)| GroupCode::new(name).ok());
+                let stressed = rng.gen_bool(self.high_stress_rate.clamp(0.0, 1.0));
+                CheckRecord {
+                    id: format!("sim-{:06}", index + 1),
+                    department,
+                    demographics: Some(Demographics {
+                        age_band: Some(AgeBand::ALL[rng.gen_range(0..AgeBand::ALL.len())]),
+                        employment_type: Some(
+                            EmploymentType::ALL[rng.gen_range(0..EmploymentType::ALL.len())],
+                        ),
+                        tenure: Some(Tenure::ALL[rng.gen_range(0..Tenure::ALL.len())]),
+                    }),
+                    consent: None,
+                    answers: answers(&mut rng, stressed),
+                    amendments: vec![],
+                }
+            })
+            .collect()
+    }
+}
+
+/// ストレスの高さ（合計点数方式の点数）の分布から回答を生成する
+fn answers(rng: &mut StdRng, stressed: bool) -> AnswerStore {
+    // 1〜4点の出やすさ
+    let weights: [u32; 4] = if stressed {
+        [5, 15, 40, 40]
+    } else {
+        [25, 45, 22, 8]
+    };
+    let total = weights.iter().sum::<u32>();
+    let mut store = AnswerStore::default();
+    for question in 1..=57 {
+        let mut pick = rng.gen_range(0..total);
+        let score = weights
+            .iter()
+            .position(|&weight| {
+                if pick < weight {
+                    true
+                } else {
+                    pick -= weight;
+                    false
+                }
+            })
+            .unwrap_or(3) as u8
+            + 1;
+        // 逆転項目は点数から回答番号に戻す
+        store.push(reverse_if((question, score))).unwrap();
+    }
+    store
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::StressCriteria;
+
+    #[test]
+    fn test_generate() {
+        let population = Population::new(1000, 4).with_seed(42);
+        let records = population.generate();
+        assert_eq!(records.len(), 1000);
+        assert_eq!(records[0].id, "sim-000001");
+        assert_eq!(
+            population.generate()[10].answers.values(),
+            records[10].answers.values()
+        );
+
+        let high_stress = records
+            .iter()
+            .filter(|record| StressCriteria::SUMUP.evaluate(&record.answers).unwrap())
+            .count();
+        assert!((50..=150).contains(&high_stress), "{}", high_stress);
+        assert!(records.iter().all(|record| record
+            .department
+            .as_deref()
+            .unwrap()
+            .starts_with("部署")));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let records = Population::new(20, 2).generate();
+        let mut output = vec![];
+        crate::write_bulk_records(&mut output, &records).unwrap();
+        let read = crate::read_bulk_records(output.as_slice());
+        assert_eq!(read.len(), 20);
+        assert!(read.iter().all(|record| record.is_ok()));
+        let record = read[3].as_ref().unwrap();
+        assert_eq!(record.id, records[3].id);
+        assert_eq!(record.department, records[3].department);
+        assert_eq!(record.demographics, records[3].demographics);
+        assert_eq!(record.answers.values(), records[3].answers.values());
+    }
+}
@@ -0,0 +1,234 @@
+//! 設問の出し分け
+//!
+//! 拡張した調査票では、前の設問の回答に応じて追加の設問を出すことがある。
+//! マスタの設問に`condition`（`SkipRule`）を指定し、回答済みの内容から
+//! 次に提示する設問を求める。57項目の調査票には条件付きの設問はない。
+//!
+//! 条件とする設問は出現順でその設問より前になければならない（条件は循環しない）。
+//! 条件とする設問自体が提示されない場合は、その設問も提示しない。条件が連なる場合も同様に辿る。
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::verify::Discrepancy;
+use crate::{Question, SimpleStress};
+
+/// 設問を提示する条件
+/// `question`の回答が`min`以上の場合に限り提示する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkipRule {
+    /// 条件とする設問番号
+    pub question: u32,
+    /// 提示する回答番号の下限
+    pub min: u8,
+}
+
+impl SkipRule {
+    /// 条件を満たすか
+    /// 条件とする設問が未回答の場合は`None`を返す。
+    pub fn is_satisfied(&self, answers: &BTreeMap<u32, u8>) -> Option<bool> {
+        answers
+            .get(&self.question)
+            .map(|&answer| answer >= self.min)
+    }
+}
+
+/// 設問の提示状況
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Visibility {
+    /// 提示する
+    Shown,
+    /// 条件を満たさないため提示しない
+    Skipped,
+    /// 条件とする設問が未回答のため決まらない
+    Pending,
+}
+
+/// 出現順で`index`番目の設問の提示状況を求める
+/// 条件とする設問が前にある場合に限り、その提示状況を辿る。
+fn resolve(questions: &[Arc<Question>], index: usize, answers: &BTreeMap<u32, u8>) -> Visibility {
+    let Some(rule) = questions[index].condition else {
+        return Visibility::Shown;
+    };
+    let parent = questions[..index]
+        .iter()
+        .position(|question| question.id == rule.question);
+    if let Some(parent) = parent {
+        if resolve(questions, parent, answers) == Visibility::Skipped {
+            return Visibility::Skipped;
+        }
+    }
+    match rule.is_satisfied(answers) {
+        Some(true) => Visibility::Shown,
+        Some(false) => Visibility::Skipped,
+        None => Visibility::Pending,
+    }
+}
+
+impl SimpleStress {
+    /// 設問の提示状況を求める
+    /// 条件とする設問が提示されない場合は、回答の有無にかかわらず`Skipped`とする。
+    pub fn visibility(&self, question: &Question, answers: &BTreeMap<u32, u8>) -> Visibility {
+        let questions = self.questions();
+        match questions.iter().position(|other| other.id == question.id) {
+            Some(index) => resolve(&questions, index, answers),
+            None => Visibility::Shown,
+        }
+    }
+
+    /// 次に提示する設問
+    /// 出現順に、未回答かつ提示する設問のうち最初のものを返す。回答が必要な設問が全て回答済みの場合は`None`。
+    pub fn next_question(&self, answers: &BTreeMap<u32, u8>) -> Option<Arc<Question>> {
+        let questions = self.questions();
+        (0..questions.len())
+            .find(|&index| {
+                !answers.contains_key(&questions[index].id)
+                    && resolve(&questions, index, answers) == Visibility::Shown
+            })
+            .map(|index| questions[index].clone())
+    }
+
+    /// 回答が必要な設問が全て回答済みか
+    pub fn is_complete(&self, answers: &BTreeMap<u32, u8>) -> bool {
+        let questions = self.questions();
+        (0..questions.len()).all(|index| {
+            answers.contains_key(&questions[index].id)
+                || resolve(&questions, index, answers) == Visibility::Skipped
+        })
+    }
+
+    /// 提示する条件の不整合
+    /// 条件とする設問が出現順でその設問より前にない（存在しない・自身・後にある）ものを返す。
+    pub fn verify_conditions(&self) -> Vec<Discrepancy> {
+        let questions = self.questions();
+        questions
+            .iter()
+            .enumerate()
+            .filter_map(|(index, question)| {
+                let rule = question.condition?;
+                let earlier = questions[..index]
+                    .iter()
+                    .any(|other| other.id == rule.question);
+                (!earlier).then_some(Discrepancy::Condition {
+                    id: question.id,
+                    question: rule.question,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    fn master() -> SimpleStress {
+        serde_json::from_value(serde_json::json!({
+            "simple_stress": [{
+                "theme": "拡張設問",
+                "questions": [{
+                    "questions": [
+                        {"id": 1, "text": "眠れない", "reverse": false, "scores": []},
+                        {"id": 2, "text": "何日続いていますか", "reverse": false, "scores": [],
+                         "condition": {"question": 1, "min": 3}},
+                        {"id": 3, "text": "食欲がない", "reverse": false, "scores": []}
+                    ]
+                }]
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_next_question() {
+        let master = master();
+        let mut answers = BTreeMap::new();
+        assert_eq!(master.next_question(&answers).unwrap().id, 1);
+
+        answers.insert(1, 4);
+        assert_eq!(master.next_question(&answers).unwrap().id, 2);
+
+        answers.insert(1, 1);
+        assert_eq!(master.next_question(&answers).unwrap().id, 3);
+        answers.insert(3, 2);
+        assert!(master.next_question(&answers).is_none());
+        assert!(master.is_complete(&answers));
+
+        assert_eq!(QUESTIONS.next_question(&BTreeMap::new()).unwrap().id, 1);
+        assert!(QUESTIONS.verify_conditions().is_empty());
+    }
+
+    fn chained(condition_of_first: Option<u32>) -> SimpleStress {
+        serde_json::from_value(serde_json::json!({
+            "simple_stress": [{
+                "theme": "拡張設問",
+                "questions": [{
+                    "questions": [
+                        {"id": 1, "text": "眠れない", "reverse": false, "scores": [],
+                         "condition": condition_of_first.map(|question| serde_json::json!({"question": question, "min": 1}))},
+                        {"id": 2, "text": "何日続いていますか", "reverse": false, "scores": [],
+                         "condition": {"question": 1, "min": 3}},
+                        {"id": 3, "text": "受診しましたか", "reverse": false, "scores": [],
+                         "condition": {"question": 2, "min": 2}},
+                        {"id": 4, "text": "食欲がない", "reverse": false, "scores": []}
+                    ]
+                }]
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_chained_conditions() {
+        let master = chained(None);
+        assert!(master.verify_conditions().is_empty());
+        let mut answers = BTreeMap::new();
+
+        // 設問2が提示されないため、設問2を条件とする設問3も提示しない
+        answers.insert(1, 1);
+        let third = master.question(3).unwrap();
+        assert_eq!(master.visibility(&third, &answers), Visibility::Skipped);
+        assert_eq!(master.next_question(&answers).unwrap().id, 4);
+        answers.insert(4, 2);
+        assert!(master.next_question(&answers).is_none());
+        assert!(master.is_complete(&answers));
+
+        // 設問2が提示される場合は、その回答で設問3の提示が決まる
+        answers.insert(1, 4);
+        assert_eq!(master.visibility(&third, &answers), Visibility::Pending);
+        assert_eq!(master.next_question(&answers).unwrap().id, 2);
+        assert!(!master.is_complete(&answers));
+        answers.insert(2, 2);
+        assert_eq!(master.next_question(&answers).unwrap().id, 3);
+
+        // 後の設問を条件とする（循環する）条件は不整合とする
+        let master = chained(Some(3));
+        assert_eq!(
+            master.verify_conditions(),
+            vec![Discrepancy::Condition { id: 1, question: 3 }]
+        );
+        let path = std::env::temp_dir().join("stresscheck_test_skip_cycle.json");
+        let value = serde_json::json!({
+            "simple_stress": [{"theme": "拡張設問", "questions": [{"questions": [
+                {"id": 1, "text": "a", "reverse": false, "scores": [],
+                 "condition": {"question": 2, "min": 1}},
+                {"id": 2, "text": "b", "reverse": false, "scores": [],
+                 "condition": {"question": 1, "min": 1}}
+            ]}]}]
+        });
+        std::fs::write(&path, value.to_string()).unwrap();
+        assert!(matches!(
+            SimpleStress::load(&path),
+            Err(crate::Error::IllegalMaster(_))
+        ));
+        std::fs::remove_file(&path).unwrap();
+        let first = master.question(1).unwrap();
+        assert_eq!(
+            master.visibility(&first, &BTreeMap::new()),
+            Visibility::Pending
+        );
+    }
+}
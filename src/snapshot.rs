@@ -0,0 +1,332 @@
+//! 一括判定の実行結果のスナップショットと差分
+//!
+//! 一括判定の入力のハッシュと全受検者の判定結果を、来歴付きの文書として保存する。
+//! 実施の途中でこのプログラムの版を上げる場合に、同じ入力を新旧の版で判定した
+//! スナップショットを突き合わせ、判定が変わった受検者を洗い出す。
+
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::export::{Sanitizer, SanitizingWriter};
+use crate::stamp::Stamped;
+use crate::verify::sha256_hex;
+use crate::{AnswerStore, CheckResult, Error, SimpleStress};
+
+/// 受検者一人分の記録
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotEntry {
+    /// ユーザ特定キー。読み込めなかった行は`入力ファイル:行番号`とする
+    pub id: String,
+    /// 回答のハッシュ。読み込めなかった行は空
+    pub input_hash: String,
+    /// 判定結果。判定できなかった場合は`None`
+    pub result: Option<CheckResult>,
+    /// 判定できなかった場合のエラーコード
+    pub error: Option<String>,
+}
+
+/// 一括判定の実行結果
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RunSnapshot {
+    /// 全受検者の回答のハッシュ（来歴を刻印する際に算出する）
+    pub inputs_hash: String,
+    pub entries: Vec<SnapshotEntry>,
+}
+
+/// 来歴を刻印したスナップショット
+pub type Snapshot = Stamped<RunSnapshot>;
+
+/// 回答のハッシュ
+fn answers_hash(store: &AnswerStore) -> String {
+    let text = store
+        .values()
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    sha256_hex(text.as_bytes())
+}
+
+impl RunSnapshot {
+    /// 受検者一人分の判定結果を加える
+    pub fn push(&mut self, id: &str, answers: &AnswerStore, result: Result<CheckResult, Error>) {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(e) => (None, Some(e.code().to_string())),
+        };
+        self.entries.push(SnapshotEntry {
+            id: id.to_string(),
+            input_hash: answers_hash(answers),
+            result,
+            error,
+        });
+    }
+
+    /// 読み込めなかった行を加える
+    /// `key`はユーザ特定キーの代わりに用いる`入力ファイル:行番号`。
+    pub fn push_unreadable(&mut self, key: &str, error: &Error) {
+        self.entries.push(SnapshotEntry {
+            id: key.to_string(),
+            input_hash: String::new(),
+            result: None,
+            error: Some(error.code().to_string()),
+        });
+    }
+
+    /// 全受検者の回答のハッシュを算出して来歴を刻印する
+    pub fn stamp(mut self, config: &Config, master: &SimpleStress) -> Result<Snapshot, Error> {
+        let hashes = self
+            .entries
+            .iter()
+            .map(|entry| format!("{}\t{}", entry.id, entry.input_hash))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.inputs_hash = sha256_hex(hashes.as_bytes());
+        Stamped::new(self, config, master)
+    }
+}
+
+/// スナップショットを読み込む
+pub fn read_snapshot<R: BufRead>(reader: R) -> Result<Snapshot, Error> {
+    serde_json::from_reader(reader).map_err(Error::JSONReadError)
+}
+
+/// 判定結果が変わった受検者
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotChange {
+    pub id: String,
+    /// 回答も変わっているか
+    pub input_changed: bool,
+    pub previous_scores: Option<(u8, u8, u8)>,
+    pub scores: Option<(u8, u8, u8)>,
+    pub previous_has_stress: Option<bool>,
+    pub has_stress: Option<bool>,
+    pub previous_error: Option<String>,
+    pub error: Option<String>,
+}
+
+impl SnapshotChange {
+    /// 高ストレス者の判定（判定できたか否かを含む）が変わったか
+    pub fn judgement_changed(&self) -> bool {
+        self.previous_has_stress != self.has_stress
+    }
+}
+
+/// スナップショットの差分
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SnapshotDiff {
+    /// 前回のプログラムの版
+    pub previous_version: String,
+    /// 今回のプログラムの版
+    pub current_version: String,
+    /// 入力全体が異なるか
+    pub inputs_changed: bool,
+    /// 判定結果が変わった受検者
+    pub changes: Vec<SnapshotChange>,
+    /// 今回のみにある受検者
+    pub added: Vec<String>,
+    /// 前回のみにある受検者
+    pub removed: Vec<String>,
+}
+
+/// 差分の集計
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SnapshotDiffSummary {
+    pub changed: usize,
+    /// 高ストレス者の判定が変わった人数
+    pub judgement_changed: usize,
+    /// 回答が同じまま判定結果が変わった人数
+    pub changed_with_same_input: usize,
+    pub added: usize,
+    pub removed: usize,
+}
+
+#[derive(Serialize)]
+struct ChangeRow<'a> {
+    id: &'a str,
+    input_changed: bool,
+    judgement_changed: bool,
+    previous_scores: String,
+    scores: String,
+    previous_has_stress: String,
+    has_stress: String,
+    previous_error: &'a str,
+    error: &'a str,
+}
+
+fn format_option<T: std::fmt::Debug>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map(|value| format!("{:?}", value))
+        .unwrap_or_default()
+}
+
+impl SnapshotDiff {
+    pub fn summary(&self) -> SnapshotDiffSummary {
+        SnapshotDiffSummary {
+            changed: self.changes.len(),
+            judgement_changed: self
+                .changes
+                .iter()
+                .filter(|change| change.judgement_changed())
+                .count(),
+            changed_with_same_input: self
+                .changes
+                .iter()
+                .filter(|change| !change.input_changed)
+                .count(),
+            added: self.added.len(),
+            removed: self.removed.len(),
+        }
+    }
+
+    /// 判定結果が変わった受検者をCSVで書き出す
+    /// 高ストレス者の判定が変わった受検者を先に並べる。
+    pub fn write_csv<W: Write>(&self, writer: W, sanitizer: &Sanitizer) -> Result<(), Error> {
+        let mut writer = SanitizingWriter::new(writer, sanitizer);
+        let mut changes = self.changes.iter().collect::<Vec<_>>();
+        changes.sort_by_key(|change| !change.judgement_changed());
+        for change in changes {
+            writer.serialize(ChangeRow {
+                id: &change.id,
+                input_changed: change.input_changed,
+                judgement_changed: change.judgement_changed(),
+                previous_scores: format_option(&change.previous_scores),
+                scores: format_option(&change.scores),
+                previous_has_stress: format_option(&change.previous_has_stress),
+                has_stress: format_option(&change.has_stress),
+                previous_error: change.previous_error.as_deref().unwrap_or_default(),
+                error: change.error.as_deref().unwrap_or_default(),
+            })?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// 二つのスナップショットを受検者ごとに突き合わせる
+/// 同じユーザ特定キーが複数ある場合は後のものを用いる。
+pub fn diff_snapshots(previous: &Snapshot, current: &Snapshot) -> SnapshotDiff {
+    let index = |snapshot: &Snapshot| {
+        snapshot
+            .document
+            .entries
+            .iter()
+            .map(|entry| (entry.id.clone(), entry.clone()))
+            .collect::<BTreeMap<String, SnapshotEntry>>()
+    };
+    let previous_entries = index(previous);
+    let current_entries = index(current);
+    let mut changes = vec![];
+    let mut added = vec![];
+    for (id, entry) in &current_entries {
+        let Some(before) = previous_entries.get(id) else {
+            added.push(id.clone());
+            continue;
+        };
+        if before.result == entry.result && before.error == entry.error {
+            continue;
+        }
+        changes.push(SnapshotChange {
+            id: id.clone(),
+            input_changed: before.input_hash != entry.input_hash,
+            previous_scores: before.result.as_ref().map(|result| result.scores),
+            scores: entry.result.as_ref().map(|result| result.scores),
+            previous_has_stress: before.result.as_ref().map(|result| result.has_stress),
+            has_stress: entry.result.as_ref().map(|result| result.has_stress),
+            previous_error: before.error.clone(),
+            error: entry.error.clone(),
+        });
+    }
+    let removed = previous_entries
+        .keys()
+        .filter(|id| !current_entries.contains_key(*id))
+        .cloned()
+        .collect();
+    SnapshotDiff {
+        previous_version: previous.provenance.crate_version.clone(),
+        current_version: current.provenance.crate_version.clone(),
+        inputs_changed: previous.document.inputs_hash != current.document.inputs_hash,
+        changes,
+        added,
+        removed,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{StressCriteria, QUESTIONS};
+    use std::io::Cursor;
+
+    fn store(answer: u8) -> AnswerStore {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(answer).unwrap();
+        }
+        store
+    }
+
+    fn snapshot(rows: &[(&str, u8)], criteria: StressCriteria) -> Snapshot {
+        let mut run = RunSnapshot::default();
+        for &(id, answer) in rows {
+            let answers = store(answer);
+            run.push(id, &answers, CheckResult::new(&answers, criteria));
+        }
+        run.stamp(&Config::default(), &QUESTIONS).unwrap()
+    }
+
+    #[test]
+    fn test_push_unreadable() {
+        let mut run = RunSnapshot::default();
+        run.push(
+            "a",
+            &store(1),
+            CheckResult::new(&store(1), StressCriteria::SUMUP),
+        );
+        run.push_unreadable("a.csv:1", &Error::NotFullfilled);
+        let stamped = run.stamp(&Config::default(), &QUESTIONS).unwrap();
+        assert_eq!(stamped.document.inputs_hash.len(), 64);
+        let entry = &stamped.document.entries[1];
+        assert_eq!(entry.id, "a.csv:1");
+        assert_eq!(entry.error.as_deref(), Some("E_INCOMPLETE"));
+        assert!(entry.result.is_none());
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let previous = snapshot(&[("a", 1), ("b", 3), ("c", 2)], StressCriteria::SUMUP);
+        let text = serde_json::to_string(&previous).unwrap();
+        let read = read_snapshot(Cursor::new(text)).unwrap();
+        assert_eq!(read, previous);
+        assert!(diff_snapshots(&previous, &read).changes.is_empty());
+
+        // 同じ回答を別の選定方法で判定すると、判定は変わらずに点数のみが変わる
+        let current = snapshot(&[("a", 1), ("b", 3), ("d", 2)], StressCriteria::CONVERSION);
+        let diff = diff_snapshots(&previous, &current);
+        assert!(diff.inputs_changed);
+        assert_eq!(diff.added, vec!["d"]);
+        assert_eq!(diff.removed, vec!["c"]);
+        assert_eq!(diff.changes.len(), 2);
+        assert!(diff.changes.iter().all(|change| !change.input_changed));
+        let summary = diff.summary();
+        assert_eq!(summary.changed_with_same_input, 2);
+        assert_eq!(summary.judgement_changed, 0);
+
+        let current = snapshot(&[("a", 4), ("b", 3), ("c", 2)], StressCriteria::SUMUP);
+        let diff = diff_snapshots(&previous, &current);
+        assert!(diff.inputs_changed);
+        assert_eq!(diff.changes.len(), 1);
+        assert!(diff.changes[0].input_changed);
+        assert!(diff.changes[0].judgement_changed());
+
+        let mut buffer = vec![];
+        diff.write_csv(&mut buffer, &Sanitizer::default()).unwrap();
+        let text = String::from_utf8(buffer).unwrap();
+        assert!(text.starts_with("id,input_changed,judgement_changed,"));
+        assert!(text.contains("a,true,true,\"(50, 38, 9)\",\"(35, 107, 36)\",false,true,,"));
+    }
+}
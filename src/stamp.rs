@@ -0,0 +1,180 @@
+//! 出力文書の来歴の刻印
+//!
+//! 出力した結果文書に、作成したプログラムの版・マスタ・実施設定と本文のチェックサムを
+//! 埋め込み、後から改変されていないこと・既知の設定で作成されたことを確認できるようにする。
+//! チェックサムはキーを整列したJSONのSHA-256とする。
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::Config;
+use crate::verify::sha256_hex;
+use crate::{CheckResult, Error, SimpleStress};
+
+/// このプログラムの版
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 文書の来歴
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// 作成したプログラムの版
+    pub crate_version: String,
+    /// 調査票の版
+    pub questionnaire_version: String,
+    /// マスタのチェックサム
+    pub master_checksum: String,
+    /// 実施設定のハッシュ
+    pub config_hash: String,
+    /// 本文のチェックサム
+    pub content_checksum: String,
+}
+
+/// 来歴を刻印した文書
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stamped<T> {
+    pub provenance: Provenance,
+    pub document: T,
+}
+
+/// 受検者一人分の結果文書
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResultDocument {
+    /// ユーザ特定キー
+    pub id: String,
+    pub result: CheckResult,
+}
+
+/// 来歴の不一致
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum StampDiscrepancy {
+    /// 本文が改変されている
+    ContentChecksum { expected: String, actual: String },
+    /// 調査票の版が異なる
+    QuestionnaireVersion { expected: String, actual: String },
+    /// 別のマスタで作成されている
+    MasterChecksum { expected: String, actual: String },
+    /// 別の実施設定で作成されている
+    ConfigHash { expected: String, actual: String },
+}
+
+/// キーを整列したJSONのSHA-256
+fn json_checksum<T: Serialize>(value: &T) -> Result<String, Error> {
+    let value = serde_json::to_value(value).map_err(Error::JSONReadError)?;
+    Ok(sha256_hex(value.to_string().as_bytes()))
+}
+
+/// 実施設定のハッシュ
+pub fn config_hash(config: &Config) -> Result<String, Error> {
+    json_checksum(config)
+}
+
+impl<T: Serialize> Stamped<T> {
+    /// 文書に来歴を刻印する
+    pub fn new(document: T, config: &Config, master: &SimpleStress) -> Result<Self, Error> {
+        Ok(Self {
+            provenance: Provenance {
+                crate_version: CRATE_VERSION.to_string(),
+                questionnaire_version: config.questionnaire_version.clone(),
+                master_checksum: master.checksum(),
+                config_hash: config_hash(config)?,
+                content_checksum: json_checksum(&document)?,
+            },
+            document,
+        })
+    }
+
+    /// 本文が改変されておらず、指定のマスタ・実施設定で作成されたことを確認する
+    /// プログラムの版は異なっていてもよい。
+    pub fn verify(
+        &self,
+        config: &Config,
+        master: &SimpleStress,
+    ) -> Result<Vec<StampDiscrepancy>, Error> {
+        let provenance = &self.provenance;
+        let mut discrepancies = vec![];
+        let actual = json_checksum(&self.document)?;
+        if provenance.content_checksum != actual {
+            discrepancies.push(StampDiscrepancy::ContentChecksum {
+                expected: provenance.content_checksum.clone(),
+                actual,
+            });
+        }
+        if provenance.questionnaire_version != config.questionnaire_version {
+            discrepancies.push(StampDiscrepancy::QuestionnaireVersion {
+                expected: config.questionnaire_version.clone(),
+                actual: provenance.questionnaire_version.clone(),
+            });
+        }
+        let expected = master.checksum();
+        if provenance.master_checksum != expected {
+            discrepancies.push(StampDiscrepancy::MasterChecksum {
+                expected,
+                actual: provenance.master_checksum.clone(),
+            });
+        }
+        let expected = config_hash(config)?;
+        if provenance.config_hash != expected {
+            discrepancies.push(StampDiscrepancy::ConfigHash {
+                expected,
+                actual: provenance.config_hash.clone(),
+            });
+        }
+        Ok(discrepancies)
+    }
+}
+
+/// JSONの文書を読み込む
+/// 本文の型を問わずに検証できるよう、本文は`Value`のまま扱う。
+pub fn read_stamped(value: &str) -> Result<Stamped<Value>, Error> {
+    serde_json::from_str(value).map_err(Error::JSONReadError)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, StressCriteria, QUESTIONS};
+
+    fn document() -> ResultDocument {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(4).unwrap();
+        }
+        ResultDocument {
+            id: "0001".to_string(),
+            result: CheckResult::new(&store, StressCriteria::SUMUP).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_stamp() {
+        let config = Config::default();
+        let stamped = Stamped::new(document(), &config, &QUESTIONS).unwrap();
+        assert_eq!(stamped.provenance.crate_version, CRATE_VERSION);
+        let text = serde_json::to_string(&stamped).unwrap();
+
+        let read = read_stamped(&text).unwrap();
+        assert_eq!(read.verify(&config, &QUESTIONS).unwrap(), vec![]);
+
+        let tampered = text.replace("\"has_stress\":true", "\"has_stress\":false");
+        assert_ne!(tampered, text);
+        let discrepancies = read_stamped(&tampered)
+            .unwrap()
+            .verify(&config, &QUESTIONS)
+            .unwrap();
+        assert!(matches!(
+            discrepancies[..],
+            [StampDiscrepancy::ContentChecksum { .. }]
+        ));
+
+        let other = Config {
+            criteria: StressCriteria::CONVERSION,
+            ..Config::default()
+        };
+        let discrepancies = read.verify(&other, &QUESTIONS).unwrap();
+        assert!(matches!(
+            discrepancies[..],
+            [StampDiscrepancy::ConfigHash { .. }]
+        ));
+    }
+}
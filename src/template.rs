@@ -0,0 +1,364 @@
+//! 一括判定の入力テンプレート
+//!
+//! 調査を委託する業者とのやり取りを減らすため、一括判定が読み込む列の定義（[`bulk_columns`]）から
+//! 見出し行だけのCSVと、入力規則（選択肢のリスト・入力時の説明）付きのXLSXを生成する。
+//! 各列の必須・選択肢・説明は、別途CSVの一覧（[`write_hints_csv`]）としても出力できる。
+
+use std::io::Write;
+
+use crate::demographics::{AgeBand, EmploymentType, Tenure};
+use crate::web::escape_html;
+use crate::{Error, SimpleStress};
+
+/// XLSXで入力規則を設定する行数（見出し行を除く）
+pub const TEMPLATE_ROWS: usize = 10000;
+
+/// 一括判定の列
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BulkColumn {
+    pub name: String,
+    /// 必須の列か
+    pub required: bool,
+    /// 入力できる値（自由記述の場合は空）
+    pub allowed: Vec<String>,
+    pub description: String,
+}
+
+impl BulkColumn {
+    fn new(name: &str, required: bool, allowed: Vec<String>, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            required,
+            allowed,
+            description: description.to_string(),
+        }
+    }
+}
+
+/// 一括判定で読み込む列（読み込む順）
+/// 設問の列の説明には設問文を用いる。
+pub fn bulk_columns(master: &SimpleStress) -> Vec<BulkColumn> {
+    let mut columns = vec![BulkColumn::new(
+        "id",
+        true,
+        vec![],
+        "ユーザ特定キー（受検者ごとに重複しない値）",
+    )];
+    for question in master.questions() {
+        columns.push(BulkColumn::new(
+            &format!("q_{}", question.id),
+            true,
+            question
+                .scores
+                .iter()
+                .map(|score| score.score.to_string())
+                .collect(),
+            &format!(
+                "{}（{}）",
+                question.text,
+                question
+                    .scores
+                    .iter()
+                    .map(|score| format!("{}={}", score.score, score.text))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        ));
+    }
+    columns.push(BulkColumn::new(
+        "department",
+        false,
+        vec![],
+        "所属部署（集団分析に用いる）",
+    ));
+    columns.push(BulkColumn::new(
+        "age_band",
+        false,
+        AgeBand::ALL
+            .iter()
+            .map(|v| v.as_str().to_string())
+            .collect(),
+        "年齢階層",
+    ));
+    columns.push(BulkColumn::new(
+        "employment_type",
+        false,
+        EmploymentType::ALL
+            .iter()
+            .map(|v| v.as_str().to_string())
+            .collect(),
+        "雇用形態",
+    ));
+    columns.push(BulkColumn::new(
+        "tenure",
+        false,
+        Tenure::ALL.iter().map(|v| v.as_str().to_string()).collect(),
+        "勤続年数",
+    ));
+    columns
+}
+
+/// 見出し行だけのCSVを書き出す
+pub fn write_csv_template<W: Write>(writer: W, columns: &[BulkColumn]) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer
+        .write_record(columns.iter().map(|column| column.name.as_str()))
+        .map_err(Error::CSVWriteError)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// 列ごとの必須・選択肢・説明の一覧をCSVで書き出す
+pub fn write_hints_csv<W: Write>(writer: W, columns: &[BulkColumn]) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer
+        .write_record(["column", "required", "allowed", "description"])
+        .map_err(Error::CSVWriteError)?;
+    for column in columns {
+        writer
+            .write_record([
+                column.name.as_str(),
+                if column.required { "yes" } else { "no" },
+                column.allowed.join("|").as_str(),
+                column.description.as_str(),
+            ])
+            .map_err(Error::CSVWriteError)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// 列番号（0始まり）をXLSXの列名（`A`・`B`…`AA`）にする
+fn column_letter(mut index: usize) -> String {
+    let mut letters = vec![];
+    loop {
+        letters.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+/// 文字数を制限する（入力規則の説明は255文字まで）
+fn truncate(value: &str, max: usize) -> String {
+    value.chars().take(max).collect()
+}
+
+fn worksheet(columns: &[BulkColumn]) -> String {
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <worksheet xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\">\
+         <sheetData><row r=\"1\">",
+    );
+    for (index, column) in columns.iter().enumerate() {
+        xml.push_str(&format!(
+            "<c r=\"{}1\" t=\"inlineStr\"><is><t>{}</t></is></c>",
+            column_letter(index),
+            escape_html(&column.name)
+        ));
+    }
+    xml.push_str("</row></sheetData>");
+    xml.push_str(&format!("<dataValidations count=\"{}\">", columns.len()));
+    for (index, column) in columns.iter().enumerate() {
+        let letter = column_letter(index);
+        let prompt = escape_html(&truncate(
+            &format!(
+                "{}{}",
+                if column.required {
+                    "必須。"
+                } else {
+                    "任意。"
+                },
+                column.description
+            ),
+            255,
+        ));
+        let title = escape_html(&truncate(&column.name, 32));
+        let range = format!("{0}2:{0}{1}", letter, TEMPLATE_ROWS + 1);
+        if column.allowed.is_empty() {
+            xml.push_str(&format!(
+                "<dataValidation allowBlank=\"1\" showInputMessage=\"1\" \
+                 promptTitle=\"{}\" prompt=\"{}\" sqref=\"{}\"/>",
+                title, prompt, range
+            ));
+        } else {
+            xml.push_str(&format!(
+                "<dataValidation type=\"list\" allowBlank=\"1\" showInputMessage=\"1\" \
+                 showErrorMessage=\"1\" promptTitle=\"{}\" prompt=\"{}\" sqref=\"{}\">\
+                 <formula1>\"{}\"</formula1></dataValidation>",
+                title,
+                prompt,
+                range,
+                escape_html(&column.allowed.join(","))
+            ));
+        }
+    }
+    xml.push_str("</dataValidations></worksheet>\n");
+    xml
+}
+
+const CONTENT_TYPES: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+<Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+<Default Extension=\"xml\" ContentType=\"application/xml\"/>\
+<Override PartName=\"/xl/workbook.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml\"/>\
+<Override PartName=\"/xl/worksheets/sheet1.xml\" ContentType=\"application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml\"/>\
+</Types>\n";
+
+const ROOT_RELS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument\" Target=\"xl/workbook.xml\"/>\
+</Relationships>\n";
+
+const WORKBOOK: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<workbook xmlns=\"http://schemas.openxmlformats.org/spreadsheetml/2006/main\" \
+xmlns:r=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships\">\
+<sheets><sheet name=\"回答\" sheetId=\"1\" r:id=\"rId1\"/></sheets></workbook>\n";
+
+const WORKBOOK_RELS: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+<Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+<Relationship Id=\"rId1\" Type=\"http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet\" Target=\"worksheets/sheet1.xml\"/>\
+</Relationships>\n";
+
+/// 入力規則付きのXLSXを書き出す
+/// 1枚目のシートに見出し行を置き、各列に選択肢のリストと入力時の説明を設定する。
+pub fn write_xlsx_template<W: Write>(writer: W, columns: &[BulkColumn]) -> Result<(), Error> {
+    let sheet = worksheet(columns);
+    write_zip(
+        writer,
+        &[
+            ("[Content_Types].xml", CONTENT_TYPES.as_bytes()),
+            ("_rels/.rels", ROOT_RELS.as_bytes()),
+            ("xl/workbook.xml", WORKBOOK.as_bytes()),
+            ("xl/_rels/workbook.xml.rels", WORKBOOK_RELS.as_bytes()),
+            ("xl/worksheets/sheet1.xml", sheet.as_bytes()),
+        ],
+    )
+}
+
+/// CRC-32（ZIPの検査値）
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// 無圧縮のZIPを書き出す
+fn write_zip<W: Write>(mut writer: W, files: &[(&str, &[u8])]) -> Result<(), Error> {
+    // 1980-01-01 00:00（MS-DOS形式）
+    const DOS_TIME: u16 = 0;
+    const DOS_DATE: u16 = (1 << 5) | 1;
+    // ファイル名をUTF-8とする
+    const FLAGS: u16 = 0x0800;
+
+    let mut offset = 0u32;
+    let mut central = vec![];
+    for (name, data) in files {
+        let crc = crc32(data);
+        let size = data.len() as u32;
+        let mut local = vec![];
+        local.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        for value in [20, FLAGS, 0, DOS_TIME, DOS_DATE] {
+            local.extend_from_slice(&u16::to_le_bytes(value));
+        }
+        for value in [crc, size, size] {
+            local.extend_from_slice(&value.to_le_bytes());
+        }
+        local.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        local.extend_from_slice(&0u16.to_le_bytes());
+        local.extend_from_slice(name.as_bytes());
+        writer.write_all(&local)?;
+        writer.write_all(data)?;
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        for value in [20, 20, FLAGS, 0, DOS_TIME, DOS_DATE] {
+            central.extend_from_slice(&u16::to_le_bytes(value));
+        }
+        for value in [crc, size, size] {
+            central.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [name.len() as u16, 0, 0, 0, 0] {
+            central.extend_from_slice(&value.to_le_bytes());
+        }
+        central.extend_from_slice(&0u32.to_le_bytes());
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+        offset += local.len() as u32 + size;
+    }
+    writer.write_all(&central)?;
+    let mut end = vec![];
+    end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    for value in [0, 0, files.len() as u16, files.len() as u16] {
+        end.extend_from_slice(&u16::to_le_bytes(value));
+    }
+    end.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    end.extend_from_slice(&offset.to_le_bytes());
+    end.extend_from_slice(&0u16.to_le_bytes());
+    writer.write_all(&end)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{read_bulk_records, QUESTIONS};
+
+    #[test]
+    fn test_csv_template() {
+        let columns = bulk_columns(&QUESTIONS);
+        assert_eq!(columns.len(), 62);
+        assert!(columns
+            .iter()
+            .all(|column| crate::header::is_column(&column.name)));
+
+        let mut csv = vec![];
+        write_csv_template(&mut csv, &columns).unwrap();
+        let mut csv = String::from_utf8(csv).unwrap();
+        assert!(csv.starts_with("id,q_1,q_2,"));
+        assert!(csv.ends_with(",q_57,department,age_band,employment_type,tenure\n"));
+        csv.push_str(&format!("a,{},,,,\n", vec!["2"; 57].join(",")));
+        let records = read_bulk_records(csv.as_bytes())
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(records[0].id, "a");
+
+        let mut hints = vec![];
+        write_hints_csv(&mut hints, &columns).unwrap();
+        let hints = String::from_utf8(hints).unwrap();
+        assert!(hints.contains("\nq_1,yes,1|2|3|4,"));
+        assert!(hints.contains("\ntenure,no,under_one_year|"));
+    }
+
+    #[test]
+    fn test_xlsx_template() {
+        assert_eq!(column_letter(0), "A");
+        assert_eq!(column_letter(25), "Z");
+        assert_eq!(column_letter(26), "AA");
+        assert_eq!(column_letter(61), "BJ");
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+
+        let columns = bulk_columns(&QUESTIONS);
+        let sheet = worksheet(&columns);
+        assert!(sheet.contains("<c r=\"BJ1\" t=\"inlineStr\"><is><t>tenure</t></is></c>"));
+        assert!(sheet.contains("sqref=\"B2:B10001\"><formula1>\"1,2,3,4\"</formula1>"));
+        assert_eq!(sheet.matches("<dataValidation ").count(), 62);
+
+        let mut xlsx = vec![];
+        write_xlsx_template(&mut xlsx, &columns).unwrap();
+        assert!(xlsx.starts_with(b"PK\x03\x04"));
+        assert_eq!(&xlsx[xlsx.len() - 22..xlsx.len() - 18], b"PK\x05\x06");
+    }
+}
@@ -0,0 +1,193 @@
+//! 複数事業者の収容
+//!
+//! 1つのサーバで複数の事業者（テナント）のストレスチェックを実施するために、テナントごとの
+//! 実施設定・マスタ・表示文言・保存先の接頭辞を管理する。テナントはURLの先頭
+//! （`/t/<テナントID>/...`）またはトークンの接頭辞（`<テナントID>.<トークン>`）で特定する。
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::Error;
+
+/// 画面・通知に表示する文言
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Branding {
+    /// 事業者名
+    pub organization: String,
+    /// 調査票の表題
+    pub title: Option<String>,
+}
+
+/// テナントの設定
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Tenant {
+    /// テナントID（半角英数字・`-`・`_`）
+    pub id: String,
+    /// 設問マスタ。省略時は同梱のマスタを用いる
+    #[serde(default)]
+    pub master: Option<PathBuf>,
+    #[serde(default)]
+    pub config: Config,
+    #[serde(default)]
+    pub branding: Branding,
+}
+
+impl Tenant {
+    /// 保存先の接頭辞
+    /// 他のテナントのデータと混在しないよう、保存するキーは必ずこの接頭辞の下に置く。
+    pub fn storage_prefix(&self) -> String {
+        format!("tenants/{}/", self.id)
+    }
+
+    /// 保存先のキー
+    pub fn storage_key(&self, name: &str) -> String {
+        format!("{}{}", self.storage_prefix(), name)
+    }
+}
+
+#[derive(Deserialize)]
+struct TenantsFile {
+    tenants: Vec<Tenant>,
+}
+
+/// テナントの一覧
+#[derive(Debug, Clone, Default)]
+pub struct TenantRegistry {
+    tenants: HashMap<String, Tenant>,
+}
+
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+impl TenantRegistry {
+    /// テナントを登録する
+    /// IDが不正・重複している場合や、設定値が不正な場合はエラーを返す。
+    pub fn new(tenants: Vec<Tenant>) -> Result<Self, Error> {
+        let mut registry = HashMap::new();
+        for tenant in tenants {
+            if !is_valid_id(&tenant.id) {
+                return Err(Error::IllegalConfig);
+            }
+            tenant.config.validate()?;
+            if registry.contains_key(&tenant.id) {
+                return Err(Error::DuplicateId(tenant.id));
+            }
+            registry.insert(tenant.id.clone(), tenant);
+        }
+        Ok(Self { tenants: registry })
+    }
+
+    /// `[[tenants]]`を並べたTOML文字列から読み込む
+    pub fn from_toml_str(value: &str) -> Result<Self, Error> {
+        let file: TenantsFile = toml::from_str(value).map_err(Error::ConfigReadError)?;
+        Self::new(file.tenants)
+    }
+
+    /// TOMLファイルから読み込む
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::from_toml_str(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tenants.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tenants.is_empty()
+    }
+
+    /// IDでテナントを引く
+    pub fn get(&self, id: &str) -> Result<&Tenant, Error> {
+        self.tenants
+            .get(id)
+            .ok_or_else(|| Error::UnknownTenant(id.to_string()))
+    }
+
+    /// `/t/<テナントID>/<残り>`のパスからテナントと残りのパスを引く
+    pub fn resolve_path<'a>(&self, path: &'a str) -> Result<(&Tenant, &'a str), Error> {
+        let rest = path
+            .strip_prefix("/t/")
+            .ok_or_else(|| Error::UnknownTenant(path.to_string()))?;
+        let (id, rest) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        Ok((self.get(id)?, rest))
+    }
+
+    /// `<テナントID>.<トークン>`の形式のトークンからテナントと個人のトークンを引く
+    pub fn resolve_token<'a>(&self, token: &'a str) -> Result<(&Tenant, &'a str), Error> {
+        let (id, token) = token
+            .split_once('.')
+            .ok_or_else(|| Error::UnknownTenant(String::new()))?;
+        Ok((self.get(id)?, token))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::StressCriteria;
+
+    const TENANTS: &str = r#"
+[[tenants]]
+id = "acme"
+branding.organization = "株式会社アクメ"
+
+[[tenants]]
+id = "globex"
+master = "resources/57.json"
+branding = { organization = "グロービクス", title = "職業性ストレス簡易調査票" }
+
+[tenants.config.criteria]
+method = "conversion"
+b = 12
+ac = 26
+b_with_ac = 17
+"#;
+
+    #[test]
+    fn test_registry() {
+        let registry = TenantRegistry::from_toml_str(TENANTS).unwrap();
+        assert_eq!(registry.len(), 2);
+
+        let (tenant, rest) = registry.resolve_path("/t/globex/submissions").unwrap();
+        assert_eq!(tenant.id, "globex");
+        assert_eq!(rest, "/submissions");
+        assert_eq!(tenant.config.criteria, StressCriteria::CONVERSION);
+        assert_eq!(
+            tenant.storage_key("results.jsonl"),
+            "tenants/globex/results.jsonl"
+        );
+
+        let (tenant, token) = registry.resolve_token("acme.abc123").unwrap();
+        assert_eq!(tenant.config.criteria, StressCriteria::SUMUP);
+        assert_eq!(tenant.branding.organization, "株式会社アクメ");
+        assert_eq!(token, "abc123");
+
+        let error = registry.resolve_path("/t/initech/").unwrap_err();
+        assert_eq!(error.code(), "E_TENANT_UNKNOWN");
+        assert_eq!(error.http_status(), 404);
+        assert!(registry.resolve_token("abc123").is_err());
+    }
+
+    #[test]
+    fn test_illegal_tenants() {
+        assert!(matches!(
+            TenantRegistry::from_toml_str("[[tenants]]\nid = \"../acme\""),
+            Err(Error::IllegalConfig)
+        ));
+        assert!(matches!(
+            TenantRegistry::from_toml_str("[[tenants]]\nid = \"a\"\n[[tenants]]\nid = \"a\""),
+            Err(Error::DuplicateId(_))
+        ));
+    }
+}
@@ -0,0 +1,168 @@
+//! 設問の文言の差し替え
+//!
+//! 組織ごとに直した文言や独自の表記を、同梱のマスタを書き換えずに差し込むための仕組み。
+//! 文言は[`TextProvider`]から設問番号と[`Locale`]で取得し、返さなかったものはマスタの文言を用いる。
+//! 差し替えるのは文言だけで、設問番号・逆転項目・回答番号は常にマスタのものを用いるため採点は変わらない。
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Locale;
+use crate::translation::QuestionnaireLanguage;
+use crate::{Error, SimpleStress};
+
+/// 設問の文言の取得元
+pub trait TextProvider {
+    /// 設問文（マスタの文言を用いる場合は`None`）
+    fn question_text(&self, id: u32, locale: &Locale) -> Option<String>;
+
+    /// 回答選択肢の文言（マスタの文言を用いる場合は`None`）
+    fn score_text(&self, _id: u32, _score: u8, _locale: &Locale) -> Option<String> {
+        None
+    }
+}
+
+/// 設問1つ分の差し替え
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemText {
+    /// 差し替える調査票の言語
+    #[serde(default)]
+    pub language: QuestionnaireLanguage,
+    /// 設問番号
+    pub id: u32,
+    /// 設問文
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    /// 回答選択肢の文言（回答番号1〜4の順）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scores: Vec<String>,
+}
+
+/// ファイルから読み込む文言の差し替え
+///
+/// ```toml
+/// [[item]]
+/// id = 1
+/// text = "とても多くの仕事をしなければならない"
+///
+/// [[item]]
+/// language = "pt"
+/// id = 56
+/// scores = ["Satisfeito", "Mais ou menos satisfeito", "Pouco satisfeito", "Insatisfeito"]
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextOverrides {
+    #[serde(default, rename = "item")]
+    pub items: Vec<ItemText>,
+}
+
+impl TextOverrides {
+    /// TOMLから読み込む
+    /// 設問番号が1〜57でなければ`IllegalQuestion`、選択肢の文言が4つでなければ`IllegalScore`。
+    pub fn from_toml_str(value: &str) -> Result<Self, Error> {
+        let overrides: TextOverrides = toml::from_str(value).map_err(Error::ConfigReadError)?;
+        for item in &overrides.items {
+            if !(1..=57).contains(&item.id) {
+                return Err(Error::IllegalQuestion);
+            }
+            if !item.scores.is_empty() && item.scores.len() != 4 {
+                return Err(Error::IllegalScore);
+            }
+        }
+        Ok(overrides)
+    }
+
+    fn find(&self, id: u32, locale: &Locale) -> Option<&ItemText> {
+        self.items
+            .iter()
+            .rev()
+            .find(|item| item.id == id && item.language == locale.language)
+    }
+}
+
+impl TextProvider for TextOverrides {
+    fn question_text(&self, id: u32, locale: &Locale) -> Option<String> {
+        self.find(id, locale)?.text.clone()
+    }
+
+    fn score_text(&self, id: u32, score: u8, locale: &Locale) -> Option<String> {
+        self.find(id, locale)?
+            .scores
+            .get((score as usize).checked_sub(1)?)
+            .cloned()
+    }
+}
+
+impl SimpleStress {
+    /// 文言を差し替えたマスタ
+    /// 設問文を差し替えた設問は、元の文に付けたふりがなを外す。
+    pub fn with_texts(mut self, provider: &dyn TextProvider, locale: &Locale) -> SimpleStress {
+        for theme in &mut self.simple_stress {
+            for outer_question in &mut theme.questions {
+                for question in &mut outer_question.questions {
+                    let question = Arc::make_mut(question);
+                    if let Some(text) = provider.question_text(question.id, locale) {
+                        question.text = text;
+                        question.furigana = None;
+                    }
+                    for score in &mut question.scores {
+                        if let Some(text) = provider.score_text(question.id, score.score, locale) {
+                            score.text = text;
+                        }
+                    }
+                }
+            }
+        }
+        self
+    }
+}
+
+impl Locale {
+    /// 言語に応じた設問マスタを読み込み、文言を差し替える
+    pub fn master_with(&self, provider: &dyn TextProvider) -> Result<SimpleStress, Error> {
+        Ok(self.master()?.with_texts(provider, self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::resource;
+
+    #[test]
+    fn test_text_overrides() {
+        let overrides = TextOverrides::from_toml_str(
+            "[[item]]\nid = 1\ntext = \"仕事がとても多い\"\n\n\
+             [[item]]\nlanguage = \"pt\"\nid = 56\nscores = [\"a\", \"b\", \"c\", \"d\"]\n",
+        )
+        .unwrap();
+        let master = resource::embedded_master()
+            .unwrap()
+            .with_texts(&overrides, &Locale::default());
+        let question = master.question(1).unwrap();
+        assert_eq!(question.text, "仕事がとても多い");
+        assert_eq!(question.furigana, None);
+        assert_eq!(master.question(56).unwrap().scores[0].text, "満足");
+        assert_eq!(master.verify_translation(), vec![]);
+
+        let locale = Locale {
+            language: QuestionnaireLanguage::Pt,
+            ..Locale::default()
+        };
+        let master = locale.master_with(&overrides).unwrap();
+        let question = master.question(56).unwrap();
+        assert_eq!(question.scores[3].text, "d");
+        assert_eq!(question.scores[3].score, 4);
+        assert_ne!(master.question(1).unwrap().text, "仕事がとても多い");
+
+        assert!(matches!(
+            TextOverrides::from_toml_str("[[item]]\nid = 58\ntext = \"x\""),
+            Err(Error::IllegalQuestion)
+        ));
+        assert!(matches!(
+            TextOverrides::from_toml_str("[[item]]\nid = 1\nscores = [\"a\"]"),
+            Err(Error::IllegalScore)
+        ));
+    }
+}
@@ -0,0 +1,151 @@
+//! 判定の計算過程
+//!
+//! 監査担当者や医師が個人の判定を検算できるよう、逆転項目の置き換え、尺度ごとの
+//! 素点と換算後の評価点、数値基準との比較を順に記録する。
+
+use serde::Serialize;
+
+use crate::{reverse_if, AnswerStore, Error, Scale, Stress, StressCriteria};
+
+/// 設問ごとの計算
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ItemTrace {
+    /// 設問番号
+    pub question: usize,
+    /// 回答番号
+    pub answer: u8,
+    /// 逆転項目か
+    pub reversed: bool,
+    /// 合計点数方式で用いる点数
+    pub score: u8,
+}
+
+/// 尺度ごとの計算
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScaleTrace {
+    pub scale: Scale,
+    /// 素点
+    pub raw: u8,
+    /// 素点換算表による評価点
+    pub evaluation_point: u8,
+}
+
+/// 数値基準との比較
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Comparison {
+    /// 比較した値（`b` または `a+c`）
+    pub subject: &'static str,
+    pub value: u8,
+    /// `>=` または `<=`
+    pub operator: &'static str,
+    pub threshold: u8,
+    pub satisfied: bool,
+}
+
+/// 判定の計算過程
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScoreTrace {
+    pub criteria: StressCriteria,
+    pub items: Vec<ItemTrace>,
+    /// 合計点数方式による領域Ａ・Ｂ・Ｃの点数
+    pub sumup_scores: (u8, u8, u8),
+    pub scales: Vec<ScaleTrace>,
+    /// 判定に用いた領域Ａ・Ｂ・Ｃの点数
+    pub scores: (u8, u8, u8),
+    /// ㋐ 領域Ｂの基準
+    pub b: Comparison,
+    /// ㋑ 領域ＡとＣの合算の基準
+    pub ac: Comparison,
+    /// ㋑ あわせて用いる領域Ｂの基準
+    pub b_with_ac: Comparison,
+    pub has_stress: bool,
+}
+
+impl AnswerStore {
+    /// 判定の計算過程を記録する
+    pub fn score_trace(&self, criteria: StressCriteria) -> Result<ScoreTrace, Error> {
+        let sumup = self.to_sumup_score()?;
+        let intermediate = self.to_intermediate_score()?;
+        let conversion = self.to_conversion_score()?;
+        let items = self
+            .values()
+            .iter()
+            .enumerate()
+            .map(|(index, &answer)| {
+                let score = reverse_if((index + 1, answer));
+                ItemTrace {
+                    question: index + 1,
+                    answer,
+                    reversed: reverse_if((index + 1, 1)) != 1,
+                    score,
+                }
+            })
+            .collect();
+        let scales = Scale::ALL
+            .iter()
+            .map(|&scale| ScaleTrace {
+                scale,
+                raw: intermediate.get(scale),
+                evaluation_point: conversion.get(scale),
+            })
+            .collect();
+
+        let scores = criteria.score(self)?.scores();
+        let (a, b, c) = scores;
+        let (operator, thresholds) = match criteria {
+            StressCriteria::Sumup(thresholds) => (">=", thresholds),
+            StressCriteria::Conversion(thresholds) => ("<=", thresholds),
+        };
+        let compare = |subject, value: u8, threshold: u8| Comparison {
+            subject,
+            value,
+            operator,
+            threshold,
+            satisfied: match operator {
+                ">=" => value >= threshold,
+                _ => value <= threshold,
+            },
+        };
+        Ok(ScoreTrace {
+            criteria,
+            items,
+            sumup_scores: sumup.scores(),
+            scales,
+            scores,
+            b: compare("b", b, thresholds.b),
+            ac: compare("a+c", a + c, thresholds.ac),
+            b_with_ac: compare("b", b, thresholds.b_with_ac),
+            has_stress: criteria.judge(scores),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_score_trace() {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(4).unwrap();
+        }
+        let trace = store.score_trace(StressCriteria::SUMUP).unwrap();
+        assert_eq!(trace.items.len(), 57);
+        assert!(trace.items[0].reversed);
+        assert_eq!(trace.items[0].score, 1);
+        assert!(!trace.items[7].reversed);
+        assert_eq!(trace.scales.len(), 18);
+        assert_eq!(trace.b.operator, ">=");
+        assert_eq!(
+            trace.has_stress,
+            trace.b.satisfied || (trace.ac.satisfied && trace.b_with_ac.satisfied)
+        );
+
+        let trace = store.score_trace(StressCriteria::CONVERSION).unwrap();
+        assert_eq!(trace.b.operator, "<=");
+        let value = serde_json::to_value(&trace).unwrap();
+        assert_eq!(value["criteria"]["method"], "conversion");
+        assert_eq!(value["scales"][0]["scale"], "mental_work_stress_volume");
+    }
+}
@@ -0,0 +1,146 @@
+//! 調査票の翻訳
+//!
+//! 日本で働く人に多い言語（中国語・ポルトガル語・ベトナム語）の設問マスタを同梱し、
+//! [`Locale`]の`language`で選べるようにする。翻訳したマスタも設問番号・逆転項目・
+//! 回答番号は日本語のマスタと同じで、採点は変わらない。
+//! 翻訳は`57.<言語コード>.json`にあり、リソースの場所になければ組み込みのものを用いる。
+
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Locale;
+use crate::verify::Discrepancy;
+use crate::{resource, Error, SimpleStress};
+
+/// バイナリに組み込んだ中国語（簡体字）のマスタ
+pub const EMBEDDED_ZH: &str = include_str!("../resources/57.zh.json");
+/// バイナリに組み込んだポルトガル語のマスタ
+pub const EMBEDDED_PT: &str = include_str!("../resources/57.pt.json");
+/// バイナリに組み込んだベトナム語のマスタ
+pub const EMBEDDED_VI: &str = include_str!("../resources/57.vi.json");
+
+/// 調査票の言語
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionnaireLanguage {
+    /// 日本語（原文）
+    #[default]
+    Ja,
+    /// 中国語（簡体字）
+    Zh,
+    /// ポルトガル語
+    Pt,
+    /// ベトナム語
+    Vi,
+}
+
+impl QuestionnaireLanguage {
+    pub const ALL: [QuestionnaireLanguage; 4] = [
+        QuestionnaireLanguage::Ja,
+        QuestionnaireLanguage::Zh,
+        QuestionnaireLanguage::Pt,
+        QuestionnaireLanguage::Vi,
+    ];
+
+    /// 言語コード（ISO 639-1）
+    pub fn code(&self) -> &'static str {
+        match self {
+            QuestionnaireLanguage::Ja => "ja",
+            QuestionnaireLanguage::Zh => "zh",
+            QuestionnaireLanguage::Pt => "pt",
+            QuestionnaireLanguage::Vi => "vi",
+        }
+    }
+
+    /// マスタのファイル名
+    pub fn master_file(&self) -> String {
+        match self {
+            QuestionnaireLanguage::Ja => resource::MASTER_FILE.to_string(),
+            language => format!("57.{}.json", language.code()),
+        }
+    }
+
+    fn embedded(&self) -> &'static str {
+        match self {
+            QuestionnaireLanguage::Ja => resource::EMBEDDED_MASTER,
+            QuestionnaireLanguage::Zh => EMBEDDED_ZH,
+            QuestionnaireLanguage::Pt => EMBEDDED_PT,
+            QuestionnaireLanguage::Vi => EMBEDDED_VI,
+        }
+    }
+}
+
+impl Locale {
+    /// 言語に応じた設問マスタを読み込む
+    /// 翻訳したマスタは設問の構成を検証し、採点と食い違えば`IllegalMaster`とする。
+    /// ファイルがない場合の扱いは`resource::load_master`と同じ。
+    pub fn master(&self) -> Result<SimpleStress, Error> {
+        let language = self.language;
+        if language == QuestionnaireLanguage::Ja {
+            return resource::load_master();
+        }
+        let path = resource::resource_path(language.master_file());
+        let master = if path.is_file() {
+            SimpleStress::load(path)?
+        } else if resource::is_default_resource_dir() {
+            serde_json::from_str(language.embedded()).map_err(Error::MasterReadError)?
+        } else {
+            return Err(Error::ResourceNotFound(path));
+        };
+        let discrepancies = master.verify_translation();
+        if !discrepancies.is_empty() {
+            return Err(Error::IllegalMaster(discrepancies));
+        }
+        Ok(master)
+    }
+}
+
+impl SimpleStress {
+    /// 翻訳したマスタを検証する
+    /// 文言は原文と異なるため、チェックサム以外の不変条件だけを確かめる。
+    pub fn verify_translation(&self) -> Vec<Discrepancy> {
+        self.verify()
+            .into_iter()
+            .filter(|discrepancy| !matches!(discrepancy, Discrepancy::Checksum { .. }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    #[test]
+    fn test_translated_masters() {
+        let original = QUESTIONS.questions();
+        for language in QuestionnaireLanguage::ALL {
+            let locale = Locale {
+                language,
+                ..Locale::default()
+            };
+            let master = locale.master().unwrap();
+            assert_eq!(master.verify_translation(), vec![], "{}", language.code());
+            assert_eq!(master.simple_stress.len(), QUESTIONS.simple_stress.len());
+            for (question, base) in master.questions().iter().zip(original.iter()) {
+                assert_eq!(question.id, base.id);
+                assert_eq!(question.reverse, base.reverse);
+                assert_eq!(question.scores.len(), base.scores.len());
+            }
+        }
+        let master = Locale {
+            language: QuestionnaireLanguage::Pt,
+            ..Locale::ISO
+        }
+        .master()
+        .unwrap();
+        assert_eq!(master.questions()[55].scores[0].text, "Satisfeito(a)");
+    }
+
+    #[test]
+    fn test_language_serde() {
+        let locale: Locale = toml::from_str("language = \"vi\"").unwrap();
+        assert_eq!(locale.language, QuestionnaireLanguage::Vi);
+        assert_eq!(locale.language.master_file(), "57.vi.json");
+        assert_eq!(Locale::default().language, QuestionnaireLanguage::Ja);
+    }
+}
@@ -0,0 +1,131 @@
+//! 受検者ごとの経年変化
+//!
+//! 個人結果の帳票に折れ線グラフで経年変化を示すため、保存先から直近数回分の判定結果を
+//! 取り出し、実施回ごとの系列に並べ替える。
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::repository::{ResultRepository, StoredResult};
+use crate::{Error, Scale};
+
+/// 実施回ごとの系列
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trajectory {
+    /// 受検者のトークン
+    pub token: String,
+    /// 実施回（古い順）。各系列の横軸
+    pub periods: Vec<String>,
+    /// 領域Ａ・Ｂ・Ｃの点数
+    pub scores: Vec<(u8, u8, u8)>,
+    /// 尺度ごとの評価点
+    pub evaluation_points: BTreeMap<Scale, Vec<u8>>,
+    /// 高ストレス者に該当したか
+    pub has_stress: Vec<bool>,
+}
+
+impl Trajectory {
+    /// 古い順に並んだ判定結果から系列を作る
+    pub fn from_history(token: &str, history: &[StoredResult]) -> Self {
+        let mut evaluation_points: BTreeMap<Scale, Vec<u8>> = BTreeMap::new();
+        for stored in history {
+            for scale in Scale::ALL {
+                let point = stored
+                    .result
+                    .evaluation_points
+                    .get(&scale)
+                    .copied()
+                    .unwrap_or_default();
+                evaluation_points.entry(scale).or_default().push(point);
+            }
+        }
+        Self {
+            token: token.to_string(),
+            periods: history.iter().map(|stored| stored.period.clone()).collect(),
+            scores: history.iter().map(|stored| stored.result.scores).collect(),
+            evaluation_points,
+            has_stress: history
+                .iter()
+                .map(|stored| stored.result.has_stress)
+                .collect(),
+        }
+    }
+
+    /// 実施回の数
+    pub fn len(&self) -> usize {
+        self.periods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.periods.is_empty()
+    }
+}
+
+/// トークンの直近`last`回分の系列を取得する
+pub fn trajectory<R: ResultRepository + ?Sized>(
+    repository: &R,
+    token: &str,
+    last: usize,
+) -> Result<Trajectory, Error> {
+    Ok(Trajectory::from_history(
+        token,
+        &repository.history(token, last)?,
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::repository::MemoryRepository;
+    use crate::{AnswerStore, CheckResult, StressCriteria};
+    use chrono::DateTime;
+
+    #[test]
+    fn test_trajectory() {
+        let mut repository = MemoryRepository::new();
+        for (year, answer) in [
+            (2020, 1),
+            (2021, 2),
+            (2022, 3),
+            (2023, 4),
+            (2024, 1),
+            (2025, 2),
+        ] {
+            let mut store = AnswerStore::default();
+            for _ in 0..57 {
+                store.push(answer).unwrap();
+            }
+            repository
+                .save(&StoredResult {
+                    token: "t".to_string(),
+                    period: year.to_string(),
+                    department: None,
+                    submitted_at: DateTime::parse_from_rfc3339(&format!(
+                        "{}-06-01T09:00:00+09:00",
+                        year
+                    ))
+                    .unwrap(),
+                    consent: None,
+                    result: CheckResult::new(&store, StressCriteria::SUMUP).unwrap(),
+                    answers: None,
+                    previous_results: vec![],
+                })
+                .unwrap();
+        }
+
+        let trajectory = trajectory(&repository, "t", 5).unwrap();
+        assert_eq!(trajectory.len(), 5);
+        assert_eq!(trajectory.periods, ["2021", "2022", "2023", "2024", "2025"]);
+        assert_eq!(trajectory.has_stress, [false, true, true, false, false]);
+        assert_eq!(trajectory.evaluation_points.len(), Scale::ALL.len());
+        assert!(trajectory
+            .evaluation_points
+            .values()
+            .all(|points| points.len() == 5));
+        assert_eq!(trajectory.scores[4], (45, 61, 18));
+
+        let value = serde_json::to_value(&trajectory).unwrap();
+        assert_eq!(value["evaluation_points"]["work_control"][0], 4);
+    }
+}
@@ -0,0 +1,109 @@
+//! 設問文の言い換え
+//!
+//! 日本語を母語としない人などのため、設問文を「やさしい日本語」に言い換えた文をマスタに持たせ、
+//! 表示の際に選べるようにする。採点は設問番号と回答番号だけで行うため、どの文で示しても判定は同じ。
+//! 同梱の言い換えは`57.plain.json`（設問番号→文）にあり、マスタの読み込み時に加える。
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{resource, Error, Question, SimpleStress};
+
+/// 「やさしい日本語」の言い換えのファイル名
+pub const PLAIN_TEXT_FILE: &str = "57.plain.json";
+
+/// バイナリに組み込んだ「やさしい日本語」の言い換え
+pub const EMBEDDED_PLAIN_TEXT: &str = include_str!("../resources/57.plain.json");
+
+/// 設問文の種類
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum TextVariant {
+    /// 調査票の原文
+    #[default]
+    Standard,
+    /// やさしい日本語
+    Plain,
+}
+
+impl Question {
+    /// 種類を指定して設問文を取得する（言い換えがなければ原文）
+    pub fn text_for(&self, variant: TextVariant) -> &str {
+        match variant {
+            TextVariant::Standard => &self.text,
+            variant => self
+                .variants
+                .get(&variant)
+                .map(String::as_str)
+                .unwrap_or(&self.text),
+        }
+    }
+}
+
+impl SimpleStress {
+    /// 設問番号ごとの言い換えを加える
+    /// マスタに既にある言い換えは置き換えない。
+    pub fn add_variant(&mut self, variant: TextVariant, texts: &BTreeMap<u32, String>) {
+        for theme in &mut self.simple_stress {
+            for outer_question in &mut theme.questions {
+                for question in &mut outer_question.questions {
+                    if let Some(text) = texts.get(&question.id) {
+                        Arc::make_mut(question)
+                            .variants
+                            .entry(variant)
+                            .or_insert_with(|| text.clone());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 「やさしい日本語」の言い換えを読み込む
+/// リソースの場所にファイルがなければ組み込みの言い換えを用いる。
+pub fn load_plain_texts() -> Result<BTreeMap<u32, String>, Error> {
+    let path = resource::resource_path(PLAIN_TEXT_FILE);
+    if path.is_file() {
+        let reader = std::io::BufReader::new(std::fs::File::open(path)?);
+        serde_json::from_reader(reader).map_err(Error::MasterReadError)
+    } else {
+        serde_json::from_str(EMBEDDED_PLAIN_TEXT).map_err(Error::MasterReadError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    #[test]
+    fn test_plain_variant() {
+        let questions = QUESTIONS.questions();
+        assert!(questions
+            .iter()
+            .all(|question| question.variants.contains_key(&TextVariant::Plain)));
+        let question = &questions[0];
+        assert_eq!(
+            question.text_for(TextVariant::Plain),
+            "しごとが とても おおい"
+        );
+        assert_eq!(question.text_for(TextVariant::Standard), question.text);
+
+        let mut master = resource::embedded_master().unwrap();
+        assert!(master.questions()[0].variants.is_empty());
+        let mut texts = BTreeMap::new();
+        texts.insert(1, "いいかえ".to_string());
+        master.add_variant(TextVariant::Plain, &texts);
+        master.add_variant(TextVariant::Plain, &load_plain_texts().unwrap());
+        let questions = master.questions();
+        assert_eq!(questions[0].text_for(TextVariant::Plain), "いいかえ");
+        assert_eq!(
+            questions[1].text_for(TextVariant::Plain),
+            "きめられた じかんの なかで しごとが おわらない"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::{reverse_if, ruby, SimpleStress};
+
+/// 同梱している57設問マスタの正規化テキストのSHA-256
+pub const MASTER_CHECKSUM: &str =
+    "063135dd402750eea870e2cd9d1c983fbed87c19d1b66ee638534cf63efc1378";
+
+/// マスタの不整合
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Discrepancy {
+    /// 設問数が57ではない
+    QuestionCount { expected: usize, actual: usize },
+    /// 設問番号が並び順と一致しない
+    QuestionId { index: usize, actual: u32 },
+    /// 逆転項目の指定が採点と一致しない
+    ReverseFlag { id: u32, expected: bool },
+    /// 回答選択肢が1〜4の4つではない
+    ScoreOptions { id: u32 },
+    /// 空の文言がある
+    EmptyText { id: Option<u32> },
+    /// ふりがなを除いた文が設問文と一致しない
+    Furigana { id: u32 },
+    /// 正規化テキストのチェックサムが一致しない
+    Checksum { expected: String, actual: String },
+    /// 提示する条件とする設問が、出現順でその設問より前にない
+    Condition { id: u32, question: u32 },
+}
+
+/// SHA-256を16進表記で返す
+pub(crate) fn sha256_hex(value: &[u8]) -> String {
+    Sha256::digest(value)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+impl SimpleStress {
+    /// チェックサムの算出に用いる正規化テキスト
+    /// 教示文・設問・回答選択肢を出現順に1行ずつ並べたもの。
+    pub fn canonical_text(&self) -> String {
+        let mut lines = vec![];
+        for theme in &self.simple_stress {
+            lines.push(format!("theme\t{}", theme.theme));
+            for outer_question in &theme.questions {
+                if let Some(ref title) = outer_question.title {
+                    lines.push(format!("title\t{}", title));
+                }
+                for question in &outer_question.questions {
+                    lines.push(format!(
+                        "question\t{}\t{}\t{}",
+                        question.id, question.reverse, question.text
+                    ));
+                    for score in &question.scores {
+                        lines.push(format!("score\t{}\t{}", score.score, score.text));
+                    }
+                }
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// 正規化テキストのSHA-256（16進表記）
+    pub fn checksum(&self) -> String {
+        sha256_hex(self.canonical_text().as_bytes())
+    }
+
+    /// 組み込みの不変条件に照らしてマスタを検証する
+    /// リソースファイルが誤って編集されていないかを確認するために用いる。
+    pub fn verify(&self) -> Vec<Discrepancy> {
+        let mut discrepancies = vec![];
+        let questions = self.questions();
+        if questions.len() != 57 {
+            discrepancies.push(Discrepancy::QuestionCount {
+                expected: 57,
+                actual: questions.len(),
+            });
+        }
+        for theme in &self.simple_stress {
+            if theme.theme.trim().is_empty() {
+                discrepancies.push(Discrepancy::EmptyText { id: None });
+            }
+        }
+        for (index, question) in questions.iter().enumerate() {
+            if question.id as usize != index + 1 {
+                discrepancies.push(Discrepancy::QuestionId {
+                    index,
+                    actual: question.id,
+                });
+            }
+            let expected = reverse_if((question.id as usize, 1)) != 1;
+            if question.reverse != expected {
+                discrepancies.push(Discrepancy::ReverseFlag {
+                    id: question.id,
+                    expected,
+                });
+            }
+            let scores = question
+                .scores
+                .iter()
+                .map(|score| score.score)
+                .collect::<Vec<_>>();
+            if scores != [1, 2, 3, 4] {
+                discrepancies.push(Discrepancy::ScoreOptions { id: question.id });
+            }
+            if question.text.trim().is_empty()
+                || question
+                    .scores
+                    .iter()
+                    .any(|score| score.text.trim().is_empty())
+            {
+                discrepancies.push(Discrepancy::EmptyText {
+                    id: Some(question.id),
+                });
+            }
+            if let Some(furigana) = &question.furigana {
+                if ruby::base_text(furigana) != question.text {
+                    discrepancies.push(Discrepancy::Furigana { id: question.id });
+                }
+            }
+        }
+        discrepancies.extend(self.verify_conditions());
+        let actual = self.checksum();
+        if actual != MASTER_CHECKSUM {
+            discrepancies.push(Discrepancy::Checksum {
+                expected: MASTER_CHECKSUM.to_string(),
+                actual,
+            });
+        }
+        discrepancies
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(QUESTIONS.verify(), vec![]);
+    }
+
+    #[test]
+    fn test_verify_edited() {
+        let mut master: SimpleStress =
+            serde_json::from_reader(std::fs::File::open("resources/57.json").unwrap()).unwrap();
+        let question =
+            std::sync::Arc::make_mut(&mut master.simple_stress[0].questions[0].questions[0]);
+        question.reverse = false;
+        question.text = "".to_string();
+        question.scores.pop();
+
+        let discrepancies = master.verify();
+        assert!(discrepancies.contains(&Discrepancy::ReverseFlag {
+            id: 1,
+            expected: true
+        }));
+        assert!(discrepancies.contains(&Discrepancy::ScoreOptions { id: 1 }));
+        assert!(discrepancies.contains(&Discrepancy::EmptyText { id: Some(1) }));
+        assert!(discrepancies.contains(&Discrepancy::Furigana { id: 1 }));
+        assert!(matches!(
+            discrepancies.last(),
+            Some(Discrepancy::Checksum { .. })
+        ));
+    }
+}
@@ -0,0 +1,165 @@
+//! 立場ごとの結果の見え方
+//!
+//! ストレスチェックの結果は本人と実施者（医師・保健師等）のみが把握でき、
+//! 事業者へは本人の同意がある場合に限り提供できる。設問ごとの回答は事業者へは
+//! 提供しない。立場ごとに別の型を返すことで、この境界を型で保証する。
+
+use serde::Serialize;
+
+use crate::{CheckRecord, CheckResult, Error, StressCriteria};
+
+/// 受検者本人向けの結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RespondentView {
+    /// ユーザ特定キー
+    pub id: String,
+    /// 設問ごとの回答
+    pub answers: Vec<u8>,
+    pub result: CheckResult,
+}
+
+impl RespondentView {
+    pub fn new(record: &CheckRecord, criteria: StressCriteria) -> Result<Self, Error> {
+        Ok(Self {
+            id: record.id.clone(),
+            answers: record.answers.values().to_vec(),
+            result: CheckResult::new(&record.answers, criteria)?,
+        })
+    }
+}
+
+/// 実施者向けの結果
+/// 面接指導の要否の確認のため、所属部署と設問ごとの回答も含む。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PractitionerView {
+    /// ユーザ特定キー
+    pub id: String,
+    /// 所属部署
+    pub department: Option<String>,
+    /// 設問ごとの回答
+    pub answers: Vec<u8>,
+    pub result: CheckResult,
+}
+
+impl PractitionerView {
+    pub fn new(record: &CheckRecord, criteria: StressCriteria) -> Result<Self, Error> {
+        Ok(Self {
+            id: record.id.clone(),
+            department: record.department.clone().map(String::from),
+            answers: record.answers.values().to_vec(),
+            result: CheckResult::new(&record.answers, criteria)?,
+        })
+    }
+}
+
+/// 事業者向けの結果
+/// 設問ごとの回答は持たない。判定結果は本人が提供に同意した場合のみ含む。
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmployerView {
+    /// ユーザ特定キー
+    pub id: String,
+    /// 所属部署
+    pub department: Option<String>,
+    /// 判定結果（同意がない場合は`None`）
+    pub result: Option<CheckResult>,
+}
+
+impl EmployerView {
+    /// `shared`は本人が事業者への提供に同意しているか
+    pub fn new(
+        record: &CheckRecord,
+        criteria: StressCriteria,
+        shared: bool,
+    ) -> Result<Self, Error> {
+        let result = if shared {
+            Some(CheckResult::new(&record.answers, criteria)?)
+        } else {
+            None
+        };
+        Ok(Self {
+            id: record.id.clone(),
+            department: record.department.clone().map(String::from),
+            result,
+        })
+    }
+
+    /// 受検記録に付された同意に従って作成する
+    pub fn from_consent(record: &CheckRecord, criteria: StressCriteria) -> Result<Self, Error> {
+        Self::new(record, criteria, record.is_shared_with_employer())
+    }
+}
+
+/// 立場ごとの結果
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "view", rename_all = "snake_case")]
+pub enum ResultView {
+    /// 受検者本人
+    Respondent(RespondentView),
+    /// 実施者
+    Practitioner(PractitionerView),
+    /// 事業者
+    Employer(EmployerView),
+}
+
+impl ResultView {
+    /// 結果に設問ごとの回答が含まれるか
+    pub fn has_answers(&self) -> bool {
+        !matches!(self, ResultView::Employer(_))
+    }
+}
+
+impl From<RespondentView> for ResultView {
+    fn from(view: RespondentView) -> Self {
+        ResultView::Respondent(view)
+    }
+}
+
+impl From<PractitionerView> for ResultView {
+    fn from(view: PractitionerView) -> Self {
+        ResultView::Practitioner(view)
+    }
+}
+
+impl From<EmployerView> for ResultView {
+    fn from(view: EmployerView) -> Self {
+        ResultView::Employer(view)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::AnswerStore;
+
+    fn record() -> CheckRecord {
+        let mut answers = AnswerStore::default();
+        for _ in 0..57 {
+            answers.push(2).unwrap();
+        }
+        CheckRecord {
+            id: "1".to_string(),
+            department: Some(crate::group_code::GroupCode::new("営業部").unwrap()),
+            demographics: None,
+            consent: None,
+            answers,
+            amendments: vec![],
+        }
+    }
+
+    #[test]
+    fn test_views() {
+        let record = record();
+        let practitioner = PractitionerView::new(&record, StressCriteria::SUMUP).unwrap();
+        assert_eq!(practitioner.answers.len(), 57);
+
+        let employer = EmployerView::new(&record, StressCriteria::SUMUP, true).unwrap();
+        assert_eq!(employer.result, Some(practitioner.result));
+        let value = serde_json::to_value(ResultView::from(employer)).unwrap();
+        assert_eq!(value["view"], "employer");
+        assert!(value.get("answers").is_none());
+
+        let employer = EmployerView::new(&record, StressCriteria::SUMUP, false).unwrap();
+        assert!(employer.result.is_none());
+        assert!(!ResultView::from(employer).has_answers());
+    }
+}
@@ -0,0 +1,119 @@
+//! 判定を妨げない注意事項
+//!
+//! 判定は成功するが、結果の扱いに注意を要する事項を`Warnings`に集める。
+//! 行を失敗させずに、後段で確認を促すために用いる。
+
+use serde::Serialize;
+
+use crate::{AnswerStore, CheckResult, Error, StressCriteria};
+
+/// 注意事項
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Warning {
+    /// 未回答を補完した設問がある
+    ImputedAnswers { questions: Vec<u8> },
+    /// 全ての設問に同じ回答をしている
+    StraightLine { answer: u8 },
+    /// 全設問の回答後に回答を変更した
+    AnswerChanged { question: u8, from: u8, to: u8 },
+}
+
+impl Warning {
+    /// 安定した識別子
+    pub fn code(&self) -> &'static str {
+        match self {
+            Warning::ImputedAnswers { .. } => "W_IMPUTED",
+            Warning::StraightLine { .. } => "W_STRAIGHT_LINE",
+            Warning::AnswerChanged { .. } => "W_ANSWER_CHANGED",
+        }
+    }
+}
+
+/// 注意事項の集まり
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct Warnings(Vec<Warning>);
+
+impl Warnings {
+    pub fn push(&mut self, warning: Warning) {
+        self.0.push(warning);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Warning> {
+        self.0.iter()
+    }
+
+    pub fn into_inner(self) -> Vec<Warning> {
+        self.0
+    }
+}
+
+impl Extend<Warning> for Warnings {
+    fn extend<T: IntoIterator<Item = Warning>>(&mut self, iter: T) {
+        self.0.extend(iter);
+    }
+}
+
+impl AnswerStore {
+    /// 回答に関する注意事項
+    pub fn warnings(&self) -> Warnings {
+        let mut warnings = Warnings::default();
+        let first = self.values[0];
+        if first != 0 && self.values.iter().all(|&value| value == first) {
+            warnings.push(Warning::StraightLine { answer: first });
+        }
+        warnings.extend(
+            self.changes
+                .iter()
+                .map(|&(question, from, to)| Warning::AnswerChanged { question, from, to }),
+        );
+        warnings
+    }
+}
+
+impl CheckResult {
+    /// 判定結果とあわせて注意事項を返す
+    pub fn with_warnings(
+        store: &AnswerStore,
+        criteria: StressCriteria,
+    ) -> Result<(Self, Warnings), Error> {
+        Ok((Self::new(store, criteria)?, store.warnings()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_warnings() {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(2).unwrap();
+        }
+        let (_, warnings) = CheckResult::with_warnings(&store, StressCriteria::SUMUP).unwrap();
+        assert_eq!(
+            warnings.into_inner(),
+            vec![Warning::StraightLine { answer: 2 }]
+        );
+
+        store.insert(3, 4).unwrap();
+        store.insert(3, 4).unwrap();
+        let warnings = store.warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings.iter().next().unwrap().code(), "W_ANSWER_CHANGED");
+        assert_eq!(
+            serde_json::to_value(&warnings).unwrap(),
+            serde_json::json!([{"kind": "answer_changed", "question": 3, "from": 2, "to": 4}])
+        );
+    }
+}
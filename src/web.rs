@@ -0,0 +1,171 @@
+//! 静的な回答ページの生成
+//!
+//! サーバを用意できない事業場向けに、マスタから1枚のHTMLを生成する。
+//! 回答はブラウザ上で検証し、一括読み込みと同じ列名（`id`, `q_1`〜`q_57`）の
+//! JSONとしてダウンロードさせる。ダウンロードしたJSONは`read_bulk_json`で読み込める。
+
+use crate::variant::TextVariant;
+use crate::SimpleStress;
+
+/// HTMLの特殊文字を置き換える
+pub fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const SCRIPT: &str = r#"
+document.getElementById("survey").addEventListener("submit", function (event) {
+  event.preventDefault();
+  var form = event.target;
+  var id = form.elements["id"].value.trim();
+  if (id === "") {
+    alert("受検番号を入力してください。");
+    return;
+  }
+  var answers = { id: id };
+  var missing = [];
+  for (var i = 1; i <= QUESTION_COUNT; i++) {
+    var checked = form.querySelector('input[name="q_' + i + '"]:checked');
+    if (checked) {
+      answers["q_" + i] = Number(checked.value);
+    } else {
+      missing.push(i);
+    }
+  }
+  if (missing.length > 0) {
+    alert("未回答の設問があります: " + missing.join(", "));
+    document.getElementById("question-" + missing[0]).scrollIntoView();
+    return;
+  }
+  var blob = new Blob([JSON.stringify(answers)], { type: "application/json" });
+  var link = document.createElement("a");
+  link.href = URL.createObjectURL(blob);
+  link.download = "stresscheck-" + id + ".json";
+  link.click();
+});
+"#;
+
+/// 回答ページの設問文の示し方
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SurveyOptions {
+    /// 設問文にふりがなを添える（原文の場合のみ）
+    pub furigana: bool,
+    /// 設問文の種類
+    pub variant: TextVariant,
+}
+
+/// 回答ページのHTMLを生成する
+pub fn render_survey(master: &SimpleStress, title: &str) -> String {
+    render_survey_with(master, title, &SurveyOptions::default())
+}
+
+/// 設問文にふりがなを添えた回答ページのHTMLを生成する
+pub fn render_survey_with_furigana(master: &SimpleStress, title: &str) -> String {
+    render_survey_with(
+        master,
+        title,
+        &SurveyOptions {
+            furigana: true,
+            ..Default::default()
+        },
+    )
+}
+
+/// 設問文の示し方を指定して回答ページのHTMLを生成する
+pub fn render_survey_with(master: &SimpleStress, title: &str, options: &SurveyOptions) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{}</title>\n", escape_html(title)));
+    html.push_str(
+        "<style>fieldset{margin:1em 0}label{margin-right:1em;white-space:nowrap}</style>\n",
+    );
+    html.push_str("</head>\n<body>\n");
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(title)));
+    html.push_str("<form id=\"survey\">\n");
+    html.push_str(
+        "<p><label>受検番号 <input name=\"id\" required autocomplete=\"off\"></label></p>\n",
+    );
+    for theme in &master.simple_stress {
+        html.push_str(&format!("<h2>{}</h2>\n", escape_html(&theme.theme)));
+        for outer_question in &theme.questions {
+            if let Some(ref title) = outer_question.title {
+                html.push_str(&format!("<h3>{}</h3>\n", escape_html(title)));
+            }
+            for question in &outer_question.questions {
+                html.push_str(&format!(
+                    "<fieldset id=\"question-{}\">\n<legend>{}. {}</legend>\n",
+                    question.id,
+                    question.id,
+                    match options.variant {
+                        TextVariant::Standard => question.text_html(options.furigana),
+                        variant => escape_html(question.text_for(variant)),
+                    }
+                ));
+                for score in &question.scores {
+                    html.push_str(&format!(
+                        "<label><input type=\"radio\" name=\"q_{}\" value=\"{}\"> {}</label>\n",
+                        question.id,
+                        score.score,
+                        escape_html(&score.text)
+                    ));
+                }
+                html.push_str("</fieldset>\n");
+            }
+        }
+    }
+    html.push_str("<button type=\"submit\">回答を保存する</button>\n</form>\n");
+    html.push_str(&format!(
+        "<script>\nvar QUESTION_COUNT = {};{}</script>\n",
+        master.questions().len(),
+        SCRIPT
+    ));
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::QUESTIONS;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_render_survey() {
+        let html = render_survey(&QUESTIONS, "ストレスチェック");
+        assert!(html.contains("<title>ストレスチェック</title>"));
+        assert_eq!(html.matches("<fieldset").count(), 57);
+        assert!(html.contains("name=\"q_57\" value=\"4\""));
+        assert!(html.contains("var QUESTION_COUNT = 57;"));
+        assert!(!html.contains("<ruby>"));
+
+        let html = render_survey_with_furigana(&QUESTIONS, "ストレスチェック");
+        assert!(html.contains("<legend>1. <ruby>非常<rt>ひじょう</rt></ruby>に"));
+        assert!(html.contains("<legend>23. イライラしている</legend>"));
+
+        let options = SurveyOptions {
+            furigana: true,
+            variant: TextVariant::Plain,
+        };
+        let html = render_survey_with(&QUESTIONS, "ストレスチェック", &options);
+        assert!(html.contains("<legend>1. しごとが とても おおい</legend>"));
+        assert!(html.contains("name=\"q_1\" value=\"1\""));
+    }
+}
@@ -0,0 +1,131 @@
+//! 受検完了時のWebhook送信
+//!
+//! 判定が済むたびに結果のJSONを設定したURLへPOSTし、人事システム等へ即時に連携する。
+//! 本文はHMAC-SHA256で署名し、`X-Stresscheck-Signature`ヘッダに`sha256=<16進>`として付与する。
+
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::{CheckResult, Error};
+
+/// 署名を格納するヘッダ
+pub const SIGNATURE_HEADER: &str = "X-Stresscheck-Signature";
+
+/// 標準のタイムアウト
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 送信するJSON
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WebhookPayload<'a> {
+    /// イベント名
+    pub event: &'static str,
+    /// ユーザ特定キー
+    pub id: &'a str,
+    pub result: &'a CheckResult,
+}
+
+/// 本文の署名（`sha256=<16進>`）
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC can take key of any size");
+    mac.update(body);
+    let digest = mac
+        .finalize()
+        .into_bytes()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+    format!("sha256={}", digest)
+}
+
+/// 受信側で署名を検証する
+pub fn verify_signature(secret: &[u8], body: &[u8], signature: &str) -> bool {
+    let Some(digest) = signature.strip_prefix("sha256=") else {
+        return false;
+    };
+    let Some(expected) = (0..digest.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digest.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()
+    else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC can take key of any size");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Webhookの送信先
+pub struct WebhookSink {
+    url: String,
+    secret: Vec<u8>,
+    agent: ureq::Agent,
+}
+
+impl WebhookSink {
+    pub fn new(url: &str, secret: &[u8]) -> Self {
+        Self {
+            url: url.to_string(),
+            secret: secret.to_vec(),
+            agent: ureq::AgentBuilder::new().timeout(DEFAULT_TIMEOUT).build(),
+        }
+    }
+
+    /// 送信する本文と署名
+    pub fn body(&self, id: &str, result: &CheckResult) -> (Vec<u8>, String) {
+        let payload = WebhookPayload {
+            event: "submission.scored",
+            id,
+            result,
+        };
+        let body = serde_json::to_vec(&payload).unwrap();
+        let signature = sign(&self.secret, &body);
+        (body, signature)
+    }
+
+    /// 判定結果を送信する
+    pub fn emit(&self, id: &str, result: &CheckResult) -> Result<(), Error> {
+        let (body, signature) = self.body(id, result);
+        self.agent
+            .post(&self.url)
+            .set("Content-Type", "application/json")
+            .set(SIGNATURE_HEADER, &signature)
+            .send_bytes(&body)
+            .map_err(|e| Error::WebhookError(e.to_string()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, StressCriteria};
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 Test Case 2
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_body() {
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(4).unwrap();
+        }
+        let result = CheckResult::new(&store, StressCriteria::SUMUP).unwrap();
+        let sink = WebhookSink::new("http://localhost/hook", b"secret");
+        let (body, signature) = sink.body("1", &result);
+        let value: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(value["event"], "submission.scored");
+        assert_eq!(value["result"]["has_stress"], true);
+        assert!(verify_signature(b"secret", &body, &signature));
+        assert!(!verify_signature(b"other", &body, &signature));
+        assert!(!verify_signature(b"secret", &body, "sha256=zz"));
+    }
+}
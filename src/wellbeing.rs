@@ -0,0 +1,104 @@
+//! 領域ごとのウェルビーイング指数（任意）
+//!
+//! 経営層への報告などで部署間を1つの数値で比べられるよう、領域Ａ・Ｂ・Ｃの点数を0〜100の
+//! 指数に換算する。100が最も良好（ストレスが少ない・サポートが多い）、0が最も不良な状態を表す。
+//! 公式の判定に用いる点数ではないため、必ず領域ごとの点数・高ストレス者率と併せて示す。
+//!
+//! 換算は各領域の点数の取りうる範囲による線形変換とする。
+//!
+//! | 選定方法 | 領域Ａ | 領域Ｂ | 領域Ｃ | 指数 |
+//! |---|---|---|---|---|
+//! | 合計点数方式 | 17〜68 | 29〜116 | 9〜36 | `(上限 - 点数) / (上限 - 下限) × 100` |
+//! | 素点換算表方式 | 9〜45 | 6〜30 | 3〜15 | `(点数 - 下限) / (上限 - 下限) × 100` |
+//!
+//! 合計点数方式は点数が高いほど、素点換算表方式は低いほどストレスが高いため、向きを揃えている。
+//! 選定方法が異なると指数の分布も異なるため、同じ選定方法の指数どうしでのみ比べる。
+
+use serde::Serialize;
+
+use crate::StressCriteria;
+
+/// 合計点数方式の領域Ａ・Ｂ・Ｃの点数の範囲
+pub const SUMUP_RANGES: [(u8, u8); 3] = [(17, 68), (29, 116), (9, 36)];
+/// 素点換算表方式の領域Ａ・Ｂ・Ｃの点数の範囲
+pub const CONVERSION_RANGES: [(u8, u8); 3] = [(9, 45), (6, 30), (3, 15)];
+
+/// 領域ごとのウェルビーイング指数（0〜100）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct WellbeingIndex {
+    /// 領域Ａ（仕事のストレス要因）
+    pub a: f64,
+    /// 領域Ｂ（心身のストレス反応）
+    pub b: f64,
+    /// 領域Ｃ（周囲のサポート）
+    pub c: f64,
+}
+
+impl WellbeingIndex {
+    /// 一人分の領域ごとの点数から換算する
+    pub fn new(criteria: StressCriteria, scores: (u8, u8, u8)) -> Self {
+        let (a, b, c) = scores;
+        Self::from_means(criteria, (a as f64, b as f64, c as f64))
+    }
+
+    /// 集団の領域ごとの点数の平均から換算する
+    /// 範囲外の値は0〜100に収める。
+    pub fn from_means(criteria: StressCriteria, means: (f64, f64, f64)) -> Self {
+        let (ranges, reverse) = match criteria {
+            StressCriteria::Sumup(_) => (SUMUP_RANGES, true),
+            StressCriteria::Conversion(_) => (CONVERSION_RANGES, false),
+        };
+        let index = |value: f64, (lower, upper): (u8, u8)| {
+            let ratio = (value - lower as f64) / (upper - lower) as f64;
+            let ratio = if reverse { 1.0 - ratio } else { ratio };
+            (ratio * 100.0).clamp(0.0, 100.0)
+        };
+        let (a, b, c) = means;
+        Self {
+            a: index(a, ranges[0]),
+            b: index(b, ranges[1]),
+            c: index(c, ranges[2]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AnswerStore, Stress};
+
+    #[test]
+    fn test_wellbeing_index() {
+        let index = WellbeingIndex::new(StressCriteria::SUMUP, (17, 116, 36));
+        assert_eq!(
+            index,
+            WellbeingIndex {
+                a: 100.0,
+                b: 0.0,
+                c: 0.0
+            }
+        );
+        let index = WellbeingIndex::new(StressCriteria::CONVERSION, (45, 6, 9));
+        assert_eq!(
+            index,
+            WellbeingIndex {
+                a: 100.0,
+                b: 0.0,
+                c: 50.0
+            }
+        );
+        let index = WellbeingIndex::from_means(StressCriteria::SUMUP, (0.0, 42.5, 200.0));
+        assert_eq!((index.a, index.c), (100.0, 0.0));
+
+        let mut store = AnswerStore::default();
+        for _ in 0..57 {
+            store.push(4).unwrap();
+        }
+        for criteria in [StressCriteria::SUMUP, StressCriteria::CONVERSION] {
+            let index = WellbeingIndex::new(criteria, criteria.score(&store).unwrap().scores());
+            for value in [index.a, index.b, index.c] {
+                assert!((0.0..=100.0).contains(&value), "{}", value);
+            }
+        }
+    }
+}